@@ -0,0 +1,58 @@
+use lazy_static::*;
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use super::config::ATTR_CACHE_CAPACITY;
+
+// serialized metadata of paths with the instant it stops being trustworthy, that is the end of
+// the lease in "lease" mode. entries are dropped as soon as this client changes the path
+pub struct AttrCache {
+    entries_: Mutex<HashMap<String, (Instant, Vec<u8>)>>,
+}
+lazy_static! {
+    static ref ATTR_CACHE: AttrCache = AttrCache {
+        entries_: Mutex::new(HashMap::new()),
+    };
+}
+impl AttrCache {
+    pub fn get_instance() -> &'static AttrCache {
+        &ATTR_CACHE
+    }
+    pub fn insert(&self, path: String, md: Vec<u8>, expire: Instant) {
+        let now = Instant::now();
+        if expire <= now {
+            return;
+        }
+        let mut entries = self.entries_.lock().unwrap();
+        if entries.len() >= ATTR_CACHE_CAPACITY {
+            entries.retain(|_, (expire, _)| *expire > now);
+            if entries.len() >= ATTR_CACHE_CAPACITY {
+                entries.clear();
+            }
+        }
+        entries.insert(path, (expire, md));
+    }
+    pub fn get(&self, path: &String) -> Option<Vec<u8>> {
+        let mut entries = self.entries_.lock().unwrap();
+        match entries.get(path) {
+            Some((expire, md)) if *expire > Instant::now() => Some(md.clone()),
+            Some(_) => {
+                entries.remove(path);
+                None
+            }
+            None => None,
+        }
+    }
+    // until when the cached metadata of 'path' is trusted
+    pub fn get_expire(&self, path: &String) -> Option<Instant> {
+        match self.entries_.lock().unwrap().get(path) {
+            Some((expire, _)) if *expire > Instant::now() => Some(*expire),
+            _ => None,
+        }
+    }
+    pub fn remove(&self, path: &String) {
+        self.entries_.lock().unwrap().remove(path);
+    }
+}
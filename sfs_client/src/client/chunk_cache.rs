@@ -0,0 +1,126 @@
+use lazy_static::*;
+use sfs_global::global::network::config::CHUNK_SIZE;
+use sfs_global::global::util::env_util::get_var;
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use super::config::{CHUNK_CACHE_SIZE, READ_AHEAD_CHUNKS};
+
+struct CachedChunk {
+    expire: Instant,
+    tick: u64,
+    data: Arc<Vec<u8>>,
+}
+struct ChunkCacheInner {
+    chunks: HashMap<(String, u64), CachedChunk>,
+    // least recently used first
+    lru: BTreeMap<u64, (String, u64)>,
+    tick: u64,
+}
+// chunks read by this process, shared by every descriptor of a file. a chunk is trusted as long as
+// the metadata of its file was when the chunk got fetched, i.e. until the lease or cache time ends
+pub struct ChunkCache {
+    capacity_: usize,
+    read_ahead_: u64,
+    inner_: Mutex<ChunkCacheInner>,
+}
+lazy_static! {
+    static ref CHUNK_CACHE: ChunkCache = ChunkCache {
+        capacity_: (get_var(
+            "SFS_CHUNK_CACHE_SIZE".to_string(),
+            CHUNK_CACHE_SIZE.to_string()
+        )
+        .parse::<u64>()
+        .unwrap_or(CHUNK_CACHE_SIZE)
+            / CHUNK_SIZE) as usize,
+        read_ahead_: get_var(
+            "SFS_READ_AHEAD_CHUNKS".to_string(),
+            READ_AHEAD_CHUNKS.to_string()
+        )
+        .parse::<u64>()
+        .unwrap_or(READ_AHEAD_CHUNKS),
+        inner_: Mutex::new(ChunkCacheInner {
+            chunks: HashMap::new(),
+            lru: BTreeMap::new(),
+            tick: 0,
+        }),
+    };
+}
+impl ChunkCache {
+    pub fn get_instance() -> &'static ChunkCache {
+        &CHUNK_CACHE
+    }
+    pub fn enabled(&self) -> bool {
+        self.capacity_ != 0
+    }
+    pub fn get_read_ahead(&self) -> u64 {
+        self.read_ahead_
+    }
+    pub fn get(&self, path: &String, chunk_id: u64) -> Option<Arc<Vec<u8>>> {
+        let mut inner = self.inner_.lock().unwrap();
+        let key = (path.clone(), chunk_id);
+        let (expire, old_tick) = match inner.chunks.get(&key) {
+            Some(chunk) => (chunk.expire, chunk.tick),
+            None => return None,
+        };
+        inner.lru.remove(&old_tick);
+        if expire <= Instant::now() {
+            inner.chunks.remove(&key);
+            return None;
+        }
+        inner.tick += 1;
+        let tick = inner.tick;
+        inner.lru.insert(tick, key.clone());
+        let chunk = inner.chunks.get_mut(&key).unwrap();
+        chunk.tick = tick;
+        Some(Arc::clone(&chunk.data))
+    }
+    pub fn insert(&self, path: &String, chunk_id: u64, data: Vec<u8>, expire: Instant) {
+        if !self.enabled() || expire <= Instant::now() {
+            return;
+        }
+        let mut inner = self.inner_.lock().unwrap();
+        let key = (path.clone(), chunk_id);
+        if let Some(old) = inner.chunks.remove(&key) {
+            inner.lru.remove(&old.tick);
+        }
+        while inner.chunks.len() >= self.capacity_ {
+            let oldest = *inner.lru.keys().next().unwrap();
+            let victim = inner.lru.remove(&oldest).unwrap();
+            inner.chunks.remove(&victim);
+        }
+        inner.tick += 1;
+        let tick = inner.tick;
+        inner.lru.insert(tick, key.clone());
+        inner.chunks.insert(
+            key,
+            CachedChunk {
+                expire,
+                tick,
+                data: Arc::new(data),
+            },
+        );
+    }
+    // drop chunks 'chunk_start' to 'chunk_end' of 'path', both included
+    pub fn invalidate(&self, path: &String, chunk_start: u64, chunk_end: u64) {
+        if !self.enabled() {
+            return;
+        }
+        let mut inner = self.inner_.lock().unwrap();
+        let victims: Vec<(String, u64)> = inner
+            .chunks
+            .keys()
+            .filter(|(p, chunk_id)| p.eq(path) && *chunk_id >= chunk_start && *chunk_id <= chunk_end)
+            .cloned()
+            .collect();
+        for victim in victims {
+            let chunk = inner.chunks.remove(&victim).unwrap();
+            inner.lru.remove(&chunk.tick);
+        }
+    }
+    pub fn invalidate_path(&self, path: &String) {
+        self.invalidate(path, 0, u64::MAX);
+    }
+}
@@ -1 +1,35 @@
 pub const CHECK_PARENT_DIR: bool = false;
+// fetch the attributes of every entry along with a directory listing, so 'ls -l' style
+// traversals do not stat each entry separately
+pub const READDIR_PLUS: bool = true;
+pub const ATTR_CACHE_CAPACITY: usize = 65536;
+// write-back buffer of each open file in bytes, 0 disables buffering. overridden by 'SFS_WRITE_BUFFER_SIZE'
+pub const WRITE_BUFFER_SIZE: u64 = 0;
+// memory all write-back buffers of a process may take, overridden by 'SFS_WRITE_BUFFER_LIMIT'
+pub const WRITE_BUFFER_LIMIT: u64 = 256 * 1024 * 1024;
+// memory for cached chunks of a process, 0 disables the cache. overridden by 'SFS_CHUNK_CACHE_SIZE'
+pub const CHUNK_CACHE_SIZE: u64 = 64 * 1024 * 1024;
+// chunks fetched beyond a sequential read. overridden by 'SFS_READ_AHEAD_CHUNKS'
+pub const READ_AHEAD_CHUNKS: u64 = 4;
+// leave size updates of writes to close, fsync or a batch, overridden by 'SFS_DEFER_SIZE_UPDATES'
+pub const DEFER_SIZE_UPDATES: bool = false;
+// deferred sizes are committed once this many files have one
+pub const SIZE_BATCH_FILES: usize = 64;
+// threads running the network I/O of the process, overridden by 'SFS_IO_THREADS'.
+// the connections to each host are set by 'SFS_CM_IDS_PER_HOST'
+pub const IO_THREADS: usize = 4;
+// deadline of a single call in milliseconds, overridden by 'SFS_RPC_TIMEOUT_MS'
+pub const RPC_TIMEOUT_MS: u64 = 30000;
+// extra attempts of an idempotent call after a timeout or lost connection, overridden by 'SFS_RPC_RETRIES'
+pub const RPC_RETRIES: u32 = 3;
+// first pause between attempts, doubled after every failure up to 'RPC_BACKOFF_MAX_MS'
+pub const RPC_BACKOFF_MS: u64 = 20;
+pub const RPC_BACKOFF_MAX_MS: u64 = 2000;
+// consecutive unanswered calls after which a host is skipped, overridden by 'SFS_CIRCUIT_FAILURES'
+pub const CIRCUIT_FAILURES: u32 = 3;
+// how long a host is skipped before a single call probes it again, overridden by 'SFS_CIRCUIT_OPEN_MS'
+pub const CIRCUIT_OPEN_MS: u64 = 5000;
+// calls of one fan-out, e.g. 'statfs', in flight at once, overridden by 'SFS_RPC_FANOUT'
+pub const RPC_FANOUT: usize = 64;
+// attempts to reach each server at startup, overridden by 'SFS_CONNECT_RETRIES'
+pub const CONNECT_RETRIES: u32 = 5;
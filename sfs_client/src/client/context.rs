@@ -1,9 +1,8 @@
 use lazy_static::*;
 use rdma_sys::{rdma_event_channel, rdma_cm_id, rdma_disconnect};
 use sfs_global::global::endpoint::SFSEndpoint;
-use sfs_global::global::network::config::CLIENT_CM_IDS;
 use sfs_rpc::proto::server_grpc::SfsHandleClient;
-use tokio::runtime::{Builder, Runtime};
+use sfs_rpc::security::TlsConfig;
 
 use std::collections::HashMap;
 use std::ptr::null_mut;
@@ -285,19 +284,26 @@ pub struct StaticContext {
     clients_: Vec<SfsHandleClient>,
     local_host_id: u64,
     fwd_host_id: u64,
+    // identifies this process to the lease tables of metadata servers
+    client_id_: u64,
+    // shared secret of the job sent with every call, empty without authentication
+    token_: String,
+    tls_: Option<TlsConfig>,
+    // the servers use checksums, RDMA transfers carry one per piece
+    checksum_: bool,
     rpc_protocol_: String,
     auto_sm_: bool,
 
     internal_fds_must_relocate_: bool,
-    runtime_: Arc<Runtime>,
 
     pub event_channel: u64,
     pub handle: Option<JoinHandle<()>>,
 
-    pub write_cm_ids: HashMap<u64, Vec<Mutex<u64>>>,
+    // pools of RDMA connections per host, taken in ascending host order by every operation
+    pub write_cm_ids: HashMap<u64, Vec<tokio::sync::Mutex<u64>>>,
     pub wait_write_idx: Mutex<usize>,
 
-    pub read_cm_ids: HashMap<u64, Vec<Mutex<u64>>>,
+    pub read_cm_ids: HashMap<u64, Vec<tokio::sync::Mutex<u64>>>,
     pub wait_read_idx: Mutex<usize>,
 
     pub init_flag: bool,
@@ -320,17 +326,14 @@ impl StaticContext {
             clients_: Vec::new(),
             local_host_id: 0,
             fwd_host_id: 0,
+            client_id_: 0,
+            token_: "".to_string(),
+            tls_: None,
+            checksum_: false,
             rpc_protocol_: "tcp".to_string(),
             auto_sm_: false,
             internal_fds_must_relocate_: true,
             init_flag: false,
-            runtime_: Arc::new(
-                Builder::new_current_thread()
-                    .enable_all()
-                    .thread_stack_size(12 * 1024 * 1024)
-                    .build()
-                    .unwrap(),
-            ),
             event_channel: null_mut() as *mut rdma_event_channel as u64,
             write_cm_ids: HashMap::new(),
             wait_write_idx: Mutex::new(0),
@@ -380,6 +383,30 @@ impl StaticContext {
     pub fn get_local_host_id(&self) -> u64 {
         self.local_host_id.clone()
     }
+    pub fn set_client_id(&mut self, client_id: u64) {
+        self.client_id_ = client_id;
+    }
+    pub fn get_client_id(&self) -> u64 {
+        self.client_id_
+    }
+    pub fn set_token(&mut self, token: String) {
+        self.token_ = token;
+    }
+    pub fn get_token(&self) -> &String {
+        &self.token_
+    }
+    pub fn set_tls(&mut self, tls: Option<TlsConfig>) {
+        self.tls_ = tls;
+    }
+    pub fn get_tls(&self) -> &Option<TlsConfig> {
+        &self.tls_
+    }
+    pub fn set_checksum(&mut self, checksum: bool) {
+        self.checksum_ = checksum;
+    }
+    pub fn get_checksum(&self) -> bool {
+        self.checksum_
+    }
     pub fn set_fwd_host_id(&mut self, host_id: u64) {
         self.fwd_host_id = host_id;
     }
@@ -413,58 +440,53 @@ impl StaticContext {
     pub fn get_init_flag(&self) -> bool {
         self.init_flag
     }
-    pub fn get_runtime(&self) -> Arc<Runtime> {
-        Arc::clone(&self.runtime_)
-    }
     pub fn get_event_channel(&self) -> *mut rdma_event_channel{
         self.event_channel as *mut rdma_event_channel
     }
-    pub fn get_write_cm_id(&'static self, host_id: u64) -> Option<MutexGuard<'static, u64>>{
-        assert_eq!(self.write_cm_ids.get(&host_id).unwrap().len(), CLIENT_CM_IDS);
-        for lock in self.write_cm_ids.get(&host_id).unwrap().iter(){
+    // a free connection of the pool, or the turn on a busy one. waiting yields to the other
+    // operations of the I/O thread, one of them may be the holder
+    pub async fn get_write_cm_id(&'static self, host_id: u64) -> Option<tokio::sync::MutexGuard<'static, u64>>{
+        // host without connection, callers may fail over to another replica
+        let cm_ids = self.write_cm_ids.get(&host_id)?;
+        for lock in cm_ids.iter(){
             if let Ok(guard) = lock.try_lock(){
                 return Some(guard);
             }
         }
         let mut idx_guard = self.wait_write_idx.lock().unwrap();
-        let idx = *idx_guard;
+        let idx = *idx_guard % cm_ids.len();
         (*idx_guard) = idx + 1;
-        if (*idx_guard) >= CLIENT_CM_IDS{
-            (*idx_guard) = 0;
-        }
         drop(idx_guard);
-        return Some(self.write_cm_ids.get(&host_id).unwrap().get(idx).unwrap().lock().unwrap());
+        return Some(cm_ids.get(idx).unwrap().lock().await);
     }
-    pub fn get_read_cm_id(&'static self, host_id: u64) -> Option<MutexGuard<'static, u64>>{
-        assert_eq!(self.read_cm_ids.get(&host_id).unwrap().len(), CLIENT_CM_IDS);
-        for lock in self.read_cm_ids.get(&host_id).unwrap().iter(){
+    pub async fn get_read_cm_id(&'static self, host_id: u64) -> Option<tokio::sync::MutexGuard<'static, u64>>{
+        // host without connection, callers may fail over to another replica
+        let cm_ids = self.read_cm_ids.get(&host_id)?;
+        for lock in cm_ids.iter(){
             if let Ok(guard) = lock.try_lock(){
                 return Some(guard);
             }
         }
         let mut idx_guard = self.wait_read_idx.lock().unwrap();
-        let idx = *idx_guard;
+        let idx = *idx_guard % cm_ids.len();
         (*idx_guard) = idx + 1;
-        if (*idx_guard) >= CLIENT_CM_IDS{
-            (*idx_guard) = 0;
-        }
         drop(idx_guard);
-        return Some(self.read_cm_ids.get(&host_id).unwrap().get(idx).unwrap().lock().unwrap());
+        return Some(cm_ids.get(idx).unwrap().lock().await);
     }
 }
 impl Drop for StaticContext{
     fn drop(&mut self) {
-        for (_host_id, cm_ids) in self.write_cm_ids.iter(){
-            for lock in cm_ids{
-                let cm_id = *lock.lock().unwrap() as *mut rdma_cm_id;
+        for (_host_id, cm_ids) in self.write_cm_ids.iter_mut(){
+            for lock in cm_ids.iter_mut(){
+                let cm_id = *lock.get_mut() as *mut rdma_cm_id;
                 unsafe{
                     rdma_disconnect(cm_id);
                 }
             }
         }
-        for (_host_id, cm_ids) in self.read_cm_ids.iter(){
-            for lock in cm_ids{
-                let cm_id = *lock.lock().unwrap() as *mut rdma_cm_id;
+        for (_host_id, cm_ids) in self.read_cm_ids.iter_mut(){
+            for lock in cm_ids.iter_mut(){
+                let cm_id = *lock.get_mut() as *mut rdma_cm_id;
                 unsafe{
                     rdma_disconnect(cm_id);
                 }
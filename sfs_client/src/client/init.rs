@@ -1,35 +1,41 @@
-use core::time;
 use std::{
     fs::OpenOptions,
     io::{BufRead, BufReader, Error},
     path::Path,
-    sync::{Arc, Mutex},
+    sync::Arc,
     thread,
+    time::Duration,
 };
 
-use grpcio::{ChannelBuilder, Environment};
+use grpcio::{ChannelBuilder, Environment, RpcStatusCode};
 use rdma_sys::{rdma_create_event_channel, rdma_event_channel};
+use tokio::runtime::Builder;
 use regex::Regex;
+use xxhash_rust::xxh3::xxh3_64;
 
 use sfs_global::global::{
     distributor::SimpleHashDistributor,
     endpoint::SFSEndpoint,
     error_msg::error_msg,
     fsconfig::{ENABLE_OUTPUT, HOSTFILE_PATH},
-    network::{
-        config::CLIENT_CM_IDS,
-        post::{option2i, PostOption},
-    },
+    network::{config::FEATURE_CHECKSUM, handshake::HandshakeInfo},
     util::{
+        checksum_util::token_digest,
         env_util::{get_hostname, get_var},
-        serde_util::serialize,
     },
 };
-use sfs_rpc::{post, proto::server_grpc::SfsHandleClient};
+use sfs_rpc::{
+    proto::{server::HandshakeRequest, server_grpc::SfsHandleClient},
+    security::{connect, load_token, TlsConfig},
+    status_errno,
+};
 
 use super::{
     context::{DynamicContext, StaticContext},
-    network::{forward_msg::forward_get_fs_config, rdmacm::process_cm_event, rdma_write::new_write_cm_id, rdma_read::new_read_cm_id},
+    io_service::IoService,
+    config::RPC_BACKOFF_MS,
+    syscall::commit_at_exit,
+    network::{forward_msg::forward_get_fs_config, rdmacm::process_cm_event, rdma_write::new_write_cm_id, rdma_read::new_read_cm_id, rpc_policy::RpcPolicy},
 };
 
 fn extract_protocol(_uri: &String) {}
@@ -62,26 +68,60 @@ fn load_host_file(path: &String) -> Result<Vec<(String, String)>, Error> {
     extract_protocol(&hosts[0].1);
     return Ok(hosts);
 }
+// connects to 'uri' and makes sure the server speaks our protocol, returns the handshake of the server
 fn lookup_endpoint(
     uri: &String,
-    max_retries: i32,
-    host_id: u64,
-) -> Result<(SFSEndpoint, SfsHandleClient), Error> {
+    max_retries: u32,
+    local: &HandshakeInfo,
+    context: &StaticContext,
+) -> Result<(SFSEndpoint, SfsHandleClient, HandshakeInfo), Error> {
     let endp = SFSEndpoint { addr: uri.clone() };
+    let mut req = HandshakeRequest::default();
+    req.set_protocol_version(local.protocol_version);
+    req.set_features(local.features);
+    req.set_chunk_size(local.chunk_size);
+    req.set_cluster_id(local.cluster_id.clone());
+    let policy = RpcPolicy::get_instance();
     for i in 0..max_retries {
-        let serialized_data = serialize(&host_id);
-        let post = post(option2i(&PostOption::Lookup), serialized_data, vec![0; 0]);
         let env = Arc::new(Environment::new(4));
-        let channel = ChannelBuilder::new(env)
+        // the channel reconnects on its own once a lost server is back, at the latest when
+        // its circuit lets the next call through
+        let builder = ChannelBuilder::new(env)
             .max_receive_message_len(128 * 1024 * 1024)
             .max_send_message_len(128 * 1024 * 1024)
-            .connect(&format!("{}:{}", endp.addr, 8082));
+            .initial_reconnect_backoff(Duration::from_millis(RPC_BACKOFF_MS))
+            .max_reconnect_backoff(policy.get_open());
+        let channel = connect(builder, &format!("{}:{}", endp.addr, 8082), context.get_tls());
         let client = SfsHandleClient::new(channel);
-        if let Ok(_post_res) = client.handle(&post) {
+        let handshake_res = client.handshake_opt(&req, policy.call_option(context.get_token()));
+        if let Err(grpcio::Error::RpcFailure(status)) = &handshake_res {
+            if status.code() == RpcStatusCode::UNAUTHENTICATED {
+                return Err(Error::new(
+                    std::io::ErrorKind::PermissionDenied,
+                    "missing or wrong token, see 'SFS_TOKEN' and 'SFS_TOKEN_FILE'",
+                ));
+            }
+        }
+        if let Ok(response) = handshake_res {
+            let remote = HandshakeInfo {
+                protocol_version: response.protocol_version,
+                features: response.features,
+                chunk_size: response.chunk_size,
+                cluster_id: response.cluster_id.clone(),
+            };
+            // the server refuses as well, its reason is the one to report then
+            let check = if status_errno(response.get_status()) != 0 {
+                Err(response.get_status().message.clone())
+            } else {
+                local.check(&remote)
+            };
+            if let Err(reason) = check {
+                return Err(Error::new(std::io::ErrorKind::InvalidData, reason));
+            }
             if ENABLE_OUTPUT {
                 println!("connected: '{}'", uri);
             }
-            return Ok((endp, client));
+            return Ok((endp, client, remote));
         } else {
             error_msg(
                 "client::init::lookup_endpoint".to_string(),
@@ -92,7 +132,7 @@ fn lookup_endpoint(
                     max_retries
                 ),
             );
-            thread::sleep(time::Duration::from_millis(5));
+            thread::sleep(policy.backoff(i + 1));
         }
     }
     Err(Error::new(
@@ -100,8 +140,33 @@ fn lookup_endpoint(
         "fail to connect to target host",
     ))
 }
-#[tokio::main]
-async fn connect_hosts(hosts: &mut Vec<(String, String)>, context: &mut StaticContext) -> u64 {
+// RDMA connections to 'uri' for writes and reads. a thread and runtime of their own, the context
+// may be set up by the first operation running on an I/O thread
+fn create_cm_ids(
+    event_channel: u64,
+    uri: &String,
+    count: usize,
+    token: &String,
+) -> (Vec<u64>, Vec<u64>) {
+    let uri = uri.clone();
+    let digest = token_digest(token);
+    thread::spawn(move || {
+        let runtime = Builder::new_current_thread().enable_all().build().unwrap();
+        runtime.block_on(async move {
+            let ec = event_channel as *mut rdma_event_channel;
+            let mut write_ids = Vec::new();
+            let mut read_ids = Vec::new();
+            for _i in 0..count {
+                write_ids.push(new_write_cm_id(ec, &uri, digest).await);
+                read_ids.push(new_read_cm_id(ec, &uri, digest).await);
+            }
+            (write_ids, read_ids)
+        })
+    })
+    .join()
+    .unwrap()
+}
+fn connect_hosts(hosts: &mut Vec<(String, String)>, context: &mut StaticContext) -> u64 {
     let local_hostname = get_hostname(true);
     if ENABLE_OUTPUT {
         println!("localhost name: {}", local_hostname);
@@ -110,13 +175,32 @@ async fn connect_hosts(hosts: &mut Vec<(String, String)>, context: &mut StaticCo
     let mut addrs = Vec::new();
     let mut clients = Vec::new();
     let host_id: Vec<u64> = (0..(hosts.len() as u64)).collect();
+    let local = HandshakeInfo::new(
+        HandshakeInfo::supported_features(),
+        get_var("SFS_CLUSTER_ID".to_string(), "".to_string()),
+    );
+    let mut first: Option<HandshakeInfo> = None;
 
     for id in host_id {
         let hostname = &hosts.get(id as usize).unwrap().0;
         let uri = &hosts.get(id as usize).unwrap().1;
 
-        let lookup = lookup_endpoint(uri, 1, id);
-        if let Err(_e) = lookup {
+        let lookup = lookup_endpoint(
+            uri,
+            RpcPolicy::get_instance().get_connect_retries(),
+            &local,
+            context,
+        );
+        if let Err(e) = lookup {
+            match e.kind() {
+                std::io::ErrorKind::InvalidData => {
+                    eprintln!("sfs: refusing to mount, host '{}' is incompatible: {}", hostname, e)
+                }
+                std::io::ErrorKind::PermissionDenied => {
+                    eprintln!("sfs: host '{}' rejected this client: {}", hostname, e)
+                }
+                _ => {}
+            }
             error_msg(
                 "client::init::connect_hosts".to_string(),
                 format!("can not reach host '{}' with '{}'", hostname, uri),
@@ -124,20 +208,39 @@ async fn connect_hosts(hosts: &mut Vec<(String, String)>, context: &mut StaticCo
             return 0;
         } else {
             let res = lookup.unwrap();
+            // a host file mixing servers of different clusters, or of one cluster configured apart
+            match &first {
+                Some(expected) if expected.cluster_id != res.2.cluster_id => {
+                    eprintln!(
+                        "sfs: refusing to mount, host '{}' belongs to cluster '{}' instead of '{}'",
+                        hostname, res.2.cluster_id, expected.cluster_id
+                    );
+                    return 0;
+                }
+                Some(expected) if (expected.features ^ res.2.features) & FEATURE_CHECKSUM != 0 => {
+                    eprintln!(
+                        "sfs: refusing to mount, host '{}' does not agree on checksums with host '{}'",
+                        hostname, hosts[0].0
+                    );
+                    return 0;
+                }
+                Some(_) => {}
+                None => {
+                    context.set_checksum(res.2.features & FEATURE_CHECKSUM != 0);
+                    first = Some(res.2.clone());
+                }
+            }
             addrs.push(res.0);
             clients.push(res.1);
 
-            let mut cm_ids = Vec::new();
-            for _i in 0..CLIENT_CM_IDS{
-                cm_ids.push(Mutex::new(new_write_cm_id(context.event_channel as *mut rdma_event_channel, uri).await));
-            }
-            context.write_cm_ids.insert(id, cm_ids);
-            
-            let mut cm_ids = Vec::new();
-            for _i in 0..CLIENT_CM_IDS{
-                cm_ids.push(Mutex::new(new_read_cm_id(context.event_channel as *mut rdma_event_channel, uri).await));
-            }
-            context.read_cm_ids.insert(id, cm_ids);
+            let (write_ids, read_ids) = create_cm_ids(
+                context.event_channel,
+                uri,
+                IoService::get_instance().get_cm_ids_per_host(),
+                context.get_token(),
+            );
+            context.write_cm_ids.insert(id, write_ids.into_iter().map(tokio::sync::Mutex::new).collect());
+            context.read_cm_ids.insert(id, read_ids.into_iter().map(tokio::sync::Mutex::new).collect());
 
         }
         if !local_host_found && hostname.eq(&local_hostname) {
@@ -167,6 +270,29 @@ fn read_host_file() -> Vec<(String, String)> {
     let hosts = load_res.unwrap();
     return hosts;
 }
+// TLS and the token of the job come from the environment, nothing set means plain text
+fn load_credentials(context: &mut StaticContext) -> bool {
+    let tls = TlsConfig::load(
+        &get_var("SFS_TLS_CA".to_string(), "".to_string()),
+        &get_var("SFS_TLS_CERT".to_string(), "".to_string()),
+        &get_var("SFS_TLS_KEY".to_string(), "".to_string()),
+    );
+    let token = load_token(
+        std::env::var("SFS_TOKEN").ok(),
+        std::env::var("SFS_TOKEN_FILE").ok(),
+    );
+    match (tls, token) {
+        (Ok(tls), Ok(token)) => {
+            context.set_tls(tls);
+            context.set_token(token);
+            true
+        }
+        (Err(e), _) | (_, Err(e)) => {
+            eprintln!("sfs: fail to load credentials: {}", e);
+            false
+        }
+    }
+}
 pub fn init_environment() -> StaticContext {
     DynamicContext::get_instance();
     let mut hosts = read_host_file();
@@ -178,6 +304,10 @@ pub fn init_environment() -> StaticContext {
     context.event_channel = unsafe{rdma_create_event_channel() as u64};
     let ec = context.event_channel;
     context.handle = Some(std::thread::spawn(move || {process_cm_event(ec)}));
+    // without interception the job would write to local paths unnoticed, it must not go on
+    if !load_credentials(&mut context) {
+        std::process::exit(1);
+    }
 
     let host_len = connect_hosts(&mut hosts, &mut context);
     if host_len == 0 {
@@ -193,8 +323,18 @@ pub fn init_environment() -> StaticContext {
             "fail to fetch fs config".to_string(),
         );
     }
+    // 0 stands for no client in lease requests
+    let client_id = xxh3_64(format!("{}:{}", get_hostname(true), std::process::id()).as_bytes());
+    context.set_client_id(u64::max(client_id, 1));
+    let replicas = context.get_fsconfig().replicas;
+    if replicas > 1 {
+        context.set_distributor(SimpleHashDistributor::with_replicas(host_id, host_len, replicas));
+    }
 
     context.init_flag = true;
+    unsafe {
+        libc::atexit(commit_at_exit);
+    }
 
     return context;
 }
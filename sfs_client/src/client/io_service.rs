@@ -0,0 +1,98 @@
+use lazy_static::*;
+use sfs_global::global::network::config::CLIENT_CM_IDS;
+use sfs_global::global::util::env_util::get_var;
+use tokio::runtime::Builder;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+use tokio::task::LocalSet;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::sync_channel;
+use std::thread;
+
+use super::config::IO_THREADS;
+
+type Task = Box<dyn FnOnce() -> Pin<Box<dyn Future<Output = ()>>> + Send>;
+
+// network I/O of every application thread runs here. a syscall hands its operation over the
+// submission queue of a worker and sleeps until the result is back, the workers keep any number
+// of operations in flight, so threads talking to different hosts don't wait for each other.
+// operations are built on the worker, they may hold raw pointers and RDMA handles
+pub struct IoService {
+    workers_: Vec<UnboundedSender<Task>>,
+    next_: AtomicUsize,
+    cm_ids_per_host_: usize,
+}
+fn spawn_worker(id: usize) -> UnboundedSender<Task> {
+    let (tx, mut rx) = unbounded_channel::<Task>();
+    thread::Builder::new()
+        .name(format!("sfs-io-{}", id))
+        .stack_size(12 * 1024 * 1024)
+        .spawn(move || {
+            let runtime = Builder::new_current_thread().enable_all().build().unwrap();
+            let local = LocalSet::new();
+            local.block_on(&runtime, async move {
+                while let Some(task) = rx.recv().await {
+                    tokio::task::spawn_local(task());
+                }
+            });
+        })
+        .unwrap();
+    tx
+}
+lazy_static! {
+    static ref IO_SERVICE: IoService = {
+        let threads = get_var("SFS_IO_THREADS".to_string(), IO_THREADS.to_string())
+            .parse::<usize>()
+            .unwrap_or(IO_THREADS);
+        IoService {
+            workers_: (0..usize::max(threads, 1)).map(spawn_worker).collect(),
+            next_: AtomicUsize::new(0),
+            cm_ids_per_host_: get_var(
+                "SFS_CM_IDS_PER_HOST".to_string(),
+                CLIENT_CM_IDS.to_string(),
+            )
+            .parse::<usize>()
+            .unwrap_or(CLIENT_CM_IDS),
+        }
+    };
+}
+impl IoService {
+    pub fn get_instance() -> &'static IoService {
+        &IO_SERVICE
+    }
+    // connections kept to every host for chunk transfers
+    pub fn get_cm_ids_per_host(&self) -> usize {
+        usize::max(self.cm_ids_per_host_, 1)
+    }
+    // runs the operation built by 'f' on a worker and waits for its result.
+    // must not be called from a worker, that one would wait for itself
+    pub fn submit<F, Fut>(&self, f: F) -> Fut::Output
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future + 'static,
+        Fut::Output: Send + 'static,
+    {
+        let (tx, rx) = sync_channel(1);
+        let task: Task = Box::new(move || -> Pin<Box<dyn Future<Output = ()>>> {
+            Box::pin(async move {
+                let _ = tx.send(f().await);
+            })
+        });
+        let worker = self.next_.fetch_add(1, Ordering::Relaxed) % self.workers_.len();
+        if let Err(_e) = self.workers_[worker].send(task) {
+            panic!("client::io_service: worker {} is gone", worker);
+        }
+        rx.recv().unwrap()
+    }
+}
+// runs the synchronous calls of 'f' off the worker: a worker blocked in a call or a backoff
+// holds up every other operation it has in flight
+pub async fn off_worker<F, R>(f: F) -> R
+where
+    F: FnOnce() -> R + Send + 'static,
+    R: Send + 'static,
+{
+    tokio::task::spawn_blocking(f).await.unwrap()
+}
@@ -1,9 +1,15 @@
+pub mod attr_cache;
+pub mod chunk_cache;
 pub mod config;
 pub mod context;
 pub mod init;
+pub mod io_service;
 pub mod openfile;
 pub mod path;
+pub mod pending_size;
+pub mod placement_cache;
 pub mod syscall;
 pub mod util;
+pub mod write_buffer;
 
 pub mod network;
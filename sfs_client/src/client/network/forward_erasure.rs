@@ -0,0 +1,251 @@
+use std::collections::HashMap;
+
+use libc::{c_char, EDQUOT, EIO, ENOSPC};
+use sfs_global::global::distributor::Distributor;
+use sfs_global::global::erasure::{erasure_layout, ErasureLayout, ReedSolomon};
+use sfs_global::global::error_msg::error_msg;
+use sfs_global::global::metadata::Metadata;
+use sfs_global::global::network::config::CHUNK_SIZE;
+use sfs_global::global::network::forward_data::{EncodeStripeData, ReadChunkData};
+use sfs_global::global::network::post::PostOption;
+use sfs_global::global::util::arith_util::offset_to_chunk_id;
+use sfs_rdma::transfer::ChunkMetadata;
+use sfs_rpc::grpc_errno;
+
+use crate::client::context::StaticContext;
+use crate::client::io_service::off_worker;
+
+use super::forward_msg::{forward_stat, read_round, write_round};
+use super::network_service::NetworkService;
+
+// erasure-coded layout of a path, None if the path is replicated
+pub fn get_layout(path: &String) -> Option<ErasureLayout> {
+    erasure_layout(&StaticContext::get_instance().get_fsconfig().erasure_dirs, path)
+}
+// hosts holding the pieces of a stripe, data pieces come first
+pub fn stripe_hosts(path: &String, layout: &ErasureLayout, stripe_id: u64) -> Vec<u64> {
+    StaticContext::get_instance()
+        .get_distributor()
+        .locate_stripe(path, stripe_id, layout.width())
+}
+fn data_host(path: &String, layout: &ErasureLayout, chunk_id: u64) -> u64 {
+    stripe_hosts(path, layout, layout.stripe_of(chunk_id))[(chunk_id % layout.data) as usize]
+}
+fn fetch_piece(host: u64, path: &String, file_id: u64, chunk_id: u64) -> Result<Vec<u8>, i32> {
+    let post_res = NetworkService::post::<ReadChunkData>(
+        host,
+        ReadChunkData {
+            path: path.as_str(),
+            file_id,
+            chunk_id,
+        },
+        PostOption::ReadChunk,
+    );
+    if let Err(e) = post_res {
+        return Err(grpc_errno(&e));
+    }
+    let result = post_res.unwrap();
+    if result.err != 0 {
+        return Err(result.err);
+    }
+    let mut piece = result.extra;
+    piece.resize(CHUNK_SIZE as usize, 0);
+    Ok(piece)
+}
+// range of 'chunk_id' covered by the request as (offset in chunk, offset in buffer, length)
+fn chunk_range(chunk_id: u64, offset: i64, size: i64) -> (u64, u64, u64) {
+    let chunk_begin = chunk_id * CHUNK_SIZE;
+    let begin = u64::max(chunk_begin, offset as u64);
+    let end = u64::min(chunk_begin + CHUNK_SIZE, (offset + size) as u64);
+    (begin - chunk_begin, begin - offset as u64, end - begin)
+}
+// let the first parity host of a stripe recompute and store its parity pieces. it updates one
+// stripe at a time, so concurrent writes to a stripe do not lose each other's parity
+pub fn encode_stripe(path: &String, file_id: u64, layout: &ErasureLayout, stripe_id: u64) -> i32 {
+    if layout.parity == 0 {
+        return 0;
+    }
+    let host = stripe_hosts(path, layout, stripe_id)[layout.data as usize];
+    let post_res = NetworkService::post::<EncodeStripeData>(
+        host,
+        EncodeStripeData {
+            path: path.as_str(),
+            file_id,
+            stripe_id,
+        },
+        PostOption::EncodeStripe,
+    );
+    let err = match post_res {
+        Ok(result) => result.err,
+        Err(e) => grpc_errno(&e),
+    };
+    if err != 0 {
+        error_msg(
+            "client::forward_erasure::encode_stripe".to_string(),
+            format!("fail to update parity of stripe {} of '{}' on host {}", stripe_id, path, host),
+        );
+    }
+    err
+}
+// rebuild a lost data chunk from any k pieces of its stripe
+fn reconstruct_chunk(
+    path: &String,
+    file_id: u64,
+    layout: &ErasureLayout,
+    chunk_id: u64,
+) -> Result<Vec<u8>, i32> {
+    let stripe_id = layout.stripe_of(chunk_id);
+    let hosts = stripe_hosts(path, layout, stripe_id);
+    let mut pieces: Vec<Option<Vec<u8>>> = vec![None; layout.width() as usize];
+    let mut found = 0;
+    for piece in 0..layout.width() {
+        if found == layout.data {
+            break;
+        }
+        let piece_id = layout.piece_chunk_id(stripe_id, piece);
+        if piece_id == chunk_id {
+            continue;
+        }
+        if let Ok(buf) = fetch_piece(hosts[piece as usize], path, file_id, piece_id) {
+            pieces[piece as usize] = Some(buf);
+            found += 1;
+        }
+    }
+    ReedSolomon::from_layout(layout).reconstruct(&mut pieces)?;
+    Ok(pieces[(chunk_id % layout.data) as usize].take().unwrap())
+}
+// data chunks go through RDMA like the replicated layout, parity is updated afterwards.
+// every host of the touched stripes has to be reachable for the write to succeed
+pub async fn forward_write_erasure(
+    path: &String,
+    file_id: u64,
+    layout: &ErasureLayout,
+    buf: *const c_char,
+    offset: i64,
+    write_size: i64,
+) -> (i32, i64) {
+    let chunk_start = offset_to_chunk_id(offset, CHUNK_SIZE);
+    let chunk_end = offset_to_chunk_id(offset + write_size - 1, CHUNK_SIZE);
+    let mut target_chunks: HashMap<u64, Vec<u64>> = HashMap::new();
+    let mut targets: Vec<u64> = Vec::new();
+    for chunk_id in chunk_start..(chunk_end + 1) {
+        let target = data_host(path, layout, chunk_id);
+        if !target_chunks.contains_key(&target) {
+            target_chunks.insert(target, Vec::new());
+            targets.push(target);
+        }
+        target_chunks.get_mut(&target).unwrap().push(chunk_id);
+    }
+    let metadata = ChunkMetadata {
+        path: path.to_string(),
+        file_id,
+        chunk_start: chunk_start as u64,
+        offset: offset as u64 % CHUNK_SIZE,
+        size: write_size as u64,
+    };
+    for (_target, _chunk_ids, res) in write_round(buf, metadata, targets, target_chunks).await {
+        // the pieces of a stripe are not placed elsewhere, a full host fails the write
+        if let Err(e) = res {
+            return (if e == EDQUOT { ENOSPC } else { e }, 0);
+        }
+    }
+
+    // a stripe whose parity is not stored on every parity host is encoded again later by its
+    // first parity host, the write fails meanwhile
+    let stripe_start = layout.stripe_of(chunk_start);
+    let stripe_end = layout.stripe_of(chunk_end);
+    for stripe_id in stripe_start..(stripe_end + 1) {
+        let (stripe_path, stripe_layout) = (path.clone(), *layout);
+        let err = off_worker(move || {
+            encode_stripe(&stripe_path, file_id, &stripe_layout, stripe_id)
+        })
+        .await;
+        if err != 0 {
+            return (err, 0);
+        }
+    }
+    return (0, write_size);
+}
+// chunks on unreachable hosts are rebuilt from the rest of their stripe
+pub async fn forward_read_erasure(
+    path: &String,
+    file_id: u64,
+    layout: &ErasureLayout,
+    buf: *mut c_char,
+    offset: i64,
+    read_size: i64,
+) -> (i32, u64) {
+    let chunk_start = offset_to_chunk_id(offset, CHUNK_SIZE);
+    let chunk_end = offset_to_chunk_id(offset + read_size - 1, CHUNK_SIZE);
+    let mut target_chunks: HashMap<u64, Vec<u64>> = HashMap::new();
+    let mut targets: Vec<u64> = Vec::new();
+    for chunk_id in chunk_start..(chunk_end + 1) {
+        let target = data_host(path, layout, chunk_id);
+        if !target_chunks.contains_key(&target) {
+            target_chunks.insert(target, Vec::new());
+            targets.push(target);
+        }
+        target_chunks.get_mut(&target).unwrap().push(chunk_id);
+    }
+    let metadata = ChunkMetadata {
+        path: path.to_string(),
+        file_id,
+        chunk_start: chunk_start as u64,
+        offset: offset as u64 % CHUNK_SIZE,
+        size: read_size as u64,
+    };
+    let mut tot_read = 0;
+    let mut lost = Vec::new();
+    // pieces their hosts do not hold are holes, erasure-coded chunks are never placed elsewhere
+    for (chunk_ids, res, _missing) in read_round(buf, metadata, targets, target_chunks).await {
+        match res {
+            Ok(len) => tot_read += len as u64,
+            Err(_e) => lost.extend(chunk_ids),
+        }
+    }
+    if lost.len() == 0 {
+        return (0, tot_read);
+    }
+
+    // rebuilt chunks are zero padded, so the file size bounds what is returned
+    let (lost_path, lost_layout) = (path.clone(), *layout);
+    let rebuilt = off_worker(move || -> Result<(u64, Vec<(u64, Vec<u8>)>), i32> {
+        let file_size = Metadata::deserialize(&forward_stat(&lost_path)?).get_size() as u64;
+        let mut chunks = Vec::new();
+        for chunk_id in lost {
+            match reconstruct_chunk(&lost_path, file_id, &lost_layout, chunk_id) {
+                Ok(chunk) => chunks.push((chunk_id, chunk)),
+                Err(_e) => {
+                    error_msg(
+                        "client::forward_erasure::forward_read_erasure".to_string(),
+                        format!("more than {} pieces of chunk {} are lost", lost_layout.parity, chunk_id),
+                    );
+                    return Err(EIO);
+                }
+            }
+        }
+        Ok((file_size, chunks))
+    })
+    .await;
+    let (file_size, chunks) = match rebuilt {
+        Ok(rebuilt) => rebuilt,
+        Err(e) => return (e, 0),
+    };
+    for (chunk_id, chunk) in chunks {
+        let (in_chunk, in_buf, len) = chunk_range(chunk_id, offset, read_size);
+        let chunk_begin = chunk_id * CHUNK_SIZE;
+        if chunk_begin + in_chunk >= file_size {
+            continue;
+        }
+        let len = u64::min(len, file_size - chunk_begin - in_chunk);
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                chunk.as_ptr().add(in_chunk as usize),
+                (buf as *mut u8).add(in_buf as usize),
+                len as usize,
+            );
+        }
+        tot_read += len;
+    }
+    return (0, tot_read);
+}
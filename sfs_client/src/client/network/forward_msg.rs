@@ -1,181 +1,350 @@
 use std::collections::HashMap;
 #[allow(unused)]
 use std::slice;
-use std::sync::{Arc, Mutex};
-#[allow(unused)]
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
+use futures::future::join_all;
 use futures::{TryStreamExt};
 use grpcio::Error;
-use libc::{c_char, EBUSY};
+use libc::{c_char, EBUSY, EDQUOT, EEXIST, EIO, ENOSPC};
 use rdma_sys::rdma_cm_id;
-use sfs_global::global::util::serde_util::{deserialize, serialize};
-use sfs_rdma::chunk_operation::ChunkOp;
+use sfs_global::global::util::serde_util::{deserialize, serialize, try_deserialize};
+use sfs_rdma::chunk_operation::{chunk_piece, ChunkOp};
 use sfs_rdma::transfer::{ChunkTransferTask, ChunkMetadata};
-use sfs_rpc::post;
+use sfs_rpc::proto::server::{
+    Attr, CreateRequest, DecreaseSizeRequest, DirentRequest, GetDirentsRequest, PathRequest,
+    PostResult, SizeResponse, StatRequest, StatusResponse, UpdateSizeRequest,
+};
+use sfs_rpc::proto::server_grpc::SfsHandleClient;
+use sfs_rpc::{grpc_errno, post, status_errno};
 
+use crate::client::attr_cache::AttrCache;
+use crate::client::chunk_cache::ChunkCache;
 #[allow(unused)]
 use crate::client::context::{DynamicContext, StaticContext};
-use crate::client::openfile::{FileType, OpenFile, O_RDONLY};
+use crate::client::io_service::off_worker;
+use crate::client::openfile::FileType;
+use crate::client::placement_cache::PlacementCache;
 use sfs_global::global::distributor::Distributor;
+use sfs_global::global::erasure::check_erasure_dirs;
 use sfs_global::global::error_msg::error_msg;
-use sfs_global::global::fsconfig::SFSConfig;
+use sfs_global::global::fsconfig::{Consistency, SFSConfig};
+use sfs_global::global::metadata::{DirentAttr, Metadata, S_ISDIR};
 use sfs_global::global::network::config::CHUNK_SIZE;
 use sfs_global::global::network::forward_data::{
-    ChunkStat, CreateData, DecrData, DirentData, TruncData,
-    UpdateMetadentryData, //WriteData,
+    ChunkStat, LinkChunksData, PlaceData, RemoveData, SizeBatchData, TruncData, //WriteData,
 };
 use sfs_global::global::network::post::{option2i, PostOption};
+use sfs_global::global::placement::Placement;
 use sfs_global::global::util::arith_util::{
     block_index, offset_to_chunk_id,
 };
+use sfs_global::global::util::checksum_util::chunk_digest;
+use sfs_global::global::util::path_util::dirname;
 
+use super::forward_erasure::{
+    encode_stripe, forward_read_erasure, forward_write_erasure, get_layout, stripe_hosts,
+};
 use super::network_service::NetworkService;
-use super::rdma_read::recver_client_on_id;
+use super::rpc_policy::RpcPolicy;
+use crate::client::util::child_path;
+use super::rdma_read::{missing_chunks, recver_client_on_id};
 use super::rdma_write::sender_client_on_id;
 
-pub fn forward_stat(path: &String) -> Result<Vec<u8>, i32> {
-    let endp_id = StaticContext::get_instance()
+fn write_quorum(replicas: usize) -> usize {
+    let quorum = StaticContext::get_instance().get_fsconfig().write_quorum as usize;
+    if quorum == 0 || quorum > replicas {
+        replicas
+    } else {
+        quorum
+    }
+}
+// send the same request to every replica, succeed once the write quorum acknowledged it
+fn post_replicated<F>(hosts: &Vec<u64>, idempotent: bool, call: F) -> Result<(), i32>
+where
+    F: Fn(&SfsHandleClient) -> Result<StatusResponse, Error>,
+{
+    let quorum = write_quorum(hosts.len());
+    let mut acks = 0;
+    let mut err = EIO;
+    for host in hosts.iter() {
+        let res = NetworkService::call_change(*host, idempotent, &call, |response| {
+            status_errno(response.get_status())
+        });
+        match res {
+            Ok(response) => {
+                let status_err = status_errno(response.get_status());
+                if status_err != 0 {
+                    err = status_err;
+                    continue;
+                }
+                acks += 1;
+            }
+            Err(e) => {
+                error_msg(
+                    "client::network::post_replicated".to_string(),
+                    format!("error {} occurs on replica {}", e, host),
+                );
+                err = grpc_errno(&e);
+            }
+        }
+    }
+    if acks < quorum {
+        return Err(err);
+    }
+    Ok(())
+}
+// try replicas in order until one of them answers, only for reads
+fn post_failover<R, F>(hosts: &Vec<u64>, call: F) -> Result<R, Error>
+where
+    F: Fn(&SfsHandleClient) -> Result<R, Error>,
+{
+    let mut last_err = Error::RemoteStopped;
+    for host in hosts.iter() {
+        match NetworkService::call(*host, true, &call) {
+            Ok(result) => return Ok(result),
+            Err(e) => {
+                error_msg(
+                    "client::network::post_failover".to_string(),
+                    format!("error {} occurs on replica {}, trying next one", e, host),
+                );
+                last_err = e;
+            }
+        }
+    }
+    Err(last_err)
+}
+// every host of a fan-out is reported, the first error is the one returned
+fn fanout_errno(results: &Vec<(u64, Result<PostResult, Error>)>, caller: &str) -> i32 {
+    let mut err = 0;
+    for (host, res) in results.iter() {
+        let host_err = match res {
+            Ok(result) => result.err,
+            Err(e) => {
+                error_msg(caller.to_string(), format!("error {} occurs on host {}", e, host));
+                grpc_errno(e)
+            }
+        };
+        if err == 0 {
+            err = host_err;
+        }
+    }
+    err
+}
+fn attr_from_proto(attr: &Attr) -> DirentAttr {
+    DirentAttr {
+        mode: attr.mode,
+        size: attr.size,
+        access_time: attr.access_time,
+        modify_time: attr.modify_time,
+        change_time: attr.change_time,
+        link_count: attr.link_count,
+        blocks: attr.blocks,
+        file_id: attr.file_id,
+    }
+}
+// every host that may keep chunk files of 'chunk_id', including parity pieces of its stripe
+fn data_hosts(path: &String, chunk_id: u64) -> Vec<u64> {
+    if let Some(layout) = get_layout(path) {
+        return stripe_hosts(path, &layout, layout.stripe_of(chunk_id));
+    }
+    let distributor = StaticContext::get_instance().get_distributor();
+    PlacementCache::get_instance()
+        .get(path)
+        .locate(distributor.as_ref(), path, chunk_id)
+}
+// metadata of 'path' and the lease granted on it in milliseconds, client 0 asks for no lease
+fn stat(path: &String, client: u64) -> Result<(Vec<u8>, u64), i32> {
+    let replicas = StaticContext::get_instance()
         .get_distributor()
-        .locate_file_metadata(path);
-    let post_res = NetworkService::post::<&str>(
-        StaticContext::get_instance()
-            .get_clients()
-            .get(endp_id as usize)
-            .unwrap(),
-        path.as_str(),
-        PostOption::Stat,
-    );
-    if let Err(e) = post_res {
+        .locate_file_metadata_replicas(path);
+    let mut req = StatRequest::default();
+    req.set_path(path.clone());
+    req.set_client(client);
+    let stat_res = post_failover(&replicas, |host| host.stat_opt(&req, NetworkService::call_option()));
+    if let Err(e) = stat_res {
         error_msg(
             "client::network::forward_stat".to_string(),
             format!("error {} occurs while fetching file stat", e),
         );
-        return Err(EBUSY);
+        return Err(grpc_errno(&e));
     }
-    let result = post_res.unwrap();
-    if result.err != 0 {
-        return Err(result.err);
+    let mut response = stat_res.unwrap();
+    let err = status_errno(response.get_status());
+    if err != 0 {
+        return Err(err);
+    }
+    // every stat brings the placement of the file up to date
+    let placement = match try_deserialize::<Placement>(&response.placement) {
+        Ok(placement) if response.placement.len() != 0 => placement,
+        _ => Placement::new(),
+    };
+    PlacementCache::get_instance().set(path, placement);
+    let mut md = Metadata::from_attr(&attr_from_proto(response.get_attr()));
+    if response.stuffed {
+        md.set_inline_data(response.take_data());
+    } else {
+        md.unstuff();
     }
-    return Ok(result.data);
+    return Ok((md.serialize(), response.lease_ms));
+}
+pub fn forward_stat(path: &String) -> Result<Vec<u8>, i32> {
+    return stat(path, 0).map(|(md, _)| md);
 }
-pub fn forward_create(path: &String, mode: u32) -> Result<i32, Error> {
-    let endp_id = StaticContext::get_instance()
+pub fn forward_stat_lease(path: &String) -> Result<(Vec<u8>, u64), i32> {
+    return stat(path, StaticContext::get_instance().get_client_id());
+}
+// the first metadata replica to take the file hands out its file id, the others are sent the
+// same one. returns (errno, file id)
+pub fn forward_create(path: &String, mode: u32) -> Result<(i32, u64), Error> {
+    AttrCache::get_instance().remove(path);
+    let replicas = StaticContext::get_instance()
         .get_distributor()
-        .locate_file_metadata(path);
-    let post_res = NetworkService::post::<CreateData>(
-        StaticContext::get_instance()
-            .get_clients()
-            .get(endp_id as usize)
-            .unwrap(),
-        CreateData {
-            path: path.as_str(),
-            mode: mode,
-        },
-        PostOption::Create,
-    );
+        .locate_file_metadata_replicas(path);
+    let mut req = CreateRequest::default();
+    req.set_path(path.clone());
+    req.set_mode(mode);
+    req.set_client(StaticContext::get_instance().get_client_id());
+    let quorum = write_quorum(replicas.len());
+    let mut acks = 0;
+    let mut err = EIO;
+    for host in replicas.iter() {
+        let res = NetworkService::call_change(
+            *host,
+            false,
+            |client| client.create_opt(&req, NetworkService::call_option()),
+            |response| status_errno(response.get_status()),
+        );
+        match res {
+            Ok(response) => {
+                let status_err = status_errno(response.get_status());
+                if status_err != 0 {
+                    err = status_err;
+                    continue;
+                }
+                if req.get_file_id() == 0 {
+                    req.set_file_id(response.get_file_id());
+                }
+                acks += 1;
+            }
+            Err(e) => {
+                error_msg(
+                    "client::network::forward_create".to_string(),
+                    format!("error {} occurs on replica {}", e, host),
+                );
+                err = grpc_errno(&e);
+            }
+        }
+    }
+    if acks < quorum {
+        if err != EEXIST {
+            error_msg(
+                "client::network::forward_create".to_string(),
+                format!("error {} occurs while creating file", err),
+            );
+        }
+        return Ok((err, 0));
+    }
+    return Ok((forward_dirent(path, S_ISDIR(mode), true), req.get_file_id()));
+}
+// add or remove the entry of 'path' in its parent directory
+fn forward_dirent(path: &String, is_dir: bool, add: bool) -> i32 {
+    let dir = dirname(path);
+    let name = path[path.rfind('/').unwrap() + 1..].to_string();
+    let replicas = StaticContext::get_instance()
+        .get_distributor()
+        .locate_file_metadata_replicas(&dir);
+    let mut req = DirentRequest::default();
+    req.set_dir(dir.clone());
+    req.set_name(name);
+    req.set_is_dir(is_dir);
+    let post_res = post_replicated(&replicas, true, |host| {
+        if add {
+            host.add_dirent_opt(&req, NetworkService::call_option())
+        } else {
+            host.remove_dirent_opt(&req, NetworkService::call_option())
+        }
+    });
     if let Err(e) = post_res {
         error_msg(
-            "client::network::forward_create".to_string(),
-            format!("error {} occurs while fetching file stat", e),
+            "client::network::forward_dirent".to_string(),
+            format!("error {} occurs while updating entries of '{}'", e, dir),
         );
-        return Ok(EBUSY);
-    } else {
-        let result = post_res.unwrap();
-        if result.err != 0 {
-            return Ok(result.err);
-        }
-        return Ok(0);
+        return e;
     }
+    return 0;
 }
 pub fn forward_remove(
     path: &String,
+    file_id: u64,
     remove_metadentry_only: bool,
     size: i64,
 ) -> Result<i32, Error> {
-    let endp_id = StaticContext::get_instance()
+    AttrCache::get_instance().remove(path);
+    ChunkCache::get_instance().invalidate_path(path);
+    let replicas = StaticContext::get_instance()
         .get_distributor()
-        .locate_file_metadata(path);
-    let _post_res = NetworkService::post::<&str>(
-        StaticContext::get_instance()
-            .get_clients()
-            .get(endp_id as usize)
-            .unwrap(),
-        path.as_str(),
-        PostOption::RemoveMeta,
-    )?;
+        .locate_file_metadata_replicas(path);
+    let mut req = PathRequest::default();
+    req.set_path(path.clone());
+    req.set_client(StaticContext::get_instance().get_client_id());
+    let post_res = post_replicated(&replicas, false, |host| {
+        host.remove_metadata_opt(&req, NetworkService::call_option())
+    });
+    // the chunks stay as long as the metadata does
+    if let Err(e) = post_res {
+        error_msg(
+            "client::network::forward_remove".to_string(),
+            format!("error {} occurs while removing metadata", e),
+        );
+        return Ok(e);
+    }
+    forward_dirent(path, false, false);
     if remove_metadentry_only {
         return Ok(0);
     }
+    let remove = serialize(RemoveData {
+        path: path.as_str(),
+        file_id,
+    });
     let mut posts = Vec::new();
     if (size / CHUNK_SIZE as i64) < StaticContext::get_instance().get_hosts().len() as i64 {
-        let meta_host_id = StaticContext::get_instance()
+        let mut hosts = StaticContext::get_instance()
             .get_distributor()
-            .locate_file_metadata(path);
+            .locate_file_metadata_replicas(path);
 
         let chunk_start = 0;
         let chunk_end = size as u64 / CHUNK_SIZE;
-        posts.push((
-            StaticContext::get_instance()
-                .get_clients()
-                .get(meta_host_id as usize)
-                .unwrap(),
-            post(
-                option2i(&PostOption::Remove),
-                serialize(path.as_str()),
-                vec![0; 0],
-            ),
-        ));
-
         for chunk_id in chunk_start..(chunk_end + 1) {
-            let chunk_host_id = StaticContext::get_instance()
-                .get_distributor()
-                .locate_data(path, chunk_id);
-            if chunk_host_id == meta_host_id {
-                continue;
+            for chunk_host_id in data_hosts(path, chunk_id) {
+                if !hosts.contains(&chunk_host_id) {
+                    hosts.push(chunk_host_id);
+                }
             }
+        }
+        for host_id in hosts {
             posts.push((
-                StaticContext::get_instance()
-                    .get_clients()
-                    .get(chunk_host_id as usize)
-                    .unwrap(),
-                post(
-                    option2i(&PostOption::Remove),
-                    serialize(path.as_str()),
-                    vec![0; 0],
-                ),
+                host_id,
+                post(option2i(&PostOption::Remove), remove.clone(), vec![0; 0]),
             ));
         }
     } else {
-        for client in StaticContext::get_instance().get_clients().iter() {
+        for host_id in 0..StaticContext::get_instance().get_clients().len() {
             posts.push((
-                client,
-                post(
-                    option2i(&PostOption::Remove),
-                    serialize(path.as_str()),
-                    vec![0; 0],
-                ),
+                host_id as u64,
+                post(option2i(&PostOption::Remove), remove.clone(), vec![0; 0]),
             ));
         }
     }
+    PlacementCache::get_instance().set(path, Placement::new());
     let post_results = NetworkService::group_post(posts);
-    if let Err(e) = post_results {
-        return Err(e);
-    } else {
-        let result_vec = post_results.unwrap();
-        for result in result_vec {
-            if result.err != 0 {
-                return Ok(result.err);
-            }
-        }
-    }
-    Ok(0)
+    Ok(fanout_errno(&post_results, "client::network::forward_remove"))
 }
 pub fn forward_get_chunk_stat() -> (i32, ChunkStat) {
     let mut posts = Vec::new();
-    for client in StaticContext::get_instance().get_clients().iter() {
+    for host_id in 0..StaticContext::get_instance().get_clients().len() {
         posts.push((
-            client,
+            host_id as u64,
             post(
                 option2i(&PostOption::ChunkStat),
                 "0".as_bytes().to_vec(),
@@ -186,20 +355,40 @@ pub fn forward_get_chunk_stat() -> (i32, ChunkStat) {
     let chunk_size = CHUNK_SIZE;
     let mut chunk_total = 0;
     let mut chunk_free = 0;
-    let post_results = NetworkService::group_post(posts);
-    if let Err(_e) = post_results {
-        return (-1, ChunkStat::new());
-    } else {
-        let result_vec = post_results.unwrap();
-        for result in result_vec {
-            if result.err != 0 {
-                return (result.err, ChunkStat::new());
+    let mut tiers: Vec<(u64, u64)> = Vec::new();
+    // hosts that do not answer are left out of the sum, only all of them failing is an error
+    let mut answered = 0;
+    let mut err = EIO;
+    for (host, res) in NetworkService::group_post(posts) {
+        let result = match res {
+            Ok(result) if result.err == 0 => result,
+            res => {
+                err = match res {
+                    Ok(result) => result.err,
+                    Err(e) => grpc_errno(&e),
+                };
+                error_msg(
+                    "client::network::forward_get_chunk_stat".to_string(),
+                    format!("no chunk stat from host {}, error {}", host, err),
+                );
+                continue;
+            }
+        };
+        let chunk_stat: ChunkStat = deserialize::<ChunkStat>(&result.data);
+        assert_eq!(chunk_stat.chunk_size, chunk_size);
+        chunk_total += chunk_stat.chunk_total;
+        chunk_free += chunk_stat.chunk_free;
+        for (i, (total, free)) in chunk_stat.tiers.into_iter().enumerate() {
+            if tiers.len() <= i {
+                tiers.push((0, 0));
             }
-            let chunk_stat: ChunkStat = deserialize::<ChunkStat>(&result.data);
-            assert_eq!(chunk_stat.chunk_size, chunk_size);
-            chunk_total += chunk_stat.chunk_total;
-            chunk_free += chunk_stat.chunk_free;
+            tiers[i].0 += total;
+            tiers[i].1 += free;
         }
+        answered += 1;
+    }
+    if answered == 0 {
+        return (err, ChunkStat::new());
     }
     (
         0,
@@ -207,58 +396,48 @@ pub fn forward_get_chunk_stat() -> (i32, ChunkStat) {
             chunk_size,
             chunk_total,
             chunk_free,
+            tiers,
         },
     )
 }
 pub fn forward_get_metadentry_size(path: &String) -> (i32, i64) {
-    let post_result = NetworkService::post::<&str>(
-        StaticContext::get_instance()
-            .get_clients()
-            .get(
-                StaticContext::get_instance()
-                    .get_distributor()
-                    .locate_file_metadata(&path) as usize,
-            )
-            .unwrap(),
-        path.as_str(),
-        PostOption::UpdateMetadentry,
-    );
-    if let Err(_e) = post_result {
-        return (-1, 0);
+    let replicas = StaticContext::get_instance()
+        .get_distributor()
+        .locate_file_metadata_replicas(&path);
+    let mut req = PathRequest::default();
+    req.set_path(path.clone());
+    let size_res = post_failover(&replicas, |host| host.get_size_opt(&req, NetworkService::call_option()));
+    if let Err(e) = size_res {
+        return (grpc_errno(&e), 0);
     } else {
-        let result = post_result.unwrap();
-        if result.err != 0 {
-            return (result.err, 0);
+        let response = size_res.unwrap();
+        let err = status_errno(response.get_status());
+        if err != 0 {
+            return (err, 0);
         }
-        return (0, deserialize::<i64>(&result.data));
+        return (0, response.size);
     }
 }
 pub fn forward_decr_size(path: &String, new_size: i64) -> i32 {
-    let host_id = StaticContext::get_instance()
+    AttrCache::get_instance().remove(path);
+    let replicas = StaticContext::get_instance()
         .get_distributor()
-        .locate_file_metadata(&path);
-    let post_result = NetworkService::post::<DecrData>(
-        StaticContext::get_instance()
-            .get_clients()
-            .get(host_id as usize)
-            .unwrap(),
-        DecrData {
-            path: path.as_str(),
-            new_size,
-        },
-        PostOption::DecrSize,
-    );
-    if let Err(_e) = post_result {
-        return -1;
-    } else {
-        let result = post_result.unwrap();
-        if result.err != 0 {
-            return result.err;
-        }
-        return 0;
+        .locate_file_metadata_replicas(&path);
+    let mut req = DecreaseSizeRequest::default();
+    req.set_path(path.clone());
+    req.set_new_size(new_size);
+    req.set_client(StaticContext::get_instance().get_client_id());
+    let post_result = post_replicated(&replicas, true, |host| {
+        host.decrease_size_opt(&req, NetworkService::call_option())
+    });
+    if let Err(e) = post_result {
+        return e;
     }
+    return 0;
 }
-pub fn forward_truncate(path: &String, old_size: i64, new_size: i64) -> i32 {
+pub fn forward_truncate(path: &String, file_id: u64, old_size: i64, new_size: i64) -> i32 {
+    AttrCache::get_instance().remove(path);
+    ChunkCache::get_instance().invalidate_path(path);
     if old_size < new_size {
         return -1;
     }
@@ -266,17 +445,17 @@ pub fn forward_truncate(path: &String, old_size: i64, new_size: i64) -> i32 {
     let chunk_end = block_index(old_size - new_size - 1, CHUNK_SIZE);
     let mut hosts: Vec<u64> = Vec::new();
     for chunk_id in chunk_start..(chunk_end + 1) {
-        let host_id = StaticContext::get_instance()
-            .get_distributor()
-            .locate_data(path, chunk_id);
-        if !hosts.contains(&host_id) {
-            hosts.push(host_id);
+        for host_id in data_hosts(path, chunk_id) {
+            if !hosts.contains(&host_id) {
+                hosts.push(host_id);
+            }
         }
     }
     let mut posts = Vec::new();
     for host in hosts {
         let trunc_data = TruncData {
             path: path.as_str(),
+            file_id,
             new_size,
         };
         let post = post(
@@ -284,27 +463,24 @@ pub fn forward_truncate(path: &String, old_size: i64, new_size: i64) -> i32 {
             serialize(&trunc_data),
             vec![0; 0],
         );
-        posts.push((
-            StaticContext::get_instance()
-                .get_clients()
-                .get(host as usize)
-                .unwrap(),
-            post,
-        ));
+        posts.push((host, post));
     }
     let post_results = NetworkService::group_post(posts);
-    if let Err(_e) = post_results {
-        return EBUSY;
+    let err = fanout_errno(&post_results, "client::network::forward_truncate");
+    if err != 0 {
+        return err;
     }
-    let results = post_results.unwrap();
-    for result in results {
-        if result.err != 0 {
-            return result.err;
+    // the stripe cut by the new size gets fresh parity
+    if let Some(layout) = get_layout(path) {
+        if new_size as u64 % (layout.data * CHUNK_SIZE) != 0 {
+            let stripe_id = layout.stripe_of(block_index(new_size, CHUNK_SIZE));
+            return encode_stripe(path, file_id, &layout, stripe_id);
         }
     }
 
     return 0;
 }
+// (-1, bytes written) if 'stuff' was kept inline by the metadata hosts, the write is done then
 pub fn forward_update_metadentry_size(
     path: &String,
     size: u64,
@@ -312,43 +488,130 @@ pub fn forward_update_metadentry_size(
     append_flag: bool,
     stuff: Vec<u8>,
 ) -> (i32, i64) {
-    let update_data = UpdateMetadentryData {
-        path: path.as_str(),
-        size,
-        offset,
-        append: append_flag,
-    };
-    let host_id = StaticContext::get_instance()
+    AttrCache::get_instance().remove(path);
+    let mut req = UpdateSizeRequest::default();
+    req.set_path(path.clone());
+    req.set_size(size);
+    req.set_offset(offset);
+    req.set_append(append_flag);
+    req.set_client(StaticContext::get_instance().get_client_id());
+    req.set_data(stuff);
+    let replicas = StaticContext::get_instance()
         .get_distributor()
-        .locate_file_metadata(&path);
-    let post_result = NetworkService::post_stuff::<UpdateMetadentryData>(
-        StaticContext::get_instance()
-            .get_clients()
-            .get(host_id as usize)
-            .unwrap(),
-        update_data,
-        stuff,
-        PostOption::UpdateMetadentry,
-    );
-    if let Err(_e) = post_result {
-        return (EBUSY, 0);
-    } else {
-        let res = post_result.unwrap();
-        // stuffing enabled and the file is stuffed
-        if res.extra.len() != 0 {
-            return (
-                if res.err != 0 { res.err } else { -1 },
-                deserialize::<u64>(&res.extra) as i64,
-            );
+        .locate_file_metadata_replicas(&path);
+    let quorum = write_quorum(replicas.len());
+    let mut acked: Option<(i32, i64)> = None;
+    let mut placement: Option<Placement> = None;
+    let mut acks = 0;
+    let mut err = EIO;
+    for host_id in replicas.iter() {
+        // appends move the end of the file once more when sent twice
+        let update_res = NetworkService::call_change(
+            *host_id,
+            !append_flag,
+            |client| client.update_size_opt(&req, NetworkService::call_option()),
+            |response: &SizeResponse| status_errno(response.get_status()),
+        )
+        .map(|response| {
+            let err = status_errno(response.get_status());
+            if err == 0 && placement.is_none() {
+                placement = Some(match try_deserialize::<Placement>(&response.placement) {
+                    Ok(placement) if response.placement.len() != 0 => placement,
+                    _ => Placement::new(),
+                });
+            }
+            if err == 0 && response.stuffed {
+                (-1, size as i64)
+            } else {
+                (err, response.size)
+            }
+        });
+        // a replica that failed the update does not count towards the quorum
+        match update_res {
+            Ok(res) if res.0 == 0 || res.0 == -1 => {
+                acks += 1;
+                if let None = acked {
+                    acked = Some(res);
+                }
+            }
+            Ok((e, _size)) => err = e,
+            Err(e) => err = grpc_errno(&e),
+        }
+    }
+    if acks < quorum {
+        return (err, 0);
+    }
+    // the replies bring the placement of the file up to date like a stat does
+    if let Some(placement) = placement {
+        PlacementCache::get_instance().set(path, placement);
+    }
+    return acked.unwrap();
+}
+// commit deferred sizes, every metadata host gets one message for all the files it keeps
+pub fn forward_update_size_batch(entries: &Vec<(String, u64)>) -> i32 {
+    let distributor = StaticContext::get_instance().get_distributor();
+    let mut host_entries: HashMap<u64, Vec<(String, u64)>> = HashMap::new();
+    let mut hosts: Vec<u64> = Vec::new();
+    let mut replicas = Vec::new();
+    for (path, size) in entries.iter() {
+        AttrCache::get_instance().remove(path);
+        let path_replicas = distributor.locate_file_metadata_replicas(path);
+        for host in path_replicas.iter() {
+            if !host_entries.contains_key(host) {
+                host_entries.insert(*host, Vec::new());
+                hosts.push(*host);
+            }
+            host_entries.get_mut(host).unwrap().push((path.clone(), *size));
         }
-        return (
-            if res.err != 0 { res.err } else { 0 },
-            deserialize::<i64>(&res.data),
+        replicas.push(path_replicas);
+    }
+    let mut failed: Vec<u64> = Vec::new();
+    let mut err = EIO;
+    for host in hosts {
+        let post = post(
+            option2i(&PostOption::UpdateSizeBatch),
+            serialize(&SizeBatchData {
+                entries: host_entries.remove(&host).unwrap(),
+                client: StaticContext::get_instance().get_client_id(),
+            }),
+            vec![0; 0],
+        );
+        // the sizes are absolute, the whole batch is sent again when it is turned away
+        let post_res = NetworkService::call_change(
+            host,
+            true,
+            |client| client.handle_opt(&post, NetworkService::call_option()),
+            |result: &PostResult| result.err,
         );
+        match post_res {
+            Ok(result) if result.err == 0 => {}
+            res => {
+                err = match res {
+                    Ok(result) => result.err,
+                    Err(e) => grpc_errno(&e),
+                };
+                error_msg(
+                    "client::network::forward_update_size_batch".to_string(),
+                    format!("fail to update sizes on host {}", host),
+                );
+                failed.push(host);
+            }
+        }
     }
+    for path_replicas in replicas {
+        let acks = path_replicas
+            .iter()
+            .filter(|host| !failed.contains(host))
+            .count();
+        if acks < write_quorum(path_replicas.len()) {
+            return err;
+        }
+    }
+    return 0;
 }
 pub async fn forward_write(
     path: &String,
+    file_id: u64,
     buf: *const c_char,
     append_flag: bool,
     in_offset: i64,
@@ -363,185 +626,471 @@ pub async fn forward_write(
     } else {
         updated_metadentry_size - write_size
     };
+    if let Some(layout) = get_layout(path) {
+        return forward_write_erasure(path, file_id, &layout, buf, offset, write_size).await;
+    }
     let chunk_start = offset_to_chunk_id(offset, CHUNK_SIZE);
     let chunk_end = offset_to_chunk_id(offset + write_size - 1, CHUNK_SIZE);
-    let mut target_chunks: HashMap<u64, Vec<u64>> = HashMap::new();
-    let mut targets: Vec<u64> = Vec::new();
+    let distributor = StaticContext::get_instance().get_distributor();
+    let mut placement = PlacementCache::get_instance().get(path);
+    let metadata = ChunkMetadata {
+        path: path.to_string(),
+        file_id,
+        chunk_start: chunk_start as u64,
+        offset: offset as u64 % CHUNK_SIZE,
+        size: write_size as u64,
+    };
+    let candidates = if StaticContext::get_instance().get_fsconfig().dedup {
+        link_candidates(buf, &metadata, chunk_end as u64)
+    } else {
+        HashMap::new()
+    };
 
-    for chunk_id in chunk_start..(chunk_end + 1) {
-        let target = StaticContext::get_instance()
-            .get_distributor()
-            .locate_data(path, chunk_id);
-        if !target_chunks.contains_key(&target) {
-            target_chunks.insert(target, Vec::new());
-            target_chunks.get_mut(&target).unwrap().push(chunk_id);
-            targets.push(target);
-        } else {
-            target_chunks.get_mut(&target).unwrap().push(chunk_id);
+    let mut acks: HashMap<u64, usize> = HashMap::new();
+    let mut err = EBUSY;
+    let mut pending: Vec<u64> = (chunk_start..(chunk_end + 1)).collect();
+    // chunks nearly full hosts refused are placed on other hosts once and written there
+    for attempt in 0..2 {
+        let replicas: Vec<Vec<u64>> = pending
+            .iter()
+            .map(|chunk_id| placement.locate(distributor.as_ref(), path, *chunk_id))
+            .collect();
+        let replica_cnt = replicas[0].len();
+        let mut refused: Vec<u64> = Vec::new();
+        let mut full: Vec<u64> = Vec::new();
+        // every replica rank is written in its own round, so one host never holds two cm ids at once
+        for rank in 0..replica_cnt {
+            let mut target_chunks: HashMap<u64, Vec<u64>> = HashMap::new();
+            for (idx, chunk_id) in pending.iter().enumerate() {
+                target_chunks
+                    .entry(replicas[idx][rank])
+                    .or_insert(Vec::new())
+                    .push(*chunk_id);
+            }
+            // chunks whose content a host holds already are not sent to it
+            if !candidates.is_empty() {
+                let targets: Vec<u64> = target_chunks.keys().cloned().collect();
+                let links = join_all(targets.iter().map(|target| {
+                    link_chunks(path, file_id, *target, &target_chunks[target], &candidates)
+                }))
+                .await;
+                for (target, linked) in targets.into_iter().zip(links.into_iter()) {
+                    let chunk_ids = target_chunks.get_mut(&target).unwrap();
+                    chunk_ids.retain(|chunk_id| !linked.contains(chunk_id));
+                    for chunk_id in linked {
+                        *acks.entry(chunk_id).or_insert(0) += 1;
+                    }
+                }
+                target_chunks.retain(|_target, chunk_ids| !chunk_ids.is_empty());
+            }
+            // a host refusing new chunks takes the ones it holds, which are which is only
+            // told apart by sending them one at a time
+            let mut singles: HashMap<u64, Vec<u64>> = HashMap::new();
+            while !target_chunks.is_empty() {
+                let targets: Vec<u64> = target_chunks.keys().cloned().collect();
+                for (target, chunk_ids, res) in write_round(buf, metadata.clone(), targets, target_chunks).await {
+                    match res {
+                        Ok(_) => {
+                            for chunk_id in chunk_ids {
+                                *acks.entry(chunk_id).or_insert(0) += 1;
+                            }
+                        }
+                        Err(EDQUOT) if chunk_ids.len() > 1 => {
+                            singles.insert(target, chunk_ids);
+                        }
+                        Err(EDQUOT) => {
+                            refused.extend(chunk_ids);
+                            if !full.contains(&target) {
+                                full.push(target);
+                            }
+                        }
+                        Err(e) => err = e,
+                    }
+                }
+                target_chunks = HashMap::new();
+                for (target, chunk_ids) in singles.iter_mut() {
+                    target_chunks.insert(*target, vec![chunk_ids.remove(0)]);
+                }
+                singles.retain(|_target, chunk_ids| !chunk_ids.is_empty());
+            }
+        }
+        let quorum = write_quorum(replica_cnt);
+        pending.retain(|chunk_id| *acks.get(chunk_id).unwrap_or(&0) < quorum);
+        if pending.is_empty() {
+            return (0, write_size);
+        }
+        if refused.is_empty() {
+            break;
+        }
+        // no room left on the hosts the chunks could go to
+        err = ENOSPC;
+        if attempt == 1 {
+            break;
+        }
+        refused.sort();
+        refused.dedup();
+        let place_path = path.clone();
+        placement = match off_worker(move || forward_place(&place_path, &refused, &full)).await {
+            Ok(placement) => placement,
+            Err(_e) => break,
+        };
+        for chunk_id in pending.iter() {
+            acks.remove(chunk_id);
+        }
+    }
+    error_msg(
+        "client::forward_write".to_string(),
+        format!("write quorum not reached on chunk {}", pending[0]),
+    );
+    return (err, 0);
+}
+// place 'chunks' of 'path' away from the hosts 'full' that refused them, the metadata hosts
+// keep the placement and every client finds it in the stat of the file
+fn forward_place(path: &String, chunks: &Vec<u64>, full: &Vec<u64>) -> Result<Placement, i32> {
+    let replicas = StaticContext::get_instance()
+        .get_distributor()
+        .locate_file_metadata_replicas(path);
+    let data = PlaceData {
+        path: path.as_str(),
+        chunks: chunks.clone(),
+        full: full.clone(),
+    };
+    let place = post(option2i(&PostOption::Place), serialize(&data), vec![0; 0]);
+    let post_res = post_failover(&replicas, |host| host.handle_opt(&place, NetworkService::call_option()));
+    let result = match post_res {
+        Ok(result) => result,
+        Err(e) => {
+            error_msg(
+                "client::network::forward_place".to_string(),
+                format!("error {} occurs while placing chunks of '{}'", e, path),
+            );
+            return Err(grpc_errno(&e));
+        }
+    };
+    if result.err != 0 {
+        return Err(result.err);
+    }
+    let placement = try_deserialize::<Placement>(&result.data)?;
+    PlacementCache::get_instance().set(path, placement.clone());
+    Ok(placement)
+}
+// digest and length of the pieces of a write that start their chunk, by chunk id
+fn link_candidates(
+    buf: *const c_char,
+    metadata: &ChunkMetadata,
+    chunk_end: u64,
+) -> HashMap<u64, (Vec<u8>, u64)> {
+    let mut candidates = HashMap::new();
+    for chunk_id in metadata.chunk_start..(chunk_end + 1) {
+        let (buffer_offset, size, offset) = chunk_piece(metadata, chunk_id);
+        if offset != 0 {
+            continue;
         }
+        let piece =
+            unsafe { slice::from_raw_parts((buf as *const u8).add(buffer_offset as usize), size as usize) };
+        candidates.insert(chunk_id, (chunk_digest(piece), size));
+    }
+    candidates
+}
+// chunks of 'chunk_ids' that 'host' pointed at contents it holds already, dedup mode only
+async fn link_chunks(
+    path: &String,
+    file_id: u64,
+    host: u64,
+    chunk_ids: &Vec<u64>,
+    candidates: &HashMap<u64, (Vec<u8>, u64)>,
+) -> Vec<u64> {
+    let chunks: Vec<(u64, Vec<u8>, u64)> = chunk_ids
+        .iter()
+        .filter_map(|chunk_id| {
+            candidates
+                .get(chunk_id)
+                .map(|(digest, len)| (*chunk_id, digest.clone(), *len))
+        })
+        .collect();
+    if chunks.len() == 0 {
+        return Vec::new();
     }
-    let mut tot_write = 0;
-    //let buf = unsafe { CStr::from_ptr(buf).to_string_lossy().into_owned() };
-    //let buf = unsafe { slice::from_raw_parts(buf as *const u8, write_size as usize) };
-    //let mut handles = Vec::new();
+    let data = LinkChunksData {
+        path: path.as_str(),
+        file_id,
+        chunks,
+    };
+    match NetworkService::post_async::<LinkChunksData>(host, data, PostOption::LinkChunks).await {
+        Ok(result) if result.err == 0 => deserialize::<Vec<u64>>(&result.data),
+        // the chunks are sent as usual
+        _ => Vec::new(),
+    }
+}
+pub(crate) async fn write_round(
+    buf: *const c_char,
+    metadata: ChunkMetadata,
+    mut targets: Vec<u64>,
+    mut target_chunks: HashMap<u64, Vec<u64>>,
+) -> Vec<(u64, Vec<u64>, Result<i64, i32>)> {
+    let mut results = Vec::new();
     let mut rdma_handles = Vec::new();
     let mut cm_id_mtx = Vec::new();
+    // ascending order, so two operations never wait for each other's connections
+    targets.sort();
     for target in targets {
         let addr = buf as u64;
         let chunk_ids = target_chunks.remove(&target).unwrap();
         let op = ChunkOp::none();
         let chunk_transfer = ChunkTransferTask {
-            chunk_id: chunk_ids,
-            metadata: ChunkMetadata{
-                path: path.to_string(),
-                chunk_start: chunk_start as u64,
-                offset: offset as u64 % CHUNK_SIZE,
-                size: write_size as u64,
-            },
+            chunk_id: chunk_ids.clone(),
+            metadata: metadata.clone(),
             addr: addr,
         };
-        if let Some(guard) = StaticContext::get_instance().get_write_cm_id(target){
+        if let Some(guard) = StaticContext::get_instance().get_write_cm_id(target).await{
             let cm_id = *guard as *mut rdma_cm_id;
             cm_id_mtx.push(guard);
-            rdma_handles.push(
+            rdma_handles.push((
+                target,
+                chunk_ids,
                 sender_client_on_id(cm_id, chunk_transfer, op).await
-            );
+            ));
         }
         else{
-            error_msg("error::client::forward_write".to_string(), "no available pre-created cm id".to_string());
-            return (EBUSY, 0);
+            error_msg("error::client::forward_write".to_string(), format!("no available pre-created cm id for host {}", target));
+            results.push((target, chunk_ids, Err(EBUSY)));
         }
     }
-    for rdma in rdma_handles {
-        let res = rdma.await.unwrap();
-        if let Err(e) = res{
-            return (e, 0);
-        }
-        tot_write += res.unwrap();
+    for (target, chunk_ids, rdma) in rdma_handles {
+        // transfers report errors negated
+        let res = match rdma.await {
+            Ok(Err(e)) => Err(e.abs()),
+            Ok(res) => res,
+            Err(_e) => Err(EIO),
+        };
+        results.push((target, chunk_ids, res));
     }
-    return (0, tot_write);
+    results
 }
 pub async fn forward_read(
     path: &String,
+    file_id: u64,
     buf: *mut c_char,
     offset: i64,
     read_size: i64,
 ) -> (i32, u64) {
+    if let Some(layout) = get_layout(path) {
+        return forward_read_erasure(path, file_id, &layout, buf, offset, read_size).await;
+    }
     let chunk_start = offset_to_chunk_id(offset, CHUNK_SIZE);
     let chunk_end = offset_to_chunk_id(offset + read_size - 1, CHUNK_SIZE);
-    let mut target_chunks: HashMap<u64, Vec<u64>> = HashMap::new();
-    let mut targets: Vec<u64> = Vec::new();
+    let distributor = StaticContext::get_instance().get_distributor();
+    let mut placement = PlacementCache::get_instance().get(path);
+    let mut refreshed = false;
+    let metadata = ChunkMetadata {
+        path: path.to_string(),
+        file_id,
+        chunk_start: chunk_start as u64,
+        offset: offset as u64 % CHUNK_SIZE,
+        size: read_size as u64,
+    };
 
-    for chunk_id in chunk_start..(chunk_end + 1) {
-        let target = StaticContext::get_instance()
-            .get_distributor()
-            .locate_data(path, chunk_id);
-        if !target_chunks.contains_key(&target) {
-            target_chunks.insert(target, Vec::new());
-            target_chunks.get_mut(&target).unwrap().push(chunk_id);
-            targets.push(target);
-        } else {
-            target_chunks.get_mut(&target).unwrap().push(chunk_id);
+    // chunks whose replica failed are retried on the next replica of their set, each chunk with
+    // the rank it is read from
+    let mut pending: Vec<(u64, usize)> = (chunk_start..(chunk_end + 1))
+        .map(|chunk_id| (chunk_id, 0))
+        .collect();
+    let mut err = EBUSY;
+    let mut tot_read = 0;
+    while pending.len() != 0 {
+        let mut target_chunks: HashMap<u64, Vec<u64>> = HashMap::new();
+        let mut targets: Vec<u64> = Vec::new();
+        for (chunk_id, rank) in pending.iter() {
+            let replicas = placement.locate(distributor.as_ref(), path, *chunk_id);
+            if *rank >= replicas.len() {
+                error_msg(
+                    "client::forward_read".to_string(),
+                    format!("no replica available for chunk {}", chunk_id),
+                );
+                return (err, 0);
+            }
+            let target = replicas[*rank];
+            if !target_chunks.contains_key(&target) {
+                target_chunks.insert(target, Vec::new());
+                targets.push(target);
+            }
+            target_chunks.get_mut(&target).unwrap().push(*chunk_id);
+        }
+        let ranks: HashMap<u64, usize> = pending.drain(..).collect();
+        let mut missing = Vec::new();
+        let results = read_round(buf, metadata.clone(), targets, target_chunks).await;
+        for (chunk_ids, res, absent) in results {
+            match res {
+                Ok(len) => {
+                    tot_read += len;
+                    missing.extend(absent);
+                }
+                Err(e) => {
+                    err = e;
+                    let next = chunk_ids.iter().map(|chunk_id| (*chunk_id, ranks[chunk_id] + 1));
+                    pending.extend(next);
+                }
+            }
+        }
+        // a chunk its hosts do not hold is a hole, or was placed on other hosts by a client
+        // whose placement this one has not seen. the placement is fetched once to tell them apart
+        if !missing.is_empty() && !refreshed {
+            refreshed = true;
+            let place_path = path.clone();
+            let fetched = off_worker(move || forward_place(&place_path, &Vec::new(), &Vec::new()));
+            if let Ok(fresh) = fetched.await {
+                for chunk_id in missing {
+                    if fresh.locate(distributor.as_ref(), path, chunk_id)
+                        != placement.locate(distributor.as_ref(), path, chunk_id)
+                    {
+                        pending.push((chunk_id, 0));
+                    }
+                }
+                placement = fresh;
+            }
         }
+        pending.sort();
     }
-    let mut tot_read = 0;
+    return (0, tot_read as u64);
+}
+pub(crate) async fn read_round(
+    buf: *mut c_char,
+    metadata: ChunkMetadata,
+    mut targets: Vec<u64>,
+    mut target_chunks: HashMap<u64, Vec<u64>>,
+) -> Vec<(Vec<u64>, Result<i64, i32>, Vec<u64>)> {
+    let mut results = Vec::new();
     let mut rdma_handles = Vec::new();
     let mut cm_id_mtx = Vec::new();
+    // ascending order, so two operations never wait for each other's connections
+    targets.sort();
     for target in targets {
         let addr = buf as u64;
         let chunk_ids = target_chunks.remove(&target).unwrap();
         let op = ChunkOp::none();
         let chunk_transfer = ChunkTransferTask {
-            chunk_id: chunk_ids,
-            metadata: ChunkMetadata{
-                path: path.to_string(),
-                chunk_start: chunk_start as u64,
-                offset: offset as u64 % CHUNK_SIZE,
-                size: read_size as u64,
-            },
+            chunk_id: chunk_ids.clone(),
+            metadata: metadata.clone(),
             addr: addr,
         };
-        if let Some(guard) = StaticContext::get_instance().get_read_cm_id(target){
+        if let Some(guard) = StaticContext::get_instance().get_read_cm_id(target).await{
             let cm_id = *guard as *mut rdma_cm_id;
             cm_id_mtx.push(guard);
-            rdma_handles.push(
+            rdma_handles.push((
+                chunk_ids,
+                cm_id as u64,
                 recver_client_on_id(cm_id, chunk_transfer, op).await
-            )
+            ))
         }
         else{
-            error_msg("error::client::forward_read".to_string(), "no available pre-created cm id".to_string());
-            return (EBUSY, 0);
+            error_msg("error::client::forward_read".to_string(), format!("no available pre-created cm id for host {}", target));
+            results.push((chunk_ids, Err(EBUSY), Vec::new()));
         }
     }
-    for rdma in rdma_handles {
-        let res = rdma.await.unwrap();
-        if let Err(e) = res{
-            return (e, 0);
-        }
-        tot_read += res.unwrap();
+    for (chunk_ids, cm_id, rdma) in rdma_handles {
+        // transfers report errors negated
+        let res = match rdma.await {
+            Ok(Err(e)) => Err(e.abs()),
+            Ok(res) => res,
+            Err(_e) => Err(EIO),
+        };
+        // the cm id is still held, nothing else transferred on it meanwhile
+        results.push((chunk_ids, res, missing_chunks(cm_id as *mut rdma_cm_id)));
     }
-    return (0, tot_read as u64);
+    results
 }
-pub async fn forward_get_dirents(path: &String) -> (i32, Arc<Mutex<OpenFile>>) {
+// fetch at most 'limit' entries of a directory, starting after the name 'start_after'.
+// with 'plus' the attributes of the entries come along and land in the attribute cache,
+// under a lease in "lease" mode. nothing is cached in "strict" mode
+pub async fn forward_get_dirents(
+    path: &String,
+    start_after: &String,
+    limit: u64,
+    plus: bool,
+) -> Result<Vec<(String, FileType)>, i32> {
+    let fs_config = StaticContext::get_instance().get_fsconfig();
+    let plus = plus && fs_config.consistency != Consistency::Strict;
+    let client = if fs_config.consistency == Consistency::Lease {
+        StaticContext::get_instance().get_client_id()
+    } else {
+        0
+    };
+    // leases start no earlier on the server than the request is sent
+    let expire = Instant::now() + Duration::from_millis(fs_config.lease_ms);
     let targets = StaticContext::get_instance()
         .get_distributor()
         .locate_dir_metadata(path);
-    //let buf: Box<[u8; DIRENT_BUF_SIZE as usize]> = Box::new([0; DIRENT_BUF_SIZE as usize]);
-    //let buf_size_per_host = DIRENT_BUF_SIZE / targets.len() as u64;
-    let mut posts = Vec::new();
+    let mut req = GetDirentsRequest::default();
+    req.set_path(path.clone());
+    req.set_start_after(start_after.clone());
+    req.set_limit(limit);
+    req.set_plus(plus);
+    req.set_client(client);
+    // every replica of the directory holds all of its entries, the first one answering is enough
+    let mut err = EIO;
     for target in targets.iter() {
-        posts.push((
-            StaticContext::get_instance()
-                .get_clients()
-                .get(*target as usize)
-                .unwrap(),
-            post(
-                option2i(&PostOption::GetDirents),
-                serialize(&DirentData {
-                    path: path.as_str(),
-                }),
-                vec![0; 0],
-            ),
-        ));
-    }
-    let open_dir = Arc::new(Mutex::new(OpenFile::new(
-        path,
-        O_RDONLY,
-        crate::client::openfile::FileType::SFS_DIRECTORY,
-    )));
-    let mut handles = Vec::new();
-    for (client, post) in posts {
-        let dir = open_dir.clone();
-        handles.push(tokio::spawn(async move {
-            let mut receiver = client.handle_dirents(&post).unwrap();
-            while let Some(res) = receiver.try_next().await.unwrap() {
-                if res.err != 0{
-                    continue;
+        let (target, dirents_req) = (*target, req.clone());
+        let receiver = off_worker(move || {
+            NetworkService::call(target, true, |client| {
+                client.get_dirents_opt(&dirents_req, NetworkService::call_option())
+            })
+        })
+        .await;
+        if let Err(e) = receiver {
+            error_msg(
+                "client::forward_get_dirents".to_string(),
+                format!("error {} occurs while fetching dirents", e),
+            );
+            err = grpc_errno(&e);
+            continue;
+        }
+        let mut receiver = receiver.unwrap();
+        let mut entries = Vec::new();
+        let mut failed = false;
+        loop {
+            match receiver.try_next().await {
+                Ok(Some(mut dirent)) => {
+                    if status_errno(dirent.get_status()) != 0 {
+                        continue;
+                    }
+                    if dirent.has_attr() {
+                        let attr = attr_from_proto(dirent.get_attr());
+                        AttrCache::get_instance().insert(
+                            child_path(path, &dirent.name),
+                            Metadata::from_attr(&attr).serialize(),
+                            expire,
+                        );
+                    }
+                    entries.push((
+                        dirent.take_name(),
+                        if dirent.is_dir {
+                            FileType::SFS_DIRECTORY
+                        } else {
+                            FileType::SFS_REGULAR
+                        },
+                    ));
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    err = grpc_errno(&e);
+                    failed = true;
+                    break;
                 }
-                let entry: (String, bool) = deserialize::<(String, bool)>(&res.data);
-                dir.lock().unwrap().add(
-                    entry.0,
-                    if entry.1 {
-                        FileType::SFS_DIRECTORY
-                    } else {
-                        FileType::SFS_REGULAR
-                    },
-                );
-
             }
-        }));
-    }
-    for handle in handles{
-        handle.await.unwrap();
+        }
+        if failed {
+            continue;
+        }
+        return Ok(entries);
     }
-    (0, open_dir)
+    Err(err)
 }
 
 pub fn forward_get_fs_config(context: &mut StaticContext) -> bool {
     let host_id = context.get_local_host_id();
     let post = post(option2i(&PostOption::FsConfig), vec![0; 0], vec![0; 0]);
     let client = context.get_clients().get(host_id as usize).unwrap();
-    let fsconf_res = client.handle(&post);
+    let fsconf_res = client.handle_opt(&post, RpcPolicy::get_instance().call_option(context.get_token()));
     if let Err(_e) = fsconf_res {
         return false;
     }
@@ -550,6 +1099,13 @@ pub fn forward_get_fs_config(context: &mut StaticContext) -> bool {
         return false;
     }
     let config: SFSConfig = deserialize::<SFSConfig>(&result.data);
+    if let Err(e) = check_erasure_dirs(&config.erasure_dirs) {
+        error_msg(
+            "client::network::forward_get_fs_config".to_string(),
+            format!("invalid erasure layout, error {}", e),
+        );
+        return false;
+    }
     context.set_mountdir(config.mountdir.clone());
     context.set_fsconfig(config);
     return true;
@@ -1,5 +1,7 @@
+pub mod forward_erasure;
 pub mod forward_msg;
 pub mod network_service;
 pub mod rdmacm;
 pub mod rdma_write;
 pub mod rdma_read;
+pub mod rpc_policy;
@@ -1,64 +1,193 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
 use futures::{join, SinkExt, TryStreamExt};
-use grpcio::{Error, WriteFlags};
+use grpcio::{CallOption, Error, WriteFlags};
+use libc::EAGAIN;
 use serde::Serialize;
 use sfs_global::global::util::serde_util::serialize;
 use sfs_rpc::{
-    post,
+    host_unreachable, post,
     proto::{
         server::{Post, PostResult},
         server_grpc::SfsHandleClient,
     },
 };
 
-use sfs_global::global::network::post::{option2i, PostOption};
+use crate::client::context::StaticContext;
+
+use sfs_global::global::network::post::{i2option, idempotent, option2i, PostOption};
+
+use super::rpc_policy::RpcPolicy;
 
 pub struct NetworkService {}
 impl NetworkService {
-    pub fn post<T: Serialize>(
-        client: &SfsHandleClient,
-        data: T,
-        opt: PostOption,
-    ) -> Result<PostResult, Error> {
+    // carries the token of the job and the deadline, every call has to use it
+    pub fn call_option() -> CallOption {
+        RpcPolicy::get_instance().call_option(StaticContext::get_instance().get_token())
+    }
+    // runs 'f' against the client of 'host' unless its circuit is open. calls the host left
+    // unanswered are sent again with backoff, if 'idempotent' says that is harmless
+    pub fn call<R, F>(host: u64, idempotent: bool, f: F) -> Result<R, Error>
+    where
+        F: Fn(&SfsHandleClient) -> Result<R, Error>,
+    {
+        let policy = RpcPolicy::get_instance();
+        let client = StaticContext::get_instance()
+            .get_clients()
+            .get(host as usize)
+            .unwrap();
+        let mut attempt = 0;
+        loop {
+            if !policy.allow(host) {
+                return Err(policy.skipped(host));
+            }
+            let res = f(client);
+            let unreachable = match &res {
+                Err(e) => host_unreachable(e),
+                Ok(_) => false,
+            };
+            if !unreachable {
+                policy.record_success(host);
+                return res;
+            }
+            policy.record_failure(host);
+            if !idempotent || attempt >= policy.get_retries() {
+                return res;
+            }
+            attempt += 1;
+            thread::sleep(policy.backoff(attempt));
+        }
+    }
+    // a change of a path other clients hold leases on is turned away with EAGAIN and nothing
+    // changed, so it goes to 'host' again until their leases ran out. 'errno' reads the status
+    pub fn call_change<R, F, E>(host: u64, idempotent: bool, f: F, errno: E) -> Result<R, Error>
+    where
+        F: Fn(&SfsHandleClient) -> Result<R, Error>,
+        E: Fn(&R) -> i32,
+    {
+        let lease_ms = StaticContext::get_instance().get_fsconfig().lease_ms;
+        let deadline = Instant::now() + Duration::from_millis(2 * lease_ms);
+        let mut attempt = 0;
+        loop {
+            let res = Self::call(host, idempotent, &f);
+            match &res {
+                Ok(response) if errno(response) == EAGAIN && Instant::now() < deadline => {
+                    attempt += 1;
+                    thread::sleep(RpcPolicy::get_instance().backoff(attempt));
+                }
+                _ => return res,
+            }
+        }
+    }
+    pub fn post<T: Serialize>(host: u64, data: T, opt: PostOption) -> Result<PostResult, Error> {
         let serialized_data = serialize(&data);
-        let post = post(option2i(&opt), serialized_data, vec![0; 0]);
-        let post_result = client.handle(&post)?;
-        return Ok(post_result);
+        return Self::post_serialized(host, serialized_data, opt);
     }
-    pub fn post_serialized(
-        client: &SfsHandleClient,
-        data: Vec<u8>,
+    // 'post' for callers on the I/O workers, the worker is free while the host answers. not sent
+    // again, the circuit of the host is kept all the same
+    pub async fn post_async<T: Serialize>(
+        host: u64,
+        data: T,
         opt: PostOption,
     ) -> Result<PostResult, Error> {
+        let policy = RpcPolicy::get_instance();
+        if !policy.allow(host) {
+            return Err(policy.skipped(host));
+        }
+        let client = StaticContext::get_instance()
+            .get_clients()
+            .get(host as usize)
+            .unwrap();
+        let post = post(option2i(&opt), serialize(&data), vec![0; 0]);
+        let res = match client.handle_async_opt(&post, Self::call_option()) {
+            Ok(receiver) => receiver.await,
+            Err(e) => Err(e),
+        };
+        match &res {
+            Err(e) if host_unreachable(e) => policy.record_failure(host),
+            _ => policy.record_success(host),
+        }
+        return res;
+    }
+    pub fn post_serialized(host: u64, data: Vec<u8>, opt: PostOption) -> Result<PostResult, Error> {
         let post = post(option2i(&opt), data, vec![0; 0]);
-        let post_result = client.handle(&post)?;
-        return Ok(post_result);
+        return Self::call(host, idempotent(&opt), |client| {
+            client.handle_opt(&post, Self::call_option())
+        });
     }
     pub fn post_stuff<T: Serialize>(
-        client: &SfsHandleClient,
+        host: u64,
         data: T,
         stuff: Vec<u8>,
         opt: PostOption,
     ) -> Result<PostResult, Error> {
         let serialized_data = serialize(&data);
         let post = post(option2i(&opt), serialized_data, stuff);
-        let post_result = client.handle(&post)?;
-        return Ok(post_result);
+        return Self::call(host, idempotent(&opt), |client| {
+            client.handle_opt(&post, Self::call_option())
+        });
     }
 
-    pub fn group_post(posts: Vec<(&SfsHandleClient, Post)>) -> Result<Vec<PostResult>, Error> {
-        let mut post_results: Vec<PostResult> = Vec::new();
-        for (client, post) in posts {
-            let post_result = client.handle(&post)?;
-            post_results.push(post_result);
+    // sends every post at once, at most 'fanout' of them in flight, and returns the outcome of
+    // each host in the order of 'posts'. retries follow the same rules as 'call'
+    pub fn group_post(posts: Vec<(u64, Post)>) -> Vec<(u64, Result<PostResult, Error>)> {
+        let policy = RpcPolicy::get_instance();
+        let clients = StaticContext::get_instance().get_clients();
+        let mut results: Vec<Option<Result<PostResult, Error>>> =
+            posts.iter().map(|_| None).collect();
+        let mut pending: Vec<usize> = (0..posts.len()).collect();
+        let mut attempt = 0;
+        while pending.len() != 0 {
+            let mut retry = Vec::new();
+            for wave in pending.chunks(policy.get_fanout()) {
+                let mut receivers = Vec::new();
+                for i in wave.iter() {
+                    let (host, post) = &posts[*i];
+                    if !policy.allow(*host) {
+                        results[*i] = Some(Err(policy.skipped(*host)));
+                        continue;
+                    }
+                    let client = clients.get(*host as usize).unwrap();
+                    receivers.push((*i, client.handle_async_opt(post, Self::call_option())));
+                }
+                // the calls progress on their own, waiting for them in turn costs nothing
+                for (i, receiver) in receivers {
+                    let (host, post) = &posts[i];
+                    let res = receiver.and_then(|mut r| r.receive_sync().map(|(_, res, _)| res));
+                    let unreachable = match &res {
+                        Err(e) => host_unreachable(e),
+                        Ok(_) => false,
+                    };
+                    if !unreachable {
+                        policy.record_success(*host);
+                    } else {
+                        policy.record_failure(*host);
+                        if idempotent(&i2option(post.option)) && attempt < policy.get_retries() {
+                            retry.push(i);
+                        }
+                    }
+                    results[i] = Some(res);
+                }
+            }
+            if retry.len() != 0 {
+                attempt += 1;
+                thread::sleep(policy.backoff(attempt));
+            }
+            pending = retry;
         }
-        return Ok(post_results);
+        posts
+            .into_iter()
+            .zip(results.into_iter())
+            .map(|((host, _), res)| (host, res.unwrap()))
+            .collect()
     }
 
     pub async fn post_stream(
         client: &SfsHandleClient,
         posts: Vec<Post>,
     ) -> Result<Vec<PostResult>, Error> {
-        let (mut sink, mut receiver) = client.handle_stream()?;
+        let (mut sink, mut receiver) = client.handle_stream_opt(Self::call_option())?;
         let send = async move {
             for post in posts {
                 sink.send((post, WriteFlags::default())).await?;
@@ -1,11 +1,15 @@
 
 use std::ptr::null_mut;
 
+use sfs_global::global::error_msg::error_msg;
 use sfs_global::global::network::config::CHUNK_SIZE;
+use sfs_global::global::util::checksum_util::{
+    chunk_checksum, token_digest, CHECKSUM_SIZE, TOKEN_DIGEST_SIZE,
+};
 use sfs_rdma::{
-    transfer::{ChunkTransferTask, TransferMetadata}, rdma::RDMAContext, RDMA_READ_PORT,
+    transfer::{ChunkTransferTask, TransferMetadata, READ_FAILED, READ_MISSING}, rdma::RDMAContext, RDMA_READ_PORT,
 };
-use libc::{calloc, in_addr, sockaddr, sockaddr_in, AF_INET, INADDR_LOOPBACK, c_void};
+use libc::{calloc, in_addr, sockaddr, sockaddr_in, AF_INET, EIO, INADDR_LOOPBACK, c_void};
 use rdma_sys::{
     ibv_access_flags, ibv_alloc_pd, ibv_create_comp_channel, ibv_create_cq, ibv_dealloc_pd,
     ibv_dereg_mr, ibv_destroy_comp_channel, ibv_destroy_cq, ibv_mr, ibv_pd, ibv_post_recv,
@@ -43,6 +47,14 @@ struct ReceiverClientContext {
     pub msg: *mut Message,
     pub msg_mr: *mut ibv_mr,
 
+    // the server puts the checksum of every piece here before the piece itself
+    pub checksum: *mut u64,
+    pub checksum_mr: *mut ibv_mr,
+    // a piece of the current transfer failed to read or to verify
+    pub failed: bool,
+    // chunks of the current transfer the server does not hold
+    pub missing: Vec<u64>,
+
     pub peer_addr: u64,
     pub peer_rkey: u32,
 }
@@ -56,6 +68,10 @@ impl ReceiverClientContext {
             buffer_mr: null_mut(),
             msg: null_mut(),
             msg_mr: null_mut(),
+            checksum: null_mut(),
+            checksum_mr: null_mut(),
+            failed: false,
+            missing: Vec::new(),
             peer_addr: 0,
             peer_rkey: 0,
         }
@@ -95,6 +111,7 @@ pub(crate) async fn recver_client(
         let mut md = TransferMetadata::default();
         md.size = task.metadata.size;
         md.offset = task.metadata.offset;
+        md.file_id = task.metadata.file_id;
         md.chunk_start = task.metadata.chunk_start;
         md.path_len = task.metadata.path.len();
         libc::memcpy(
@@ -112,6 +129,7 @@ pub(crate) async fn recver_client(
             on_established,
             on_disconnect,
             s_ctx: null_mut(),
+            digest: token_digest(StaticContext::get_instance().get_token()),
             tx: None
         };
         libc::memcpy(
@@ -161,10 +179,25 @@ fn on_completion(wc: *mut ibv_wc, pd: *mut ibv_pd, _op: &ChunkOp) -> Result<i64,
 
         if (*wc).opcode == IBV_WC_RECV_RDMA_WITH_IMM {
             let read_len = u32::from_be((*wc).imm_data_invalidated_rkey_union.imm_data);
+            // the piece just landed in the current buffer, the message still names its chunk
+            let read_len = if read_len == READ_MISSING {
+                (*ctx).missing.push((*(*ctx).msg).data);
+                0
+            } else if read_len == READ_FAILED || !piece_intact(ctx, read_len) {
+                (*ctx).failed = true;
+                0
+            } else {
+                read_len
+            };
             if (*ctx).chunk_id.len() == 0 {
                 post_receive_msg(id);
                 (*(*ctx).msg).mtype = MessageType::MSG_DONE;
                 send_message(id);
+                // the chunks are read again from another replica
+                if (*ctx).failed {
+                    (*ctx).failed = false;
+                    return Err(-EIO);
+                }
                 return Err(read_len as i32);
             } else {
                 post_receive_data(id);
@@ -196,6 +229,7 @@ fn on_completion(wc: *mut ibv_wc, pd: *mut ibv_pd, _op: &ChunkOp) -> Result<i64,
                 (*(*ctx).msg).addr = (*(*ctx).buffer_mr).addr as u64;
                 (*(*ctx).msg).rkey = (*(*ctx).buffer_mr).rkey;
                 (*(*ctx).msg).data = (*ctx).chunk_id[0];
+                set_checksum_target(ctx);
 
                 send_message(id);
                 (*ctx).chunk_id.remove(0);
@@ -238,6 +272,7 @@ fn on_completion(wc: *mut ibv_wc, pd: *mut ibv_pd, _op: &ChunkOp) -> Result<i64,
                 (*(*ctx).msg).addr = (*(*ctx).buffer_mr).addr as u64;
                 (*(*ctx).msg).rkey = (*(*ctx).buffer_mr).rkey;
                 (*(*ctx).msg).data = (*ctx).chunk_id[0];
+                set_checksum_target(ctx);
 
                 (*ctx).chunk_id.remove(0);
                 send_message(id);
@@ -247,6 +282,34 @@ fn on_completion(wc: *mut ibv_wc, pd: *mut ibv_pd, _op: &ChunkOp) -> Result<i64,
         return Ok(0);
     }
 }
+// whether the piece of 'len' bytes in the current buffer matches the checksum sent along
+fn piece_intact(ctx: *mut ReceiverClientContext, len: u32) -> bool {
+    unsafe {
+        if !(*ctx).metadata.checksum {
+            return true;
+        }
+        let piece = std::slice::from_raw_parts((*ctx).buffer, len as usize);
+        if chunk_checksum(piece) != *(*ctx).checksum {
+            error_msg(
+                "client::network::rdma_read::piece_intact".to_string(),
+                "checksum mismatch on a read piece".to_string(),
+            );
+            return false;
+        }
+        return true;
+    }
+}
+fn set_checksum_target(ctx: *mut ReceiverClientContext) {
+    unsafe {
+        if (*ctx).metadata.checksum {
+            (*(*ctx).msg).checksum_addr = (*ctx).checksum as u64;
+            (*(*ctx).msg).checksum_rkey = (*(*ctx).checksum_mr).rkey;
+        } else {
+            (*(*ctx).msg).checksum_addr = 0;
+            (*(*ctx).msg).checksum_rkey = 0;
+        }
+    }
+}
 fn send_message(id: *mut rdma_cm_id) {
     unsafe {
         let cm_ctx = (*id).context as *mut RDMACMContext;
@@ -398,6 +461,14 @@ pub fn on_route_resolved(cm_id: *mut rdma_cm_id){
             ibv_access_flags::IBV_ACCESS_LOCAL_WRITE.0 as i32,
         );
         
+        (*ctx).checksum = calloc(1, CHECKSUM_SIZE) as *mut u64;
+        (*ctx).checksum_mr = ibv_reg_mr(
+            pd,
+            (*ctx).checksum.cast(),
+            CHECKSUM_SIZE,
+            (ibv_access_flags::IBV_ACCESS_REMOTE_WRITE | ibv_access_flags::IBV_ACCESS_LOCAL_WRITE).0
+                as i32,
+        );
         // this mr register is temporary, without this send_metadata will fail when dereg mr!
         (*ctx).buffer_mr = ibv_reg_mr(
             pd,
@@ -439,10 +510,12 @@ pub fn on_disconnect(cm_id: *mut rdma_cm_id){
         
         ibv_dereg_mr((*ctx).buffer_mr);
         ibv_dereg_mr((*ctx).msg_mr);
+        ibv_dereg_mr((*ctx).checksum_mr);
 
         rdma_destroy_qp(cm_id);
         rdma_destroy_id(cm_id);
         libc::free((*ctx).msg.cast());
+        libc::free((*ctx).checksum.cast());
 
         ibv_dealloc_pd(pd);
         ibv_destroy_cq(cq);
@@ -456,7 +529,8 @@ pub fn on_disconnect(cm_id: *mut rdma_cm_id){
 
 pub async fn new_read_cm_id(
     ec: *mut rdma_event_channel,
-    addr: &String
+    addr: &String,
+    digest: [u8; TOKEN_DIGEST_SIZE],
 ) -> u64
 {
     let mut server_sockaddr = sockaddr_in {
@@ -493,6 +567,7 @@ pub async fn new_read_cm_id(
             on_established,
             on_disconnect,
             s_ctx: null_mut(),
+            digest,
             tx: None
         };
         libc::memcpy(
@@ -523,6 +598,14 @@ pub async fn new_read_cm_id(
     }   
 }
 
+// chunks the server of the last transfer on 'cm_id' does not hold, read as holes
+pub(crate) fn missing_chunks(cm_id: *mut rdma_cm_id) -> Vec<u64> {
+    unsafe {
+        let cm_ctx = (*cm_id).context as *mut RDMACMContext;
+        let ctx = (*cm_ctx).ctx as *mut ReceiverClientContext;
+        std::mem::take(&mut (*ctx).missing)
+    }
+}
 pub(crate) async fn recver_client_on_id(
     cm_id: *mut rdma_cm_id,
     task: ChunkTransferTask,
@@ -536,8 +619,12 @@ pub(crate) async fn recver_client_on_id(
         let mut md = TransferMetadata::default();
         md.size = task.metadata.size;
         md.offset = task.metadata.offset;
+        md.file_id = task.metadata.file_id;
         md.chunk_start = task.metadata.chunk_start;
         md.path_len = task.metadata.path.len();
+        md.checksum = StaticContext::get_instance().get_checksum();
+        (*ctx).failed = false;
+        (*ctx).missing.clear();
         libc::memcpy(
             md.path.as_mut_ptr().cast(),
             task.metadata.path.as_ptr().cast(),
@@ -18,6 +18,9 @@ use rdma_sys::{
     rdma_resolve_addr, rdma_event_channel, 
 };
 use sfs_global::global::network::config::CHUNK_SIZE;
+use sfs_global::global::util::checksum_util::{
+    chunk_checksum, token_digest, CHECKSUM_SIZE, TOKEN_DIGEST_SIZE,
+};
 use sfs_rdma::{transfer::{MessageType, TransferMetadata}, rdma::{CQPoller, RDMAContext}, RDMA_WRITE_PORT};
 
 use sfs_rdma::{
@@ -42,6 +45,10 @@ struct SenderClientContext {
     pub msg: *mut Message,
     pub msg_mr: *mut ibv_mr,
 
+    // checksum of the piece in flight, sent right behind it
+    pub checksum: *mut u64,
+    pub checksum_mr: *mut ibv_mr,
+
     pub peer_addr: u64,
     pub peer_rkey: u32,
 }
@@ -55,6 +62,8 @@ impl SenderClientContext {
             buffer_mr: null_mut(),
             msg: null_mut(),
             msg_mr: null_mut(),
+            checksum: null_mut(),
+            checksum_mr: null_mut(),
             peer_addr: 0,
             peer_rkey: 0
         }
@@ -99,6 +108,7 @@ pub(crate) async fn sender_client(
         let mut md = TransferMetadata::default();
         md.size = task.metadata.size;
         md.offset = task.metadata.offset;
+        md.file_id = task.metadata.file_id;
         md.chunk_start = task.metadata.chunk_start;
         md.path_len = task.metadata.path.len();
         libc::memcpy(
@@ -116,6 +126,7 @@ pub(crate) async fn sender_client(
             on_established,
             on_disconnect,
             s_ctx: null_mut(),
+            digest: token_digest(StaticContext::get_instance().get_token()),
             tx: None
         };
         libc::memcpy(
@@ -218,14 +229,25 @@ fn write_remote(id: *mut rdma_cm_id, len: u32, op: WriteOp) -> Result<i64, i32>
         wr.wr.rdma.remote_addr = (*ctx).peer_addr;
         wr.wr.rdma.rkey = (*ctx).peer_rkey;
 
-        let mut sge = ibv_sge {
-            addr: (*ctx).buffer as u64,
-            length: len,
-            lkey: (*(*ctx).buffer_mr).lkey,
-        };
+        let mut sge = [
+            ibv_sge {
+                addr: (*ctx).buffer as u64,
+                length: len,
+                lkey: (*(*ctx).buffer_mr).lkey,
+            },
+            ibv_sge {
+                addr: (*ctx).checksum as u64,
+                length: CHECKSUM_SIZE as u32,
+                lkey: if (*ctx).checksum_mr.is_null() { 0 } else { (*(*ctx).checksum_mr).lkey },
+            },
+        ];
         if len > 0 {
-            wr.sg_list = (&mut sge) as *mut ibv_sge;
+            wr.sg_list = sge.as_mut_ptr();
             wr.num_sge = 1;
+            // the piece and its checksum land next to each other in the buffer of the server
+            if matches!(op, WriteOp::DATA) && (*ctx).metadata.checksum {
+                wr.num_sge = 2;
+            }
         }
         let mut bad_wr: *mut ibv_send_wr = null_mut();
 
@@ -261,6 +283,10 @@ fn send_next_chunk(id: *mut rdma_cm_id, pd: *mut ibv_pd) -> Result<i64, i32> {
                 u64::min(CHUNK_SIZE, (*ctx).metadata.size - offset)
             };
             (*ctx).buffer_mr = ibv_reg_mr(pd, (*ctx).buffer.cast(), len as usize, 0);
+            if (*ctx).metadata.checksum {
+                let piece = std::slice::from_raw_parts((*ctx).buffer, len as usize);
+                *(*ctx).checksum = chunk_checksum(piece);
+            }
 
             //println!("{} - {}: {} {} | {} {}", (*ctx).chunk_start, (*ctx).chunk_id[0], (*ctx).offset, (*ctx).size, offset, len);
             len
@@ -360,6 +386,8 @@ pub fn on_route_resolved(cm_id: *mut rdma_cm_id){
             std::mem::size_of::<Message>(),
             ibv_access_flags::IBV_ACCESS_LOCAL_WRITE.0 as i32,
         );
+        (*ctx).checksum = calloc(1, CHECKSUM_SIZE) as *mut u64;
+        (*ctx).checksum_mr = ibv_reg_mr(pd, (*ctx).checksum.cast(), CHECKSUM_SIZE, 0);
         // this mr register is temporary, without this send_metadata will fail when dereg mr!
         (*ctx).buffer_mr = ibv_reg_mr(
             pd,
@@ -400,10 +428,12 @@ pub fn on_disconnect(cm_id: *mut rdma_cm_id){
 
         ibv_dereg_mr((*ctx).buffer_mr);
         ibv_dereg_mr((*ctx).msg_mr);
+        ibv_dereg_mr((*ctx).checksum_mr);
 
         rdma_destroy_qp(cm_id);
         rdma_destroy_id(cm_id);
         libc::free((*ctx).msg.cast());
+        libc::free((*ctx).checksum.cast());
 
         ibv_dealloc_pd(pd);
         ibv_destroy_cq(cq);
@@ -417,7 +447,8 @@ pub fn on_disconnect(cm_id: *mut rdma_cm_id){
 
 pub async fn new_write_cm_id(
     ec: *mut rdma_event_channel,
-    addr: &String
+    addr: &String,
+    digest: [u8; TOKEN_DIGEST_SIZE],
 ) -> u64
 {
     let mut server_sockaddr = sockaddr_in {
@@ -454,6 +485,7 @@ pub async fn new_write_cm_id(
             on_established,
             on_disconnect,
             s_ctx: null_mut(),
+            digest,
             tx: None
         };
         libc::memcpy(
@@ -497,8 +529,10 @@ pub(crate) async fn sender_client_on_id(
         let mut md = TransferMetadata::default();
         md.size = task.metadata.size;
         md.offset = task.metadata.offset;
+        md.file_id = task.metadata.file_id;
         md.chunk_start = task.metadata.chunk_start;
         md.path_len = task.metadata.path.len();
+        md.checksum = StaticContext::get_instance().get_checksum();
         libc::memcpy(
             md.path.as_mut_ptr().cast(),
             task.metadata.path.as_ptr().cast(),
@@ -1,5 +1,6 @@
 use std::ptr::null_mut;
 use rdma_sys::{rdma_event_channel, rdma_cm_event, rdma_get_cm_event, rdma_ack_cm_event, rdma_event_str, rdma_cm_event_type::{RDMA_CM_EVENT_ESTABLISHED, RDMA_CM_EVENT_DISCONNECTED, RDMA_CM_EVENT_ADDR_RESOLVED, RDMA_CM_EVENT_ROUTE_RESOLVED}, rdma_resolve_route, rdma_conn_param, rdma_connect, rdma_cm_id, rdma_destroy_event_channel};
+use sfs_global::global::util::checksum_util::TOKEN_DIGEST_SIZE;
 use sfs_rdma::{build_params, rdma::RDMAContext};
 use tokio::sync::oneshot::{Sender};
 
@@ -10,6 +11,8 @@ pub struct RDMACMContext{
     pub on_route_resolved: fn(*mut rdma_cm_id),
     pub on_established: fn(*mut rdma_cm_id),
     pub on_disconnect: fn(*mut rdma_cm_id),
+    // digest of the token of the job, sent with the connection request
+    pub digest: [u8; TOKEN_DIGEST_SIZE],
     
     pub tx: Option<Sender<u64>>
 }
@@ -42,6 +45,9 @@ pub fn process_cm_event(ec: u64){
                     // connect server
                     let mut cm_params: rdma_conn_param = std::mem::zeroed();
                     build_params(&mut cm_params);
+                    // servers holding a token turn requests without its digest away
+                    cm_params.private_data = (*ctx).digest.as_ptr().cast();
+                    cm_params.private_data_len = TOKEN_DIGEST_SIZE as u8;
                     rdma_connect(cm_id, &mut cm_params);
                 }
                 RDMA_CM_EVENT_ESTABLISHED => {
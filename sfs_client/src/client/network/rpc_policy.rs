@@ -0,0 +1,138 @@
+use grpcio::{CallOption, Error, RpcStatus, RpcStatusCode};
+use lazy_static::*;
+use sfs_global::global::util::env_util::get_var;
+use sfs_rpc::security::call_option;
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::client::config::{
+    CIRCUIT_FAILURES, CIRCUIT_OPEN_MS, CONNECT_RETRIES, RPC_BACKOFF_MAX_MS, RPC_BACKOFF_MS,
+    RPC_FANOUT, RPC_RETRIES, RPC_TIMEOUT_MS,
+};
+
+struct HostHealth {
+    failures: u32,
+    // calls are refused until then, None while the host answers
+    open_until: Option<Instant>,
+    // a call is out to find out whether the host is back
+    probing: bool,
+}
+// deadlines and retries of every call, and a circuit breaker per host: a host that left
+// 'failures_' calls in a row unanswered is skipped for 'open_', then a single call probes it
+pub struct RpcPolicy {
+    timeout_: Duration,
+    retries_: u32,
+    failures_: u32,
+    open_: Duration,
+    connect_retries_: u32,
+    fanout_: usize,
+    hosts_: Mutex<HashMap<u64, HostHealth>>,
+}
+fn env_u64(name: &str, default: u64) -> u64 {
+    get_var(name.to_string(), default.to_string())
+        .parse::<u64>()
+        .unwrap_or(default)
+}
+lazy_static! {
+    static ref RPC_POLICY: RpcPolicy = RpcPolicy::new(
+        Duration::from_millis(env_u64("SFS_RPC_TIMEOUT_MS", RPC_TIMEOUT_MS)),
+        env_u64("SFS_RPC_RETRIES", RPC_RETRIES as u64) as u32,
+        env_u64("SFS_CIRCUIT_FAILURES", CIRCUIT_FAILURES as u64) as u32,
+        Duration::from_millis(env_u64("SFS_CIRCUIT_OPEN_MS", CIRCUIT_OPEN_MS)),
+        env_u64("SFS_CONNECT_RETRIES", CONNECT_RETRIES as u64) as u32,
+        env_u64("SFS_RPC_FANOUT", RPC_FANOUT as u64) as usize,
+    );
+}
+impl RpcPolicy {
+    pub fn new(
+        timeout: Duration,
+        retries: u32,
+        failures: u32,
+        open: Duration,
+        connect_retries: u32,
+        fanout: usize,
+    ) -> RpcPolicy {
+        RpcPolicy {
+            timeout_: timeout,
+            retries_: retries,
+            failures_: failures,
+            open_: open,
+            connect_retries_: connect_retries,
+            fanout_: fanout,
+            hosts_: Mutex::new(HashMap::new()),
+        }
+    }
+    pub fn get_instance() -> &'static RpcPolicy {
+        &RPC_POLICY
+    }
+    pub fn get_timeout(&self) -> Duration {
+        self.timeout_
+    }
+    pub fn get_retries(&self) -> u32 {
+        self.retries_
+    }
+    pub fn get_open(&self) -> Duration {
+        self.open_
+    }
+    pub fn get_connect_retries(&self) -> u32 {
+        u32::max(self.connect_retries_, 1)
+    }
+    pub fn get_fanout(&self) -> usize {
+        usize::max(self.fanout_, 1)
+    }
+    // token of the job and the deadline, every call has to use them
+    pub fn call_option(&self, token: &String) -> CallOption {
+        call_option(token).timeout(self.timeout_)
+    }
+    // pause before retry 'attempt', counted from 1, 0 pauses as long as 1
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let ms = RPC_BACKOFF_MS.saturating_mul(1 << u32::min(attempt.saturating_sub(1), 16));
+        Duration::from_millis(u64::min(ms, RPC_BACKOFF_MAX_MS))
+    }
+    // whether a call to 'host' may go out now, every allowed call has to report its outcome
+    pub fn allow(&self, host: u64) -> bool {
+        let mut hosts = self.hosts_.lock().unwrap();
+        let health = match hosts.get_mut(&host) {
+            Some(health) => health,
+            None => return true,
+        };
+        match health.open_until {
+            None => true,
+            Some(until) if Instant::now() < until => false,
+            Some(_) => {
+                // half open, the others keep failing fast until the probe is back
+                if health.probing {
+                    return false;
+                }
+                health.probing = true;
+                true
+            }
+        }
+    }
+    // error of calls refused by an open circuit
+    pub fn skipped(&self, host: u64) -> Error {
+        Error::RpcFailure(RpcStatus::with_message(
+            RpcStatusCode::UNAVAILABLE,
+            format!("host {} is skipped after repeated failures", host),
+        ))
+    }
+    // the host answered, even with an error
+    pub fn record_success(&self, host: u64) {
+        self.hosts_.lock().unwrap().remove(&host);
+    }
+    pub fn record_failure(&self, host: u64) {
+        let mut hosts = self.hosts_.lock().unwrap();
+        let health = hosts.entry(host).or_insert(HostHealth {
+            failures: 0,
+            open_until: None,
+            probing: false,
+        });
+        health.failures += 1;
+        health.probing = false;
+        if self.failures_ != 0 && health.failures >= self.failures_ {
+            health.open_until = Some(Instant::now() + self.open_);
+        }
+    }
+}
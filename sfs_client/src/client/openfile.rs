@@ -7,6 +7,7 @@ use std::{
 use sfs_global::global::error_msg::error_msg;
 
 use super::context::MIN_INTERNAL_FD;
+use super::write_buffer::WriteBuffer;
 
 pub static O_RDONLY: i32 = 0x0000; // open for reading only
 pub static O_WRONLY: i32 = 0x0001; // open for writing only
@@ -82,9 +83,15 @@ fn to_index(flag: OpenFileFlags) -> usize {
 pub struct OpenFile {
     type_: FileType,
     path_: String,
+    file_id_: u64, // for regular file, names its chunks on the data hosts
     flags_: BitVec,
     pos_: i64,
-    pub entries_: Vec<Arc<SFSDirEntry>>, // for directory
+    pub entries_: Vec<Arc<SFSDirEntry>>, // for directory, entries of the current page
+    page_: u64,
+    page_cursors_: Vec<String>, // name after which each known page starts
+    last_page_: bool,
+    write_buffer_: Option<WriteBuffer>, // for regular file, writes not flushed yet
+    read_end_: i64, // for regular file, where the last read stopped
 }
 impl OpenFile {
     pub fn new(_path: &String, _flags: i32, _type: FileType) -> OpenFile {
@@ -111,10 +118,16 @@ impl OpenFile {
         OpenFile {
             type_: _type,
             path_: _path.clone(),
+            file_id_: 0,
             flags_: flag_vec,
             pos_: 0,
             entries_: Vec::new(), //pos_mutex_: Mutex::new(0),
                                   //flag_mutex_: Mutex::new(0)
+            page_: 0,
+            page_cursors_: vec!["".to_string()],
+            last_page_: true,
+            write_buffer_: None,
+            read_end_: 0,
         }
     }
     pub fn get_path(&self) -> &String {
@@ -123,12 +136,33 @@ impl OpenFile {
     pub fn set_path(&mut self, new_path: String) {
         self.path_ = new_path;
     }
+    pub fn get_file_id(&self) -> u64 {
+        self.file_id_
+    }
+    pub fn set_file_id(&mut self, file_id: u64) {
+        self.file_id_ = file_id;
+    }
     pub fn get_pos(&self) -> i64 {
         self.pos_
     }
     pub fn set_pos(&mut self, new_pos: i64) {
         self.pos_ = new_pos;
     }
+    pub fn get_write_buffer(&mut self) -> Option<&mut WriteBuffer> {
+        self.write_buffer_.as_mut()
+    }
+    pub fn set_write_buffer(&mut self, write_buffer: WriteBuffer) {
+        self.write_buffer_ = Some(write_buffer);
+    }
+    pub fn take_write_buffer(&mut self) -> Option<WriteBuffer> {
+        self.write_buffer_.take()
+    }
+    // whether a read continues the previous one, reads from the start of a file count as sequential
+    pub fn track_read(&mut self, offset: i64, count: i64) -> bool {
+        let sequential = offset == self.read_end_;
+        self.read_end_ = offset + count;
+        sequential
+    }
     pub fn get_flag(&self, flag: OpenFileFlags) -> bool {
         let res = self.flags_.get(to_index(flag));
         if let Some(b) = res {
@@ -169,6 +203,28 @@ impl OpenFile {
             FileType::SFS_DIRECTORY => self.entries_.len(),
         }
     }
+    pub fn get_page(&self) -> u64 {
+        self.page_
+    }
+    pub fn is_last_page(&self) -> bool {
+        self.last_page_
+    }
+    // cursor of a page, only pages reached by reading through the directory are known
+    pub fn get_page_cursor(&self, page: u64) -> Option<String> {
+        self.page_cursors_.get(page as usize).cloned()
+    }
+    pub fn set_page(&mut self, page: u64, entries: Vec<(String, FileType)>, last_page: bool) {
+        self.entries_.clear();
+        for (name, file_type) in entries {
+            self.add(name, file_type);
+        }
+        self.page_ = page;
+        self.last_page_ = last_page;
+        if !last_page && self.page_cursors_.len() as u64 == page + 1 {
+            let cursor = self.entries_.last().unwrap().get_name();
+            self.page_cursors_.push(cursor);
+        }
+    }
 }
 
 pub struct OpenFileMap {
@@ -198,6 +254,28 @@ impl OpenFileMap {
             None
         }
     }
+    // every open file of 'path', a file shows up once however many descriptors refer to it
+    pub fn get_by_path(&self, path: &String) -> Vec<Arc<Mutex<OpenFile>>> {
+        let mut files: Vec<Arc<Mutex<OpenFile>>> = Vec::new();
+        for f in self.files_.lock().unwrap().values() {
+            if files.iter().any(|file| Arc::ptr_eq(file, f)) {
+                continue;
+            }
+            if f.lock().unwrap().get_path().eq(path) {
+                files.push(Arc::clone(f));
+            }
+        }
+        files
+    }
+    pub fn get_all(&self) -> Vec<Arc<Mutex<OpenFile>>> {
+        let mut files: Vec<Arc<Mutex<OpenFile>>> = Vec::new();
+        for f in self.files_.lock().unwrap().values() {
+            if !files.iter().any(|file| Arc::ptr_eq(file, f)) {
+                files.push(Arc::clone(f));
+            }
+        }
+        files
+    }
     pub fn get_dir(&self, dirfd: i32) -> Option<Arc<Mutex<OpenFile>>> {
         if let Some(f) = self.get(dirfd) {
             match f.lock().unwrap().get_type() {
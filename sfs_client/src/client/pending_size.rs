@@ -0,0 +1,75 @@
+use lazy_static::*;
+use sfs_global::global::util::env_util::get_var;
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use super::config::{DEFER_SIZE_UPDATES, SIZE_BATCH_FILES};
+use super::network::forward_msg::forward_update_size_batch;
+
+// sizes of files written by this process that the metadata servers don't know yet,
+// each is the end of the furthest write as the servers keep the larger size
+pub struct PendingSizes {
+    defer_: bool,
+    sizes_: Mutex<HashMap<String, u64>>,
+}
+lazy_static! {
+    static ref PENDING_SIZES: PendingSizes = PendingSizes {
+        defer_: get_var(
+            "SFS_DEFER_SIZE_UPDATES".to_string(),
+            DEFER_SIZE_UPDATES.to_string()
+        )
+        .parse::<bool>()
+        .unwrap_or(DEFER_SIZE_UPDATES),
+        sizes_: Mutex::new(HashMap::new()),
+    };
+}
+impl PendingSizes {
+    pub fn get_instance() -> &'static PendingSizes {
+        &PENDING_SIZES
+    }
+    pub fn enabled(&self) -> bool {
+        self.defer_
+    }
+    // returns how many files have a pending size
+    pub fn record(&self, path: &String, size: u64) -> usize {
+        let mut sizes = self.sizes_.lock().unwrap();
+        let pending = sizes.entry(path.clone()).or_insert(0);
+        *pending = u64::max(*pending, size);
+        sizes.len()
+    }
+    pub fn get(&self, path: &String) -> Option<u64> {
+        self.sizes_.lock().unwrap().get(path).cloned()
+    }
+    // forget the pending size of a removed file
+    pub fn remove(&self, path: &String) {
+        self.sizes_.lock().unwrap().remove(path);
+    }
+    pub fn commit(&self, path: &String) -> i32 {
+        let size = self.sizes_.lock().unwrap().remove(path);
+        match size {
+            Some(size) => self.commit_entries(vec![(path.clone(), size)]),
+            None => 0,
+        }
+    }
+    pub fn commit_all(&self) -> i32 {
+        let entries: Vec<(String, u64)> = self.sizes_.lock().unwrap().drain().collect();
+        if entries.len() == 0 {
+            return 0;
+        }
+        self.commit_entries(entries)
+    }
+    // entries that could not be committed stay pending
+    fn commit_entries(&self, entries: Vec<(String, u64)>) -> i32 {
+        let err = forward_update_size_batch(&entries);
+        if err != 0 {
+            for (path, size) in entries.iter() {
+                self.record(path, *size);
+            }
+        }
+        err
+    }
+    pub fn batch_full(&self, pending: usize) -> bool {
+        pending >= SIZE_BATCH_FILES
+    }
+}
@@ -0,0 +1,36 @@
+use lazy_static::*;
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use sfs_global::global::placement::Placement;
+
+// placements of files with chunks moved off full hosts, as the last stat or placement request
+// returned them. files missing here have all their chunks where the distributor puts them
+pub struct PlacementCache {
+    placements_: Mutex<HashMap<String, Placement>>,
+}
+lazy_static! {
+    static ref PLACEMENT_CACHE: PlacementCache = PlacementCache {
+        placements_: Mutex::new(HashMap::new()),
+    };
+}
+impl PlacementCache {
+    pub fn get_instance() -> &'static PlacementCache {
+        &PLACEMENT_CACHE
+    }
+    pub fn get(&self, path: &String) -> Placement {
+        match self.placements_.lock().unwrap().get(path) {
+            Some(placement) => placement.clone(),
+            None => Placement::new(),
+        }
+    }
+    pub fn set(&self, path: &String, placement: Placement) {
+        let mut placements = self.placements_.lock().unwrap();
+        if placement.is_empty() {
+            placements.remove(path);
+        } else {
+            placements.insert(path.clone(), placement);
+        }
+    }
+}
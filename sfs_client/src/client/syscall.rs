@@ -1,9 +1,8 @@
-use std::collections::hash_map::DefaultHasher;
 use std::ffi::CStr;
-use std::hash::{Hash, Hasher};
 use std::os::raw::c_char;
 use std::slice;
 use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Instant;
 
 use errno::{set_errno, Errno};
 #[allow(unused_imports)]
@@ -20,23 +19,27 @@ use libc::{
 
 use sfs_global::global;
 use sfs_global::global::error_msg::error_msg;
-use sfs_global::global::fsconfig::{ENABLE_STUFFING, ZERO_BUF_BEFORE_READ};
+use sfs_global::global::fsconfig::{ENABLE_STUFFING, STUFF_SIZE, ZERO_BUF_BEFORE_READ};
 use sfs_global::global::metadata::{S_ISDIR, S_ISREG};
-use sfs_global::global::network::config::CHUNK_SIZE;
+use sfs_global::global::network::config::{CHUNK_SIZE, DIRENT_PAGE_SIZE};
 use sfs_global::global::util::path_util::dirname;
-use xxhash_rust::xxh3::xxh3_64;
 
-use super::config::CHECK_PARENT_DIR;
-use super::context::StaticContext;
+use super::attr_cache::AttrCache;
+use super::chunk_cache::ChunkCache;
+use super::config::{CHECK_PARENT_DIR, READDIR_PLUS};
+use super::io_service::IoService;
 #[allow(unused_imports)]
 use super::context::{interception_enabled, DynamicContext};
+use super::network::forward_erasure::get_layout;
 use super::network::forward_msg::{
     forward_create, forward_decr_size, forward_get_chunk_stat, forward_get_dirents,
     forward_get_metadentry_size, forward_read, forward_remove, forward_truncate,
     forward_update_metadentry_size, forward_write,
 };
-use super::openfile::{FileType, OpenFile};
-use super::util::{get_metadata, metadata_to_stat};
+use super::openfile::{FileType, OpenFile, SFSDirEntry, O_RDONLY};
+use super::pending_size::PendingSizes;
+use super::util::{child_path, get_fresh_metadata, get_metadata, metadata_to_stat, path_to_ino};
+use super::write_buffer::{write_buffer_size, WriteBuffer};
 
 #[no_mangle]
 pub extern "C" fn sfs_open(path: *const c_char, mode: u32, flag: i32) -> i32 {
@@ -57,8 +60,12 @@ pub extern "C" fn sfs_open(path: *const c_char, mode: u32, flag: i32) -> i32 {
         set_errno(Errno(ENOTSUP));
         return -1;
     }
-    let md_res = get_metadata(&s, false);
-    if let Err(e) = md_res {
+    let md_res = if flag & O_TRUNC != 0 {
+        get_fresh_metadata(&s)
+    } else {
+        get_metadata(&s, false)
+    };
+    let file_id = if let Err(e) = md_res {
         if e == ENOENT {
             if flag & O_CREAT == 0 {
                 error_msg(
@@ -76,12 +83,15 @@ pub extern "C" fn sfs_open(path: *const c_char, mode: u32, flag: i32) -> i32 {
                 set_errno(Errno(ENOTSUP));
                 return -1;
             }
-            if sfs_create(path, mode | S_IFREG) != 0 {
-                error_msg(
-                    "client::sfs_open".to_string(),
-                    "error occurs while creating non-existing file".to_string(),
-                );
-                return -1;
+            match internal_create(&s, mode | S_IFREG) {
+                Some(file_id) => file_id,
+                None => {
+                    error_msg(
+                        "client::sfs_open".to_string(),
+                        "error occurs while creating non-existing file".to_string(),
+                    );
+                    return -1;
+                }
             }
         } else {
             error_msg(
@@ -103,7 +113,7 @@ pub extern "C" fn sfs_open(path: *const c_char, mode: u32, flag: i32) -> i32 {
             return sfs_opendir(path);
         }
         if flag & O_TRUNC != 0 && (flag & O_RDONLY != 0 || flag & O_WRONLY != 0) {
-            if internal_truncate(path, md.get_size(), 0) != 0 {
+            if internal_truncate(path, md.get_file_id(), md.get_size(), 0) != 0 {
                 error_msg(
                     "client::sfs_open".to_string(),
                     "fail to truncate 'O_TRUNC' file".to_string(),
@@ -111,16 +121,15 @@ pub extern "C" fn sfs_open(path: *const c_char, mode: u32, flag: i32) -> i32 {
                 return -1;
             }
         }
-    }
+        md.get_file_id()
+    };
+    let mut open_file = OpenFile::new(&s, flag, FileType::SFS_REGULAR);
+    open_file.set_file_id(file_id);
     return DynamicContext::get_instance()
         .get_ofm()
         .lock()
         .unwrap()
-        .add(Arc::new(Mutex::new(OpenFile::new(
-            &s,
-            flag,
-            FileType::SFS_REGULAR,
-        ))));
+        .add(Arc::new(Mutex::new(open_file)));
 }
 fn check_parent_dir(path: &String) -> i32 {
     if !CHECK_PARENT_DIR {
@@ -205,34 +214,53 @@ pub extern "C" fn sfs_create(path: *const c_char, mut mode: u32) -> i32 {
         }
     }
     let path = unsafe { CStr::from_ptr(path).to_string_lossy().into_owned() };
-    if check_parent_dir(&path) != 0 {
+    match internal_create(&path, mode) {
+        Some(_file_id) => return 0,
+        None => return -1,
+    }
+}
+// create 'path' and return its file id, the one handed out by the metadata servers or the one
+// of the file created first by someone else. None with errno set if it fails
+fn internal_create(path: &String, mode: u32) -> Option<u64> {
+    if check_parent_dir(path) != 0 {
         error_msg(
             "client:sfs_create".to_string(),
             "check parent failed".to_string(),
         );
-        return -1;
+        return None;
     }
-    let create_res = forward_create(&path, mode);
+    let create_res = forward_create(path, mode);
     if let Err(_e) = create_res {
         error_msg(
             "client:sfs_create".to_string(),
             "error occurs while creating file".to_string(),
         );
         set_errno(Errno(EBUSY));
-        return -1;
-    } else {
-        let err = create_res.unwrap();
-        if err != 0 && err != EEXIST {
-            set_errno(Errno(err));
-            return -1;
+        return None;
+    }
+    let (err, file_id) = create_res.unwrap();
+    if err == EEXIST {
+        match get_fresh_metadata(path) {
+            Ok(md) => return Some(md.get_file_id()),
+            Err(e) => {
+                set_errno(Errno(e));
+                return None;
+            }
         }
-        return 0;
     }
+    if err != 0 {
+        set_errno(Errno(err));
+        return None;
+    }
+    return Some(file_id);
 }
 #[no_mangle]
 pub extern "C" fn sfs_remove(path: *const c_char) -> i32 {
     let path = unsafe { CStr::from_ptr(path).to_string_lossy().into_owned() };
-    let md_res = get_metadata(&path, false);
+    if flush_path(&path) != 0 {
+        return -1;
+    }
+    let md_res = get_fresh_metadata(&path);
     if let Err(e) = md_res {
         error_msg(
             "client::sfs_remove".to_string(),
@@ -242,8 +270,9 @@ pub extern "C" fn sfs_remove(path: *const c_char) -> i32 {
         return -1;
     }
     let md = md_res.unwrap();
+    PendingSizes::get_instance().remove(&path);
     let has_data = S_ISREG(md.get_mode()) && md.get_size() != 0;
-    let rm_res = forward_remove(&path, !has_data, md.get_size());
+    let rm_res = forward_remove(&path, md.get_file_id(), !has_data, md.get_size());
     if let Err(_e) = rm_res {
         error_msg(
             "client::sfs_remove".to_string(),
@@ -297,6 +326,9 @@ pub struct stat {
 #[no_mangle]
 pub extern "C" fn sfs_stat(path: *const c_char, buf: *mut stat, _follow_links: bool) -> i32 {
     let path = unsafe { CStr::from_ptr(path).to_string_lossy().into_owned() };
+    if flush_path(&path) != 0 {
+        return -1;
+    }
     let md_res = get_metadata(&path, false);
     if let Err(_e) = md_res {
         return -1;
@@ -394,12 +426,23 @@ pub fn internal_lseek(
             fd.set_pos(curr_pos + offset);
         }
         SEEK_END => {
+            // directory offsets are cursors from 'telldir', they have no end
+            if let FileType::SFS_DIRECTORY = fd.get_type() {
+                set_errno(Errno(EINVAL));
+                return (fd, -1);
+            }
+            if flush_write_buffer(&mut fd) != 0 {
+                return (fd, -1);
+            }
             let ret = forward_get_metadentry_size(fd.get_path());
             if ret.0 != 0 {
                 set_errno(Errno(ret.0));
                 return (fd, -1);
             }
-            let file_size = ret.1;
+            let file_size = match PendingSizes::get_instance().get(fd.get_path()) {
+                Some(size) => i64::max(ret.1, size as i64),
+                None => ret.1,
+            };
             if offset < 0 && file_size < -offset {
                 set_errno(Errno(EINVAL));
                 return (fd, -1);
@@ -423,7 +466,12 @@ pub fn internal_lseek(
     return (fd, pos);
 }
 #[no_mangle]
-pub extern "C" fn internal_truncate(path: *const c_char, old_size: i64, new_size: i64) -> i32 {
+pub extern "C" fn internal_truncate(
+    path: *const c_char,
+    file_id: u64,
+    old_size: i64,
+    new_size: i64,
+) -> i32 {
     if new_size < 0 || new_size > old_size {
         return -1;
     }
@@ -431,12 +479,18 @@ pub extern "C" fn internal_truncate(path: *const c_char, old_size: i64, new_size
         return 0;
     }
     let path = unsafe { CStr::from_ptr(path).to_string_lossy().into_owned() };
+    // a deferred size would grow the file again later
+    let err = PendingSizes::get_instance().commit(&path);
+    if err != 0 {
+        set_errno(Errno(err));
+        return -1;
+    }
     let err = forward_decr_size(&path, new_size);
     if err != 0 {
         set_errno(Errno(err));
         return -1;
     }
-    let err = forward_truncate(&path, old_size, new_size);
+    let err = forward_truncate(&path, file_id, old_size, new_size);
     if err != 0 {
         set_errno(Errno(err));
         return -1;
@@ -446,12 +500,15 @@ pub extern "C" fn internal_truncate(path: *const c_char, old_size: i64, new_size
 #[no_mangle]
 pub extern "C" fn sfs_truncate(path: *const c_char, length: i64) -> i32 {
     let spath = unsafe { CStr::from_ptr(path).to_string_lossy().into_owned() };
-    let md_res = get_metadata(&spath, false);
+    if flush_path(&spath) != 0 {
+        return -1;
+    }
+    let md_res = get_fresh_metadata(&spath);
     if let Err(_e) = md_res {
         return -1;
     }
     let md = md_res.unwrap();
-    return internal_truncate(path, md.get_size(), length);
+    return internal_truncate(path, md.get_file_id(), md.get_size(), length);
 }
 #[no_mangle]
 pub extern "C" fn sfs_dup(oldfd: i32) -> i32 {
@@ -469,8 +526,135 @@ pub extern "C" fn sfs_dup2(oldfd: i32, newfd: i32) -> i32 {
         .unwrap()
         .dup2(oldfd, newfd);
 }
+// one size update and one transfer, bypassing the write-back buffer.
+// the size update is left pending if size updates are deferred
+fn write_through(
+    path: &String,
+    file_id: u64,
+    buf: *const c_char,
+    count: i64,
+    offset: i64,
+    append_flag: bool,
+) -> i64 {
+    // small writes travel with the size update and stay there while the file is stuffed
+    let stuff = ENABLE_STUFFING
+        && !append_flag
+        && offset + count <= STUFF_SIZE as i64
+        && get_layout(path).is_none();
+    // the size update may move stuffed data to chunk 0, it has to come before writes into it
+    let defer = PendingSizes::get_instance().enabled()
+        && !append_flag
+        && !(ENABLE_STUFFING && offset < STUFF_SIZE as i64);
+    let updated_size = if defer {
+        offset + count
+    } else {
+        let ret_update_size = if stuff {
+            forward_update_metadentry_size(
+                path,
+                count as u64,
+                offset,
+                append_flag,
+                unsafe { slice::from_raw_parts(buf as *const u8, count as usize) }.to_vec(),
+            )
+        } else {
+            forward_update_metadentry_size(path, count as u64, offset, append_flag, vec![0; 0])
+        };
+        if ret_update_size.0 > 0 {
+            error_msg(
+                "client::sfs_pwrite".to_string(),
+                format!("update metadentry size with error {}", ret_update_size.0),
+            );
+            set_errno(Errno(ret_update_size.0));
+            return -1;
+        }
+        // kept inline, nothing goes to the chunks
+        if ret_update_size.0 == -1 {
+            return ret_update_size.1;
+        }
+        ret_update_size.1
+    };
+    let write_path = path.clone();
+    let addr = buf as u64;
+    let write_res = IoService::get_instance().submit(move || async move {
+        forward_write(
+            &write_path,
+            file_id,
+            addr as *const c_char,
+            append_flag,
+            offset,
+            count,
+            updated_size,
+        )
+        .await
+    });
+    if append_flag {
+        ChunkCache::get_instance().invalidate_path(path);
+    } else if count > 0 {
+        ChunkCache::get_instance().invalidate(
+            path,
+            offset as u64 / CHUNK_SIZE,
+            (offset + count - 1) as u64 / CHUNK_SIZE,
+        );
+    }
+    if write_res.0 != 0 {
+        error_msg(
+            "client::sfs_pwrite".to_string(),
+            format!("write with error {}", write_res.0),
+        );
+        set_errno(Errno(write_res.0));
+        return -1;
+    }
+    if defer {
+        let pending = PendingSizes::get_instance().record(path, (offset + count) as u64);
+        if PendingSizes::get_instance().batch_full(pending) {
+            let err = PendingSizes::get_instance().commit_all();
+            if err != 0 {
+                set_errno(Errno(err));
+                return -1;
+            }
+        }
+    }
+    return write_res.1;
+}
+// send the buffered writes of 'f' to the servers
+fn flush_write_buffer(f: &mut OpenFile) -> i32 {
+    if let Some(write_buffer) = f.take_write_buffer() {
+        let data = write_buffer.get_data();
+        let write_res = write_through(
+            f.get_path(),
+            f.get_file_id(),
+            data.as_ptr() as *const c_char,
+            data.len() as i64,
+            write_buffer.get_offset(),
+            false,
+        );
+        if write_res < 0 {
+            return -1;
+        }
+    }
+    return 0;
+}
+// writes buffered by other descriptors of 'path' become visible to path based calls
+fn flush_path(path: &String) -> i32 {
+    if write_buffer_size() == 0 {
+        return 0;
+    }
+    let files = DynamicContext::get_instance()
+        .get_ofm()
+        .lock()
+        .unwrap()
+        .get_by_path(path);
+    for f in files {
+        if flush_write_buffer(&mut f.lock().unwrap()) != 0 {
+            return -1;
+        }
+    }
+    return 0;
+}
+// small sequential writes are gathered in the write-back buffer of the file when it is enabled,
+// so a whole buffer costs one size update and one transfer
 fn internal_pwrite(
-    f: MutexGuard<'_, OpenFile>,
+    mut f: MutexGuard<'_, OpenFile>,
     buf: *const c_char,
     count: i64,
     offset: i64,
@@ -487,50 +671,96 @@ fn internal_pwrite(
         FileType::SFS_REGULAR => {}
     }
     let append_flag = f.get_flag(super::openfile::OpenFileFlags::Append);
-    let path = f.get_path();
-    let ret_update_size = if ENABLE_STUFFING && offset + count < CHUNK_SIZE as i64 {
-        forward_update_metadentry_size(
-            path,
-            count as u64,
-            offset,
-            append_flag,
-            unsafe { slice::from_raw_parts(buf as *const u8, count as usize) }.to_vec(),
-        )
-    } else {
-        forward_update_metadentry_size(path, count as u64, offset, append_flag, vec![0; 0])
-    };
-    if ret_update_size.0 > 0 {
-        error_msg(
-            "client::sfs_pwrite".to_string(),
-            format!("update metadentry size with error {}", ret_update_size.0),
-        );
-        set_errno(Errno(ret_update_size.0));
-        return (f, -1);
+    let file_id = f.get_file_id();
+    let buffer_size = write_buffer_size();
+    if append_flag || buffer_size == 0 {
+        let write_res = write_through(f.get_path(), file_id, buf, count, offset, append_flag);
+        return (f, write_res);
     }
-    // stuffed file
-    if ret_update_size.0 == -1 {
-        return (f, ret_update_size.1);
+    if let Some(write_buffer) = f.get_write_buffer() {
+        if write_buffer.get_end() != offset || write_buffer.len() + count as u64 > buffer_size {
+            if flush_write_buffer(&mut f) != 0 {
+                return (f, -1);
+            }
+        }
     }
-    let updated_size = ret_update_size.1;
-    let write_res = StaticContext::get_instance()
-        .get_runtime()
-        .block_on(forward_write(
-            path,
-            buf,
-            append_flag,
-            offset,
-            count,
-            updated_size,
-        ));
-    if write_res.0 != 0 {
-        error_msg(
-            "client::sfs_pwrite".to_string(),
-            format!("write with error {}", write_res.0),
-        );
-        set_errno(Errno(write_res.0));
-        return (f, -1);
+    if count as u64 >= buffer_size {
+        let write_res = write_through(f.get_path(), file_id, buf, count, offset, false);
+        return (f, write_res);
+    }
+    if let None = f.get_write_buffer() {
+        match WriteBuffer::new(offset) {
+            Some(write_buffer) => f.set_write_buffer(write_buffer),
+            None => {
+                let write_res = write_through(f.get_path(), file_id, buf, count, offset, false);
+                return (f, write_res);
+            }
+        }
     }
-    return (f, write_res.1);
+    let write_buffer = f.get_write_buffer().unwrap();
+    write_buffer.append(unsafe { slice::from_raw_parts(buf as *const u8, count as usize) });
+    if write_buffer.len() == buffer_size {
+        if flush_write_buffer(&mut f) != 0 {
+            return (f, -1);
+        }
+    }
+    return (f, count);
+}
+#[no_mangle]
+pub extern "C" fn sfs_close(fd: i32) -> i32 {
+    let f = DynamicContext::get_instance()
+        .get_ofm()
+        .lock()
+        .unwrap()
+        .get(fd);
+    if let None = f {
+        set_errno(Errno(EBADF));
+        return -1;
+    }
+    let err = commit_file(&mut f.unwrap().lock().unwrap());
+    DynamicContext::get_instance()
+        .get_ofm()
+        .lock()
+        .unwrap()
+        .remove(fd);
+    return err;
+}
+#[no_mangle]
+pub extern "C" fn sfs_fsync(fd: i32) -> i32 {
+    let f = DynamicContext::get_instance()
+        .get_ofm()
+        .lock()
+        .unwrap()
+        .get(fd);
+    if let None = f {
+        set_errno(Errno(EBADF));
+        return -1;
+    }
+    return commit_file(&mut f.unwrap().lock().unwrap());
+}
+// buffered writes and the deferred size of 'f' reach the servers
+fn commit_file(f: &mut OpenFile) -> i32 {
+    if flush_write_buffer(f) != 0 {
+        return -1;
+    }
+    let err = PendingSizes::get_instance().commit(f.get_path());
+    if err != 0 {
+        set_errno(Errno(err));
+        return -1;
+    }
+    return 0;
+}
+// registered with 'atexit', processes rarely close everything they wrote
+pub extern "C" fn commit_at_exit() {
+    let files = DynamicContext::get_instance()
+        .get_ofm()
+        .lock()
+        .unwrap()
+        .get_all();
+    for f in files {
+        flush_write_buffer(&mut f.lock().unwrap());
+    }
+    PendingSizes::get_instance().commit_all();
 }
 #[no_mangle]
 pub extern "C" fn sfs_pwrite(fd: i32, buf: *const c_char, count: i64, offset: i64) -> i64 {
@@ -579,8 +809,129 @@ pub extern "C" fn sfs_write(fd: i32, buf: *const c_char, count: i64) -> i64 {
     }
     return write_res;
 }
+// fetch chunks 'chunk_start' to 'chunk_end' of a file of 'size' bytes into the chunk cache
+fn fetch_chunks(
+    path: &String,
+    file_id: u64,
+    chunk_start: u64,
+    chunk_end: u64,
+    size: i64,
+    expire: Instant,
+) -> bool {
+    let offset = (chunk_start * CHUNK_SIZE) as i64;
+    let len = i64::min(((chunk_end + 1) * CHUNK_SIZE) as i64, size) - offset;
+    let mut data = vec![0u8; len as usize];
+    let read_path = path.clone();
+    let addr = data.as_mut_ptr() as u64;
+    let read_res = IoService::get_instance().submit(move || async move {
+        forward_read(&read_path, file_id, addr as *mut c_char, offset, len).await
+    });
+    if read_res.0 != 0 {
+        return false;
+    }
+    for (idx, chunk) in data.chunks(CHUNK_SIZE as usize).enumerate() {
+        ChunkCache::get_instance().insert(path, chunk_start + idx as u64, chunk.to_vec(), expire);
+    }
+    return true;
+}
+// serve a read of a stuffed file from the data that came with its metadata.
+// None if the file has chunks
+fn stuffed_read(path: &String, buf: *mut c_char, offset: i64, count: i64) -> Option<i64> {
+    if !ENABLE_STUFFING || count <= 0 {
+        return None;
+    }
+    let mut md = get_metadata(path, false).ok()?;
+    if !md.is_stuffed() {
+        return None;
+    }
+    // a deferred size may be about to move the data to the chunks, attributes from a
+    // directory listing don't carry the data
+    if PendingSizes::get_instance().get(path).is_some() || md.get_inline_data().is_none() {
+        if PendingSizes::get_instance().commit(path) != 0 {
+            return None;
+        }
+        md = get_fresh_metadata(path).ok()?;
+    }
+    let data = md.get_inline_data()?;
+    let size = md.get_size();
+    if offset >= size {
+        return Some(0);
+    }
+    let end = i64::min(offset + count, size);
+    let avail = i64::max(i64::min(end, data.len() as i64) - offset, 0);
+    unsafe {
+        if avail > 0 {
+            std::ptr::copy_nonoverlapping(
+                data.as_ptr().add(offset as usize),
+                buf as *mut u8,
+                avail as usize,
+            );
+        }
+        // the rest is a hole
+        memset((buf as *mut u8).add(avail as usize) as *mut c_void, 0, (end - offset - avail) as usize);
+    }
+    return Some(end - offset);
+}
+// serve a read from the chunk cache, sequential reads fill it ahead of the read position.
+// None if the read has to go to the servers directly
+fn cached_read(
+    path: &String,
+    file_id: u64,
+    buf: *mut c_char,
+    offset: i64,
+    count: i64,
+    sequential: bool,
+) -> Option<i64> {
+    if !ChunkCache::get_instance().enabled() || count <= 0 {
+        return None;
+    }
+    // nothing is cached in "strict" mode or without a lease, reads then skip the stat
+    let expire = AttrCache::get_instance().get_expire(path)?;
+    let size = get_metadata(path, false).ok()?.get_size();
+    if offset >= size {
+        return Some(0);
+    }
+    let end = i64::min(offset + count, size);
+    let chunk_start = offset as u64 / CHUNK_SIZE;
+    let chunk_end = (end - 1) as u64 / CHUNK_SIZE;
+    let missing = (chunk_start..(chunk_end + 1))
+        .find(|chunk_id| ChunkCache::get_instance().get(path, *chunk_id).is_none());
+    if let Some(first_missing) = missing {
+        if !sequential {
+            return None;
+        }
+        let last_chunk = (size - 1) as u64 / CHUNK_SIZE;
+        let fetch_end = u64::min(chunk_end + ChunkCache::get_instance().get_read_ahead(), last_chunk);
+        if !fetch_chunks(path, file_id, first_missing, fetch_end, size, expire) {
+            return None;
+        }
+    }
+    let out = unsafe { std::slice::from_raw_parts_mut(buf as *mut u8, (end - offset) as usize) };
+    for chunk_id in chunk_start..(chunk_end + 1) {
+        // a tiny cache may have evicted chunks of this very read
+        let chunk = ChunkCache::get_instance().get(path, chunk_id)?;
+        copy_cached_chunk(&chunk, chunk_id, offset, out);
+    }
+    return Some(end - offset);
+}
+// copy the part of cached chunk 'chunk_id' that a read from 'offset' into 'out' covers. the
+// chunk may have been cached before the file grew, the bytes past it are a hole then
+pub fn copy_cached_chunk(chunk: &[u8], chunk_id: u64, offset: i64, out: &mut [u8]) {
+    let chunk_begin = (chunk_id * CHUNK_SIZE) as i64;
+    let from = i64::max(offset, chunk_begin);
+    let to = i64::min(offset + out.len() as i64, chunk_begin + CHUNK_SIZE as i64);
+    if to <= from {
+        return;
+    }
+    let data_to = i64::max(i64::min(to, chunk_begin + chunk.len() as i64), from);
+    if data_to > from {
+        let data = &chunk[(from - chunk_begin) as usize..(data_to - chunk_begin) as usize];
+        out[(from - offset) as usize..(data_to - offset) as usize].copy_from_slice(data);
+    }
+    out[(data_to - offset) as usize..(to - offset) as usize].fill(0);
+}
 fn internal_pread(
-    f: MutexGuard<'_, OpenFile>,
+    mut f: MutexGuard<'_, OpenFile>,
     buf: *mut c_char,
     count: i64,
     offset: i64,
@@ -596,13 +947,29 @@ fn internal_pread(
         }
         FileType::SFS_REGULAR => {}
     }
+    // reads see what this file wrote before
+    if flush_write_buffer(&mut f) != 0 {
+        return (f, -1);
+    }
     if ZERO_BUF_BEFORE_READ {
         unsafe {
             memset(buf as *mut c_void, 0, count as usize);
         }
     }
+    let sequential = f.track_read(offset, count);
     let path = f.get_path();
-    let read_res = StaticContext::get_instance().get_runtime().block_on(forward_read(path, buf, offset, count));
+    let file_id = f.get_file_id();
+    if let Some(read) = stuffed_read(path, buf, offset, count) {
+        return (f, read);
+    }
+    if let Some(read) = cached_read(path, file_id, buf, offset, count, sequential) {
+        return (f, read);
+    }
+    let read_path = path.clone();
+    let addr = buf as u64;
+    let read_res = IoService::get_instance().submit(move || async move {
+        forward_read(&read_path, file_id, addr as *mut c_char, offset, count).await
+    });
     if read_res.0 != 0 {
         error_msg(
             "client::sfs_pread".to_string(),
@@ -672,19 +1039,20 @@ pub extern "C" fn sfs_rmdir(path: *const c_char) -> i32 {
         set_errno(Errno(ENOTDIR));
         return -1;
     }
-    let dirent_res = StaticContext::get_instance()
-        .get_runtime()
-        .block_on(forward_get_dirents(&path));
-    if dirent_res.0 != 0 {
+    // one entry is enough to tell whether the directory is empty
+    let dir_path = path.clone();
+    let dirent_res = IoService::get_instance().submit(move || async move {
+        forward_get_dirents(&dir_path, &"".to_string(), 1, false).await
+    });
+    if let Err(e) = dirent_res {
         error_msg(
             "client::sfs_rmdir".to_string(),
-            format!("forward get dirents with error {}", dirent_res.0),
+            format!("forward get dirents with error {}", e),
         );
-        set_errno(Errno(dirent_res.0));
+        set_errno(Errno(e));
         return -1;
     }
-    let opendir = dirent_res.1;
-    if opendir.lock().unwrap().get_size() != 0 {
+    if dirent_res.unwrap().len() != 0 {
         error_msg(
             "client::sfs_rmdir".to_string(),
             "directory not empty".to_string(),
@@ -692,11 +1060,11 @@ pub extern "C" fn sfs_rmdir(path: *const c_char) -> i32 {
         set_errno(Errno(ENOTEMPTY));
         return -1;
     }
-    let rm_res = forward_remove(&path, true, 0);
+    let rm_res = forward_remove(&path, 0, true, 0);
     if let Err(_e) = rm_res {
         error_msg(
             "client::sfs_rmdir".to_string(),
-            format!("forward remove directory with error {}", dirent_res.0),
+            "forward remove directory failed".to_string(),
         );
         return -1;
     }
@@ -728,22 +1096,61 @@ pub extern "C" fn sfs_opendir(path: *const c_char) -> i32 {
         set_errno(Errno(ENOTDIR));
         return -1;
     }
-    let dirent_res = StaticContext::get_instance()
-        .get_runtime()
-        .block_on(forward_get_dirents(&path));
-    if dirent_res.0 != 0 {
+    let mut open_dir = OpenFile::new(&path, O_RDONLY, FileType::SFS_DIRECTORY);
+    let err = load_dir_page(&mut open_dir, 0);
+    if err != 0 {
         error_msg(
             "client::sfs_opendir".to_string(),
-            format!("forward get dirents with error {}", dirent_res.0),
+            format!("forward get dirents with error {}", err),
         );
-        set_errno(Errno(dirent_res.0));
+        set_errno(Errno(err));
         return -1;
     }
     return DynamicContext::get_instance()
         .get_ofm()
         .lock()
         .unwrap()
-        .add(dirent_res.1);
+        .add(Arc::new(Mutex::new(open_dir)));
+}
+// directories are read page by page, only the current page is kept in 'entries_'
+fn load_dir_page(dir: &mut OpenFile, page: u64) -> i32 {
+    let cursor = dir.get_page_cursor(page);
+    if let None = cursor {
+        // offset of a page never reached, so it can't come from 'telldir'
+        return EINVAL;
+    }
+    let dir_path = dir.get_path().clone();
+    let cursor = cursor.unwrap();
+    let dirent_res = IoService::get_instance().submit(move || async move {
+        forward_get_dirents(&dir_path, &cursor, DIRENT_PAGE_SIZE, READDIR_PLUS).await
+    });
+    if let Err(e) = dirent_res {
+        return e;
+    }
+    let entries = dirent_res.unwrap();
+    let last_page = (entries.len() as u64) < DIRENT_PAGE_SIZE;
+    dir.set_page(page, entries, last_page);
+    return 0;
+}
+// entry at directory offset 'pos' and the offset following it, None at the end of directory
+fn next_dirent(dir: &mut OpenFile, mut pos: i64) -> Result<Option<(Arc<SFSDirEntry>, i64)>, i32> {
+    loop {
+        let page = pos as u64 / DIRENT_PAGE_SIZE;
+        let index = pos as u64 % DIRENT_PAGE_SIZE;
+        if page != dir.get_page() {
+            let err = load_dir_page(dir, page);
+            if err != 0 {
+                return Err(err);
+            }
+        }
+        if (index as usize) < dir.get_size() {
+            return Ok(Some((dir.getdent(index as i64), pos + 1)));
+        }
+        if dir.is_last_page() {
+            return Ok(None);
+        }
+        pos = ((page + 1) * DIRENT_PAGE_SIZE) as i64;
+    }
 }
 fn align(size: usize, step: usize) -> usize {
     (size + step) & (!step + 1)
@@ -776,50 +1183,49 @@ pub extern "C" fn sfs_getdents(fd: i32, dirp: *mut linux_dirent, count: i64) ->
         return -1;
     }
     let opendir = opendir.unwrap();
-    let mut pos = opendir.lock().unwrap().get_pos();
-    if pos >= opendir.lock().unwrap().get_size() as i64 {
-        return 0;
-    }
+    let mut dir = opendir.lock().unwrap();
+    let mut pos = dir.get_pos();
     let mut written = 0;
-    let size = opendir.lock().unwrap().get_size() as i64;
-    while pos < size {
-        let de = opendir.lock().unwrap().getdent(pos);
+    loop {
+        let next = next_dirent(&mut dir, pos);
+        if let Err(e) = next {
+            set_errno(Errno(e));
+            return -1;
+        }
+        let next = next.unwrap();
+        if let None = next {
+            break;
+        }
+        let (de, next_pos) = next.unwrap();
         let total_size = align(18 + de.get_name().len() + 1 + 1, 8);
         if total_size as i64 > count - written {
             break;
         }
         let current_dirp =
             unsafe { (dirp as *mut c_char).offset(written as isize) as *mut linux_dirent };
-        let mut s = DefaultHasher::new();
-        let p = opendir.lock().unwrap().get_path().clone() + "/" + &de.get_name();
-        p.hash(&mut s);
+        let p = child_path(dir.get_path(), &de.get_name());
         let name = de.get_name() + "\0";
         unsafe {
-            (*current_dirp).d_ino = s.finish() as u64;
+            (*current_dirp).d_ino = path_to_ino(&p);
             (*current_dirp).d_reclen = total_size as u16;
             let c: u8;
             match de.get_type() {
                 FileType::SFS_REGULAR => c = DT_REG,
                 FileType::SFS_DIRECTORY => c = DT_DIR,
             }
-            /*
-            strcpy(
-                (*current_dirp).d_name.as_ptr() as *mut i8,
-                name.as_ptr() as *const i8,
-            );
-            */
             memcpy(
                 (current_dirp as *const c_char).offset(18) as *mut c_void,
                 name.as_ptr() as *const c_void,
                 name.len(),
             );
             *(current_dirp as *mut u8).offset(total_size as isize - 1) = c;
-            (*current_dirp).d_off = pos as u64;
-            pos += 1;
+            // offset of the next entry, what 'telldir' reports after this one
+            (*current_dirp).d_off = next_pos as u64;
+            pos = next_pos;
             written += total_size as i64;
         }
     }
-    opendir.lock().unwrap().set_pos(pos);
+    dir.set_pos(pos);
     return written as i32;
 }
 #[no_mangle]
@@ -841,24 +1247,30 @@ pub extern "C" fn sfs_getdents64(fd: i32, dirp: *mut dirent64, count: i64) -> i3
         return -1;
     }
     let opendir = opendir.unwrap();
-    let mut pos = opendir.lock().unwrap().get_pos();
-    if pos >= opendir.lock().unwrap().get_size() as i64 {
-        return 0;
-    }
+    let mut dir = opendir.lock().unwrap();
+    let mut pos = dir.get_pos();
     let mut written = 0;
-    let size = opendir.lock().unwrap().get_size() as i64;
-    while pos < size {
-        let de = opendir.lock().unwrap().getdent(pos);
+    loop {
+        let next = next_dirent(&mut dir, pos);
+        if let Err(e) = next {
+            set_errno(Errno(e));
+            return -1;
+        }
+        let next = next.unwrap();
+        if let None = next {
+            break;
+        }
+        let (de, next_pos) = next.unwrap();
         let total_size = align(19 + de.get_name().len() + 1 + 1, 8);
         if total_size as i64 > count - written {
             break;
         }
         let current_dirp =
             unsafe { (dirp as *mut c_char).offset(written as isize) as *mut dirent64 };
-        let p = opendir.lock().unwrap().get_path().clone() + "/" + &de.get_name();
+        let p = child_path(dir.get_path(), &de.get_name());
         let name = de.get_name() + "\0";
         unsafe {
-            (*current_dirp).d_ino = xxh3_64(p.as_bytes()) as u64;
+            (*current_dirp).d_ino = path_to_ino(&p);
             (*current_dirp).d_reclen = total_size as u16;
             let c: u8;
             match de.get_type() {
@@ -866,22 +1278,16 @@ pub extern "C" fn sfs_getdents64(fd: i32, dirp: *mut dirent64, count: i64) -> i3
                 FileType::SFS_DIRECTORY => c = DT_DIR,
             }
             (*current_dirp).d_type = c;
-            /*
-            strcpy(
-                (*current_dirp).d_name.as_ptr() as *mut i8,
-                name.as_ptr() as *const i8,
-            );
-            */
             memcpy(
                 (current_dirp as *const c_char).offset(19) as *mut c_void,
                 name.as_ptr() as *const c_void,
                 name.len(),
             );
-            (*current_dirp).d_off = pos as i64;
-            pos += 1;
+            (*current_dirp).d_off = next_pos as i64;
+            pos = next_pos;
             written += total_size as i64;
         }
     }
-    opendir.lock().unwrap().set_pos(pos);
+    dir.set_pos(pos);
     return written as i32;
 }
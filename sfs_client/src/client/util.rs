@@ -1,28 +1,84 @@
+use crate::client::attr_cache::AttrCache;
 use crate::client::context::StaticContext;
+use crate::client::pending_size::PendingSizes;
 use errno::{set_errno, Errno};
 use libc::{makedev, stat};
 use sfs_global::global::distributor::Distributor;
+use sfs_global::global::fsconfig::Consistency;
 #[allow(unused_imports)]
 use sfs_global::global::{metadata::Metadata, network::config::CHUNK_SIZE};
+use std::time::{Duration, Instant};
 use xxhash_rust::xxh3::xxh3_64;
 
 use super::network::forward_msg;
 
-pub fn get_metadata(path: &String, _follow_link: bool) -> Result<Metadata, i32> {
-    let md_res = forward_msg::forward_stat(path);
+// sizes deferred by this process show up as if they were committed
+pub fn get_metadata(path: &String, follow_link: bool) -> Result<Metadata, i32> {
+    let mut md = lookup_metadata(path, follow_link)?;
+    if let Some(size) = PendingSizes::get_instance().get(path) {
+        if size as i64 > md.get_size() {
+            md.set_size(size as i64);
+        }
+    }
+    return Ok(md);
+}
+fn lookup_metadata(path: &String, _follow_link: bool) -> Result<Metadata, i32> {
+    let fs_config = StaticContext::get_instance().get_fsconfig();
+    if fs_config.consistency != Consistency::Strict {
+        if let Some(md) = AttrCache::get_instance().get(path) {
+            return Ok(Metadata::deserialize(&md));
+        }
+    }
+    // leases start no earlier on the server than the request is sent
+    let sent = Instant::now();
+    let md_res = match fs_config.consistency {
+        Consistency::Strict => forward_msg::forward_stat(path),
+        Consistency::Lease => forward_msg::forward_stat_lease(path).map(|(md, lease_ms)| {
+            AttrCache::get_instance().insert(
+                path.clone(),
+                md.clone(),
+                sent + Duration::from_millis(lease_ms),
+            );
+            md
+        }),
+        Consistency::Relaxed => forward_msg::forward_stat(path).map(|md| {
+            AttrCache::get_instance().insert(
+                path.clone(),
+                md.clone(),
+                sent + Duration::from_millis(fs_config.lease_ms),
+            );
+            md
+        }),
+    };
     if let Err(e) = md_res {
         set_errno(Errno(e));
         return Err(e);
     }
     return Ok(Metadata::deserialize(&md_res.unwrap()));
 }
+// bypasses cached metadata, for callers acting on the file size
+pub fn get_fresh_metadata(path: &String) -> Result<Metadata, i32> {
+    AttrCache::get_instance().remove(path);
+    return get_metadata(path, false);
+}
 // bias is used to make sure pass ctime check of pfind
 // pfind ctime check will fail caused by non-syncing clock
 pub static BIAS: i64 = 300;
+// inode number of a path, 'd_ino' of directory entries must agree with it
+pub fn path_to_ino(path: &String) -> u64 {
+    xxh3_64(path.as_bytes())
+}
+pub fn child_path(dir: &String, name: &String) -> String {
+    if dir.ends_with('/') {
+        dir.clone() + name
+    } else {
+        dir.clone() + "/" + name
+    }
+}
 pub fn metadata_to_stat(path: &String, md: Metadata, attr: *mut stat) -> i32 {
     unsafe { (*attr).st_dev = makedev(0, 0) };
     unsafe {
-        (*attr).st_ino = xxh3_64(path.as_bytes());
+        (*attr).st_ino = path_to_ino(path);
         (*attr).st_nlink = 1;
         (*attr).st_uid = StaticContext::get_instance().get_fsconfig().uid;
         (*attr).st_gid = StaticContext::get_instance().get_fsconfig().gid;
@@ -0,0 +1,75 @@
+use lazy_static::*;
+use sfs_global::global::util::env_util::get_var;
+
+use std::sync::Mutex;
+
+use super::config::{WRITE_BUFFER_LIMIT, WRITE_BUFFER_SIZE};
+
+// memory budget shared by the write-back buffers of every open file
+struct WriteBufferBudget {
+    size_: u64,
+    limit_: u64,
+    used_: Mutex<u64>,
+}
+lazy_static! {
+    static ref BUDGET: WriteBufferBudget = WriteBufferBudget {
+        size_: get_var(
+            "SFS_WRITE_BUFFER_SIZE".to_string(),
+            WRITE_BUFFER_SIZE.to_string()
+        )
+        .parse::<u64>()
+        .unwrap_or(WRITE_BUFFER_SIZE),
+        limit_: get_var(
+            "SFS_WRITE_BUFFER_LIMIT".to_string(),
+            WRITE_BUFFER_LIMIT.to_string()
+        )
+        .parse::<u64>()
+        .unwrap_or(WRITE_BUFFER_LIMIT),
+        used_: Mutex::new(0),
+    };
+}
+// capacity of a write-back buffer, 0 if buffering is disabled
+pub fn write_buffer_size() -> u64 {
+    BUDGET.size_
+}
+
+// sequential writes of an open file not yet sent to the servers, they start at 'offset_'
+pub struct WriteBuffer {
+    offset_: i64,
+    data_: Vec<u8>,
+}
+impl WriteBuffer {
+    // None if the budget is used up, the write should go to the servers directly then
+    pub fn new(offset: i64) -> Option<WriteBuffer> {
+        let mut used = BUDGET.used_.lock().unwrap();
+        if *used + BUDGET.size_ > BUDGET.limit_ {
+            return None;
+        }
+        *used += BUDGET.size_;
+        Some(WriteBuffer {
+            offset_: offset,
+            data_: Vec::with_capacity(BUDGET.size_ as usize),
+        })
+    }
+    pub fn get_offset(&self) -> i64 {
+        self.offset_
+    }
+    // offset a write has to start at to be appended
+    pub fn get_end(&self) -> i64 {
+        self.offset_ + self.data_.len() as i64
+    }
+    pub fn len(&self) -> u64 {
+        self.data_.len() as u64
+    }
+    pub fn get_data(&self) -> &Vec<u8> {
+        &self.data_
+    }
+    pub fn append(&mut self, buf: &[u8]) {
+        self.data_.extend_from_slice(buf);
+    }
+}
+impl Drop for WriteBuffer {
+    fn drop(&mut self) {
+        *BUDGET.used_.lock().unwrap() -= BUDGET.size_;
+    }
+}
@@ -192,16 +192,87 @@ mod tests {
 
     #[allow(unused_imports)]
     use crate::client::{
+        config::{RPC_BACKOFF_MAX_MS, RPC_BACKOFF_MS},
         context::{DynamicContext, StaticContext},
+        network::rpc_policy::RpcPolicy,
         path::resolve,
         syscall::{
-            internal_truncate, sfs_create, sfs_dup, sfs_dup2, sfs_getdents, sfs_lseek, sfs_open,
+            copy_cached_chunk, sfs_create, sfs_dup, sfs_dup2, sfs_getdents, sfs_lseek, sfs_open,
             sfs_opendir, sfs_pread, sfs_pwrite, sfs_read, sfs_remove, sfs_rmdir, sfs_stat,
-            sfs_write,
+            sfs_truncate, sfs_write,
         },
     };
     use sfs_global::global::network::config::CHUNK_SIZE;
+    use std::time::Duration;
+
+    fn policy(failures: u32, open_ms: u64) -> RpcPolicy {
+        RpcPolicy::new(
+            Duration::from_millis(1000),
+            2,
+            failures,
+            Duration::from_millis(open_ms),
+            1,
+            4,
+        )
+    }
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        let policy = policy(3, 100);
+        assert_eq!(policy.backoff(0), Duration::from_millis(RPC_BACKOFF_MS));
+        assert_eq!(policy.backoff(1), Duration::from_millis(RPC_BACKOFF_MS));
+        assert_eq!(policy.backoff(2), Duration::from_millis(2 * RPC_BACKOFF_MS));
+        assert_eq!(policy.backoff(3), Duration::from_millis(4 * RPC_BACKOFF_MS));
+        assert_eq!(policy.backoff(64), Duration::from_millis(RPC_BACKOFF_MAX_MS));
+    }
+    #[test]
+    fn circuit_opens_after_failures_and_probes_once() {
+        let policy = policy(2, 50);
+        assert!(policy.allow(7));
+        policy.record_failure(7);
+        assert!(policy.allow(7));
+        policy.record_failure(7);
+        // open: the host is skipped, others are not
+        assert!(!policy.allow(7));
+        assert!(policy.allow(8));
+        thread::sleep(Duration::from_millis(60));
+        // half open: a single probe goes out
+        assert!(policy.allow(7));
+        assert!(!policy.allow(7));
+        policy.record_failure(7);
+        assert!(!policy.allow(7));
+        thread::sleep(Duration::from_millis(60));
+        assert!(policy.allow(7));
+        policy.record_success(7);
+        assert!(policy.allow(7));
+        assert!(policy.allow(7));
+    }
+    #[test]
+    fn circuit_stays_closed_without_a_threshold() {
+        let policy = policy(0, 50);
+        for _i in 0..10 {
+            policy.record_failure(3);
+        }
+        assert!(policy.allow(3));
+    }
 
+    #[test]
+    fn cached_chunk_short_of_a_grown_file_reads_zeros() {
+        // chunk 1 was cached holding 10 bytes, the file grew since
+        let chunk: Vec<u8> = (1..11).collect();
+        let chunk_begin = CHUNK_SIZE as i64;
+        let mut buf = vec![0xffu8; 20];
+        copy_cached_chunk(&chunk, 1, chunk_begin + 5, &mut buf);
+        assert_eq!(&buf[..5], &chunk[5..]);
+        assert!(buf[5..].iter().all(|byte| *byte == 0));
+        // a read of the gap alone
+        let mut buf = vec![0xffu8; 8];
+        copy_cached_chunk(&chunk, 1, chunk_begin + 16, &mut buf);
+        assert_eq!(buf, vec![0u8; 8]);
+        // the part of a read past the chunk is left to the next chunk
+        let mut buf = vec![0xffu8; 4];
+        copy_cached_chunk(&chunk, 0, chunk_begin - 2, &mut buf);
+        assert_eq!(buf, vec![0, 0, 0xff, 0xff]);
+    }
     #[test]
     fn test0() {
         std::net::TcpListener::bind(("127.0.0.1", 21312)).unwrap();
@@ -434,7 +505,7 @@ mod tests {
             println!("read: {}", String::from_utf8(buf).unwrap());
         }
 
-        let tres = internal_truncate(fpath_file1.as_str().as_ptr() as *const c_char, len, 13);
+        let tres = sfs_truncate(fpath_file1.as_str().as_ptr() as *const c_char, 13);
         if tres != 0 {
             println!("truncate error ...");
             return;
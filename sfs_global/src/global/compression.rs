@@ -0,0 +1,9 @@
+// files below one of the configured directories keep their chunks compressed
+pub fn is_compressed(dirs: &[String], path: &str) -> bool {
+    dirs.iter().any(|dir| {
+        let dir = dir.trim_end_matches('/');
+        dir.is_empty()
+            || path.eq(dir)
+            || (path.starts_with(dir) && path.as_bytes()[dir.len()] == b'/')
+    })
+}
@@ -3,9 +3,27 @@ use std::{collections::HashMap, sync::Arc};
 
 pub trait Distributor {
     fn localhost(&self) -> u64;
-    fn locate_data(&self, path: &String, chunk_id: u64) -> u64;
-    fn locate_file_metadata(&self, path: &String) -> u64;
-    fn locate_dir_metadata(&self, path: &String) -> Arc<Vec<u64>>;
+    fn locate_data(&self, path: &str, chunk_id: u64) -> u64;
+    fn locate_file_metadata(&self, path: &str) -> u64;
+    fn locate_dir_metadata(&self, path: &str) -> Arc<Vec<u64>>;
+    // ordered replica set of a chunk, the first host is always the primary returned by 'locate_data'
+    fn locate_data_replicas(&self, path: &str, chunk_id: u64) -> Vec<u64>;
+    // replica set of a chunk with the hosts of 'avoid' replaced by the next ones that are not,
+    // as long as there are enough of them
+    fn locate_data_replicas_avoiding(&self, path: &str, chunk_id: u64, avoid: &[u64]) -> Vec<u64> {
+        let _ = avoid;
+        self.locate_data_replicas(path, chunk_id)
+    }
+    // ordered replica set of a metadata key, the first host is always the primary returned by 'locate_file_metadata'
+    fn locate_file_metadata_replicas(&self, path: &str) -> Vec<u64>;
+    // hosts of the 'width' pieces of an erasure-coded stripe, distinct as long as there are enough hosts
+    fn locate_stripe(&self, path: &str, stripe_id: u64, width: u64) -> Vec<u64>;
+}
+
+// replicas are placed on the hosts following the primary so that they are always distinct
+fn successor_replicas(primary: u64, replicas: u64, hosts_size: u64) -> Vec<u64> {
+    let count = u64::max(1, u64::min(replicas, hosts_size));
+    (0..count).map(|i| (primary + i) % hosts_size).collect()
 }
 
 #[derive(Debug)]
@@ -13,6 +31,7 @@ pub struct SimpleHashDistributor {
     pub localhost_: u64,
     pub hosts_size_: u64,
     pub all_hosts_: Arc<Vec<u64>>,
+    pub replicas_: u64,
     //pub str_hash_: DefaultHasher
 }
 #[allow(unused_variables)]
@@ -21,20 +40,55 @@ impl Distributor for SimpleHashDistributor {
         self.localhost_
     }
 
-    fn locate_data(&self, path: &String, chunk_id: u64) -> u64 {
+    fn locate_data(&self, path: &str, chunk_id: u64) -> u64 {
         if chunk_id == 0{
             return self.locate_file_metadata(path);
         }
-        let s = path.clone() + &chunk_id.to_string();
+        let s = path.to_string() + &chunk_id.to_string();
         xxh3_64(s.as_bytes()) % self.hosts_size_
     }
 
-    fn locate_file_metadata(&self, path: &String) -> u64 {
+    fn locate_file_metadata(&self, path: &str) -> u64 {
         xxh3_64(path.as_bytes()) % self.hosts_size_
     }
 
-    fn locate_dir_metadata(&self, path: &String) -> Arc<Vec<u64>> {
-        Arc::clone(&self.all_hosts_)
+    // entries of a directory are recorded along with the directory's own metadata
+    fn locate_dir_metadata(&self, path: &str) -> Arc<Vec<u64>> {
+        Arc::new(self.locate_file_metadata_replicas(path))
+    }
+
+    fn locate_data_replicas(&self, path: &str, chunk_id: u64) -> Vec<u64> {
+        successor_replicas(self.locate_data(path, chunk_id), self.replicas_, self.hosts_size_)
+    }
+
+    fn locate_data_replicas_avoiding(&self, path: &str, chunk_id: u64, avoid: &[u64]) -> Vec<u64> {
+        let replicas = self.locate_data_replicas(path, chunk_id);
+        let primary = replicas[0];
+        let mut hosts: Vec<u64> = (0..self.hosts_size_)
+            .map(|i| (primary + i) % self.hosts_size_)
+            .filter(|host| !avoid.contains(host))
+            .collect();
+        // too few hosts left, the avoided ones fill up the set in their usual order
+        for host in replicas {
+            if hosts.len() >= self.replicas_ as usize {
+                break;
+            }
+            if !hosts.contains(&host) {
+                hosts.push(host);
+            }
+        }
+        hosts.truncate(u64::max(1, u64::min(self.replicas_, self.hosts_size_)) as usize);
+        hosts
+    }
+
+    fn locate_file_metadata_replicas(&self, path: &str) -> Vec<u64> {
+        successor_replicas(self.locate_file_metadata(path), self.replicas_, self.hosts_size_)
+    }
+
+    fn locate_stripe(&self, path: &str, stripe_id: u64, width: u64) -> Vec<u64> {
+        let s = path.to_string() + "#" + &stripe_id.to_string();
+        let start = xxh3_64(s.as_bytes()) % self.hosts_size_;
+        (0..width).map(|i| (start + i) % self.hosts_size_).collect()
     }
 }
 impl SimpleHashDistributor {
@@ -43,17 +97,25 @@ impl SimpleHashDistributor {
             localhost_: 0,
             hosts_size_: 0,
             all_hosts_: Arc::new(Vec::new()),
+            replicas_: 1,
             //str_hash_: DefaultHasher::new()
         }
     }
     pub fn new(host_id: u64, host_size: u64) -> SimpleHashDistributor {
+        SimpleHashDistributor::with_replicas(host_id, host_size, 1)
+    }
+    pub fn with_replicas(host_id: u64, host_size: u64, replicas: u64) -> SimpleHashDistributor {
         SimpleHashDistributor {
             localhost_: host_id,
             hosts_size_: host_size,
             all_hosts_: Arc::new((0..host_size).collect()),
+            replicas_: replicas,
             //str_hash_: DefaultHasher::new()
         }
     }
+    pub fn get_replicas(&self) -> u64 {
+        self.replicas_
+    }
 }
 
 pub struct LocalOnlyDistributor {
@@ -72,17 +134,29 @@ impl Distributor for LocalOnlyDistributor {
         self.localhost_
     }
 
-    fn locate_data(&self, path: &String, chunk_id: u64) -> u64 {
+    fn locate_data(&self, path: &str, chunk_id: u64) -> u64 {
         self.localhost_
     }
 
-    fn locate_file_metadata(&self, path: &String) -> u64 {
+    fn locate_file_metadata(&self, path: &str) -> u64 {
         self.localhost_
     }
 
-    fn locate_dir_metadata(&self, path: &String) -> Arc<Vec<u64>> {
+    fn locate_dir_metadata(&self, path: &str) -> Arc<Vec<u64>> {
         Arc::new(vec![self.localhost_])
     }
+
+    fn locate_data_replicas(&self, path: &str, chunk_id: u64) -> Vec<u64> {
+        vec![self.localhost_]
+    }
+
+    fn locate_file_metadata_replicas(&self, path: &str) -> Vec<u64> {
+        vec![self.localhost_]
+    }
+
+    fn locate_stripe(&self, path: &str, stripe_id: u64, width: u64) -> Vec<u64> {
+        vec![self.localhost_; width as usize]
+    }
 }
 
 pub struct ForwardDistributor {
@@ -97,15 +171,27 @@ impl Distributor for ForwardDistributor {
         self.fwd_host_
     }
 
-    fn locate_data(&self, path: &String, chunk_id: u64) -> u64 {
+    fn locate_data(&self, path: &str, chunk_id: u64) -> u64 {
         self.fwd_host_
     }
 
-    fn locate_file_metadata(&self, path: &String) -> u64 {
+    fn locate_file_metadata(&self, path: &str) -> u64 {
         self.str_hash_.get(path).unwrap() % self.hosts_size_
     }
 
-    fn locate_dir_metadata(&self, path: &String) -> Arc<Vec<u64>> {
-        Arc::clone(&self.all_hosts_)
+    fn locate_dir_metadata(&self, path: &str) -> Arc<Vec<u64>> {
+        Arc::new(vec![self.locate_file_metadata(path)])
+    }
+
+    fn locate_data_replicas(&self, path: &str, chunk_id: u64) -> Vec<u64> {
+        vec![self.fwd_host_]
+    }
+
+    fn locate_file_metadata_replicas(&self, path: &str) -> Vec<u64> {
+        vec![self.locate_file_metadata(path)]
+    }
+
+    fn locate_stripe(&self, path: &str, stripe_id: u64, width: u64) -> Vec<u64> {
+        vec![self.fwd_host_; width as usize]
     }
 }
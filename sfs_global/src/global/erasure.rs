@@ -0,0 +1,279 @@
+use libc::{EINVAL, EIO};
+
+// parity pieces are stored as ordinary chunk files, their ids start from here so they never
+// collide with data chunks: parity piece p of stripe s has id PARITY_CHUNK_BASE + s * m + p
+pub const PARITY_CHUNK_BASE: u64 = 1 << 48;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ErasureLayout {
+    pub data: u64,
+    pub parity: u64,
+}
+impl ErasureLayout {
+    // Reed-Solomon over GF(2^8) takes at least one data piece and at most 256 pieces
+    pub fn check(&self) -> Result<(), i32> {
+        if self.data == 0 || self.width() > 256 {
+            return Err(EINVAL);
+        }
+        Ok(())
+    }
+    pub fn width(&self) -> u64 {
+        self.data + self.parity
+    }
+    pub fn stripe_of(&self, chunk_id: u64) -> u64 {
+        chunk_id / self.data
+    }
+    pub fn parity_chunk_id(&self, stripe_id: u64, piece: u64) -> u64 {
+        PARITY_CHUNK_BASE + stripe_id * self.parity + piece
+    }
+    // first parity id of the stripes holding no data chunk below 'chunk_start'
+    pub fn parity_trim_start(&self, chunk_start: u64) -> u64 {
+        self.parity_chunk_id(chunk_start.div_ceil(self.data), 0)
+    }
+    // chunk id of the n-th piece in a stripe, data pieces come first
+    pub fn piece_chunk_id(&self, stripe_id: u64, piece: u64) -> u64 {
+        if piece < self.data {
+            stripe_id * self.data + piece
+        } else {
+            self.parity_chunk_id(stripe_id, piece - self.data)
+        }
+    }
+    // stripe of a data chunk or parity piece and its place in the stripe
+    pub fn piece_of(&self, chunk_id: u64) -> (u64, u64) {
+        if is_parity_chunk(chunk_id) {
+            let parity_id = chunk_id - PARITY_CHUNK_BASE;
+            (parity_id / self.parity, self.data + parity_id % self.parity)
+        } else {
+            (chunk_id / self.data, chunk_id % self.data)
+        }
+    }
+}
+pub fn is_parity_chunk(chunk_id: u64) -> bool {
+    chunk_id >= PARITY_CHUNK_BASE
+}
+// whether a trim from 'chunk_start' drops 'chunk_id'. data chunks and parity pieces are trimmed
+// apart: parity of the remaining stripes is rewritten by the client after truncating, parity of
+// the stripes past the new end goes with a trim from their first parity id
+pub fn trims(chunk_start: u64, chunk_id: u64) -> bool {
+    chunk_id >= chunk_start && is_parity_chunk(chunk_id) == is_parity_chunk(chunk_start)
+}
+// every configured layout has to be usable, checked once the configuration is loaded
+pub fn check_erasure_dirs(dirs: &[(String, u64, u64)]) -> Result<(), i32> {
+    for (_dir, data, parity) in dirs.iter() {
+        ErasureLayout {
+            data: *data,
+            parity: *parity,
+        }
+        .check()?;
+    }
+    Ok(())
+}
+// the layout of a path is decided by the deepest configured directory containing it
+pub fn erasure_layout(dirs: &[(String, u64, u64)], path: &str) -> Option<ErasureLayout> {
+    let mut found: Option<(usize, ErasureLayout)> = None;
+    for (dir, data, parity) in dirs.iter() {
+        let dir = dir.trim_end_matches('/');
+        let matched = dir.is_empty()
+            || path.eq(dir)
+            || (path.starts_with(dir) && path.as_bytes()[dir.len()] == b'/');
+        if !matched || *data == 0 {
+            continue;
+        }
+        if let Some((len, _)) = found {
+            if len >= dir.len() {
+                continue;
+            }
+        }
+        found = Some((
+            dir.len(),
+            ErasureLayout {
+                data: *data,
+                parity: *parity,
+            },
+        ));
+    }
+    found.map(|(_, layout)| layout)
+}
+
+struct Galois {
+    exp: [u8; 512],
+    log: [u8; 256],
+}
+impl Galois {
+    fn new() -> Galois {
+        let mut exp = [0u8; 512];
+        let mut log = [0u8; 256];
+        let mut x: u16 = 1;
+        for (i, e) in exp.iter_mut().take(255).enumerate() {
+            *e = x as u8;
+            log[x as usize] = i as u8;
+            x <<= 1;
+            if x & 0x100 != 0 {
+                x ^= 0x11d;
+            }
+        }
+        for i in 255..512 {
+            exp[i] = exp[i - 255];
+        }
+        Galois { exp, log }
+    }
+    fn mul(&self, a: u8, b: u8) -> u8 {
+        if a == 0 || b == 0 {
+            return 0;
+        }
+        self.exp[self.log[a as usize] as usize + self.log[b as usize] as usize]
+    }
+    fn div(&self, a: u8, b: u8) -> u8 {
+        if a == 0 {
+            return 0;
+        }
+        self.exp[self.log[a as usize] as usize + 255 - self.log[b as usize] as usize]
+    }
+    fn pow(&self, a: u8, n: usize) -> u8 {
+        if n == 0 {
+            return 1;
+        }
+        if a == 0 {
+            return 0;
+        }
+        self.exp[(self.log[a as usize] as usize * n) % 255]
+    }
+    fn invert(&self, matrix: &[Vec<u8>]) -> Option<Vec<Vec<u8>>> {
+        let n = matrix.len();
+        let mut work: Vec<Vec<u8>> = matrix
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                let mut r = row.clone();
+                r.extend((0..n).map(|j| if i == j { 1 } else { 0 }));
+                r
+            })
+            .collect();
+        for col in 0..n {
+            let pivot = (col..n).find(|r| work[*r][col] != 0)?;
+            work.swap(col, pivot);
+            let p = work[col][col];
+            for v in work[col].iter_mut() {
+                *v = self.div(*v, p);
+            }
+            for r in 0..n {
+                if r == col || work[r][col] == 0 {
+                    continue;
+                }
+                let f = work[r][col];
+                let pivot_row = work[col].clone();
+                for (v, p) in work[r].iter_mut().zip(pivot_row.iter()) {
+                    *v ^= self.mul(f, *p);
+                }
+            }
+        }
+        Some(work.into_iter().map(|row| row[n..].to_vec()).collect())
+    }
+}
+
+// systematic Reed-Solomon code over GF(2^8): the first k pieces are the data itself,
+// any k out of the k + m pieces are enough to get the data back
+pub struct ReedSolomon {
+    data_: usize,
+    parity_: usize,
+    gf_: Galois,
+    // (k + m) x k, the top k rows are the identity
+    matrix_: Vec<Vec<u8>>,
+}
+impl ReedSolomon {
+    // layouts are checked once the configuration is loaded, see check_erasure_dirs
+    pub fn new(data: usize, parity: usize) -> ReedSolomon {
+        assert!(data > 0 && data + parity <= 256);
+        let gf = Galois::new();
+        let vandermonde: Vec<Vec<u8>> = (0..(data + parity))
+            .map(|i| (0..data).map(|j| gf.pow(i as u8, j)).collect())
+            .collect();
+        let top_inv = gf.invert(&vandermonde[..data]).unwrap();
+        let matrix = vandermonde
+            .iter()
+            .map(|row| {
+                (0..data)
+                    .map(|j| {
+                        let mut v = 0u8;
+                        for t in 0..data {
+                            v ^= gf.mul(row[t], top_inv[t][j]);
+                        }
+                        v
+                    })
+                    .collect()
+            })
+            .collect();
+        ReedSolomon {
+            data_: data,
+            parity_: parity,
+            gf_: gf,
+            matrix_: matrix,
+        }
+    }
+    pub fn from_layout(layout: &ErasureLayout) -> ReedSolomon {
+        ReedSolomon::new(layout.data as usize, layout.parity as usize)
+    }
+    fn code_row(&self, row: &[u8], inputs: &[&Vec<u8>], len: usize) -> Vec<u8> {
+        let mut out = vec![0u8; len];
+        for (coef, input) in row.iter().zip(inputs.iter()) {
+            if *coef == 0 {
+                continue;
+            }
+            for (o, v) in out.iter_mut().zip(input.iter()) {
+                *o ^= self.gf_.mul(*coef, *v);
+            }
+        }
+        out
+    }
+    // compute the parity pieces of k data pieces of equal length
+    pub fn encode(&self, data: &[Vec<u8>]) -> Result<Vec<Vec<u8>>, i32> {
+        if data.len() != self.data_ {
+            return Err(EIO);
+        }
+        let len = data[0].len();
+        if data.iter().any(|piece| piece.len() != len) {
+            return Err(EIO);
+        }
+        let inputs: Vec<&Vec<u8>> = data.iter().collect();
+        Ok((self.data_..(self.data_ + self.parity_))
+            .map(|i| self.code_row(&self.matrix_[i], &inputs, len))
+            .collect())
+    }
+    // fill in every missing piece, fails if less than k pieces are present
+    pub fn reconstruct(&self, pieces: &mut [Option<Vec<u8>>]) -> Result<(), i32> {
+        if pieces.len() != self.data_ + self.parity_ {
+            return Err(EIO);
+        }
+        let present: Vec<usize> = (0..pieces.len())
+            .filter(|i| pieces[*i].is_some())
+            .take(self.data_)
+            .collect();
+        if present.len() < self.data_ {
+            return Err(EIO);
+        }
+        if pieces.iter().all(|piece| piece.is_some()) {
+            return Ok(());
+        }
+        let len = pieces[present[0]].as_ref().unwrap().len();
+        let sub: Vec<Vec<u8>> = present.iter().map(|i| self.matrix_[*i].clone()).collect();
+        let decode = self.gf_.invert(&sub).ok_or(EIO)?;
+        let data: Vec<Vec<u8>> = {
+            let inputs: Vec<&Vec<u8>> = present.iter().map(|i| pieces[*i].as_ref().unwrap()).collect();
+            (0..self.data_)
+                .map(|j| self.code_row(&decode[j], &inputs, len))
+                .collect()
+        };
+        for i in 0..pieces.len() {
+            if pieces[i].is_some() {
+                continue;
+            }
+            if i < self.data_ {
+                pieces[i] = Some(data[i].clone());
+            } else {
+                let inputs: Vec<&Vec<u8>> = data.iter().collect();
+                pieces[i] = Some(self.code_row(&self.matrix_[i], &inputs, len));
+            }
+        }
+        Ok(())
+    }
+}
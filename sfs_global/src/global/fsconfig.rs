@@ -1,6 +1,17 @@
 use serde::{Deserialize, Serialize};
 
 pub static HOSTFILE_PATH: &str = "hostfile";
+// how long clients may trust metadata they fetched
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Consistency {
+    // every lookup goes to the metadata server
+    Strict,
+    // metadata is cached under a lease, servers turn changes away until other holders' leases run out
+    Lease,
+    // metadata is cached for a fixed time, changes by other clients may go unnoticed meanwhile
+    Relaxed,
+}
 #[derive(Serialize, Deserialize, Debug)]
 pub struct SFSConfig {
     pub atime_state: bool,
@@ -12,6 +23,18 @@ pub struct SFSConfig {
     pub gid: u32,
     pub rootdir: String,
     pub mountdir: String,
+    pub replicas: u64,
+    pub write_quorum: u64,
+    // (directory, data pieces, parity pieces) of erasure-coded directories
+    pub erasure_dirs: Vec<(String, u64, u64)>,
+    // directories whose files are compressed on the chunk servers
+    #[serde(default)]
+    pub compress_dirs: Vec<String>,
+    // chunk servers store identical chunks once
+    #[serde(default)]
+    pub dedup: bool,
+    pub consistency: Consistency,
+    pub lease_ms: u64,
 }
 impl SFSConfig {
     pub fn new() -> SFSConfig {
@@ -25,6 +48,13 @@ impl SFSConfig {
             gid: 0,
             rootdir: "".to_string(),
             mountdir: "".to_string(),
+            replicas: 1,
+            write_quorum: 0,
+            erasure_dirs: Vec::new(),
+            compress_dirs: Vec::new(),
+            dedup: false,
+            consistency: Consistency::Strict,
+            lease_ms: 0,
         }
     }
 }
@@ -40,11 +70,19 @@ impl Clone for SFSConfig {
             gid: self.gid.clone(),
             rootdir: self.rootdir.clone(),
             mountdir: self.mountdir.clone(),
+            replicas: self.replicas,
+            write_quorum: self.write_quorum,
+            erasure_dirs: self.erasure_dirs.clone(),
+            compress_dirs: self.compress_dirs.clone(),
+            dedup: self.dedup,
+            consistency: self.consistency,
+            lease_ms: self.lease_ms,
         }
     }
 }
 pub static ZERO_BUF_BEFORE_READ: bool = false;
 pub static CWD: &str = "SFS_CWD";
 pub static ENABLE_OUTPUT: bool = false;
-// stuffing is disabled due to that RDMA is added to this project
-pub static ENABLE_STUFFING: bool = false;
+// files of at most 'STUFF_SIZE' bytes keep their data with their metadata instead of in chunks
+pub static ENABLE_STUFFING: bool = true;
+pub const STUFF_SIZE: u64 = 4096;
@@ -14,6 +14,19 @@ pub fn S_ISREG(mode: u32) -> bool {
 pub fn S_ISDIR(mode: u32) -> bool {
     mode & S_IFDIR != 0
 }
+// compact attributes sent along with directory entries by readdirplus
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DirentAttr {
+    pub mode: u32,
+    pub size: i64,
+    pub access_time: i64,
+    pub modify_time: i64,
+    pub change_time: i64,
+    pub link_count: u64,
+    pub blocks: i64,
+    #[serde(default)]
+    pub file_id: u64,
+}
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Metadata {
     access_time_: i64,
@@ -23,7 +36,14 @@ pub struct Metadata {
     link_count_: u64,
     size_: i64,
     blocks_: i64,
-    stuffed_: bool
+    stuffed_: bool,
+    // names the chunks of the file on the data hosts, handed out at create. 0 for files
+    // created before file ids, their chunks are named by the path
+    #[serde(default)]
+    file_id_: u64,
+    // content of a stuffed file, only filled in on clients from the stat response
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    inline_data_: Option<Vec<u8>>,
 }
 impl fmt::Display for Metadata {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -51,7 +71,9 @@ impl Metadata {
             link_count_: 1,
             size_: 0,
             blocks_: 0,
-            stuffed_: true
+            stuffed_: true,
+            file_id_: 0,
+            inline_data_: None,
         }
     }
     pub fn deserialize(binary_str: &Vec<u8>) -> Metadata {
@@ -126,10 +148,48 @@ impl Metadata {
     pub fn set_blocks(&mut self, blocks: i64) {
         self.blocks_ = blocks;
     }
+    pub fn get_file_id(&self) -> u64 {
+        self.file_id_
+    }
+    pub fn set_file_id(&mut self, file_id: u64) {
+        self.file_id_ = file_id;
+    }
     pub fn is_stuffed(&self) -> bool{
         self.stuffed_
     }
     pub fn unstuff(&mut self){
         self.stuffed_ = false;
+        self.inline_data_ = None;
+    }
+    // None while the content is unknown, the file may still be stuffed
+    pub fn get_inline_data(&self) -> Option<&Vec<u8>> {
+        self.inline_data_.as_ref()
+    }
+    pub fn set_inline_data(&mut self, data: Vec<u8>) {
+        self.inline_data_ = Some(data);
+    }
+    pub fn to_attr(&self) -> DirentAttr {
+        DirentAttr {
+            mode: self.mode_,
+            size: self.size_,
+            access_time: self.access_time_,
+            modify_time: self.modify_time_,
+            change_time: self.change_time_,
+            link_count: self.link_count_,
+            blocks: self.blocks_,
+            file_id: self.file_id_,
+        }
+    }
+    pub fn from_attr(attr: &DirentAttr) -> Metadata {
+        let mut md = Metadata::new();
+        md.set_mode(attr.mode);
+        md.set_size(attr.size);
+        md.set_access_time(attr.access_time);
+        md.set_modify_time(attr.modify_time);
+        md.set_change_time(attr.change_time);
+        md.set_link_count(attr.link_count);
+        md.set_blocks(attr.blocks);
+        md.set_file_id(attr.file_id);
+        md
     }
 }
@@ -1,8 +1,11 @@
+pub mod compression;
 pub mod distributor;
+pub mod erasure;
 pub mod error_msg;
 pub mod fsconfig;
 pub mod metadata;
 pub mod network;
 pub mod path;
+pub mod placement;
 pub mod util;
 pub mod endpoint;
@@ -1,4 +1,13 @@
 pub const CHUNK_SIZE: u64 = 524288;
 pub const DIRENT_BUF_SIZE: u64 = 8 * 1024 * 1024;
+// entries fetched by one 'GetDirents', a directory offset is page * DIRENT_PAGE_SIZE + index in page
+pub const DIRENT_PAGE_SIZE: u64 = 1024;
 // The number of rdma_cm_id in client connection pool for each server
 pub const CLIENT_CM_IDS: usize = 1;
+// bumped whenever a message or the serde layout of 'Metadata' changes
+pub const PROTOCOL_VERSION: u32 = 1;
+// feature flags exchanged in the handshake
+pub const FEATURE_STUFFING: u64 = 1;
+pub const FEATURE_REPLICATION: u64 = 1 << 1;
+pub const FEATURE_LAYOUTS: u64 = 1 << 2;
+pub const FEATURE_CHECKSUM: u64 = 1 << 3;
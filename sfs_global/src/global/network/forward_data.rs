@@ -26,6 +26,7 @@ pub struct ReadResult {
 pub struct CreateData<'a> {
     pub path: &'a str,
     pub mode: u32,
+    pub client: u64,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -34,12 +35,17 @@ pub struct UpdateMetadentryData<'a> {
     pub size: u64,
     pub offset: i64,
     pub append: bool,
+    pub client: u64,
 }
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ChunkStat {
     pub chunk_size: u64,
     pub chunk_total: u64,
     pub chunk_free: u64,
+    // (total, free) chunks of each storage tier, the node-local one first. empty without a
+    // capacity tier
+    #[serde(default)]
+    pub tiers: Vec<(u64, u64)>,
 }
 impl ChunkStat {
     pub fn new() -> ChunkStat {
@@ -47,28 +53,162 @@ impl ChunkStat {
             chunk_size: 0,
             chunk_total: 0,
             chunk_free: 0,
+            tiers: Vec::new(),
         }
     }
 }
+// capacity of a chunk server, sent to every other server at each heartbeat
+#[derive(Serialize, Deserialize, Debug)]
+pub struct HeartbeatData {
+    pub host: u64,
+    pub stat: ChunkStat,
+}
+// chunks of 'path' the hosts 'full' refused for lack of space, the metadata host places them
+// elsewhere. no chunks only asks for the placement of the file
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PlaceData<'a> {
+    pub path: &'a str,
+    pub chunks: Vec<u64>,
+    pub full: Vec<u64>,
+}
+// change of the blocks a file takes on the chunk servers, sent to its metadata servers
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BlocksData<'a> {
+    pub path: &'a str,
+    pub blocks: i64,
+}
 #[derive(Serialize, Deserialize, Debug)]
 pub struct DecrData<'a> {
     pub path: &'a str,
     pub new_size: i64,
+    pub client: u64,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct TruncData<'a> {
     pub path: &'a str,
+    pub file_id: u64,
     pub new_size: i64,
 }
+// chunks of the file a data host drops. data hosts know a file by its path and the file id
+// from its metadata, the id names the chunks on disk
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RemoveData<'a> {
+    pub path: &'a str,
+    pub file_id: u64,
+}
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct DirentData<'a> {
     pub path: &'a str,
+    // list names sorting after this one, empty for the first page
+    pub start_after: &'a str,
+    // 0 means no limit
+    pub limit: u64,
+    // readdirplus, attributes of every entry travel in the 'extra' field of its result
+    pub plus: bool,
+    // lease holder of the attributes, 0 for none
+    pub client: u64,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct PreCreateData<'a> {
     pub path: &'a str,
+    pub file_id: u64,
     pub chunks: Vec<u64>
-}
\ No newline at end of file
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RepairData {
+    pub host_id: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RepairChunkData<'a> {
+    pub path: &'a str,
+    pub file_id: u64,
+    pub chunk_id: u64,
+    pub target: u64,
+}
+
+// chunk content travels in the 'extra' field of the post
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ReplicateChunkData<'a> {
+    pub path: &'a str,
+    pub file_id: u64,
+    pub chunk_id: u64,
+}
+// (chunk id, digest, length) of chunks a client is about to write from their start, the
+// server answers with the ids of the ones whose content it holds already
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LinkChunksData<'a> {
+    pub path: &'a str,
+    pub file_id: u64,
+    pub chunks: Vec<(u64, Vec<u8>, u64)>,
+}
+// a data mover job: 'host_dir' of the host filesystem is copied into 'sfs_dir' on stage-in,
+// 'sfs_dir' is drained into 'host_dir' on stage-out
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StageData<'a> {
+    pub stage_in: bool,
+    pub host_dir: &'a str,
+    pub sfs_dir: &'a str,
+}
+// chunks of 'path' a chunk host moves between its storage and the host file 'host_path' of
+// 'size' bytes, chunk i sits at offset i * CHUNK_SIZE of the host file
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StageChunksData<'a> {
+    pub stage_in: bool,
+    pub path: &'a str,
+    pub file_id: u64,
+    pub host_path: &'a str,
+    pub size: u64,
+    pub chunks: Vec<u64>,
+}
+// progress of a data mover job on one server
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct StageProgress {
+    pub files: u64,
+    pub skipped: u64,
+    pub bytes: u64,
+    pub errors: u64,
+    pub done: bool,
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ReadChunkData<'a> {
+    pub path: &'a str,
+    pub file_id: u64,
+    pub chunk_id: u64,
+}
+// stripe of an erasure-coded file whose data pieces changed, its first parity host recomputes
+// the parity
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EncodeStripeData<'a> {
+    pub path: &'a str,
+    pub file_id: u64,
+    pub stripe_id: u64,
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DirentRecordData<'a> {
+    pub dir: &'a str,
+    pub name: &'a str,
+    pub is_dir: bool,
+}
+// 'client' identifies the caller to the lease table of the metadata server, 0 for none:
+// a lease is granted to it on lookups and its own lease is not waited for on changes
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PathData<'a> {
+    pub path: &'a str,
+    pub client: u64,
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StatBatchData {
+    pub paths: Vec<String>,
+    pub client: u64,
+}
+// deferred size updates of many files, each entry is (path, end of the furthest write)
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SizeBatchData {
+    pub entries: Vec<(String, u64)>,
+    pub client: u64,
+}
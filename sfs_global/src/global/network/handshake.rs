@@ -0,0 +1,62 @@
+use crate::global::fsconfig::ENABLE_STUFFING;
+
+use super::config::{
+    CHUNK_SIZE, FEATURE_CHECKSUM, FEATURE_LAYOUTS, FEATURE_REPLICATION, FEATURE_STUFFING,
+    PROTOCOL_VERSION,
+};
+
+// what a client and a server have to agree on before any data is exchanged
+#[derive(Debug, Clone, PartialEq)]
+pub struct HandshakeInfo {
+    pub protocol_version: u32,
+    // features supported by a client, features in use by a server
+    pub features: u64,
+    pub chunk_size: u64,
+    // empty for a client that accepts any cluster
+    pub cluster_id: String,
+}
+impl HandshakeInfo {
+    pub fn new(features: u64, cluster_id: String) -> HandshakeInfo {
+        HandshakeInfo {
+            protocol_version: PROTOCOL_VERSION,
+            features,
+            chunk_size: CHUNK_SIZE,
+            cluster_id,
+        }
+    }
+    pub fn supported_features() -> u64 {
+        let stuffing = if ENABLE_STUFFING { FEATURE_STUFFING } else { 0 };
+        stuffing | FEATURE_CHECKSUM | FEATURE_REPLICATION | FEATURE_LAYOUTS
+    }
+    // checks the server side 'remote' against what this client supports. a client supporting
+    // checksums sends them whenever the servers use them
+    pub fn check(&self, remote: &HandshakeInfo) -> Result<(), String> {
+        if remote.protocol_version != self.protocol_version {
+            return Err(format!(
+                "protocol version {} does not match the local version {}",
+                remote.protocol_version, self.protocol_version
+            ));
+        }
+        if remote.chunk_size != self.chunk_size {
+            return Err(format!(
+                "chunk size {} does not match the local chunk size {}",
+                remote.chunk_size, self.chunk_size
+            ));
+        }
+        let unsupported = remote.features & !self.features;
+        if unsupported != 0 {
+            return Err(format!("unsupported features {:#x} in use", unsupported));
+        }
+        // stuffed data is only found where both sides stuff
+        if (remote.features ^ self.features) & FEATURE_STUFFING != 0 {
+            return Err("stuffing is enabled on one side only".to_string());
+        }
+        if !self.cluster_id.is_empty() && remote.cluster_id != self.cluster_id {
+            return Err(format!(
+                "cluster '{}' is not the expected cluster '{}'",
+                remote.cluster_id, self.cluster_id
+            ));
+        }
+        Ok(())
+    }
+}
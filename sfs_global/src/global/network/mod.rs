@@ -1,3 +1,4 @@
 pub mod config;
 pub mod forward_data;
+pub mod handshake;
 pub mod post;
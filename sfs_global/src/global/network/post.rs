@@ -19,7 +19,27 @@ pub enum PostOption {
     Unknown,
     WriteData,
     ReadData,
-    PreCreate
+    PreCreate,
+    Repair,
+    RepairChunk,
+    ReplicateChunk,
+    ReplicateMetadata,
+    ReadChunk,
+    AddDirent,
+    RemoveDirent,
+    StatBatch,
+    StatLease,
+    UpdateSizeBatch,
+    ReplicateStuff,
+    AddBlocks,
+    LinkChunks,
+    Stage,
+    StageChunks,
+    StageStatus,
+    Place,
+    PutPlacement,
+    Heartbeat,
+    EncodeStripe,
 }
 pub fn i2option(n: i32) -> PostOption {
     match n {
@@ -40,6 +60,26 @@ pub fn i2option(n: i32) -> PostOption {
         14 => PostOption::WriteData,
         15 => PostOption::ReadData,
         16 => PostOption::PreCreate,
+        17 => PostOption::Repair,
+        18 => PostOption::RepairChunk,
+        19 => PostOption::ReplicateChunk,
+        20 => PostOption::ReplicateMetadata,
+        21 => PostOption::ReadChunk,
+        22 => PostOption::AddDirent,
+        23 => PostOption::RemoveDirent,
+        24 => PostOption::StatBatch,
+        25 => PostOption::StatLease,
+        26 => PostOption::UpdateSizeBatch,
+        27 => PostOption::ReplicateStuff,
+        28 => PostOption::AddBlocks,
+        29 => PostOption::LinkChunks,
+        30 => PostOption::Stage,
+        31 => PostOption::StageChunks,
+        32 => PostOption::StageStatus,
+        33 => PostOption::Place,
+        34 => PostOption::PutPlacement,
+        35 => PostOption::Heartbeat,
+        36 => PostOption::EncodeStripe,
         _ => PostOption::Unknown,
     }
 }
@@ -62,6 +102,58 @@ pub fn option2i(option: &PostOption) -> i32 {
         PostOption::WriteData => 14,
         PostOption::ReadData => 15,
         PostOption::PreCreate => 16,
+        PostOption::Repair => 17,
+        PostOption::RepairChunk => 18,
+        PostOption::ReplicateChunk => 19,
+        PostOption::ReplicateMetadata => 20,
+        PostOption::ReadChunk => 21,
+        PostOption::AddDirent => 22,
+        PostOption::RemoveDirent => 23,
+        PostOption::StatBatch => 24,
+        PostOption::StatLease => 25,
+        PostOption::UpdateSizeBatch => 26,
+        PostOption::ReplicateStuff => 27,
+        PostOption::AddBlocks => 28,
+        PostOption::LinkChunks => 29,
+        PostOption::Stage => 30,
+        PostOption::StageChunks => 31,
+        PostOption::StageStatus => 32,
+        PostOption::Place => 33,
+        PostOption::PutPlacement => 34,
+        PostOption::Heartbeat => 35,
+        PostOption::EncodeStripe => 36,
         PostOption::Unknown => -1,
     }
-}
\ No newline at end of file
+}
+// operations a client may send again after a lost answer without changing the outcome
+pub fn idempotent(option: &PostOption) -> bool {
+    matches!(
+        option,
+        PostOption::Lookup
+            | PostOption::Stat
+            | PostOption::StatLease
+            | PostOption::StatBatch
+            | PostOption::FsConfig
+            | PostOption::Read
+            | PostOption::ReadChunk
+            | PostOption::GetMetadentry
+            | PostOption::GetDirents
+            | PostOption::ChunkStat
+            | PostOption::DecrSize
+            | PostOption::Trunc
+            | PostOption::Remove
+            | PostOption::ReplicateChunk
+            | PostOption::ReplicateStuff
+            | PostOption::AddDirent
+            | PostOption::RemoveDirent
+            | PostOption::UpdateSizeBatch
+            | PostOption::LinkChunks
+            | PostOption::Stage
+            | PostOption::StageChunks
+            | PostOption::StageStatus
+            | PostOption::Place
+            | PostOption::PutPlacement
+            | PostOption::Heartbeat
+            | PostOption::EncodeStripe
+    )
+}
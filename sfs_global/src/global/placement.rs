@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+
+use super::distributor::Distributor;
+
+// replica sets recorded for chunks of a file that were moved off the hosts the distributor
+// picks, kept by the metadata hosts of the file. runs of chunks with the same set share a range
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Placement {
+    // (first chunk, last chunk, replica set), sorted and disjoint
+    ranges: Vec<(u64, u64, Vec<u64>)>,
+}
+impl Placement {
+    pub fn new() -> Placement {
+        Placement { ranges: Vec::new() }
+    }
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+    // recorded replica set of 'chunk_id', if any
+    pub fn replicas(&self, chunk_id: u64) -> Option<&Vec<u64>> {
+        let idx = self.ranges.partition_point(|(_first, last, _hosts)| *last < chunk_id);
+        match self.ranges.get(idx) {
+            Some((first, _last, hosts)) if *first <= chunk_id => Some(hosts),
+            _ => None,
+        }
+    }
+    // replica set of 'chunk_id', the recorded one wins over the distributor
+    pub fn locate(
+        &self,
+        distributor: &dyn Distributor,
+        path: &String,
+        chunk_id: u64,
+    ) -> Vec<u64> {
+        match self.replicas(chunk_id) {
+            Some(hosts) => hosts.clone(),
+            None => distributor.locate_data_replicas(path, chunk_id),
+        }
+    }
+    // record 'hosts' for 'chunk_id', false if it has a set already
+    pub fn record(&mut self, chunk_id: u64, hosts: Vec<u64>) -> bool {
+        if self.replicas(chunk_id).is_some() {
+            return false;
+        }
+        let idx = self.ranges.partition_point(|(_first, last, _hosts)| *last < chunk_id);
+        let joins_prev = idx > 0 && {
+            let (_first, last, prev) = &self.ranges[idx - 1];
+            *last + 1 == chunk_id && *prev == hosts
+        };
+        let joins_next = match self.ranges.get(idx) {
+            Some((first, _last, next)) => chunk_id + 1 == *first && *next == hosts,
+            None => false,
+        };
+        match (joins_prev, joins_next) {
+            (true, true) => {
+                let (_first, last, _hosts) = self.ranges.remove(idx);
+                self.ranges[idx - 1].1 = last;
+            }
+            (true, false) => self.ranges[idx - 1].1 = chunk_id,
+            (false, true) => self.ranges[idx].0 = chunk_id,
+            (false, false) => self.ranges.insert(idx, (chunk_id, chunk_id, hosts)),
+        }
+        true
+    }
+    // drop the sets of the chunks from 'chunk_start' on, the file was cut there
+    pub fn trim(&mut self, chunk_start: u64) {
+        self.ranges.retain(|(first, _last, _hosts)| *first < chunk_start);
+        if let Some(range) = self.ranges.last_mut() {
+            if range.1 >= chunk_start {
+                range.1 = chunk_start - 1;
+            }
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use sha2::{Digest, Sha256};
+use xxhash_rust::xxh3::xxh3_64;
+
+// bytes of a checksum following a chunk piece in an RDMA buffer
+pub const CHECKSUM_SIZE: usize = 8;
+
+// checksum of chunk pieces and whole chunks, clients and servers have to use the same one
+pub fn chunk_checksum(data: &[u8]) -> u64 {
+    xxh3_64(data)
+}
+// name of a chunk content in dedup mode, chunks with the same digest are stored once
+pub fn chunk_digest(data: &[u8]) -> Vec<u8> {
+    Sha256::digest(data).to_vec()
+}
+// bytes of the token digest sent with RDMA connection requests
+pub const TOKEN_DIGEST_SIZE: usize = 16;
+// stands for the token of the job where no header carries it: the private data of an RDMA
+// connection request holds 56 bytes at most
+pub fn token_digest(token: &String) -> [u8; TOKEN_DIGEST_SIZE] {
+    let mut digest = [0; TOKEN_DIGEST_SIZE];
+    digest.copy_from_slice(&Sha256::digest(token.as_bytes())[..TOKEN_DIGEST_SIZE]);
+    digest
+}
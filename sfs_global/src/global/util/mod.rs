@@ -1,4 +1,5 @@
 pub mod arith_util;
+pub mod checksum_util;
 pub mod env_util;
 pub mod net_util;
 pub mod path_util;
@@ -1,3 +1,4 @@
+use libc::EINVAL;
 use serde::{Serialize, Deserialize};
 
 
@@ -7,8 +8,13 @@ pub fn serialize<T: Serialize>(data: T) -> Vec<u8>{
     return buf.view().to_vec();
 }
 
-pub fn deserialize<'a, T: Deserialize<'a>>(data: &'a Vec<u8>) -> T{
-    let reader = flexbuffers::Reader::get_root(&data as &[u8]).unwrap();
+pub fn deserialize<'a, T: Deserialize<'a>>(data: &'a [u8]) -> T{
+    let reader = flexbuffers::Reader::get_root(data).unwrap();
     let data = T::deserialize(reader).unwrap();
     return data;
-}
\ No newline at end of file
+}
+// for payloads coming from the network, malformed ones give EINVAL instead of a panic
+pub fn try_deserialize<'a, T: Deserialize<'a>>(data: &'a [u8]) -> Result<T, i32>{
+    let reader = flexbuffers::Reader::get_root(data).map_err(|_| EINVAL)?;
+    T::deserialize(reader).map_err(|_| EINVAL)
+}
@@ -1,9 +1,197 @@
 pub mod global;
 #[cfg(test)]
 mod tests {
+    use crate::global::compression::is_compressed;
+    use crate::global::distributor::{Distributor, SimpleHashDistributor};
+    use crate::global::erasure::{check_erasure_dirs, trims, ErasureLayout, ReedSolomon};
+    use crate::global::metadata::Metadata;
+    use crate::global::network::config::{FEATURE_CHECKSUM, FEATURE_LAYOUTS, FEATURE_STUFFING};
+    use crate::global::network::forward_data::{ChunkStat, CreateData};
+    use crate::global::network::handshake::HandshakeInfo;
+    use crate::global::network::post::{i2option, idempotent, PostOption};
+    use crate::global::placement::Placement;
+    use crate::global::util::checksum_util::{chunk_checksum, chunk_digest, token_digest};
+    use crate::global::util::serde_util::{serialize, try_deserialize};
+
     #[test]
     fn it_works() {
         let result = 2 + 2;
         assert_eq!(result, 4);
     }
+    #[test]
+    fn replicas_are_distinct() {
+        let distributor = SimpleHashDistributor::with_replicas(0, 4, 3);
+        let path = "/sfs/file1".to_string();
+        for chunk_id in 0..16 {
+            let mut replicas = distributor.locate_data_replicas(&path, chunk_id);
+            assert_eq!(replicas[0], distributor.locate_data(&path, chunk_id));
+            replicas.sort();
+            replicas.dedup();
+            assert_eq!(replicas.len(), 3);
+        }
+        let distributor = SimpleHashDistributor::with_replicas(0, 2, 3);
+        assert_eq!(distributor.locate_file_metadata_replicas(&path).len(), 2);
+    }
+    #[test]
+    fn erasure_reconstruct() {
+        let rs = ReedSolomon::new(4, 2);
+        let data: Vec<Vec<u8>> = (0..4)
+            .map(|i| (0..64).map(|j| (i * 64 + j) as u8).collect())
+            .collect();
+        let parity = rs.encode(&data).unwrap();
+        let mut pieces: Vec<Option<Vec<u8>>> =
+            data.iter().chain(parity.iter()).map(|p| Some(p.clone())).collect();
+        pieces[1] = None;
+        pieces[4] = None;
+        rs.reconstruct(&mut pieces).unwrap();
+        assert_eq!(pieces[1].as_ref().unwrap(), &data[1]);
+        assert_eq!(pieces[4].as_ref().unwrap(), &parity[0]);
+        pieces[0] = None;
+        pieces[2] = None;
+        pieces[3] = None;
+        assert!(rs.reconstruct(&mut pieces).is_err());
+        assert_eq!(check_erasure_dirs(&[("/sfs/ec".to_string(), 200, 100)]), Err(libc::EINVAL));
+        assert_eq!(check_erasure_dirs(&[("/sfs/ec".to_string(), 4, 2)]), Ok(()));
+    }
+    #[test]
+    fn parity_past_the_end_is_trimmed() {
+        let layout = ErasureLayout { data: 4, parity: 2 };
+        // chunks 0..5 stay, stripe 1 keeps its parity, stripes from 2 on lose theirs
+        let parity_start = layout.parity_trim_start(6);
+        assert_eq!(parity_start, layout.parity_chunk_id(2, 0));
+        assert!(trims(6, 7));
+        assert!(!trims(6, layout.parity_chunk_id(3, 1)));
+        assert!(!trims(parity_start, layout.parity_chunk_id(1, 1)));
+        assert!(trims(parity_start, layout.parity_chunk_id(2, 0)));
+        assert!(!trims(parity_start, 7));
+        assert_eq!(layout.piece_of(7), (1, 3));
+        assert_eq!(layout.piece_of(layout.parity_chunk_id(3, 1)), (3, 5));
+    }
+    #[test]
+    fn malformed_payload_is_rejected() {
+        let data = serialize(CreateData {
+            path: "/sfs/file1",
+            mode: 0o644,
+            client: 1,
+        });
+        let create = try_deserialize::<CreateData>(&data).unwrap();
+        assert_eq!(create.path, "/sfs/file1");
+        assert_eq!(try_deserialize::<CreateData>(&vec![1, 2, 3]).err(), Some(libc::EINVAL));
+        assert!(try_deserialize::<CreateData>(&serialize("/sfs/file1")).is_err());
+    }
+    #[test]
+    fn handshake_compatibility() {
+        let client = HandshakeInfo::new(FEATURE_LAYOUTS, "".to_string());
+        let mut server = HandshakeInfo::new(FEATURE_LAYOUTS, "c1".to_string());
+        assert!(client.check(&server).is_ok());
+        server.chunk_size *= 2;
+        assert!(client.check(&server).is_err());
+        let mut server = HandshakeInfo::new(FEATURE_LAYOUTS | FEATURE_STUFFING, "c1".to_string());
+        assert!(client.check(&server).is_err());
+        server.features = 0;
+        server.protocol_version += 1;
+        assert!(client.check(&server).is_err());
+        let pinned = HandshakeInfo::new(FEATURE_LAYOUTS, "c2".to_string());
+        assert!(pinned.check(&HandshakeInfo::new(0, "c1".to_string())).is_err());
+        let checksummed = HandshakeInfo::new(FEATURE_LAYOUTS | FEATURE_CHECKSUM, "".to_string());
+        assert!(checksummed.check(&HandshakeInfo::new(FEATURE_LAYOUTS, "".to_string())).is_ok());
+        assert!(checksummed.check(&HandshakeInfo::new(FEATURE_CHECKSUM, "".to_string())).is_ok());
+        assert!(client.check(&HandshakeInfo::new(FEATURE_CHECKSUM, "".to_string())).is_err());
+    }
+    #[test]
+    fn only_idempotent_posts_are_retried() {
+        assert!(idempotent(&PostOption::Stat));
+        assert!(idempotent(&i2option(21)));
+        assert!(!idempotent(&PostOption::Create));
+        assert!(!idempotent(&PostOption::UpdateMetadentry));
+        assert!(!idempotent(&PostOption::Unknown));
+    }
+    #[test]
+    fn inline_data_is_only_kept_when_set() {
+        let mut md = Metadata::new();
+        md.set_size(3);
+        let stored = md.serialize();
+        assert!(Metadata::deserialize(&stored).get_inline_data().is_none());
+        md.set_inline_data(vec![1, 2, 3]);
+        let sent = Metadata::deserialize(&md.serialize());
+        assert_eq!(sent.get_inline_data(), Some(&vec![1, 2, 3]));
+        assert!(stored.len() < md.serialize().len());
+    }
+    #[test]
+    fn chunk_checksum_detects_flipped_bits() {
+        let mut chunk = vec![7u8; 4096];
+        let sum = chunk_checksum(&chunk);
+        assert_eq!(sum, chunk_checksum(&vec![7u8; 4096]));
+        chunk[1000] ^= 1;
+        assert_ne!(sum, chunk_checksum(&chunk));
+        assert_ne!(chunk_checksum(&chunk[..10]), chunk_checksum(&chunk[..11]));
+    }
+    #[test]
+    fn chunk_digest_names_content() {
+        let chunk = vec![3u8; 8192];
+        assert_eq!(chunk_digest(&chunk), chunk_digest(&vec![3u8; 8192]));
+        assert_eq!(chunk_digest(&chunk).len(), 32);
+        assert_ne!(chunk_digest(&chunk), chunk_digest(&chunk[..8191]));
+    }
+    #[test]
+    fn compressed_dirs_match_whole_components() {
+        let dirs = vec!["/sim/out/".to_string()];
+        assert!(is_compressed(&dirs, "/sim/out"));
+        assert!(is_compressed(&dirs, "/sim/out/a/b.dat"));
+        assert!(!is_compressed(&dirs, "/sim/output.dat"));
+        assert!(!is_compressed(&[], "/sim/out/a"));
+    }
+    #[test]
+    fn chunk_stat_of_untiered_servers_decodes() {
+        #[derive(serde::Serialize)]
+        struct UntieredStat {
+            chunk_size: u64,
+            chunk_total: u64,
+            chunk_free: u64,
+        }
+        let stat = serialize(UntieredStat {
+            chunk_size: 4096,
+            chunk_total: 10,
+            chunk_free: 4,
+        });
+        let stat = try_deserialize::<ChunkStat>(&stat).unwrap();
+        assert_eq!(stat.chunk_free, 4);
+        assert!(stat.tiers.is_empty());
+    }
+    #[test]
+    fn placement_avoids_full_hosts() {
+        let distributor = SimpleHashDistributor::with_replicas(0, 4, 2);
+        let path = "/sfs/file1".to_string();
+        let mut placement = Placement::new();
+        for chunk_id in 4..8 {
+            let full = distributor.locate_data_replicas(&path, chunk_id)[0];
+            let hosts = distributor.locate_data_replicas_avoiding(&path, chunk_id, &[full]);
+            assert_eq!(hosts.len(), 2);
+            assert!(!hosts.contains(&full));
+            assert!(placement.record(chunk_id, hosts));
+        }
+        assert!(!placement.record(5, vec![0, 1]));
+        assert_eq!(placement.replicas(3), None);
+        assert_eq!(
+            placement.locate(&distributor, &path, 8),
+            distributor.locate_data_replicas(&path, 8)
+        );
+        placement.trim(6);
+        assert!(placement.replicas(5).is_some());
+        assert_eq!(placement.replicas(6), None);
+        let placement = try_deserialize::<Placement>(&serialize(&placement)).unwrap();
+        assert!(placement.replicas(4).is_some());
+        // every host full, the usual set is kept
+        assert_eq!(
+            distributor.locate_data_replicas_avoiding(&path, 1, &[0, 1, 2, 3]),
+            distributor.locate_data_replicas(&path, 1)
+        );
+    }
+    #[test]
+    fn token_digests_tell_tokens_apart() {
+        let token = "job-1234".to_string();
+        assert_eq!(token_digest(&token), token_digest(&"job-1234".to_string()));
+        assert_ne!(token_digest(&token), token_digest(&"job-1235".to_string()));
+        assert_ne!(token_digest(&token), token_digest(&String::new()));
+    }
 }
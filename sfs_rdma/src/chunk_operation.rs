@@ -1,3 +1,7 @@
+use libc::EIO;
+use sfs_global::global::error_msg::error_msg;
+use sfs_global::global::util::checksum_util::chunk_checksum;
+
 use crate::{transfer::ChunkMetadata, CHUNK_SIZE};
 pub fn offset_to_chunk_id(offset: i64, chunk_size: u64) -> u64 {
     //(chunk_align_down(offset, chunk_size) >> ((chunk_size as f64).log2() as i64)) as u64
@@ -8,36 +12,54 @@ pub struct ChunkInfo {
     pub chunk_id: u64,
     pub metadata: ChunkMetadata,
     pub data: *mut u8,
+    // checksum the client computed for the piece, checked before the piece is written
+    pub checksum: Option<u64>,
+}
+// (offset in the transfer buffer, size, offset in the chunk) of the piece of 'chunk_id'
+pub fn chunk_piece(md: &ChunkMetadata, chunk_id: u64) -> (u64, u64, u64) {
+    let buffer_offset = if chunk_id == md.chunk_start {
+        0
+    } else {
+        CHUNK_SIZE * (chunk_id - md.chunk_start) - md.offset
+    };
+    let size = if chunk_id == md.chunk_start {
+        u64::min(CHUNK_SIZE - md.offset, md.size)
+    } else {
+        u64::min(CHUNK_SIZE, md.size - buffer_offset)
+    };
+    let offset = if chunk_id == md.chunk_start {
+        md.offset
+    } else {
+        0
+    };
+    (buffer_offset, size, offset)
 }
 #[derive(Clone)]
 pub struct ChunkOp {
-    pub op: fn(&String, u64, *mut u8, u64, u64) -> Result<i64, i32>,
+    pub op: fn(&String, u64, u64, *mut u8, u64, u64) -> Result<i64, i32>,
 }
 impl ChunkOp {
     pub fn submit(&self, chunk: ChunkInfo) -> Result<i64, i32> {
         let md = chunk.metadata;
-        let buffer_offset = if chunk.chunk_id == md.chunk_start {
-            0
-        } else {
-            CHUNK_SIZE * (chunk.chunk_id - md.chunk_start) - md.offset
-        };
-        let size = if chunk.chunk_id == md.chunk_start {
-            u64::min(CHUNK_SIZE - md.offset, md.size)
-        } else {
-            u64::min(CHUNK_SIZE, md.size - buffer_offset)
-        };
+        let (_buffer_offset, size, offset) = chunk_piece(&md, chunk.chunk_id);
         //println!("{} - {}: {} {} | {} {}", self.chunk_start, chunk.chunk_id, self.offset, self.size, buffer_offset, size);
-        let offset = if chunk.chunk_id == md.chunk_start {
-            md.offset
-        } else {
-            0
-        };
-        (self.op)(&md.path, chunk.chunk_id, chunk.data, size, offset)
+        if let Some(checksum) = chunk.checksum {
+            let piece = unsafe { std::slice::from_raw_parts(chunk.data, size as usize) };
+            if chunk_checksum(piece) != checksum {
+                error_msg(
+                    "rdma::chunk_operation::submit".to_string(),
+                    format!("checksum mismatch on chunk {} of '{}'", chunk.chunk_id, md.path),
+                );
+                return Err(EIO);
+            }
+        }
+        (self.op)(&md.path, md.file_id, chunk.chunk_id, chunk.data, size, offset)
     }
     pub fn none() -> Self {
         #[allow(unused)]
         fn null_op(
             _path: &String,
+            file_id: u64,
             chunk_id: u64,
             data: *mut u8,
             size: u64,
@@ -149,6 +149,7 @@ mod tests {
     fn test_port() {}
     pub fn show(
         file_path: &String,
+        _file_id: u64,
         _chunk_id: u64,
         buf: *mut u8,
         size: u64,
@@ -175,6 +176,7 @@ mod tests {
         let task = ChunkTransferTask {
             metadata: ChunkMetadata {
                 path: "testfile".to_string(),
+                file_id: 1,
                 chunk_start,
                 offset: offset % CHUNK_SIZE,
                 size
@@ -220,6 +222,7 @@ mod tests {
         let task = ChunkTransferTask {
             metadata: ChunkMetadata {
                 path: "testfile".to_string(),
+                file_id: 1,
                 chunk_start,
                 offset: offset % CHUNK_SIZE,
                 size
@@ -223,6 +223,7 @@ fn on_completion(wc: *mut ibv_wc, _pd: *mut ibv_pd, op: &ChunkOp) -> Result<i64,
             (*ctx).metadata.path =
                 String::from_utf8(transfer_md.path[0..transfer_md.path_len as usize].to_vec())
                     .unwrap();
+            (*ctx).metadata.file_id = transfer_md.file_id;
             (*ctx).metadata.chunk_start = transfer_md.chunk_start;
             (*ctx).metadata.offset = transfer_md.offset;
             (*ctx).metadata.size = transfer_md.size;
@@ -241,6 +242,7 @@ fn on_completion(wc: *mut ibv_wc, _pd: *mut ibv_pd, op: &ChunkOp) -> Result<i64,
                 chunk_id: (*(*ctx).msg).data,
                 metadata: (*ctx).metadata.clone(),
                 data: (*ctx).buffer,
+                checksum: None,
             };
             // read chunk
             let len = op.submit(info).unwrap();
@@ -237,6 +237,7 @@ fn on_completion(wc: *mut ibv_wc, _pd: *mut ibv_pd, op: &ChunkOp) -> Result<i64,
                     chunk_id: chunk_id as u64,
                     metadata: (*ctx).metadata.clone(),
                     data: (*ctx).buffer,
+                    checksum: None,
                 });
                 (*ctx).data_receive += ret.unwrap() as u64;
                 send_message(id);
@@ -252,6 +253,7 @@ fn on_completion(wc: *mut ibv_wc, _pd: *mut ibv_pd, op: &ChunkOp) -> Result<i64,
                 (*ctx).metadata.path =
                     String::from_utf8(transfer_md.path[0..transfer_md.path_len as usize].to_vec())
                         .unwrap();
+                (*ctx).metadata.file_id = transfer_md.file_id;
                 (*ctx).metadata.chunk_start = transfer_md.chunk_start;
                 (*ctx).metadata.offset = transfer_md.offset;
                 (*ctx).metadata.size = transfer_md.size;
@@ -93,6 +93,7 @@ pub(crate) fn sender_client(
         let mut md = TransferMetadata::default();
         md.size = task.metadata.size;
         md.offset = task.metadata.offset;
+        md.file_id = task.metadata.file_id;
         md.chunk_start = task.metadata.chunk_start;
         md.path_len = task.metadata.path.len();
         libc::memcpy(
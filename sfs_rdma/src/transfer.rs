@@ -1,3 +1,8 @@
+// immediate data of a read piece the server failed to read, e.g. on a checksum mismatch
+pub const READ_FAILED: u32 = u32::MAX;
+// immediate data of a read piece of a chunk the server does not hold, a hole unless the chunk
+// was placed on other hosts
+pub const READ_MISSING: u32 = u32::MAX - 1;
 #[allow(non_camel_case_types)]
 pub enum MessageType {
     MSG_MR,
@@ -9,6 +14,9 @@ pub struct Message {
     pub addr: u64,
     pub rkey: u32,
     pub data: u64,
+    // where a sender of chunks puts the checksum of the piece, 0 without checksums
+    pub checksum_addr: u64,
+    pub checksum_rkey: u32,
 }
 
 unsafe impl Send for Message {}
@@ -21,19 +29,26 @@ pub struct ChunkTransferTask {
 #[derive(Clone, Debug)]
 pub struct TransferMetadata {
     pub path: [u8; 256],
+    // names the chunks on the server, see 'Metadata::get_file_id'
+    pub file_id: u64,
     pub chunk_start: u64,
     pub offset: u64,
     pub size: u64,
     pub path_len: usize,
+    // pieces are checked against their checksum, it follows a written piece in the buffer of
+    // the server and is put to 'checksum_addr' of the message for a read piece
+    pub checksum: bool,
 }
 impl TransferMetadata {
     pub fn default() -> TransferMetadata {
         TransferMetadata {
             path: [0; 256],
+            file_id: 0,
             chunk_start: 0,
             offset: 0,
             size: 0,
             path_len: 0,
+            checksum: false,
         }
     }
 }
@@ -41,6 +56,7 @@ impl TransferMetadata {
 #[derive(Clone, Debug)]
 pub struct ChunkMetadata {
     pub path: String,
+    pub file_id: u64,
     pub chunk_start: u64,
     pub offset: u64,
     pub size: u64,
@@ -49,6 +65,7 @@ impl ChunkMetadata {
     pub fn default() -> ChunkMetadata {
         ChunkMetadata {
             path: "".to_string(),
+            file_id: 0,
             chunk_start: 0,
             offset: 0,
             size: 0,
@@ -1,6 +1,12 @@
-use proto::server::{Post, PostResult};
+use grpcio::{Error, RpcStatusCode};
+use libc::{
+    EACCES, EAGAIN, EBUSY, ECANCELED, EEXIST, EINVAL, EIO, ENOENT, ENOSPC, ENOTCONN, ENOTEMPTY,
+    ENOTSUP, ETIMEDOUT,
+};
+use proto::server::{ErrorCode, Post, PostResult, Status, StatusResponse};
 
 pub mod proto;
+pub mod security;
 
 pub fn post(option: i32, data: Vec<u8>, extra: Vec<u8>) -> Post{
     let mut res = Post::default();
@@ -15,4 +21,104 @@ pub fn post_result(err: i32, data: Vec<u8>, extra: Vec<u8>) -> PostResult{
     res.set_data(data);
     res.set_extra(extra);
     res
-}
\ No newline at end of file
+}
+pub fn status(errno: i32, message: String) -> Status{
+    let mut res = Status::default();
+    res.set_code(match errno {
+        0 => ErrorCode::OK,
+        ENOENT => ErrorCode::NOT_FOUND,
+        EEXIST => ErrorCode::EXISTS,
+        EINVAL => ErrorCode::INVALID_ARGUMENT,
+        ENOTEMPTY => ErrorCode::NOT_EMPTY,
+        ENOSPC => ErrorCode::NO_SPACE,
+        EIO => ErrorCode::IO_ERROR,
+        EBUSY => ErrorCode::BUSY,
+        _ => ErrorCode::OTHER,
+    });
+    res.set_errno(errno);
+    res.set_message(message);
+    res
+}
+// errno carried by a status, derived from the code when the peer did not fill it in
+pub fn status_errno(status: &Status) -> i32{
+    if status.errno != 0 {
+        return status.errno;
+    }
+    match status.code {
+        ErrorCode::OK => 0,
+        ErrorCode::NOT_FOUND => ENOENT,
+        ErrorCode::EXISTS => EEXIST,
+        ErrorCode::INVALID_ARGUMENT => EINVAL,
+        ErrorCode::NOT_EMPTY => ENOTEMPTY,
+        ErrorCode::NO_SPACE => ENOSPC,
+        ErrorCode::IO_ERROR => EIO,
+        ErrorCode::BUSY | ErrorCode::OTHER => EBUSY,
+    }
+}
+pub fn status_response(errno: i32) -> StatusResponse{
+    let mut res = StatusResponse::default();
+    res.set_status(status(errno, String::new()));
+    res
+}
+// errno handed to applications when a call fails on the transport instead of in a handler
+pub fn grpc_errno(e: &Error) -> i32{
+    match e {
+        Error::RpcFailure(status) => match status.code() {
+            RpcStatusCode::DEADLINE_EXCEEDED => ETIMEDOUT,
+            RpcStatusCode::UNAVAILABLE => ENOTCONN,
+            RpcStatusCode::UNAUTHENTICATED | RpcStatusCode::PERMISSION_DENIED => EACCES,
+            RpcStatusCode::RESOURCE_EXHAUSTED => EAGAIN,
+            RpcStatusCode::CANCELLED => ECANCELED,
+            RpcStatusCode::NOT_FOUND => ENOENT,
+            RpcStatusCode::INVALID_ARGUMENT => EINVAL,
+            RpcStatusCode::UNIMPLEMENTED => ENOTSUP,
+            _ => EIO,
+        },
+        Error::RemoteStopped | Error::RpcFinished(_) => ENOTCONN,
+        _ => EIO,
+    }
+}
+// the host did not answer at all, as opposed to answering with an error
+pub fn host_unreachable(e: &Error) -> bool{
+    match e {
+        Error::RpcFailure(status) => {
+            status.code() == RpcStatusCode::UNAVAILABLE
+                || status.code() == RpcStatusCode::DEADLINE_EXCEEDED
+        }
+        Error::RemoteStopped => true,
+        _ => false,
+    }
+}
+#[cfg(test)]
+mod tests {
+    use std::io::ErrorKind;
+
+    use crate::security::{load_token, token_eq};
+
+    #[test]
+    fn token_comes_from_env_before_file() {
+        let path = std::env::temp_dir().join(format!("sfs-token-{}", std::process::id()));
+        std::fs::write(&path, "from-file\n").unwrap();
+        let file = Some(path.to_string_lossy().into_owned());
+        assert_eq!(load_token(Some(" from-env ".to_string()), file.clone()).unwrap(), "from-env");
+        assert_eq!(load_token(None, file).unwrap(), "from-file");
+        assert_eq!(load_token(None, Some(String::new())).unwrap(), "");
+        assert_eq!(load_token(None, None).unwrap(), "");
+        std::fs::remove_file(&path).unwrap();
+        assert!(load_token(None, Some(path.to_string_lossy().into_owned())).is_err());
+    }
+    #[test]
+    fn token_has_to_fit_a_header() {
+        let res = load_token(Some("two words".to_string()), None);
+        assert_eq!(res.unwrap_err().kind(), ErrorKind::InvalidData);
+        assert!(load_token(Some("caf\u{e9}".to_string()), None).is_err());
+    }
+    #[test]
+    fn token_eq_compares_whole_tokens() {
+        assert!(token_eq(b"secret", b"secret"));
+        assert!(!token_eq(b"secret", b"secreT"));
+        assert!(!token_eq(b"secret", b"secret2"));
+        assert!(!token_eq(b"", b"secret"));
+        assert!(token_eq(b"", b""));
+    }
+}
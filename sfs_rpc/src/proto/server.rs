@@ -495,16 +495,4490 @@ impl ::protobuf::reflect::ProtobufValue for PostResult {
     }
 }
 
+#[derive(PartialEq,Clone,Default)]
+pub struct Status {
+    // message fields
+    pub code: ErrorCode,
+    pub errno: i32,
+    pub message: ::std::string::String,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a Status {
+    fn default() -> &'a Status {
+        <Status as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl Status {
+    pub fn new() -> Status {
+        ::std::default::Default::default()
+    }
+
+    // .sfs_server.ErrorCode code = 1;
+
+
+    pub fn get_code(&self) -> ErrorCode {
+        self.code
+    }
+    pub fn clear_code(&mut self) {
+        self.code = ErrorCode::OK;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_code(&mut self, v: ErrorCode) {
+        self.code = v;
+    }
+
+    // int32 errno = 2;
+
+
+    pub fn get_errno(&self) -> i32 {
+        self.errno
+    }
+    pub fn clear_errno(&mut self) {
+        self.errno = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_errno(&mut self, v: i32) {
+        self.errno = v;
+    }
+
+    // string message = 3;
+
+
+    pub fn get_message(&self) -> &str {
+        &self.message
+    }
+    pub fn clear_message(&mut self) {
+        self.message.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_message(&mut self, v: ::std::string::String) {
+        self.message = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_message(&mut self) -> &mut ::std::string::String {
+        &mut self.message
+    }
+
+    // Take field
+    pub fn take_message(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.message, ::std::string::String::new())
+    }
+}
+
+impl ::protobuf::Message for Status {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_proto3_enum_with_unknown_fields_into(wire_type, is, &mut self.code, 1, &mut self.unknown_fields)?
+                },
+                2 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_int32()?;
+                    self.errno = tmp;
+                },
+                3 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.message)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if self.code != ErrorCode::OK {
+            my_size += ::protobuf::rt::enum_size(1, self.code);
+        }
+        if self.errno != 0 {
+            my_size += ::protobuf::rt::value_size(2, self.errno, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if !self.message.is_empty() {
+            my_size += ::protobuf::rt::string_size(3, &self.message);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if self.code != ErrorCode::OK {
+            os.write_enum(1, ::protobuf::ProtobufEnum::value(&self.code))?;
+        }
+        if self.errno != 0 {
+            os.write_int32(2, self.errno)?;
+        }
+        if !self.message.is_empty() {
+            os.write_string(3, &self.message)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> Status {
+        Status::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeEnum<ErrorCode>>(
+                "code",
+                |m: &Status| { &m.code },
+                |m: &mut Status| { &mut m.code },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeInt32>(
+                "errno",
+                |m: &Status| { &m.errno },
+                |m: &mut Status| { &mut m.errno },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "message",
+                |m: &Status| { &m.message },
+                |m: &mut Status| { &mut m.message },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<Status>(
+                "Status",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static Status {
+        static instance: ::protobuf::rt::LazyV2<Status> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(Status::new)
+    }
+}
+
+impl ::protobuf::Clear for Status {
+    fn clear(&mut self) {
+        self.code = ErrorCode::OK;
+        self.errno = 0;
+        self.message.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for Status {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for Status {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct StatusResponse {
+    // message fields
+    pub status: ::protobuf::SingularPtrField<Status>,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a StatusResponse {
+    fn default() -> &'a StatusResponse {
+        <StatusResponse as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl StatusResponse {
+    pub fn new() -> StatusResponse {
+        ::std::default::Default::default()
+    }
+
+    // .sfs_server.Status status = 1;
+
+
+    pub fn get_status(&self) -> &Status {
+        self.status.as_ref().unwrap_or_else(|| <Status as ::protobuf::Message>::default_instance())
+    }
+    pub fn clear_status(&mut self) {
+        self.status.clear();
+    }
+
+    pub fn has_status(&self) -> bool {
+        self.status.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_status(&mut self, v: Status) {
+        self.status = ::protobuf::SingularPtrField::some(v);
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_status(&mut self) -> &mut Status {
+        if self.status.is_none() {
+            self.status.set_default();
+        }
+        self.status.as_mut().unwrap()
+    }
+
+    // Take field
+    pub fn take_status(&mut self) -> Status {
+        self.status.take().unwrap_or_else(|| Status::new())
+    }
+}
+
+impl ::protobuf::Message for StatusResponse {
+    fn is_initialized(&self) -> bool {
+        for v in &self.status {
+            if !v.is_initialized() {
+                return false;
+            }
+        };
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_message_into(wire_type, is, &mut self.status)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if let Some(ref v) = self.status.as_ref() {
+            let len = v.compute_size();
+            my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if let Some(ref v) = self.status.as_ref() {
+            os.write_tag(1, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+            os.write_raw_varint32(v.get_cached_size())?;
+            v.write_to_with_cached_sizes(os)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> StatusResponse {
+        StatusResponse::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_singular_ptr_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<Status>>(
+                "status",
+                |m: &StatusResponse| { &m.status },
+                |m: &mut StatusResponse| { &mut m.status },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<StatusResponse>(
+                "StatusResponse",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static StatusResponse {
+        static instance: ::protobuf::rt::LazyV2<StatusResponse> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(StatusResponse::new)
+    }
+}
+
+impl ::protobuf::Clear for StatusResponse {
+    fn clear(&mut self) {
+        self.status.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for StatusResponse {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for StatusResponse {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct HandshakeRequest {
+    // message fields
+    pub protocol_version: u32,
+    pub features: u64,
+    pub chunk_size: u64,
+    pub cluster_id: ::std::string::String,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a HandshakeRequest {
+    fn default() -> &'a HandshakeRequest {
+        <HandshakeRequest as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl HandshakeRequest {
+    pub fn new() -> HandshakeRequest {
+        ::std::default::Default::default()
+    }
+
+    // uint32 protocol_version = 1;
+
+
+    pub fn get_protocol_version(&self) -> u32 {
+        self.protocol_version
+    }
+    pub fn clear_protocol_version(&mut self) {
+        self.protocol_version = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_protocol_version(&mut self, v: u32) {
+        self.protocol_version = v;
+    }
+
+    // uint64 features = 2;
+
+
+    pub fn get_features(&self) -> u64 {
+        self.features
+    }
+    pub fn clear_features(&mut self) {
+        self.features = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_features(&mut self, v: u64) {
+        self.features = v;
+    }
+
+    // uint64 chunk_size = 3;
+
+
+    pub fn get_chunk_size(&self) -> u64 {
+        self.chunk_size
+    }
+    pub fn clear_chunk_size(&mut self) {
+        self.chunk_size = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_chunk_size(&mut self, v: u64) {
+        self.chunk_size = v;
+    }
+
+    // string cluster_id = 4;
+
+
+    pub fn get_cluster_id(&self) -> &str {
+        &self.cluster_id
+    }
+    pub fn clear_cluster_id(&mut self) {
+        self.cluster_id.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_cluster_id(&mut self, v: ::std::string::String) {
+        self.cluster_id = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_cluster_id(&mut self) -> &mut ::std::string::String {
+        &mut self.cluster_id
+    }
+
+    // Take field
+    pub fn take_cluster_id(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.cluster_id, ::std::string::String::new())
+    }
+}
+
+impl ::protobuf::Message for HandshakeRequest {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint32()?;
+                    self.protocol_version = tmp;
+                },
+                2 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.features = tmp;
+                },
+                3 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.chunk_size = tmp;
+                },
+                4 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.cluster_id)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if self.protocol_version != 0 {
+            my_size += ::protobuf::rt::value_size(1, self.protocol_version, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.features != 0 {
+            my_size += ::protobuf::rt::value_size(2, self.features, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.chunk_size != 0 {
+            my_size += ::protobuf::rt::value_size(3, self.chunk_size, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if !self.cluster_id.is_empty() {
+            my_size += ::protobuf::rt::string_size(4, &self.cluster_id);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if self.protocol_version != 0 {
+            os.write_uint32(1, self.protocol_version)?;
+        }
+        if self.features != 0 {
+            os.write_uint64(2, self.features)?;
+        }
+        if self.chunk_size != 0 {
+            os.write_uint64(3, self.chunk_size)?;
+        }
+        if !self.cluster_id.is_empty() {
+            os.write_string(4, &self.cluster_id)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> HandshakeRequest {
+        HandshakeRequest::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint32>(
+                "protocol_version",
+                |m: &HandshakeRequest| { &m.protocol_version },
+                |m: &mut HandshakeRequest| { &mut m.protocol_version },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                "features",
+                |m: &HandshakeRequest| { &m.features },
+                |m: &mut HandshakeRequest| { &mut m.features },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                "chunk_size",
+                |m: &HandshakeRequest| { &m.chunk_size },
+                |m: &mut HandshakeRequest| { &mut m.chunk_size },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "cluster_id",
+                |m: &HandshakeRequest| { &m.cluster_id },
+                |m: &mut HandshakeRequest| { &mut m.cluster_id },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<HandshakeRequest>(
+                "HandshakeRequest",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static HandshakeRequest {
+        static instance: ::protobuf::rt::LazyV2<HandshakeRequest> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(HandshakeRequest::new)
+    }
+}
+
+impl ::protobuf::Clear for HandshakeRequest {
+    fn clear(&mut self) {
+        self.protocol_version = 0;
+        self.features = 0;
+        self.chunk_size = 0;
+        self.cluster_id.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for HandshakeRequest {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for HandshakeRequest {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct HandshakeResponse {
+    // message fields
+    pub status: ::protobuf::SingularPtrField<Status>,
+    pub protocol_version: u32,
+    pub features: u64,
+    pub chunk_size: u64,
+    pub cluster_id: ::std::string::String,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a HandshakeResponse {
+    fn default() -> &'a HandshakeResponse {
+        <HandshakeResponse as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl HandshakeResponse {
+    pub fn new() -> HandshakeResponse {
+        ::std::default::Default::default()
+    }
+
+    // .sfs_server.Status status = 1;
+
+
+    pub fn get_status(&self) -> &Status {
+        self.status.as_ref().unwrap_or_else(|| <Status as ::protobuf::Message>::default_instance())
+    }
+    pub fn clear_status(&mut self) {
+        self.status.clear();
+    }
+
+    pub fn has_status(&self) -> bool {
+        self.status.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_status(&mut self, v: Status) {
+        self.status = ::protobuf::SingularPtrField::some(v);
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_status(&mut self) -> &mut Status {
+        if self.status.is_none() {
+            self.status.set_default();
+        }
+        self.status.as_mut().unwrap()
+    }
+
+    // Take field
+    pub fn take_status(&mut self) -> Status {
+        self.status.take().unwrap_or_else(|| Status::new())
+    }
+
+    // uint32 protocol_version = 2;
+
+
+    pub fn get_protocol_version(&self) -> u32 {
+        self.protocol_version
+    }
+    pub fn clear_protocol_version(&mut self) {
+        self.protocol_version = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_protocol_version(&mut self, v: u32) {
+        self.protocol_version = v;
+    }
+
+    // uint64 features = 3;
+
+
+    pub fn get_features(&self) -> u64 {
+        self.features
+    }
+    pub fn clear_features(&mut self) {
+        self.features = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_features(&mut self, v: u64) {
+        self.features = v;
+    }
+
+    // uint64 chunk_size = 4;
+
+
+    pub fn get_chunk_size(&self) -> u64 {
+        self.chunk_size
+    }
+    pub fn clear_chunk_size(&mut self) {
+        self.chunk_size = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_chunk_size(&mut self, v: u64) {
+        self.chunk_size = v;
+    }
+
+    // string cluster_id = 5;
+
+
+    pub fn get_cluster_id(&self) -> &str {
+        &self.cluster_id
+    }
+    pub fn clear_cluster_id(&mut self) {
+        self.cluster_id.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_cluster_id(&mut self, v: ::std::string::String) {
+        self.cluster_id = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_cluster_id(&mut self) -> &mut ::std::string::String {
+        &mut self.cluster_id
+    }
+
+    // Take field
+    pub fn take_cluster_id(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.cluster_id, ::std::string::String::new())
+    }
+}
+
+impl ::protobuf::Message for HandshakeResponse {
+    fn is_initialized(&self) -> bool {
+        for v in &self.status {
+            if !v.is_initialized() {
+                return false;
+            }
+        };
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_message_into(wire_type, is, &mut self.status)?;
+                },
+                2 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint32()?;
+                    self.protocol_version = tmp;
+                },
+                3 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.features = tmp;
+                },
+                4 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.chunk_size = tmp;
+                },
+                5 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.cluster_id)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if let Some(ref v) = self.status.as_ref() {
+            let len = v.compute_size();
+            my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+        }
+        if self.protocol_version != 0 {
+            my_size += ::protobuf::rt::value_size(2, self.protocol_version, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.features != 0 {
+            my_size += ::protobuf::rt::value_size(3, self.features, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.chunk_size != 0 {
+            my_size += ::protobuf::rt::value_size(4, self.chunk_size, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if !self.cluster_id.is_empty() {
+            my_size += ::protobuf::rt::string_size(5, &self.cluster_id);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if let Some(ref v) = self.status.as_ref() {
+            os.write_tag(1, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+            os.write_raw_varint32(v.get_cached_size())?;
+            v.write_to_with_cached_sizes(os)?;
+        }
+        if self.protocol_version != 0 {
+            os.write_uint32(2, self.protocol_version)?;
+        }
+        if self.features != 0 {
+            os.write_uint64(3, self.features)?;
+        }
+        if self.chunk_size != 0 {
+            os.write_uint64(4, self.chunk_size)?;
+        }
+        if !self.cluster_id.is_empty() {
+            os.write_string(5, &self.cluster_id)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> HandshakeResponse {
+        HandshakeResponse::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_singular_ptr_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<Status>>(
+                "status",
+                |m: &HandshakeResponse| { &m.status },
+                |m: &mut HandshakeResponse| { &mut m.status },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint32>(
+                "protocol_version",
+                |m: &HandshakeResponse| { &m.protocol_version },
+                |m: &mut HandshakeResponse| { &mut m.protocol_version },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                "features",
+                |m: &HandshakeResponse| { &m.features },
+                |m: &mut HandshakeResponse| { &mut m.features },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                "chunk_size",
+                |m: &HandshakeResponse| { &m.chunk_size },
+                |m: &mut HandshakeResponse| { &mut m.chunk_size },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "cluster_id",
+                |m: &HandshakeResponse| { &m.cluster_id },
+                |m: &mut HandshakeResponse| { &mut m.cluster_id },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<HandshakeResponse>(
+                "HandshakeResponse",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static HandshakeResponse {
+        static instance: ::protobuf::rt::LazyV2<HandshakeResponse> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(HandshakeResponse::new)
+    }
+}
+
+impl ::protobuf::Clear for HandshakeResponse {
+    fn clear(&mut self) {
+        self.status.clear();
+        self.protocol_version = 0;
+        self.features = 0;
+        self.chunk_size = 0;
+        self.cluster_id.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for HandshakeResponse {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for HandshakeResponse {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct Attr {
+    // message fields
+    pub mode: u32,
+    pub size: i64,
+    pub access_time: i64,
+    pub modify_time: i64,
+    pub change_time: i64,
+    pub link_count: u64,
+    pub blocks: i64,
+    pub file_id: u64,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a Attr {
+    fn default() -> &'a Attr {
+        <Attr as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl Attr {
+    pub fn new() -> Attr {
+        ::std::default::Default::default()
+    }
+
+    // uint32 mode = 1;
+
+
+    pub fn get_mode(&self) -> u32 {
+        self.mode
+    }
+    pub fn clear_mode(&mut self) {
+        self.mode = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_mode(&mut self, v: u32) {
+        self.mode = v;
+    }
+
+    // int64 size = 2;
+
+
+    pub fn get_size(&self) -> i64 {
+        self.size
+    }
+    pub fn clear_size(&mut self) {
+        self.size = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_size(&mut self, v: i64) {
+        self.size = v;
+    }
+
+    // int64 access_time = 3;
+
+
+    pub fn get_access_time(&self) -> i64 {
+        self.access_time
+    }
+    pub fn clear_access_time(&mut self) {
+        self.access_time = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_access_time(&mut self, v: i64) {
+        self.access_time = v;
+    }
+
+    // int64 modify_time = 4;
+
+
+    pub fn get_modify_time(&self) -> i64 {
+        self.modify_time
+    }
+    pub fn clear_modify_time(&mut self) {
+        self.modify_time = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_modify_time(&mut self, v: i64) {
+        self.modify_time = v;
+    }
+
+    // int64 change_time = 5;
+
+
+    pub fn get_change_time(&self) -> i64 {
+        self.change_time
+    }
+    pub fn clear_change_time(&mut self) {
+        self.change_time = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_change_time(&mut self, v: i64) {
+        self.change_time = v;
+    }
+
+    // uint64 link_count = 6;
+
+
+    pub fn get_link_count(&self) -> u64 {
+        self.link_count
+    }
+    pub fn clear_link_count(&mut self) {
+        self.link_count = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_link_count(&mut self, v: u64) {
+        self.link_count = v;
+    }
+
+    // int64 blocks = 7;
+
+
+    pub fn get_blocks(&self) -> i64 {
+        self.blocks
+    }
+    pub fn clear_blocks(&mut self) {
+        self.blocks = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_blocks(&mut self, v: i64) {
+        self.blocks = v;
+    }
+
+    // uint64 file_id = 8;
+
+
+    pub fn get_file_id(&self) -> u64 {
+        self.file_id
+    }
+    pub fn clear_file_id(&mut self) {
+        self.file_id = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_file_id(&mut self, v: u64) {
+        self.file_id = v;
+    }
+}
+
+impl ::protobuf::Message for Attr {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint32()?;
+                    self.mode = tmp;
+                },
+                2 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_int64()?;
+                    self.size = tmp;
+                },
+                3 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_int64()?;
+                    self.access_time = tmp;
+                },
+                4 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_int64()?;
+                    self.modify_time = tmp;
+                },
+                5 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_int64()?;
+                    self.change_time = tmp;
+                },
+                6 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.link_count = tmp;
+                },
+                7 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_int64()?;
+                    self.blocks = tmp;
+                },
+                8 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.file_id = tmp;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if self.mode != 0 {
+            my_size += ::protobuf::rt::value_size(1, self.mode, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.size != 0 {
+            my_size += ::protobuf::rt::value_size(2, self.size, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.access_time != 0 {
+            my_size += ::protobuf::rt::value_size(3, self.access_time, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.modify_time != 0 {
+            my_size += ::protobuf::rt::value_size(4, self.modify_time, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.change_time != 0 {
+            my_size += ::protobuf::rt::value_size(5, self.change_time, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.link_count != 0 {
+            my_size += ::protobuf::rt::value_size(6, self.link_count, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.blocks != 0 {
+            my_size += ::protobuf::rt::value_size(7, self.blocks, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.file_id != 0 {
+            my_size += ::protobuf::rt::value_size(8, self.file_id, ::protobuf::wire_format::WireTypeVarint);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if self.mode != 0 {
+            os.write_uint32(1, self.mode)?;
+        }
+        if self.size != 0 {
+            os.write_int64(2, self.size)?;
+        }
+        if self.access_time != 0 {
+            os.write_int64(3, self.access_time)?;
+        }
+        if self.modify_time != 0 {
+            os.write_int64(4, self.modify_time)?;
+        }
+        if self.change_time != 0 {
+            os.write_int64(5, self.change_time)?;
+        }
+        if self.link_count != 0 {
+            os.write_uint64(6, self.link_count)?;
+        }
+        if self.blocks != 0 {
+            os.write_int64(7, self.blocks)?;
+        }
+        if self.file_id != 0 {
+            os.write_uint64(8, self.file_id)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> Attr {
+        Attr::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint32>(
+                "mode",
+                |m: &Attr| { &m.mode },
+                |m: &mut Attr| { &mut m.mode },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeInt64>(
+                "size",
+                |m: &Attr| { &m.size },
+                |m: &mut Attr| { &mut m.size },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeInt64>(
+                "access_time",
+                |m: &Attr| { &m.access_time },
+                |m: &mut Attr| { &mut m.access_time },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeInt64>(
+                "modify_time",
+                |m: &Attr| { &m.modify_time },
+                |m: &mut Attr| { &mut m.modify_time },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeInt64>(
+                "change_time",
+                |m: &Attr| { &m.change_time },
+                |m: &mut Attr| { &mut m.change_time },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                "link_count",
+                |m: &Attr| { &m.link_count },
+                |m: &mut Attr| { &mut m.link_count },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeInt64>(
+                "blocks",
+                |m: &Attr| { &m.blocks },
+                |m: &mut Attr| { &mut m.blocks },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                "file_id",
+                |m: &Attr| { &m.file_id },
+                |m: &mut Attr| { &mut m.file_id },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<Attr>(
+                "Attr",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static Attr {
+        static instance: ::protobuf::rt::LazyV2<Attr> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(Attr::new)
+    }
+}
+
+impl ::protobuf::Clear for Attr {
+    fn clear(&mut self) {
+        self.mode = 0;
+        self.size = 0;
+        self.access_time = 0;
+        self.modify_time = 0;
+        self.change_time = 0;
+        self.link_count = 0;
+        self.blocks = 0;
+        self.file_id = 0;
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for Attr {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for Attr {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct PathRequest {
+    // message fields
+    pub path: ::std::string::String,
+    pub client: u64,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a PathRequest {
+    fn default() -> &'a PathRequest {
+        <PathRequest as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl PathRequest {
+    pub fn new() -> PathRequest {
+        ::std::default::Default::default()
+    }
+
+    // string path = 1;
+
+
+    pub fn get_path(&self) -> &str {
+        &self.path
+    }
+    pub fn clear_path(&mut self) {
+        self.path.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_path(&mut self, v: ::std::string::String) {
+        self.path = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_path(&mut self) -> &mut ::std::string::String {
+        &mut self.path
+    }
+
+    // Take field
+    pub fn take_path(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.path, ::std::string::String::new())
+    }
+
+    // uint64 client = 2;
+
+
+    pub fn get_client(&self) -> u64 {
+        self.client
+    }
+    pub fn clear_client(&mut self) {
+        self.client = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_client(&mut self, v: u64) {
+        self.client = v;
+    }
+}
+
+impl ::protobuf::Message for PathRequest {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.path)?;
+                },
+                2 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.client = tmp;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.path.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.path);
+        }
+        if self.client != 0 {
+            my_size += ::protobuf::rt::value_size(2, self.client, ::protobuf::wire_format::WireTypeVarint);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.path.is_empty() {
+            os.write_string(1, &self.path)?;
+        }
+        if self.client != 0 {
+            os.write_uint64(2, self.client)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> PathRequest {
+        PathRequest::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "path",
+                |m: &PathRequest| { &m.path },
+                |m: &mut PathRequest| { &mut m.path },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                "client",
+                |m: &PathRequest| { &m.client },
+                |m: &mut PathRequest| { &mut m.client },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<PathRequest>(
+                "PathRequest",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static PathRequest {
+        static instance: ::protobuf::rt::LazyV2<PathRequest> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(PathRequest::new)
+    }
+}
+
+impl ::protobuf::Clear for PathRequest {
+    fn clear(&mut self) {
+        self.path.clear();
+        self.client = 0;
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for PathRequest {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for PathRequest {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct StatRequest {
+    // message fields
+    pub path: ::std::string::String,
+    pub client: u64,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a StatRequest {
+    fn default() -> &'a StatRequest {
+        <StatRequest as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl StatRequest {
+    pub fn new() -> StatRequest {
+        ::std::default::Default::default()
+    }
+
+    // string path = 1;
+
+
+    pub fn get_path(&self) -> &str {
+        &self.path
+    }
+    pub fn clear_path(&mut self) {
+        self.path.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_path(&mut self, v: ::std::string::String) {
+        self.path = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_path(&mut self) -> &mut ::std::string::String {
+        &mut self.path
+    }
+
+    // Take field
+    pub fn take_path(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.path, ::std::string::String::new())
+    }
+
+    // uint64 client = 2;
+
+
+    pub fn get_client(&self) -> u64 {
+        self.client
+    }
+    pub fn clear_client(&mut self) {
+        self.client = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_client(&mut self, v: u64) {
+        self.client = v;
+    }
+}
+
+impl ::protobuf::Message for StatRequest {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.path)?;
+                },
+                2 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.client = tmp;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.path.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.path);
+        }
+        if self.client != 0 {
+            my_size += ::protobuf::rt::value_size(2, self.client, ::protobuf::wire_format::WireTypeVarint);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.path.is_empty() {
+            os.write_string(1, &self.path)?;
+        }
+        if self.client != 0 {
+            os.write_uint64(2, self.client)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> StatRequest {
+        StatRequest::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "path",
+                |m: &StatRequest| { &m.path },
+                |m: &mut StatRequest| { &mut m.path },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                "client",
+                |m: &StatRequest| { &m.client },
+                |m: &mut StatRequest| { &mut m.client },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<StatRequest>(
+                "StatRequest",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static StatRequest {
+        static instance: ::protobuf::rt::LazyV2<StatRequest> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(StatRequest::new)
+    }
+}
+
+impl ::protobuf::Clear for StatRequest {
+    fn clear(&mut self) {
+        self.path.clear();
+        self.client = 0;
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for StatRequest {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for StatRequest {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct StatResponse {
+    // message fields
+    pub status: ::protobuf::SingularPtrField<Status>,
+    pub attr: ::protobuf::SingularPtrField<Attr>,
+    pub lease_ms: u64,
+    pub stuffed: bool,
+    pub data: ::std::vec::Vec<u8>,
+    pub placement: ::std::vec::Vec<u8>,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a StatResponse {
+    fn default() -> &'a StatResponse {
+        <StatResponse as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl StatResponse {
+    pub fn new() -> StatResponse {
+        ::std::default::Default::default()
+    }
+
+    // .sfs_server.Status status = 1;
+
+
+    pub fn get_status(&self) -> &Status {
+        self.status.as_ref().unwrap_or_else(|| <Status as ::protobuf::Message>::default_instance())
+    }
+    pub fn clear_status(&mut self) {
+        self.status.clear();
+    }
+
+    pub fn has_status(&self) -> bool {
+        self.status.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_status(&mut self, v: Status) {
+        self.status = ::protobuf::SingularPtrField::some(v);
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_status(&mut self) -> &mut Status {
+        if self.status.is_none() {
+            self.status.set_default();
+        }
+        self.status.as_mut().unwrap()
+    }
+
+    // Take field
+    pub fn take_status(&mut self) -> Status {
+        self.status.take().unwrap_or_else(|| Status::new())
+    }
+
+    // .sfs_server.Attr attr = 2;
+
+
+    pub fn get_attr(&self) -> &Attr {
+        self.attr.as_ref().unwrap_or_else(|| <Attr as ::protobuf::Message>::default_instance())
+    }
+    pub fn clear_attr(&mut self) {
+        self.attr.clear();
+    }
+
+    pub fn has_attr(&self) -> bool {
+        self.attr.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_attr(&mut self, v: Attr) {
+        self.attr = ::protobuf::SingularPtrField::some(v);
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_attr(&mut self) -> &mut Attr {
+        if self.attr.is_none() {
+            self.attr.set_default();
+        }
+        self.attr.as_mut().unwrap()
+    }
+
+    // Take field
+    pub fn take_attr(&mut self) -> Attr {
+        self.attr.take().unwrap_or_else(|| Attr::new())
+    }
+
+    // uint64 lease_ms = 3;
+
+
+    pub fn get_lease_ms(&self) -> u64 {
+        self.lease_ms
+    }
+    pub fn clear_lease_ms(&mut self) {
+        self.lease_ms = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_lease_ms(&mut self, v: u64) {
+        self.lease_ms = v;
+    }
+
+    // bool stuffed = 4;
+
+
+    pub fn get_stuffed(&self) -> bool {
+        self.stuffed
+    }
+    pub fn clear_stuffed(&mut self) {
+        self.stuffed = false;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_stuffed(&mut self, v: bool) {
+        self.stuffed = v;
+    }
+
+    // bytes data = 5;
+
+
+    pub fn get_data(&self) -> &[u8] {
+        &self.data
+    }
+    pub fn clear_data(&mut self) {
+        self.data.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_data(&mut self, v: ::std::vec::Vec<u8>) {
+        self.data = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_data(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.data
+    }
+
+    // Take field
+    pub fn take_data(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.data, ::std::vec::Vec::new())
+    }
+
+    // bytes placement = 6;
+
+
+    pub fn get_placement(&self) -> &[u8] {
+        &self.placement
+    }
+    pub fn clear_placement(&mut self) {
+        self.placement.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_placement(&mut self, v: ::std::vec::Vec<u8>) {
+        self.placement = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_placement(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.placement
+    }
+
+    // Take field
+    pub fn take_placement(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.placement, ::std::vec::Vec::new())
+    }
+}
+
+impl ::protobuf::Message for StatResponse {
+    fn is_initialized(&self) -> bool {
+        for v in &self.status {
+            if !v.is_initialized() {
+                return false;
+            }
+        };
+        for v in &self.attr {
+            if !v.is_initialized() {
+                return false;
+            }
+        };
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_message_into(wire_type, is, &mut self.status)?;
+                },
+                2 => {
+                    ::protobuf::rt::read_singular_message_into(wire_type, is, &mut self.attr)?;
+                },
+                3 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.lease_ms = tmp;
+                },
+                4 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_bool()?;
+                    self.stuffed = tmp;
+                },
+                5 => {
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.data)?;
+                },
+                6 => {
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.placement)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if let Some(ref v) = self.status.as_ref() {
+            let len = v.compute_size();
+            my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+        }
+        if let Some(ref v) = self.attr.as_ref() {
+            let len = v.compute_size();
+            my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+        }
+        if self.lease_ms != 0 {
+            my_size += ::protobuf::rt::value_size(3, self.lease_ms, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.stuffed != false {
+            my_size += 2;
+        }
+        if !self.data.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(5, &self.data);
+        }
+        if !self.placement.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(6, &self.placement);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if let Some(ref v) = self.status.as_ref() {
+            os.write_tag(1, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+            os.write_raw_varint32(v.get_cached_size())?;
+            v.write_to_with_cached_sizes(os)?;
+        }
+        if let Some(ref v) = self.attr.as_ref() {
+            os.write_tag(2, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+            os.write_raw_varint32(v.get_cached_size())?;
+            v.write_to_with_cached_sizes(os)?;
+        }
+        if self.lease_ms != 0 {
+            os.write_uint64(3, self.lease_ms)?;
+        }
+        if self.stuffed != false {
+            os.write_bool(4, self.stuffed)?;
+        }
+        if !self.data.is_empty() {
+            os.write_bytes(5, &self.data)?;
+        }
+        if !self.placement.is_empty() {
+            os.write_bytes(6, &self.placement)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> StatResponse {
+        StatResponse::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_singular_ptr_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<Status>>(
+                "status",
+                |m: &StatResponse| { &m.status },
+                |m: &mut StatResponse| { &mut m.status },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_singular_ptr_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<Attr>>(
+                "attr",
+                |m: &StatResponse| { &m.attr },
+                |m: &mut StatResponse| { &mut m.attr },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                "lease_ms",
+                |m: &StatResponse| { &m.lease_ms },
+                |m: &mut StatResponse| { &mut m.lease_ms },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBool>(
+                "stuffed",
+                |m: &StatResponse| { &m.stuffed },
+                |m: &mut StatResponse| { &mut m.stuffed },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                "data",
+                |m: &StatResponse| { &m.data },
+                |m: &mut StatResponse| { &mut m.data },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                "placement",
+                |m: &StatResponse| { &m.placement },
+                |m: &mut StatResponse| { &mut m.placement },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<StatResponse>(
+                "StatResponse",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static StatResponse {
+        static instance: ::protobuf::rt::LazyV2<StatResponse> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(StatResponse::new)
+    }
+}
+
+impl ::protobuf::Clear for StatResponse {
+    fn clear(&mut self) {
+        self.status.clear();
+        self.attr.clear();
+        self.lease_ms = 0;
+        self.stuffed = false;
+        self.data.clear();
+        self.placement.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for StatResponse {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for StatResponse {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct CreateRequest {
+    // message fields
+    pub path: ::std::string::String,
+    pub mode: u32,
+    pub client: u64,
+    pub file_id: u64,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a CreateRequest {
+    fn default() -> &'a CreateRequest {
+        <CreateRequest as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl CreateRequest {
+    pub fn new() -> CreateRequest {
+        ::std::default::Default::default()
+    }
+
+    // string path = 1;
+
+
+    pub fn get_path(&self) -> &str {
+        &self.path
+    }
+    pub fn clear_path(&mut self) {
+        self.path.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_path(&mut self, v: ::std::string::String) {
+        self.path = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_path(&mut self) -> &mut ::std::string::String {
+        &mut self.path
+    }
+
+    // Take field
+    pub fn take_path(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.path, ::std::string::String::new())
+    }
+
+    // uint32 mode = 2;
+
+
+    pub fn get_mode(&self) -> u32 {
+        self.mode
+    }
+    pub fn clear_mode(&mut self) {
+        self.mode = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_mode(&mut self, v: u32) {
+        self.mode = v;
+    }
+
+    // uint64 client = 3;
+
+
+    pub fn get_client(&self) -> u64 {
+        self.client
+    }
+    pub fn clear_client(&mut self) {
+        self.client = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_client(&mut self, v: u64) {
+        self.client = v;
+    }
+
+    // uint64 file_id = 4;
+
+
+    pub fn get_file_id(&self) -> u64 {
+        self.file_id
+    }
+    pub fn clear_file_id(&mut self) {
+        self.file_id = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_file_id(&mut self, v: u64) {
+        self.file_id = v;
+    }
+}
+
+impl ::protobuf::Message for CreateRequest {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.path)?;
+                },
+                2 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint32()?;
+                    self.mode = tmp;
+                },
+                3 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.client = tmp;
+                },
+                4 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.file_id = tmp;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.path.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.path);
+        }
+        if self.mode != 0 {
+            my_size += ::protobuf::rt::value_size(2, self.mode, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.client != 0 {
+            my_size += ::protobuf::rt::value_size(3, self.client, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.file_id != 0 {
+            my_size += ::protobuf::rt::value_size(4, self.file_id, ::protobuf::wire_format::WireTypeVarint);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.path.is_empty() {
+            os.write_string(1, &self.path)?;
+        }
+        if self.mode != 0 {
+            os.write_uint32(2, self.mode)?;
+        }
+        if self.client != 0 {
+            os.write_uint64(3, self.client)?;
+        }
+        if self.file_id != 0 {
+            os.write_uint64(4, self.file_id)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> CreateRequest {
+        CreateRequest::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "path",
+                |m: &CreateRequest| { &m.path },
+                |m: &mut CreateRequest| { &mut m.path },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint32>(
+                "mode",
+                |m: &CreateRequest| { &m.mode },
+                |m: &mut CreateRequest| { &mut m.mode },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                "client",
+                |m: &CreateRequest| { &m.client },
+                |m: &mut CreateRequest| { &mut m.client },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                "file_id",
+                |m: &CreateRequest| { &m.file_id },
+                |m: &mut CreateRequest| { &mut m.file_id },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<CreateRequest>(
+                "CreateRequest",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static CreateRequest {
+        static instance: ::protobuf::rt::LazyV2<CreateRequest> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(CreateRequest::new)
+    }
+}
+
+impl ::protobuf::Clear for CreateRequest {
+    fn clear(&mut self) {
+        self.path.clear();
+        self.mode = 0;
+        self.client = 0;
+        self.file_id = 0;
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for CreateRequest {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for CreateRequest {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct CreateResponse {
+    // message fields
+    pub status: ::protobuf::SingularPtrField<Status>,
+    pub file_id: u64,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a CreateResponse {
+    fn default() -> &'a CreateResponse {
+        <CreateResponse as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl CreateResponse {
+    pub fn new() -> CreateResponse {
+        ::std::default::Default::default()
+    }
+
+    // .sfs_server.Status status = 1;
+
+
+    pub fn get_status(&self) -> &Status {
+        self.status.as_ref().unwrap_or_else(|| <Status as ::protobuf::Message>::default_instance())
+    }
+    pub fn clear_status(&mut self) {
+        self.status.clear();
+    }
+
+    pub fn has_status(&self) -> bool {
+        self.status.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_status(&mut self, v: Status) {
+        self.status = ::protobuf::SingularPtrField::some(v);
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_status(&mut self) -> &mut Status {
+        if self.status.is_none() {
+            self.status.set_default();
+        }
+        self.status.as_mut().unwrap()
+    }
+
+    // Take field
+    pub fn take_status(&mut self) -> Status {
+        self.status.take().unwrap_or_else(|| Status::new())
+    }
+
+    // uint64 file_id = 2;
+
+
+    pub fn get_file_id(&self) -> u64 {
+        self.file_id
+    }
+    pub fn clear_file_id(&mut self) {
+        self.file_id = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_file_id(&mut self, v: u64) {
+        self.file_id = v;
+    }
+}
+
+impl ::protobuf::Message for CreateResponse {
+    fn is_initialized(&self) -> bool {
+        for v in &self.status {
+            if !v.is_initialized() {
+                return false;
+            }
+        };
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_message_into(wire_type, is, &mut self.status)?;
+                },
+                2 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.file_id = tmp;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if let Some(ref v) = self.status.as_ref() {
+            let len = v.compute_size();
+            my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+        }
+        if self.file_id != 0 {
+            my_size += ::protobuf::rt::value_size(2, self.file_id, ::protobuf::wire_format::WireTypeVarint);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if let Some(ref v) = self.status.as_ref() {
+            os.write_tag(1, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+            os.write_raw_varint32(v.get_cached_size())?;
+            v.write_to_with_cached_sizes(os)?;
+        }
+        if self.file_id != 0 {
+            os.write_uint64(2, self.file_id)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> CreateResponse {
+        CreateResponse::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_singular_ptr_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<Status>>(
+                "status",
+                |m: &CreateResponse| { &m.status },
+                |m: &mut CreateResponse| { &mut m.status },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                "file_id",
+                |m: &CreateResponse| { &m.file_id },
+                |m: &mut CreateResponse| { &mut m.file_id },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<CreateResponse>(
+                "CreateResponse",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static CreateResponse {
+        static instance: ::protobuf::rt::LazyV2<CreateResponse> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(CreateResponse::new)
+    }
+}
+
+impl ::protobuf::Clear for CreateResponse {
+    fn clear(&mut self) {
+        self.status.clear();
+        self.file_id = 0;
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for CreateResponse {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for CreateResponse {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct UpdateSizeRequest {
+    // message fields
+    pub path: ::std::string::String,
+    pub size: u64,
+    pub offset: i64,
+    pub append: bool,
+    pub client: u64,
+    pub data: ::std::vec::Vec<u8>,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a UpdateSizeRequest {
+    fn default() -> &'a UpdateSizeRequest {
+        <UpdateSizeRequest as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl UpdateSizeRequest {
+    pub fn new() -> UpdateSizeRequest {
+        ::std::default::Default::default()
+    }
+
+    // string path = 1;
+
+
+    pub fn get_path(&self) -> &str {
+        &self.path
+    }
+    pub fn clear_path(&mut self) {
+        self.path.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_path(&mut self, v: ::std::string::String) {
+        self.path = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_path(&mut self) -> &mut ::std::string::String {
+        &mut self.path
+    }
+
+    // Take field
+    pub fn take_path(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.path, ::std::string::String::new())
+    }
+
+    // uint64 size = 2;
+
+
+    pub fn get_size(&self) -> u64 {
+        self.size
+    }
+    pub fn clear_size(&mut self) {
+        self.size = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_size(&mut self, v: u64) {
+        self.size = v;
+    }
+
+    // int64 offset = 3;
+
+
+    pub fn get_offset(&self) -> i64 {
+        self.offset
+    }
+    pub fn clear_offset(&mut self) {
+        self.offset = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_offset(&mut self, v: i64) {
+        self.offset = v;
+    }
+
+    // bool append = 4;
+
+
+    pub fn get_append(&self) -> bool {
+        self.append
+    }
+    pub fn clear_append(&mut self) {
+        self.append = false;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_append(&mut self, v: bool) {
+        self.append = v;
+    }
+
+    // uint64 client = 5;
+
+
+    pub fn get_client(&self) -> u64 {
+        self.client
+    }
+    pub fn clear_client(&mut self) {
+        self.client = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_client(&mut self, v: u64) {
+        self.client = v;
+    }
+
+    // bytes data = 6;
+
+
+    pub fn get_data(&self) -> &[u8] {
+        &self.data
+    }
+    pub fn clear_data(&mut self) {
+        self.data.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_data(&mut self, v: ::std::vec::Vec<u8>) {
+        self.data = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_data(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.data
+    }
+
+    // Take field
+    pub fn take_data(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.data, ::std::vec::Vec::new())
+    }
+}
+
+impl ::protobuf::Message for UpdateSizeRequest {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.path)?;
+                },
+                2 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.size = tmp;
+                },
+                3 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_int64()?;
+                    self.offset = tmp;
+                },
+                4 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_bool()?;
+                    self.append = tmp;
+                },
+                5 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.client = tmp;
+                },
+                6 => {
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.data)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.path.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.path);
+        }
+        if self.size != 0 {
+            my_size += ::protobuf::rt::value_size(2, self.size, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.offset != 0 {
+            my_size += ::protobuf::rt::value_size(3, self.offset, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.append != false {
+            my_size += 2;
+        }
+        if self.client != 0 {
+            my_size += ::protobuf::rt::value_size(5, self.client, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if !self.data.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(6, &self.data);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.path.is_empty() {
+            os.write_string(1, &self.path)?;
+        }
+        if self.size != 0 {
+            os.write_uint64(2, self.size)?;
+        }
+        if self.offset != 0 {
+            os.write_int64(3, self.offset)?;
+        }
+        if self.append != false {
+            os.write_bool(4, self.append)?;
+        }
+        if self.client != 0 {
+            os.write_uint64(5, self.client)?;
+        }
+        if !self.data.is_empty() {
+            os.write_bytes(6, &self.data)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> UpdateSizeRequest {
+        UpdateSizeRequest::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "path",
+                |m: &UpdateSizeRequest| { &m.path },
+                |m: &mut UpdateSizeRequest| { &mut m.path },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                "size",
+                |m: &UpdateSizeRequest| { &m.size },
+                |m: &mut UpdateSizeRequest| { &mut m.size },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeInt64>(
+                "offset",
+                |m: &UpdateSizeRequest| { &m.offset },
+                |m: &mut UpdateSizeRequest| { &mut m.offset },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBool>(
+                "append",
+                |m: &UpdateSizeRequest| { &m.append },
+                |m: &mut UpdateSizeRequest| { &mut m.append },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                "client",
+                |m: &UpdateSizeRequest| { &m.client },
+                |m: &mut UpdateSizeRequest| { &mut m.client },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                "data",
+                |m: &UpdateSizeRequest| { &m.data },
+                |m: &mut UpdateSizeRequest| { &mut m.data },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<UpdateSizeRequest>(
+                "UpdateSizeRequest",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static UpdateSizeRequest {
+        static instance: ::protobuf::rt::LazyV2<UpdateSizeRequest> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(UpdateSizeRequest::new)
+    }
+}
+
+impl ::protobuf::Clear for UpdateSizeRequest {
+    fn clear(&mut self) {
+        self.path.clear();
+        self.size = 0;
+        self.offset = 0;
+        self.append = false;
+        self.client = 0;
+        self.data.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for UpdateSizeRequest {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for UpdateSizeRequest {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct DecreaseSizeRequest {
+    // message fields
+    pub path: ::std::string::String,
+    pub new_size: i64,
+    pub client: u64,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a DecreaseSizeRequest {
+    fn default() -> &'a DecreaseSizeRequest {
+        <DecreaseSizeRequest as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl DecreaseSizeRequest {
+    pub fn new() -> DecreaseSizeRequest {
+        ::std::default::Default::default()
+    }
+
+    // string path = 1;
+
+
+    pub fn get_path(&self) -> &str {
+        &self.path
+    }
+    pub fn clear_path(&mut self) {
+        self.path.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_path(&mut self, v: ::std::string::String) {
+        self.path = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_path(&mut self) -> &mut ::std::string::String {
+        &mut self.path
+    }
+
+    // Take field
+    pub fn take_path(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.path, ::std::string::String::new())
+    }
+
+    // int64 new_size = 2;
+
+
+    pub fn get_new_size(&self) -> i64 {
+        self.new_size
+    }
+    pub fn clear_new_size(&mut self) {
+        self.new_size = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_new_size(&mut self, v: i64) {
+        self.new_size = v;
+    }
+
+    // uint64 client = 3;
+
+
+    pub fn get_client(&self) -> u64 {
+        self.client
+    }
+    pub fn clear_client(&mut self) {
+        self.client = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_client(&mut self, v: u64) {
+        self.client = v;
+    }
+}
+
+impl ::protobuf::Message for DecreaseSizeRequest {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.path)?;
+                },
+                2 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_int64()?;
+                    self.new_size = tmp;
+                },
+                3 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.client = tmp;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.path.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.path);
+        }
+        if self.new_size != 0 {
+            my_size += ::protobuf::rt::value_size(2, self.new_size, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.client != 0 {
+            my_size += ::protobuf::rt::value_size(3, self.client, ::protobuf::wire_format::WireTypeVarint);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.path.is_empty() {
+            os.write_string(1, &self.path)?;
+        }
+        if self.new_size != 0 {
+            os.write_int64(2, self.new_size)?;
+        }
+        if self.client != 0 {
+            os.write_uint64(3, self.client)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> DecreaseSizeRequest {
+        DecreaseSizeRequest::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "path",
+                |m: &DecreaseSizeRequest| { &m.path },
+                |m: &mut DecreaseSizeRequest| { &mut m.path },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeInt64>(
+                "new_size",
+                |m: &DecreaseSizeRequest| { &m.new_size },
+                |m: &mut DecreaseSizeRequest| { &mut m.new_size },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                "client",
+                |m: &DecreaseSizeRequest| { &m.client },
+                |m: &mut DecreaseSizeRequest| { &mut m.client },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<DecreaseSizeRequest>(
+                "DecreaseSizeRequest",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static DecreaseSizeRequest {
+        static instance: ::protobuf::rt::LazyV2<DecreaseSizeRequest> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(DecreaseSizeRequest::new)
+    }
+}
+
+impl ::protobuf::Clear for DecreaseSizeRequest {
+    fn clear(&mut self) {
+        self.path.clear();
+        self.new_size = 0;
+        self.client = 0;
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for DecreaseSizeRequest {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for DecreaseSizeRequest {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct SizeResponse {
+    // message fields
+    pub status: ::protobuf::SingularPtrField<Status>,
+    pub size: i64,
+    pub stuffed: bool,
+    pub placement: ::std::vec::Vec<u8>,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a SizeResponse {
+    fn default() -> &'a SizeResponse {
+        <SizeResponse as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl SizeResponse {
+    pub fn new() -> SizeResponse {
+        ::std::default::Default::default()
+    }
+
+    // .sfs_server.Status status = 1;
+
+
+    pub fn get_status(&self) -> &Status {
+        self.status.as_ref().unwrap_or_else(|| <Status as ::protobuf::Message>::default_instance())
+    }
+    pub fn clear_status(&mut self) {
+        self.status.clear();
+    }
+
+    pub fn has_status(&self) -> bool {
+        self.status.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_status(&mut self, v: Status) {
+        self.status = ::protobuf::SingularPtrField::some(v);
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_status(&mut self) -> &mut Status {
+        if self.status.is_none() {
+            self.status.set_default();
+        }
+        self.status.as_mut().unwrap()
+    }
+
+    // Take field
+    pub fn take_status(&mut self) -> Status {
+        self.status.take().unwrap_or_else(|| Status::new())
+    }
+
+    // int64 size = 2;
+
+
+    pub fn get_size(&self) -> i64 {
+        self.size
+    }
+    pub fn clear_size(&mut self) {
+        self.size = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_size(&mut self, v: i64) {
+        self.size = v;
+    }
+
+    // bool stuffed = 3;
+
+
+    pub fn get_stuffed(&self) -> bool {
+        self.stuffed
+    }
+    pub fn clear_stuffed(&mut self) {
+        self.stuffed = false;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_stuffed(&mut self, v: bool) {
+        self.stuffed = v;
+    }
+
+    // bytes placement = 4;
+
+
+    pub fn get_placement(&self) -> &[u8] {
+        &self.placement
+    }
+    pub fn clear_placement(&mut self) {
+        self.placement.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_placement(&mut self, v: ::std::vec::Vec<u8>) {
+        self.placement = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_placement(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.placement
+    }
+
+    // Take field
+    pub fn take_placement(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.placement, ::std::vec::Vec::new())
+    }
+}
+
+impl ::protobuf::Message for SizeResponse {
+    fn is_initialized(&self) -> bool {
+        for v in &self.status {
+            if !v.is_initialized() {
+                return false;
+            }
+        };
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_message_into(wire_type, is, &mut self.status)?;
+                },
+                2 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_int64()?;
+                    self.size = tmp;
+                },
+                3 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_bool()?;
+                    self.stuffed = tmp;
+                },
+                4 => {
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.placement)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if let Some(ref v) = self.status.as_ref() {
+            let len = v.compute_size();
+            my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+        }
+        if self.size != 0 {
+            my_size += ::protobuf::rt::value_size(2, self.size, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.stuffed != false {
+            my_size += 2;
+        }
+        if !self.placement.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(4, &self.placement);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if let Some(ref v) = self.status.as_ref() {
+            os.write_tag(1, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+            os.write_raw_varint32(v.get_cached_size())?;
+            v.write_to_with_cached_sizes(os)?;
+        }
+        if self.size != 0 {
+            os.write_int64(2, self.size)?;
+        }
+        if self.stuffed != false {
+            os.write_bool(3, self.stuffed)?;
+        }
+        if !self.placement.is_empty() {
+            os.write_bytes(4, &self.placement)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> SizeResponse {
+        SizeResponse::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_singular_ptr_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<Status>>(
+                "status",
+                |m: &SizeResponse| { &m.status },
+                |m: &mut SizeResponse| { &mut m.status },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeInt64>(
+                "size",
+                |m: &SizeResponse| { &m.size },
+                |m: &mut SizeResponse| { &mut m.size },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBool>(
+                "stuffed",
+                |m: &SizeResponse| { &m.stuffed },
+                |m: &mut SizeResponse| { &mut m.stuffed },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                "placement",
+                |m: &SizeResponse| { &m.placement },
+                |m: &mut SizeResponse| { &mut m.placement },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<SizeResponse>(
+                "SizeResponse",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static SizeResponse {
+        static instance: ::protobuf::rt::LazyV2<SizeResponse> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(SizeResponse::new)
+    }
+}
+
+impl ::protobuf::Clear for SizeResponse {
+    fn clear(&mut self) {
+        self.status.clear();
+        self.size = 0;
+        self.stuffed = false;
+        self.placement.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for SizeResponse {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for SizeResponse {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct DirentRequest {
+    // message fields
+    pub dir: ::std::string::String,
+    pub name: ::std::string::String,
+    pub is_dir: bool,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a DirentRequest {
+    fn default() -> &'a DirentRequest {
+        <DirentRequest as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl DirentRequest {
+    pub fn new() -> DirentRequest {
+        ::std::default::Default::default()
+    }
+
+    // string dir = 1;
+
+
+    pub fn get_dir(&self) -> &str {
+        &self.dir
+    }
+    pub fn clear_dir(&mut self) {
+        self.dir.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_dir(&mut self, v: ::std::string::String) {
+        self.dir = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_dir(&mut self) -> &mut ::std::string::String {
+        &mut self.dir
+    }
+
+    // Take field
+    pub fn take_dir(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.dir, ::std::string::String::new())
+    }
+
+    // string name = 2;
+
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+    pub fn clear_name(&mut self) {
+        self.name.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_name(&mut self, v: ::std::string::String) {
+        self.name = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_name(&mut self) -> &mut ::std::string::String {
+        &mut self.name
+    }
+
+    // Take field
+    pub fn take_name(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.name, ::std::string::String::new())
+    }
+
+    // bool is_dir = 3;
+
+
+    pub fn get_is_dir(&self) -> bool {
+        self.is_dir
+    }
+    pub fn clear_is_dir(&mut self) {
+        self.is_dir = false;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_is_dir(&mut self, v: bool) {
+        self.is_dir = v;
+    }
+}
+
+impl ::protobuf::Message for DirentRequest {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.dir)?;
+                },
+                2 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.name)?;
+                },
+                3 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_bool()?;
+                    self.is_dir = tmp;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.dir.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.dir);
+        }
+        if !self.name.is_empty() {
+            my_size += ::protobuf::rt::string_size(2, &self.name);
+        }
+        if self.is_dir != false {
+            my_size += 2;
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.dir.is_empty() {
+            os.write_string(1, &self.dir)?;
+        }
+        if !self.name.is_empty() {
+            os.write_string(2, &self.name)?;
+        }
+        if self.is_dir != false {
+            os.write_bool(3, self.is_dir)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> DirentRequest {
+        DirentRequest::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "dir",
+                |m: &DirentRequest| { &m.dir },
+                |m: &mut DirentRequest| { &mut m.dir },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "name",
+                |m: &DirentRequest| { &m.name },
+                |m: &mut DirentRequest| { &mut m.name },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBool>(
+                "is_dir",
+                |m: &DirentRequest| { &m.is_dir },
+                |m: &mut DirentRequest| { &mut m.is_dir },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<DirentRequest>(
+                "DirentRequest",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static DirentRequest {
+        static instance: ::protobuf::rt::LazyV2<DirentRequest> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(DirentRequest::new)
+    }
+}
+
+impl ::protobuf::Clear for DirentRequest {
+    fn clear(&mut self) {
+        self.dir.clear();
+        self.name.clear();
+        self.is_dir = false;
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for DirentRequest {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for DirentRequest {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct GetDirentsRequest {
+    // message fields
+    pub path: ::std::string::String,
+    pub start_after: ::std::string::String,
+    pub limit: u64,
+    pub plus: bool,
+    pub client: u64,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a GetDirentsRequest {
+    fn default() -> &'a GetDirentsRequest {
+        <GetDirentsRequest as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl GetDirentsRequest {
+    pub fn new() -> GetDirentsRequest {
+        ::std::default::Default::default()
+    }
+
+    // string path = 1;
+
+
+    pub fn get_path(&self) -> &str {
+        &self.path
+    }
+    pub fn clear_path(&mut self) {
+        self.path.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_path(&mut self, v: ::std::string::String) {
+        self.path = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_path(&mut self) -> &mut ::std::string::String {
+        &mut self.path
+    }
+
+    // Take field
+    pub fn take_path(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.path, ::std::string::String::new())
+    }
+
+    // string start_after = 2;
+
+
+    pub fn get_start_after(&self) -> &str {
+        &self.start_after
+    }
+    pub fn clear_start_after(&mut self) {
+        self.start_after.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_start_after(&mut self, v: ::std::string::String) {
+        self.start_after = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_start_after(&mut self) -> &mut ::std::string::String {
+        &mut self.start_after
+    }
+
+    // Take field
+    pub fn take_start_after(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.start_after, ::std::string::String::new())
+    }
+
+    // uint64 limit = 3;
+
+
+    pub fn get_limit(&self) -> u64 {
+        self.limit
+    }
+    pub fn clear_limit(&mut self) {
+        self.limit = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_limit(&mut self, v: u64) {
+        self.limit = v;
+    }
+
+    // bool plus = 4;
+
+
+    pub fn get_plus(&self) -> bool {
+        self.plus
+    }
+    pub fn clear_plus(&mut self) {
+        self.plus = false;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_plus(&mut self, v: bool) {
+        self.plus = v;
+    }
+
+    // uint64 client = 5;
+
+
+    pub fn get_client(&self) -> u64 {
+        self.client
+    }
+    pub fn clear_client(&mut self) {
+        self.client = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_client(&mut self, v: u64) {
+        self.client = v;
+    }
+}
+
+impl ::protobuf::Message for GetDirentsRequest {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.path)?;
+                },
+                2 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.start_after)?;
+                },
+                3 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.limit = tmp;
+                },
+                4 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_bool()?;
+                    self.plus = tmp;
+                },
+                5 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.client = tmp;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.path.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.path);
+        }
+        if !self.start_after.is_empty() {
+            my_size += ::protobuf::rt::string_size(2, &self.start_after);
+        }
+        if self.limit != 0 {
+            my_size += ::protobuf::rt::value_size(3, self.limit, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.plus != false {
+            my_size += 2;
+        }
+        if self.client != 0 {
+            my_size += ::protobuf::rt::value_size(5, self.client, ::protobuf::wire_format::WireTypeVarint);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.path.is_empty() {
+            os.write_string(1, &self.path)?;
+        }
+        if !self.start_after.is_empty() {
+            os.write_string(2, &self.start_after)?;
+        }
+        if self.limit != 0 {
+            os.write_uint64(3, self.limit)?;
+        }
+        if self.plus != false {
+            os.write_bool(4, self.plus)?;
+        }
+        if self.client != 0 {
+            os.write_uint64(5, self.client)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> GetDirentsRequest {
+        GetDirentsRequest::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "path",
+                |m: &GetDirentsRequest| { &m.path },
+                |m: &mut GetDirentsRequest| { &mut m.path },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "start_after",
+                |m: &GetDirentsRequest| { &m.start_after },
+                |m: &mut GetDirentsRequest| { &mut m.start_after },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                "limit",
+                |m: &GetDirentsRequest| { &m.limit },
+                |m: &mut GetDirentsRequest| { &mut m.limit },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBool>(
+                "plus",
+                |m: &GetDirentsRequest| { &m.plus },
+                |m: &mut GetDirentsRequest| { &mut m.plus },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                "client",
+                |m: &GetDirentsRequest| { &m.client },
+                |m: &mut GetDirentsRequest| { &mut m.client },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<GetDirentsRequest>(
+                "GetDirentsRequest",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static GetDirentsRequest {
+        static instance: ::protobuf::rt::LazyV2<GetDirentsRequest> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(GetDirentsRequest::new)
+    }
+}
+
+impl ::protobuf::Clear for GetDirentsRequest {
+    fn clear(&mut self) {
+        self.path.clear();
+        self.start_after.clear();
+        self.limit = 0;
+        self.plus = false;
+        self.client = 0;
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for GetDirentsRequest {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for GetDirentsRequest {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct Dirent {
+    // message fields
+    pub status: ::protobuf::SingularPtrField<Status>,
+    pub name: ::std::string::String,
+    pub is_dir: bool,
+    pub attr: ::protobuf::SingularPtrField<Attr>,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a Dirent {
+    fn default() -> &'a Dirent {
+        <Dirent as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl Dirent {
+    pub fn new() -> Dirent {
+        ::std::default::Default::default()
+    }
+
+    // .sfs_server.Status status = 1;
+
+
+    pub fn get_status(&self) -> &Status {
+        self.status.as_ref().unwrap_or_else(|| <Status as ::protobuf::Message>::default_instance())
+    }
+    pub fn clear_status(&mut self) {
+        self.status.clear();
+    }
+
+    pub fn has_status(&self) -> bool {
+        self.status.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_status(&mut self, v: Status) {
+        self.status = ::protobuf::SingularPtrField::some(v);
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_status(&mut self) -> &mut Status {
+        if self.status.is_none() {
+            self.status.set_default();
+        }
+        self.status.as_mut().unwrap()
+    }
+
+    // Take field
+    pub fn take_status(&mut self) -> Status {
+        self.status.take().unwrap_or_else(|| Status::new())
+    }
+
+    // string name = 2;
+
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+    pub fn clear_name(&mut self) {
+        self.name.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_name(&mut self, v: ::std::string::String) {
+        self.name = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_name(&mut self) -> &mut ::std::string::String {
+        &mut self.name
+    }
+
+    // Take field
+    pub fn take_name(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.name, ::std::string::String::new())
+    }
+
+    // bool is_dir = 3;
+
+
+    pub fn get_is_dir(&self) -> bool {
+        self.is_dir
+    }
+    pub fn clear_is_dir(&mut self) {
+        self.is_dir = false;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_is_dir(&mut self, v: bool) {
+        self.is_dir = v;
+    }
+
+    // .sfs_server.Attr attr = 4;
+
+
+    pub fn get_attr(&self) -> &Attr {
+        self.attr.as_ref().unwrap_or_else(|| <Attr as ::protobuf::Message>::default_instance())
+    }
+    pub fn clear_attr(&mut self) {
+        self.attr.clear();
+    }
+
+    pub fn has_attr(&self) -> bool {
+        self.attr.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_attr(&mut self, v: Attr) {
+        self.attr = ::protobuf::SingularPtrField::some(v);
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_attr(&mut self) -> &mut Attr {
+        if self.attr.is_none() {
+            self.attr.set_default();
+        }
+        self.attr.as_mut().unwrap()
+    }
+
+    // Take field
+    pub fn take_attr(&mut self) -> Attr {
+        self.attr.take().unwrap_or_else(|| Attr::new())
+    }
+}
+
+impl ::protobuf::Message for Dirent {
+    fn is_initialized(&self) -> bool {
+        for v in &self.status {
+            if !v.is_initialized() {
+                return false;
+            }
+        };
+        for v in &self.attr {
+            if !v.is_initialized() {
+                return false;
+            }
+        };
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_message_into(wire_type, is, &mut self.status)?;
+                },
+                2 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.name)?;
+                },
+                3 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_bool()?;
+                    self.is_dir = tmp;
+                },
+                4 => {
+                    ::protobuf::rt::read_singular_message_into(wire_type, is, &mut self.attr)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if let Some(ref v) = self.status.as_ref() {
+            let len = v.compute_size();
+            my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+        }
+        if !self.name.is_empty() {
+            my_size += ::protobuf::rt::string_size(2, &self.name);
+        }
+        if self.is_dir != false {
+            my_size += 2;
+        }
+        if let Some(ref v) = self.attr.as_ref() {
+            let len = v.compute_size();
+            my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if let Some(ref v) = self.status.as_ref() {
+            os.write_tag(1, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+            os.write_raw_varint32(v.get_cached_size())?;
+            v.write_to_with_cached_sizes(os)?;
+        }
+        if !self.name.is_empty() {
+            os.write_string(2, &self.name)?;
+        }
+        if self.is_dir != false {
+            os.write_bool(3, self.is_dir)?;
+        }
+        if let Some(ref v) = self.attr.as_ref() {
+            os.write_tag(4, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+            os.write_raw_varint32(v.get_cached_size())?;
+            v.write_to_with_cached_sizes(os)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> Dirent {
+        Dirent::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_singular_ptr_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<Status>>(
+                "status",
+                |m: &Dirent| { &m.status },
+                |m: &mut Dirent| { &mut m.status },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "name",
+                |m: &Dirent| { &m.name },
+                |m: &mut Dirent| { &mut m.name },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBool>(
+                "is_dir",
+                |m: &Dirent| { &m.is_dir },
+                |m: &mut Dirent| { &mut m.is_dir },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_singular_ptr_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<Attr>>(
+                "attr",
+                |m: &Dirent| { &m.attr },
+                |m: &mut Dirent| { &mut m.attr },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<Dirent>(
+                "Dirent",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static Dirent {
+        static instance: ::protobuf::rt::LazyV2<Dirent> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(Dirent::new)
+    }
+}
+
+impl ::protobuf::Clear for Dirent {
+    fn clear(&mut self) {
+        self.status.clear();
+        self.name.clear();
+        self.is_dir = false;
+        self.attr.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for Dirent {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for Dirent {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(Clone,PartialEq,Eq,Debug,Hash)]
+pub enum ErrorCode {
+    OK = 0,
+    NOT_FOUND = 1,
+    EXISTS = 2,
+    INVALID_ARGUMENT = 3,
+    NOT_EMPTY = 4,
+    NO_SPACE = 5,
+    IO_ERROR = 6,
+    BUSY = 7,
+    OTHER = 8,
+}
+
+impl ::protobuf::ProtobufEnum for ErrorCode {
+    fn value(&self) -> i32 {
+        *self as i32
+    }
+
+    fn from_i32(value: i32) -> ::std::option::Option<ErrorCode> {
+        match value {
+            0 => ::std::option::Option::Some(ErrorCode::OK),
+            1 => ::std::option::Option::Some(ErrorCode::NOT_FOUND),
+            2 => ::std::option::Option::Some(ErrorCode::EXISTS),
+            3 => ::std::option::Option::Some(ErrorCode::INVALID_ARGUMENT),
+            4 => ::std::option::Option::Some(ErrorCode::NOT_EMPTY),
+            5 => ::std::option::Option::Some(ErrorCode::NO_SPACE),
+            6 => ::std::option::Option::Some(ErrorCode::IO_ERROR),
+            7 => ::std::option::Option::Some(ErrorCode::BUSY),
+            8 => ::std::option::Option::Some(ErrorCode::OTHER),
+            _ => ::std::option::Option::None
+        }
+    }
+
+    fn values() -> &'static [Self] {
+        static values: &'static [ErrorCode] = &[
+            ErrorCode::OK,
+            ErrorCode::NOT_FOUND,
+            ErrorCode::EXISTS,
+            ErrorCode::INVALID_ARGUMENT,
+            ErrorCode::NOT_EMPTY,
+            ErrorCode::NO_SPACE,
+            ErrorCode::IO_ERROR,
+            ErrorCode::BUSY,
+            ErrorCode::OTHER,
+        ];
+        values
+    }
+
+    fn enum_descriptor_static() -> &'static ::protobuf::reflect::EnumDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::EnumDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            ::protobuf::reflect::EnumDescriptor::new_pb_name::<ErrorCode>("ErrorCode", file_descriptor_proto())
+        })
+    }
+}
+
+impl ::std::marker::Copy for ErrorCode {
+}
+
+impl ::std::default::Default for ErrorCode {
+    fn default() -> Self {
+        ErrorCode::OK
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for ErrorCode {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Enum(::protobuf::ProtobufEnum::descriptor(self))
+    }
+}
+
 static file_descriptor_proto_data: &'static [u8] = b"\
     \n\x0cserver.proto\x12\nsfs_server\"H\n\x04Post\x12\x16\n\x06option\x18\
     \x01\x20\x01(\x05R\x06option\x12\x12\n\x04data\x18\x02\x20\x01(\x0cR\x04\
     data\x12\x14\n\x05extra\x18\x03\x20\x01(\x0cR\x05extra\"H\n\nPostResult\
     \x12\x10\n\x03err\x18\x01\x20\x01(\x05R\x03err\x12\x12\n\x04data\x18\x02\
     \x20\x01(\x0cR\x04data\x12\x14\n\x05extra\x18\x03\x20\x01(\x0cR\x05extra\
-    2\xbc\x01\n\tSFSHandle\x122\n\x06handle\x12\x10.sfs_server.Post\x1a\x16.\
-    sfs_server.PostResult\x12=\n\rhandle_stream\x12\x10.sfs_server.Post\x1a\
-    \x16.sfs_server.PostResult(\x010\x01\x12<\n\x0ehandle_dirents\x12\x10.sf\
-    s_server.Post\x1a\x16.sfs_server.PostResult0\x01b\x06proto3\
+    \"c\n\x06Status\x12)\n\x04code\x18\x01\x20\x01(\x0e2\x15.sfs_server.Erro\
+    rCodeR\x04code\x12\x14\n\x05errno\x18\x02\x20\x01(\x05R\x05errno\x12\x18\
+    \n\x07message\x18\x03\x20\x01(\tR\x07message\"<\n\x0eStatusResponse\x12*\
+    \n\x06status\x18\x01\x20\x01(\x0b2\x12.sfs_server.StatusR\x06status\"\
+    \x97\x01\n\x10HandshakeRequest\x12)\n\x10protocol_version\x18\x01\x20\
+    \x01(\rR\x0fprotocolVersion\x12\x1a\n\x08features\x18\x02\x20\x01(\x04R\
+    \x08features\x12\x1d\n\nchunk_size\x18\x03\x20\x01(\x04R\tchunkSize\x12\
+    \x1d\n\ncluster_id\x18\x04\x20\x01(\tR\tclusterId\"\xc4\x01\n\x11Handsha\
+    keResponse\x12*\n\x06status\x18\x01\x20\x01(\x0b2\x12.sfs_server.StatusR\
+    \x06status\x12)\n\x10protocol_version\x18\x02\x20\x01(\rR\x0fprotocolVer\
+    sion\x12\x1a\n\x08features\x18\x03\x20\x01(\x04R\x08features\x12\x1d\n\n\
+    chunk_size\x18\x04\x20\x01(\x04R\tchunkSize\x12\x1d\n\ncluster_id\x18\
+    \x05\x20\x01(\tR\tclusterId\"\xe1\x01\n\x04Attr\x12\x12\n\x04mode\x18\
+    \x01\x20\x01(\rR\x04mode\x12\x12\n\x04size\x18\x02\x20\x01(\x03R\x04size\
+    \x12\x1f\n\x0baccess_time\x18\x03\x20\x01(\x03R\naccessTime\x12\x1f\n\
+    \x0bmodify_time\x18\x04\x20\x01(\x03R\nmodifyTime\x12\x1f\n\x0bchange_ti\
+    me\x18\x05\x20\x01(\x03R\nchangeTime\x12\x1d\n\nlink_count\x18\x06\x20\
+    \x01(\x04R\tlinkCount\x12\x16\n\x06blocks\x18\x07\x20\x01(\x03R\x06block\
+    s\x12\x17\n\x07file_id\x18\x08\x20\x01(\x04R\x06fileId\"9\n\x0bPathReque\
+    st\x12\x12\n\x04path\x18\x01\x20\x01(\tR\x04path\x12\x16\n\x06client\x18\
+    \x02\x20\x01(\x04R\x06client\"9\n\x0bStatRequest\x12\x12\n\x04path\x18\
+    \x01\x20\x01(\tR\x04path\x12\x16\n\x06client\x18\x02\x20\x01(\x04R\x06cl\
+    ient\"\xc7\x01\n\x0cStatResponse\x12*\n\x06status\x18\x01\x20\x01(\x0b2\
+    \x12.sfs_server.StatusR\x06status\x12$\n\x04attr\x18\x02\x20\x01(\x0b2\
+    \x10.sfs_server.AttrR\x04attr\x12\x19\n\x08lease_ms\x18\x03\x20\x01(\x04\
+    R\x07leaseMs\x12\x18\n\x07stuffed\x18\x04\x20\x01(\x08R\x07stuffed\x12\
+    \x12\n\x04data\x18\x05\x20\x01(\x0cR\x04data\x12\x1c\n\tplacement\x18\
+    \x06\x20\x01(\x0cR\tplacement\"h\n\rCreateRequest\x12\x12\n\x04path\x18\
+    \x01\x20\x01(\tR\x04path\x12\x12\n\x04mode\x18\x02\x20\x01(\rR\x04mode\
+    \x12\x16\n\x06client\x18\x03\x20\x01(\x04R\x06client\x12\x17\n\x07file_i\
+    d\x18\x04\x20\x01(\x04R\x06fileId\"U\n\x0eCreateResponse\x12*\n\x06statu\
+    s\x18\x01\x20\x01(\x0b2\x12.sfs_server.StatusR\x06status\x12\x17\n\x07fi\
+    le_id\x18\x02\x20\x01(\x04R\x06fileId\"\x97\x01\n\x11UpdateSizeRequest\
+    \x12\x12\n\x04path\x18\x01\x20\x01(\tR\x04path\x12\x12\n\x04size\x18\x02\
+    \x20\x01(\x04R\x04size\x12\x16\n\x06offset\x18\x03\x20\x01(\x03R\x06offs\
+    et\x12\x16\n\x06append\x18\x04\x20\x01(\x08R\x06append\x12\x16\n\x06clie\
+    nt\x18\x05\x20\x01(\x04R\x06client\x12\x12\n\x04data\x18\x06\x20\x01(\
+    \x0cR\x04data\"\\\n\x13DecreaseSizeRequest\x12\x12\n\x04path\x18\x01\x20\
+    \x01(\tR\x04path\x12\x19\n\x08new_size\x18\x02\x20\x01(\x03R\x07newSize\
+    \x12\x16\n\x06client\x18\x03\x20\x01(\x04R\x06client\"\x86\x01\n\x0cSize\
+    Response\x12*\n\x06status\x18\x01\x20\x01(\x0b2\x12.sfs_server.StatusR\
+    \x06status\x12\x12\n\x04size\x18\x02\x20\x01(\x03R\x04size\x12\x18\n\x07\
+    stuffed\x18\x03\x20\x01(\x08R\x07stuffed\x12\x1c\n\tplacement\x18\x04\
+    \x20\x01(\x0cR\tplacement\"L\n\rDirentRequest\x12\x10\n\x03dir\x18\x01\
+    \x20\x01(\tR\x03dir\x12\x12\n\x04name\x18\x02\x20\x01(\tR\x04name\x12\
+    \x15\n\x06is_dir\x18\x03\x20\x01(\x08R\x05isDir\"\x8a\x01\n\x11GetDirent\
+    sRequest\x12\x12\n\x04path\x18\x01\x20\x01(\tR\x04path\x12\x1f\n\x0bstar\
+    t_after\x18\x02\x20\x01(\tR\nstartAfter\x12\x14\n\x05limit\x18\x03\x20\
+    \x01(\x04R\x05limit\x12\x12\n\x04plus\x18\x04\x20\x01(\x08R\x04plus\x12\
+    \x16\n\x06client\x18\x05\x20\x01(\x04R\x06client\"\x85\x01\n\x06Dirent\
+    \x12*\n\x06status\x18\x01\x20\x01(\x0b2\x12.sfs_server.StatusR\x06status\
+    \x12\x12\n\x04name\x18\x02\x20\x01(\tR\x04name\x12\x15\n\x06is_dir\x18\
+    \x03\x20\x01(\x08R\x05isDir\x12$\n\x04attr\x18\x04\x20\x01(\x0b2\x10.sfs\
+    _server.AttrR\x04attr*\x84\x01\n\tErrorCode\x12\x06\n\x02OK\x10\0\x12\r\
+    \n\tNOT_FOUND\x10\x01\x12\n\n\x06EXISTS\x10\x02\x12\x14\n\x10INVALID_ARG\
+    UMENT\x10\x03\x12\r\n\tNOT_EMPTY\x10\x04\x12\x0c\n\x08NO_SPACE\x10\x05\
+    \x12\x0c\n\x08IO_ERROR\x10\x06\x12\x08\n\x04BUSY\x10\x07\x12\t\n\x05OTHE\
+    R\x10\x082\xf0\x06\n\tSFSHandle\x122\n\x06handle\x12\x10.sfs_server.Post\
+    \x1a\x16.sfs_server.PostResult\x12=\n\rhandle_stream\x12\x10.sfs_server.\
+    Post\x1a\x16.sfs_server.PostResult(\x010\x01\x12<\n\x0ehandle_dirents\
+    \x12\x10.sfs_server.Post\x1a\x16.sfs_server.PostResult0\x01\x12H\n\thand\
+    shake\x12\x1c.sfs_server.HandshakeRequest\x1a\x1d.sfs_server.HandshakeRe\
+    sponse\x129\n\x04stat\x12\x17.sfs_server.StatRequest\x1a\x18.sfs_server.\
+    StatResponse\x12?\n\x06create\x12\x19.sfs_server.CreateRequest\x1a\x1a.s\
+    fs_server.CreateResponse\x12F\n\x0fremove_metadata\x12\x17.sfs_server.Pa\
+    thRequest\x1a\x1a.sfs_server.StatusResponse\x12F\n\x0bupdate_size\x12\
+    \x1d.sfs_server.UpdateSizeRequest\x1a\x18.sfs_server.SizeResponse\x12L\n\
+    \rdecrease_size\x12\x1f.sfs_server.DecreaseSizeRequest\x1a\x1a.sfs_serve\
+    r.StatusResponse\x12=\n\x08get_size\x12\x17.sfs_server.PathRequest\x1a\
+    \x18.sfs_server.SizeResponse\x12C\n\nadd_dirent\x12\x19.sfs_server.Diren\
+    tRequest\x1a\x1a.sfs_server.StatusResponse\x12F\n\rremove_dirent\x12\x19\
+    .sfs_server.DirentRequest\x1a\x1a.sfs_server.StatusResponse\x12B\n\x0bge\
+    t_dirents\x12\x1d.sfs_server.GetDirentsRequest\x1a\x12.sfs_server.Dirent\
+    0\x01b\x06proto3\
 ";
 
 static file_descriptor_proto_lazy: ::protobuf::rt::LazyV2<::protobuf::descriptor::FileDescriptorProto> = ::protobuf::rt::LazyV2::INIT;
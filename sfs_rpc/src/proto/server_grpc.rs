@@ -37,6 +37,76 @@ const METHOD_SFS_HANDLE_HANDLE_DIRENTS: ::grpcio::Method<super::server::Post, su
     resp_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
 };
 
+const METHOD_SFS_HANDLE_HANDSHAKE: ::grpcio::Method<super::server::HandshakeRequest, super::server::HandshakeResponse> = ::grpcio::Method {
+    ty: ::grpcio::MethodType::Unary,
+    name: "/sfs_server.SFSHandle/handshake",
+    req_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
+    resp_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
+};
+
+const METHOD_SFS_HANDLE_STAT: ::grpcio::Method<super::server::StatRequest, super::server::StatResponse> = ::grpcio::Method {
+    ty: ::grpcio::MethodType::Unary,
+    name: "/sfs_server.SFSHandle/stat",
+    req_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
+    resp_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
+};
+
+const METHOD_SFS_HANDLE_CREATE: ::grpcio::Method<super::server::CreateRequest, super::server::CreateResponse> = ::grpcio::Method {
+    ty: ::grpcio::MethodType::Unary,
+    name: "/sfs_server.SFSHandle/create",
+    req_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
+    resp_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
+};
+
+const METHOD_SFS_HANDLE_REMOVE_METADATA: ::grpcio::Method<super::server::PathRequest, super::server::StatusResponse> = ::grpcio::Method {
+    ty: ::grpcio::MethodType::Unary,
+    name: "/sfs_server.SFSHandle/remove_metadata",
+    req_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
+    resp_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
+};
+
+const METHOD_SFS_HANDLE_UPDATE_SIZE: ::grpcio::Method<super::server::UpdateSizeRequest, super::server::SizeResponse> = ::grpcio::Method {
+    ty: ::grpcio::MethodType::Unary,
+    name: "/sfs_server.SFSHandle/update_size",
+    req_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
+    resp_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
+};
+
+const METHOD_SFS_HANDLE_DECREASE_SIZE: ::grpcio::Method<super::server::DecreaseSizeRequest, super::server::StatusResponse> = ::grpcio::Method {
+    ty: ::grpcio::MethodType::Unary,
+    name: "/sfs_server.SFSHandle/decrease_size",
+    req_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
+    resp_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
+};
+
+const METHOD_SFS_HANDLE_GET_SIZE: ::grpcio::Method<super::server::PathRequest, super::server::SizeResponse> = ::grpcio::Method {
+    ty: ::grpcio::MethodType::Unary,
+    name: "/sfs_server.SFSHandle/get_size",
+    req_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
+    resp_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
+};
+
+const METHOD_SFS_HANDLE_ADD_DIRENT: ::grpcio::Method<super::server::DirentRequest, super::server::StatusResponse> = ::grpcio::Method {
+    ty: ::grpcio::MethodType::Unary,
+    name: "/sfs_server.SFSHandle/add_dirent",
+    req_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
+    resp_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
+};
+
+const METHOD_SFS_HANDLE_REMOVE_DIRENT: ::grpcio::Method<super::server::DirentRequest, super::server::StatusResponse> = ::grpcio::Method {
+    ty: ::grpcio::MethodType::Unary,
+    name: "/sfs_server.SFSHandle/remove_dirent",
+    req_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
+    resp_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
+};
+
+const METHOD_SFS_HANDLE_GET_DIRENTS: ::grpcio::Method<super::server::GetDirentsRequest, super::server::Dirent> = ::grpcio::Method {
+    ty: ::grpcio::MethodType::ServerStreaming,
+    name: "/sfs_server.SFSHandle/get_dirents",
+    req_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
+    resp_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
+};
+
 #[derive(Clone)]
 pub struct SfsHandleClient {
     client: ::grpcio::Client,
@@ -80,6 +150,158 @@ impl SfsHandleClient {
     pub fn handle_dirents(&self, req: &super::server::Post) -> ::grpcio::Result<::grpcio::ClientSStreamReceiver<super::server::PostResult>> {
         self.handle_dirents_opt(req, ::grpcio::CallOption::default())
     }
+
+    pub fn handshake_opt(&self, req: &super::server::HandshakeRequest, opt: ::grpcio::CallOption) -> ::grpcio::Result<super::server::HandshakeResponse> {
+        self.client.unary_call(&METHOD_SFS_HANDLE_HANDSHAKE, req, opt)
+    }
+
+    pub fn handshake(&self, req: &super::server::HandshakeRequest) -> ::grpcio::Result<super::server::HandshakeResponse> {
+        self.handshake_opt(req, ::grpcio::CallOption::default())
+    }
+
+    pub fn handshake_async_opt(&self, req: &super::server::HandshakeRequest, opt: ::grpcio::CallOption) -> ::grpcio::Result<::grpcio::ClientUnaryReceiver<super::server::HandshakeResponse>> {
+        self.client.unary_call_async(&METHOD_SFS_HANDLE_HANDSHAKE, req, opt)
+    }
+
+    pub fn handshake_async(&self, req: &super::server::HandshakeRequest) -> ::grpcio::Result<::grpcio::ClientUnaryReceiver<super::server::HandshakeResponse>> {
+        self.handshake_async_opt(req, ::grpcio::CallOption::default())
+    }
+
+    pub fn stat_opt(&self, req: &super::server::StatRequest, opt: ::grpcio::CallOption) -> ::grpcio::Result<super::server::StatResponse> {
+        self.client.unary_call(&METHOD_SFS_HANDLE_STAT, req, opt)
+    }
+
+    pub fn stat(&self, req: &super::server::StatRequest) -> ::grpcio::Result<super::server::StatResponse> {
+        self.stat_opt(req, ::grpcio::CallOption::default())
+    }
+
+    pub fn stat_async_opt(&self, req: &super::server::StatRequest, opt: ::grpcio::CallOption) -> ::grpcio::Result<::grpcio::ClientUnaryReceiver<super::server::StatResponse>> {
+        self.client.unary_call_async(&METHOD_SFS_HANDLE_STAT, req, opt)
+    }
+
+    pub fn stat_async(&self, req: &super::server::StatRequest) -> ::grpcio::Result<::grpcio::ClientUnaryReceiver<super::server::StatResponse>> {
+        self.stat_async_opt(req, ::grpcio::CallOption::default())
+    }
+
+    pub fn create_opt(&self, req: &super::server::CreateRequest, opt: ::grpcio::CallOption) -> ::grpcio::Result<super::server::CreateResponse> {
+        self.client.unary_call(&METHOD_SFS_HANDLE_CREATE, req, opt)
+    }
+
+    pub fn create(&self, req: &super::server::CreateRequest) -> ::grpcio::Result<super::server::CreateResponse> {
+        self.create_opt(req, ::grpcio::CallOption::default())
+    }
+
+    pub fn create_async_opt(&self, req: &super::server::CreateRequest, opt: ::grpcio::CallOption) -> ::grpcio::Result<::grpcio::ClientUnaryReceiver<super::server::CreateResponse>> {
+        self.client.unary_call_async(&METHOD_SFS_HANDLE_CREATE, req, opt)
+    }
+
+    pub fn create_async(&self, req: &super::server::CreateRequest) -> ::grpcio::Result<::grpcio::ClientUnaryReceiver<super::server::CreateResponse>> {
+        self.create_async_opt(req, ::grpcio::CallOption::default())
+    }
+
+    pub fn remove_metadata_opt(&self, req: &super::server::PathRequest, opt: ::grpcio::CallOption) -> ::grpcio::Result<super::server::StatusResponse> {
+        self.client.unary_call(&METHOD_SFS_HANDLE_REMOVE_METADATA, req, opt)
+    }
+
+    pub fn remove_metadata(&self, req: &super::server::PathRequest) -> ::grpcio::Result<super::server::StatusResponse> {
+        self.remove_metadata_opt(req, ::grpcio::CallOption::default())
+    }
+
+    pub fn remove_metadata_async_opt(&self, req: &super::server::PathRequest, opt: ::grpcio::CallOption) -> ::grpcio::Result<::grpcio::ClientUnaryReceiver<super::server::StatusResponse>> {
+        self.client.unary_call_async(&METHOD_SFS_HANDLE_REMOVE_METADATA, req, opt)
+    }
+
+    pub fn remove_metadata_async(&self, req: &super::server::PathRequest) -> ::grpcio::Result<::grpcio::ClientUnaryReceiver<super::server::StatusResponse>> {
+        self.remove_metadata_async_opt(req, ::grpcio::CallOption::default())
+    }
+
+    pub fn update_size_opt(&self, req: &super::server::UpdateSizeRequest, opt: ::grpcio::CallOption) -> ::grpcio::Result<super::server::SizeResponse> {
+        self.client.unary_call(&METHOD_SFS_HANDLE_UPDATE_SIZE, req, opt)
+    }
+
+    pub fn update_size(&self, req: &super::server::UpdateSizeRequest) -> ::grpcio::Result<super::server::SizeResponse> {
+        self.update_size_opt(req, ::grpcio::CallOption::default())
+    }
+
+    pub fn update_size_async_opt(&self, req: &super::server::UpdateSizeRequest, opt: ::grpcio::CallOption) -> ::grpcio::Result<::grpcio::ClientUnaryReceiver<super::server::SizeResponse>> {
+        self.client.unary_call_async(&METHOD_SFS_HANDLE_UPDATE_SIZE, req, opt)
+    }
+
+    pub fn update_size_async(&self, req: &super::server::UpdateSizeRequest) -> ::grpcio::Result<::grpcio::ClientUnaryReceiver<super::server::SizeResponse>> {
+        self.update_size_async_opt(req, ::grpcio::CallOption::default())
+    }
+
+    pub fn decrease_size_opt(&self, req: &super::server::DecreaseSizeRequest, opt: ::grpcio::CallOption) -> ::grpcio::Result<super::server::StatusResponse> {
+        self.client.unary_call(&METHOD_SFS_HANDLE_DECREASE_SIZE, req, opt)
+    }
+
+    pub fn decrease_size(&self, req: &super::server::DecreaseSizeRequest) -> ::grpcio::Result<super::server::StatusResponse> {
+        self.decrease_size_opt(req, ::grpcio::CallOption::default())
+    }
+
+    pub fn decrease_size_async_opt(&self, req: &super::server::DecreaseSizeRequest, opt: ::grpcio::CallOption) -> ::grpcio::Result<::grpcio::ClientUnaryReceiver<super::server::StatusResponse>> {
+        self.client.unary_call_async(&METHOD_SFS_HANDLE_DECREASE_SIZE, req, opt)
+    }
+
+    pub fn decrease_size_async(&self, req: &super::server::DecreaseSizeRequest) -> ::grpcio::Result<::grpcio::ClientUnaryReceiver<super::server::StatusResponse>> {
+        self.decrease_size_async_opt(req, ::grpcio::CallOption::default())
+    }
+
+    pub fn get_size_opt(&self, req: &super::server::PathRequest, opt: ::grpcio::CallOption) -> ::grpcio::Result<super::server::SizeResponse> {
+        self.client.unary_call(&METHOD_SFS_HANDLE_GET_SIZE, req, opt)
+    }
+
+    pub fn get_size(&self, req: &super::server::PathRequest) -> ::grpcio::Result<super::server::SizeResponse> {
+        self.get_size_opt(req, ::grpcio::CallOption::default())
+    }
+
+    pub fn get_size_async_opt(&self, req: &super::server::PathRequest, opt: ::grpcio::CallOption) -> ::grpcio::Result<::grpcio::ClientUnaryReceiver<super::server::SizeResponse>> {
+        self.client.unary_call_async(&METHOD_SFS_HANDLE_GET_SIZE, req, opt)
+    }
+
+    pub fn get_size_async(&self, req: &super::server::PathRequest) -> ::grpcio::Result<::grpcio::ClientUnaryReceiver<super::server::SizeResponse>> {
+        self.get_size_async_opt(req, ::grpcio::CallOption::default())
+    }
+
+    pub fn add_dirent_opt(&self, req: &super::server::DirentRequest, opt: ::grpcio::CallOption) -> ::grpcio::Result<super::server::StatusResponse> {
+        self.client.unary_call(&METHOD_SFS_HANDLE_ADD_DIRENT, req, opt)
+    }
+
+    pub fn add_dirent(&self, req: &super::server::DirentRequest) -> ::grpcio::Result<super::server::StatusResponse> {
+        self.add_dirent_opt(req, ::grpcio::CallOption::default())
+    }
+
+    pub fn add_dirent_async_opt(&self, req: &super::server::DirentRequest, opt: ::grpcio::CallOption) -> ::grpcio::Result<::grpcio::ClientUnaryReceiver<super::server::StatusResponse>> {
+        self.client.unary_call_async(&METHOD_SFS_HANDLE_ADD_DIRENT, req, opt)
+    }
+
+    pub fn add_dirent_async(&self, req: &super::server::DirentRequest) -> ::grpcio::Result<::grpcio::ClientUnaryReceiver<super::server::StatusResponse>> {
+        self.add_dirent_async_opt(req, ::grpcio::CallOption::default())
+    }
+
+    pub fn remove_dirent_opt(&self, req: &super::server::DirentRequest, opt: ::grpcio::CallOption) -> ::grpcio::Result<super::server::StatusResponse> {
+        self.client.unary_call(&METHOD_SFS_HANDLE_REMOVE_DIRENT, req, opt)
+    }
+
+    pub fn remove_dirent(&self, req: &super::server::DirentRequest) -> ::grpcio::Result<super::server::StatusResponse> {
+        self.remove_dirent_opt(req, ::grpcio::CallOption::default())
+    }
+
+    pub fn remove_dirent_async_opt(&self, req: &super::server::DirentRequest, opt: ::grpcio::CallOption) -> ::grpcio::Result<::grpcio::ClientUnaryReceiver<super::server::StatusResponse>> {
+        self.client.unary_call_async(&METHOD_SFS_HANDLE_REMOVE_DIRENT, req, opt)
+    }
+
+    pub fn remove_dirent_async(&self, req: &super::server::DirentRequest) -> ::grpcio::Result<::grpcio::ClientUnaryReceiver<super::server::StatusResponse>> {
+        self.remove_dirent_async_opt(req, ::grpcio::CallOption::default())
+    }
+
+    pub fn get_dirents_opt(&self, req: &super::server::GetDirentsRequest, opt: ::grpcio::CallOption) -> ::grpcio::Result<::grpcio::ClientSStreamReceiver<super::server::Dirent>> {
+        self.client.server_streaming(&METHOD_SFS_HANDLE_GET_DIRENTS, req, opt)
+    }
+
+    pub fn get_dirents(&self, req: &super::server::GetDirentsRequest) -> ::grpcio::Result<::grpcio::ClientSStreamReceiver<super::server::Dirent>> {
+        self.get_dirents_opt(req, ::grpcio::CallOption::default())
+    }
     pub fn spawn<F>(&self, f: F) where F: ::futures::Future<Output = ()> + Send + 'static {
         self.client.spawn(f)
     }
@@ -89,6 +311,16 @@ pub trait SfsHandle {
     fn handle(&mut self, ctx: ::grpcio::RpcContext, req: super::server::Post, sink: ::grpcio::UnarySink<super::server::PostResult>);
     fn handle_stream(&mut self, ctx: ::grpcio::RpcContext, stream: ::grpcio::RequestStream<super::server::Post>, sink: ::grpcio::DuplexSink<super::server::PostResult>);
     fn handle_dirents(&mut self, ctx: ::grpcio::RpcContext, req: super::server::Post, sink: ::grpcio::ServerStreamingSink<super::server::PostResult>);
+    fn handshake(&mut self, ctx: ::grpcio::RpcContext, req: super::server::HandshakeRequest, sink: ::grpcio::UnarySink<super::server::HandshakeResponse>);
+    fn stat(&mut self, ctx: ::grpcio::RpcContext, req: super::server::StatRequest, sink: ::grpcio::UnarySink<super::server::StatResponse>);
+    fn create(&mut self, ctx: ::grpcio::RpcContext, req: super::server::CreateRequest, sink: ::grpcio::UnarySink<super::server::CreateResponse>);
+    fn remove_metadata(&mut self, ctx: ::grpcio::RpcContext, req: super::server::PathRequest, sink: ::grpcio::UnarySink<super::server::StatusResponse>);
+    fn update_size(&mut self, ctx: ::grpcio::RpcContext, req: super::server::UpdateSizeRequest, sink: ::grpcio::UnarySink<super::server::SizeResponse>);
+    fn decrease_size(&mut self, ctx: ::grpcio::RpcContext, req: super::server::DecreaseSizeRequest, sink: ::grpcio::UnarySink<super::server::StatusResponse>);
+    fn get_size(&mut self, ctx: ::grpcio::RpcContext, req: super::server::PathRequest, sink: ::grpcio::UnarySink<super::server::SizeResponse>);
+    fn add_dirent(&mut self, ctx: ::grpcio::RpcContext, req: super::server::DirentRequest, sink: ::grpcio::UnarySink<super::server::StatusResponse>);
+    fn remove_dirent(&mut self, ctx: ::grpcio::RpcContext, req: super::server::DirentRequest, sink: ::grpcio::UnarySink<super::server::StatusResponse>);
+    fn get_dirents(&mut self, ctx: ::grpcio::RpcContext, req: super::server::GetDirentsRequest, sink: ::grpcio::ServerStreamingSink<super::server::Dirent>);
 }
 
 pub fn create_sfs_handle<S: SfsHandle + Send + Clone + 'static>(s: S) -> ::grpcio::Service {
@@ -101,9 +333,49 @@ pub fn create_sfs_handle<S: SfsHandle + Send + Clone + 'static>(s: S) -> ::grpci
     builder = builder.add_duplex_streaming_handler(&METHOD_SFS_HANDLE_HANDLE_STREAM, move |ctx, req, resp| {
         instance.handle_stream(ctx, req, resp)
     });
-    let mut instance = s;
+    let mut instance = s.clone();
     builder = builder.add_server_streaming_handler(&METHOD_SFS_HANDLE_HANDLE_DIRENTS, move |ctx, req, resp| {
         instance.handle_dirents(ctx, req, resp)
     });
+    let mut instance = s.clone();
+    builder = builder.add_unary_handler(&METHOD_SFS_HANDLE_HANDSHAKE, move |ctx, req, resp| {
+        instance.handshake(ctx, req, resp)
+    });
+    let mut instance = s.clone();
+    builder = builder.add_unary_handler(&METHOD_SFS_HANDLE_STAT, move |ctx, req, resp| {
+        instance.stat(ctx, req, resp)
+    });
+    let mut instance = s.clone();
+    builder = builder.add_unary_handler(&METHOD_SFS_HANDLE_CREATE, move |ctx, req, resp| {
+        instance.create(ctx, req, resp)
+    });
+    let mut instance = s.clone();
+    builder = builder.add_unary_handler(&METHOD_SFS_HANDLE_REMOVE_METADATA, move |ctx, req, resp| {
+        instance.remove_metadata(ctx, req, resp)
+    });
+    let mut instance = s.clone();
+    builder = builder.add_unary_handler(&METHOD_SFS_HANDLE_UPDATE_SIZE, move |ctx, req, resp| {
+        instance.update_size(ctx, req, resp)
+    });
+    let mut instance = s.clone();
+    builder = builder.add_unary_handler(&METHOD_SFS_HANDLE_DECREASE_SIZE, move |ctx, req, resp| {
+        instance.decrease_size(ctx, req, resp)
+    });
+    let mut instance = s.clone();
+    builder = builder.add_unary_handler(&METHOD_SFS_HANDLE_GET_SIZE, move |ctx, req, resp| {
+        instance.get_size(ctx, req, resp)
+    });
+    let mut instance = s.clone();
+    builder = builder.add_unary_handler(&METHOD_SFS_HANDLE_ADD_DIRENT, move |ctx, req, resp| {
+        instance.add_dirent(ctx, req, resp)
+    });
+    let mut instance = s.clone();
+    builder = builder.add_unary_handler(&METHOD_SFS_HANDLE_REMOVE_DIRENT, move |ctx, req, resp| {
+        instance.remove_dirent(ctx, req, resp)
+    });
+    let mut instance = s;
+    builder = builder.add_server_streaming_handler(&METHOD_SFS_HANDLE_GET_DIRENTS, move |ctx, req, resp| {
+        instance.get_dirents(ctx, req, resp)
+    });
     builder.build()
 }
@@ -0,0 +1,145 @@
+use std::fs;
+use std::io::{Error, ErrorKind};
+
+use grpcio::{
+    CallOption, CertificateRequestType, Channel, ChannelBuilder, ChannelCredentialsBuilder,
+    CheckResult, MetadataBuilder, RpcContext, RpcStatus, RpcStatusCode, ServerChecker,
+    ServerCredentials, ServerCredentialsBuilder,
+};
+
+// request header carrying the shared secret of a job
+pub const TOKEN_HEADER: &str = "sfs-token";
+// request header carrying the secret only the servers of a job share
+pub const SERVER_TOKEN_HEADER: &str = "sfs-server-token";
+
+// PEM encoded certificates, empty ones are not used.
+// 'ca' verifies the other side: servers for a client, clients and peers for a server
+#[derive(Clone, Default)]
+pub struct TlsConfig {
+    pub ca: Vec<u8>,
+    pub cert: Vec<u8>,
+    pub key: Vec<u8>,
+}
+fn read_pem(path: &String) -> Result<Vec<u8>, Error> {
+    if path.len() == 0 {
+        return Ok(Vec::new());
+    }
+    fs::read(path)
+}
+impl TlsConfig {
+    // None when no file is given, that is plain text
+    pub fn load(ca: &String, cert: &String, key: &String) -> Result<Option<TlsConfig>, Error> {
+        if ca.len() == 0 && cert.len() == 0 && key.len() == 0 {
+            return Ok(None);
+        }
+        Ok(Some(TlsConfig {
+            ca: read_pem(ca)?,
+            cert: read_pem(cert)?,
+            key: read_pem(key)?,
+        }))
+    }
+    // a client certificate is presented when one is configured, for servers requiring mTLS
+    pub fn connect(&self, builder: ChannelBuilder, addr: &str) -> Channel {
+        let mut creds = ChannelCredentialsBuilder::new();
+        if self.ca.len() != 0 {
+            creds = creds.root_cert(self.ca.clone());
+        }
+        if self.cert.len() != 0 {
+            creds = creds.cert(self.cert.clone(), self.key.clone());
+        }
+        builder.secure_connect(addr, creds.build())
+    }
+    // with a CA every client has to present a certificate signed by it
+    pub fn server_credentials(&self) -> ServerCredentials {
+        let mut creds = ServerCredentialsBuilder::new().add_cert(self.cert.clone(), self.key.clone());
+        if self.ca.len() != 0 {
+            creds = creds.root_cert(
+                self.ca.clone(),
+                CertificateRequestType::RequestAndRequireClientCertificateAndVerify,
+            );
+        }
+        creds.build()
+    }
+}
+pub fn connect(builder: ChannelBuilder, addr: &str, tls: &Option<TlsConfig>) -> Channel {
+    match tls {
+        Some(tls) => tls.connect(builder, addr),
+        None => builder.connect(addr),
+    }
+}
+// token from the variable itself or the file it names, empty when neither is set
+pub fn load_token(token: Option<String>, token_file: Option<String>) -> Result<String, Error> {
+    let token = match (token, token_file) {
+        (Some(token), _) => token.trim().to_string(),
+        (None, Some(path)) if path.len() != 0 => fs::read_to_string(path)?.trim().to_string(),
+        _ => String::new(),
+    };
+    // it travels as a plain request header
+    if !token.bytes().all(|b| b.is_ascii_graphic()) {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "token must consist of printable ascii characters",
+        ));
+    }
+    Ok(token)
+}
+// options of every call, carrying the token if there is one
+pub fn call_option(token: &String) -> CallOption {
+    if token.len() == 0 {
+        return CallOption::default();
+    }
+    let mut headers = MetadataBuilder::with_capacity(1);
+    headers.add_str(TOKEN_HEADER, token).unwrap();
+    CallOption::default().headers(headers.build())
+}
+// options of the calls between servers, carrying the secret of the servers as well
+pub fn server_call_option(token: &String, server_token: &String) -> CallOption {
+    let mut headers = MetadataBuilder::with_capacity(2);
+    if token.len() != 0 {
+        headers.add_str(TOKEN_HEADER, token).unwrap();
+    }
+    if server_token.len() != 0 {
+        headers.add_str(SERVER_TOKEN_HEADER, server_token).unwrap();
+    }
+    CallOption::default().headers(headers.build())
+}
+// whether the call carries the secret of the servers, a call never carries an empty one
+pub fn has_server_token(ctx: &RpcContext, server_token: &String) -> bool {
+    server_token.len() != 0
+        && ctx.request_headers().iter().any(|(key, value)| {
+            key == SERVER_TOKEN_HEADER && token_eq(value, server_token.as_bytes())
+        })
+}
+// rejects calls without the token of the job before they reach a handler
+#[derive(Clone)]
+pub struct TokenChecker {
+    token: String,
+}
+impl TokenChecker {
+    pub fn new(token: String) -> TokenChecker {
+        TokenChecker { token }
+    }
+}
+// no early exit, the time taken does not tell how much of the token was right
+pub fn token_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b.iter()).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+impl ServerChecker for TokenChecker {
+    fn check(&mut self, ctx: &RpcContext) -> CheckResult {
+        for (key, value) in ctx.request_headers().iter() {
+            if key == TOKEN_HEADER && token_eq(value, self.token.as_bytes()) {
+                return CheckResult::Continue;
+            }
+        }
+        CheckResult::Abort(RpcStatus::with_message(
+            RpcStatusCode::UNAUTHENTICATED,
+            "missing or wrong token".to_string(),
+        ))
+    }
+    fn box_clone(&self) -> Box<dyn ServerChecker> {
+        Box::new(self.clone())
+    }
+}
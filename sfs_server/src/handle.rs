@@ -1,41 +1,604 @@
 #[allow(unused)]
 use std::time::Instant;
 
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use futures::future::join_all;
+use lazy_static::*;
+use libc::{EEXIST, EIO, ENOENT};
+
+use crate::error_msg::error_msg;
+use crate::server::config::IGNORE_IF_EXISTS;
+use crate::server::filesystem::storage_context::StorageContext;
+use crate::server::network::{network_context::NetworkContext, network_service::NetworkService};
 use crate::server::storage::data::chunk_storage::ChunkStorage;
+use crate::server::space::SpaceTable;
+use crate::server::storage::data::stuff_db::StuffDB;
+use crate::server::storage::metadata::{
+    db::MetadataDB, dirent_db::DirentDB, file_ids::FileIds, lease::LeaseTable,
+    placement_db::PlacementDB,
+};
 #[allow(unused)]
 use sfs_global::global::{
+    distributor::Distributor,
+    erasure::erasure_layout,
+    fsconfig::{ENABLE_STUFFING, STUFF_SIZE},
+    metadata::{DirentAttr, Metadata, S_ISREG},
     network::{
-        config::CHUNK_SIZE,
-        forward_data::{PreCreateData, ReadData, ReadResult, TruncData, WriteData},
+        config::{
+            CHUNK_SIZE, FEATURE_CHECKSUM, FEATURE_LAYOUTS, FEATURE_REPLICATION, FEATURE_STUFFING,
+        },
+        forward_data::{
+            BlocksData, PlaceData, PreCreateData, ReadData, ReadResult, ReplicateChunkData, StatBatchData,
+            TruncData, WriteData,
+        },
+        handshake::HandshakeInfo,
+        post::PostOption,
     },
+    placement::Placement,
     util::{
         arith_util::{block_index, block_overrun},
-        serde_util::serialize,
+        serde_util::{deserialize, serialize, try_deserialize},
     },
 };
-use sfs_rpc::{post_result, proto::server::PostResult};
+use sfs_rpc::{
+    post_result,
+    proto::server::{Attr, PostResult},
+};
 
 pub fn handle_trunc(input: TruncData<'_>) -> PostResult {
     let path = input.path;
+    let file_id = input.file_id;
     let size = input.new_size;
     let mut chunk_id_start = block_index(size, CHUNK_SIZE);
     let left_pad = block_overrun(size, CHUNK_SIZE);
     if left_pad != 0 {
-        ChunkStorage::truncate_chunk_file(&path.to_string(), chunk_id_start, left_pad);
+        ChunkStorage::truncate_chunk_file(&path.to_string(), file_id, chunk_id_start, left_pad);
         chunk_id_start += 1;
     }
-    ChunkStorage::trim_chunk_space(&path.to_string(), chunk_id_start);
+    ChunkStorage::trim_chunk_space(&path.to_string(), file_id, chunk_id_start);
     let post_res = post_result(0, vec![0; 0], vec![0; 0]);
     return post_res;
 }
-pub fn handle_precreate(input: &PreCreateData) {
+pub fn handle_precreate(input: &PreCreateData) -> i32 {
+    ChunkStorage::precreate_chunks(&input.path.to_string(), input.file_id, &input.chunks)
+}
+pub fn handle_stat_batch(paths: &Vec<String>, client: u64) -> Vec<Option<DirentAttr>> {
+    paths
+        .iter()
+        .map(|path| {
+            LeaseTable::get_instance().grant(path, client);
+            MetadataDB::get_instance()
+                .get(path)
+                .map(|md| Metadata::deserialize(&md).to_attr())
+        })
+        .collect()
+}
+// attributes of the entries of 'dir', one 'StatBatch' per metadata host instead of one 'Stat' per
+// entry. the batches of all hosts are out at once and awaited, the handler thread is not held
+pub async fn collect_dirent_attrs(
+    dir: &String,
+    entries: &Vec<(String, bool)>,
+    client: u64,
+) -> Vec<Option<DirentAttr>> {
+    let distributor = NetworkContext::get_instance().get_distributor();
+    let local = NetworkContext::get_instance().get_local_host_id();
+    let mut host_entries: HashMap<u64, Vec<usize>> = HashMap::new();
+    let paths: Vec<String> = entries
+        .iter()
+        .map(|(name, _)| {
+            if dir.ends_with('/') {
+                dir.clone() + name
+            } else {
+                dir.clone() + "/" + name
+            }
+        })
+        .collect();
+    for (idx, path) in paths.iter().enumerate() {
+        host_entries
+            .entry(distributor.locate_file_metadata(path))
+            .or_insert(Vec::new())
+            .push(idx);
+    }
+    let mut attrs: Vec<Option<DirentAttr>> = entries.iter().map(|_| None).collect();
+    let mut remote = Vec::new();
+    for (host, idxs) in host_entries {
+        let batch: Vec<String> = idxs.iter().map(|idx| paths[*idx].clone()).collect();
+        if host == local {
+            for (idx, attr) in idxs.into_iter().zip(handle_stat_batch(&batch, client).into_iter()) {
+                attrs[idx] = attr;
+            }
+            continue;
+        }
+        let host_client = NetworkContext::get_instance()
+            .get_clients()
+            .get(host as usize)
+            .unwrap();
+        let data = StatBatchData {
+            paths: batch,
+            client,
+        };
+        remote.push(async move {
+            let res = NetworkService::post_async::<StatBatchData>(host_client, data, PostOption::StatBatch).await;
+            (idxs, res)
+        });
+    }
+    for (idxs, res) in join_all(remote).await {
+        // attributes are optional, entries of unreachable hosts are listed without them
+        let batch_attrs = match res {
+            Ok(res) if res.err == 0 => match try_deserialize::<Vec<Option<DirentAttr>>>(&res.data) {
+                Ok(batch_attrs) => batch_attrs,
+                Err(_) => continue,
+            },
+            _ => continue,
+        };
+        for (idx, attr) in idxs.into_iter().zip(batch_attrs.into_iter()) {
+            attrs[idx] = attr;
+        }
+    }
+    attrs
+}
+// what this server has in use, clients have to support all of it
+pub fn local_handshake() -> HandshakeInfo {
+    let mut features = if ENABLE_STUFFING { FEATURE_STUFFING } else { 0 };
+    if StorageContext::get_instance().get_checksum() {
+        features |= FEATURE_CHECKSUM;
+    }
+    if StorageContext::get_instance().get_replicas() > 1 {
+        features |= FEATURE_REPLICATION;
+    }
+    if StorageContext::get_instance().get_erasure_dirs().len() != 0 {
+        features |= FEATURE_LAYOUTS;
+    }
+    HandshakeInfo::new(features, StorageContext::get_instance().get_cluster_id().clone())
+}
+// the handlers below serve both the typed RPCs and the generic 'handle' shim
+pub fn attr_to_proto(attr: &DirentAttr) -> Attr {
+    let mut res = Attr::default();
+    res.set_mode(attr.mode);
+    res.set_size(attr.size);
+    res.set_access_time(attr.access_time);
+    res.set_modify_time(attr.modify_time);
+    res.set_change_time(attr.change_time);
+    res.set_link_count(attr.link_count);
+    res.set_blocks(attr.blocks);
+    res.set_file_id(attr.file_id);
+    res
+}
+// metadata of 'path' with the lease granted to 'client' in milliseconds, client 0 gets no lease
+pub fn handle_stat(path: &String, client: u64) -> Result<(Vec<u8>, u64), i32> {
+    if StorageContext::get_instance().output() {
+        println!("handling metadata of '{}'....", path);
+    }
+    let lease = LeaseTable::get_instance().grant(path, client);
+    match MetadataDB::get_instance().get(path) {
+        Some(md) => Ok((md, lease)),
+        None => Err(ENOENT),
+    }
+}
+// the file id of the new file, 'file_id' 0 has this host hand out one. directories have none
+pub fn handle_create(path: &String, mode: u32, file_id: u64, client: u64) -> Result<u64, i32> {
+    if StorageContext::get_instance().output() {
+        println!("handling create of '{}'....", path);
+    }
+    let _recall = LeaseTable::get_instance().recall(path, client)?;
+    if MetadataDB::get_instance().exists(path) {
+        return Err(EEXIST);
+    }
+    let file_id = if file_id != 0 || !S_ISREG(mode) {
+        file_id
+    } else {
+        FileIds::get_instance().next()?
+    };
+    let mut md = Metadata::new();
+    md.set_mode(mode);
+    md.set_file_id(file_id);
+    match MetadataDB::get_instance().put(path, md.serialize(), IGNORE_IF_EXISTS) {
+        0 => Ok(file_id),
+        e => Err(e),
+    }
+}
+pub fn handle_remove_metadata(path: &String, client: u64) -> i32 {
+    if StorageContext::get_instance().output() {
+        println!("handling remove metadata of '{}'....", path);
+    }
+    if let None = MetadataDB::get_instance().get(path) {
+        return ENOENT;
+    }
+    let _recall = match LeaseTable::get_instance().recall(path, client) {
+        Ok(recall) => recall,
+        Err(e) => return e,
+    };
+    MetadataDB::get_instance().remove(path);
+    StuffDB::get_instance().remove(path);
+    PlacementDB::get_instance().remove(path);
+    0
+}
+fn is_stuffed(path: &String) -> bool {
+    match MetadataDB::get_instance().get(path) {
+        Some(md) => ENABLE_STUFFING && Metadata::deserialize(&md).is_stuffed(),
+        None => false,
+    }
+}
+// content of 'path' if the file is stuffed
+pub fn handle_stuffed_data(path: &String) -> Option<Vec<u8>> {
+    if !is_stuffed(path) {
+        return None;
+    }
+    let _guard = StuffDB::get_instance().lock();
+    if !is_stuffed(path) {
+        return None;
+    }
+    Some(StuffDB::get_instance().get(path).unwrap_or(Vec::new()))
+}
+// write 'data' to chunk 0 of every data replica of 'path', one copy is enough to go on
+fn store_first_chunk(path: &String, file_id: u64, data: &Vec<u8>) -> i32 {
+    let local = NetworkContext::get_instance().get_local_host_id();
+    let mut err = EIO;
+    let mut stored = 0;
+    let distributor = NetworkContext::get_instance().get_distributor();
+    for host in PlacementDB::get_instance().get(path).locate(distributor.as_ref(), path, 0) {
+        let host_err = if host == local {
+            let buf = data.as_ptr() as *mut u8;
+            match ChunkStorage::write_chunk(path, file_id, 0, buf, data.len() as u64, 0) {
+                Ok(_) => 0,
+                Err(e) => e,
+            }
+        } else {
+            let client = NetworkContext::get_instance()
+                .get_clients()
+                .get(host as usize)
+                .unwrap();
+            let replicate = ReplicateChunkData {
+                path: path.as_str(),
+                file_id,
+                chunk_id: 0,
+            };
+            match NetworkService::post_stuff::<ReplicateChunkData>(
+                client,
+                replicate,
+                data.clone(),
+                PostOption::ReplicateChunk,
+            ) {
+                Ok(res) => res.err,
+                Err(_e) => EIO,
+            }
+        };
+        if host_err == 0 {
+            stored += 1;
+        } else {
+            error_msg(
+                "server::handle::store_first_chunk".to_string(),
+                format!("error {} occurs while unstuffing '{}' to host {}", host_err, path, host),
+            );
+            err = host_err;
+        }
+    }
+    if stored == 0 {
+        return err;
+    }
+    0
+}
+// move the content of a stuffed file to its chunks, the caller holds the stuffing lock.
+// the primary metadata replica writes chunk 0, the other replicas only drop their copy
+fn unstuff(path: &String) -> i32 {
+    if StorageContext::get_instance().output() {
+        println!("unstuffing '{}'....", path);
+    }
+    if is_primary(path) {
+        if let Some(data) = StuffDB::get_instance().get(path) {
+            if data.len() != 0 {
+                let file_id = match MetadataDB::get_instance().get(path) {
+                    Some(md) => Metadata::deserialize(&md).get_file_id(),
+                    None => 0,
+                };
+                let err = store_first_chunk(path, file_id, &data);
+                if err != 0 {
+                    return err;
+                }
+            }
+        }
+    }
+    MetadataDB::get_instance().unstuff(path);
+    StuffDB::get_instance().remove(path);
+    0
+}
+// returns the end of the write and whether 'data' is kept inline. a write that can't be kept
+// inline turns a stuffed file into a chunked one before the size changes
+pub fn handle_update_size(
+    path: &String,
+    size: u64,
+    offset: i64,
+    append: bool,
+    client: u64,
+    data: &[u8],
+) -> Result<(u64, bool), i32> {
+    if StorageContext::get_instance().output() {
+        println!("handling update metadentry of '{}'....", path);
+    }
+    let _recall = LeaseTable::get_instance().recall(path, client)?;
+    let end = size + offset as u64;
+    // files never turn stuffed again, the lock is only needed for stuffed ones
+    let guard = if is_stuffed(path) {
+        Some(StuffDB::get_instance().lock())
+    } else {
+        None
+    };
+    let mut stuffed = false;
+    if guard.is_some() && is_stuffed(path) {
+        if !append && data.len() as u64 == size && end <= STUFF_SIZE {
+            let err = StuffDB::get_instance().write(path, offset as u64, size, data);
+            if err != 0 {
+                return Err(err);
+            }
+            stuffed = true;
+        } else {
+            let err = unstuff(path);
+            if err != 0 {
+                return Err(err);
+            }
+        }
+    }
+    MetadataDB::get_instance().increase_size(path, size as usize + offset as usize, append);
+    Ok((end, stuffed))
+}
+// deferred size updates, stuffed files among them are unstuffed first. the sizes are absolute,
+// the client sends the whole batch again after an error
+pub fn handle_update_size_batch(entries: &Vec<(String, u64)>, client: u64) -> i32 {
+    let mut err = 0;
+    for (path, size) in entries.iter() {
+        if let Err(e) = handle_update_size(path, *size, 0, false, client, &[]) {
+            err = e;
+        }
+    }
+    err
+}
+pub fn handle_decrease_size(path: &String, new_size: i64, client: u64) -> i32 {
+    if StorageContext::get_instance().output() {
+        println!("handling decrease size of '{}'....", path);
+    }
+    let _recall = match LeaseTable::get_instance().recall(path, client) {
+        Ok(recall) => recall,
+        Err(e) => return e,
+    };
+    if is_stuffed(path) {
+        let _guard = StuffDB::get_instance().lock();
+        StuffDB::get_instance().truncate(path, new_size as u64);
+    }
+    // chunks cut off lose their placement, a later write places them anew
+    let placement_guard = PlacementDB::get_instance().lock();
+    let mut placement = PlacementDB::get_instance().get(path);
+    if !placement.is_empty() {
+        placement.trim(block_index(new_size + CHUNK_SIZE as i64 - 1, CHUNK_SIZE));
+        PlacementDB::get_instance().put(path, &placement);
+    }
+    drop(placement_guard);
+    MetadataDB::get_instance().decrease_size(path, new_size as usize);
+    0
+}
+pub fn handle_get_size(path: &String) -> Result<i64, i32> {
+    if StorageContext::get_instance().output() {
+        println!("handling get metadentry....");
+    }
+    match MetadataDB::get_instance().get(path) {
+        Some(md) => Ok(Metadata::deserialize(&md).get_size()),
+        None => Err(ENOENT),
+    }
+}
+pub fn handle_add_dirent(dir: &String, name: &String, is_dir: bool) -> i32 {
+    if StorageContext::get_instance().output() {
+        println!("handling add dirent '{}' of '{}'....", name, dir);
+    }
+    DirentDB::get_instance().put(dir, name, is_dir)
+}
+pub fn handle_remove_dirent(dir: &String, name: &String) -> i32 {
+    if StorageContext::get_instance().output() {
+        println!("handling remove dirent '{}' of '{}'....", name, dir);
+    }
+    DirentDB::get_instance().remove(dir, name)
+}
+// entries of 'path' after 'start_after', with their attributes for readdirplus
+pub async fn handle_get_dirents(
+    path: &String,
+    start_after: &String,
+    limit: u64,
+    plus: bool,
+    client: u64,
+) -> Vec<((String, bool), Option<DirentAttr>)> {
+    if StorageContext::get_instance().output() {
+        println!("handling get dirents of '{}'....", path);
+    }
+    let entries = DirentDB::get_instance().list_dirents(path, start_after, limit);
+    let attrs = if plus {
+        collect_dirent_attrs(path, &entries, client).await
+    } else {
+        entries.iter().map(|_| None).collect()
+    };
+    entries.into_iter().zip(attrs.into_iter()).collect()
+}
+lazy_static! {
+    // changes of the physical blocks of compressed chunks not told to the metadata hosts yet,
+    // by file and chunk
+    static ref PENDING_BLOCKS: Mutex<HashMap<String, HashMap<u64, i64>>> =
+        Mutex::new(HashMap::new());
+}
+// physical blocks of a chunk of a compressed file changed, 'block_reporter' tells the metadata
+// hosts
+pub fn report_blocks(path: &String, chunk_id: u64, blocks: i64) {
+    if blocks == 0 {
+        return;
+    }
+    *PENDING_BLOCKS
+        .lock()
+        .unwrap()
+        .entry(path.clone())
+        .or_default()
+        .entry(chunk_id)
+        .or_insert(0) += blocks;
+}
+// each 'interval_s' seconds, send the blocks changed since to the metadata hosts, one report
+// per file. replicas of a chunk change alike, only the first one tells. a report lost is not
+// sent again, the metadata hosts add up what they get
+pub fn block_reporter(interval_s: u64) {
+    let local = NetworkContext::get_instance().get_local_host_id();
+    let distributor = NetworkContext::get_instance().get_distributor();
+    loop {
+        thread::sleep(Duration::from_secs(interval_s));
+        let pending = std::mem::take(&mut *PENDING_BLOCKS.lock().unwrap());
+        for (path, chunks) in pending {
+            // every piece of an erasure-coded file is held by one host
+            let erasure =
+                erasure_layout(StorageContext::get_instance().get_erasure_dirs(), &path).is_some();
+            let placement = file_placement(&path);
+            let blocks: i64 = chunks
+                .into_iter()
+                .filter(|(chunk_id, _blocks)| {
+                    erasure || placement.locate(distributor.as_ref(), &path, *chunk_id)[0] == local
+                })
+                .map(|(_chunk_id, blocks)| blocks)
+                .sum();
+            if blocks == 0 {
+                continue;
+            }
+            for host in distributor.locate_file_metadata_replicas(&path) {
+                let client = NetworkContext::get_instance()
+                    .get_clients()
+                    .get(host as usize)
+                    .unwrap();
+                let data = BlocksData {
+                    path: path.as_str(),
+                    blocks,
+                };
+                if let Err(e) = NetworkService::post::<BlocksData>(client, data, PostOption::AddBlocks) {
+                    error_msg(
+                        "server::handle::block_reporter".to_string(),
+                        format!("error {} occurs while reporting blocks of '{}' to host {}", e, path, host),
+                    );
+                }
+            }
+        }
+    }
+}
+// whether this host is the first metadata replica of 'path', the one acting for all of them
+pub fn is_primary(path: &String) -> bool {
+    let context = NetworkContext::get_instance();
+    context
+        .get_distributor()
+        .locate_file_metadata_replicas(path)[0]
+        == context.get_local_host_id()
+}
+// placement of 'path', read locally on its metadata hosts and asked for elsewhere
+pub fn file_placement(path: &String) -> Placement {
+    let local = NetworkContext::get_instance().get_local_host_id();
+    let replicas = NetworkContext::get_instance()
+        .get_distributor()
+        .locate_file_metadata_replicas(path);
+    if replicas.contains(&local) {
+        return PlacementDB::get_instance().get(path);
+    }
+    for host in replicas {
+        let client = NetworkContext::get_instance()
+            .get_clients()
+            .get(host as usize)
+            .unwrap();
+        let query = PlaceData {
+            path: path.as_str(),
+            chunks: Vec::new(),
+            full: Vec::new(),
+        };
+        if let Ok(res) = NetworkService::post::<PlaceData>(client, query, PostOption::Place) {
+            if res.err == 0 {
+                if let Ok(placement) = try_deserialize::<Placement>(&res.data) {
+                    return placement;
+                }
+            }
+        }
+    }
+    Placement::new()
+}
+// place the chunks of 'input' away from the hosts that refused them and the ones reported
+// nearly full, unless they have a placement already. the other metadata hosts get the same copy,
+// the place fails unless a write quorum of them keeps it
+pub fn handle_place(input: &PlaceData) -> Result<Placement, i32> {
     let path = input.path.to_string();
-    ChunkStorage::init_chunk_space(&path);
-    for chunk_id in input.chunks.iter() {
-        let chunk_path = ChunkStorage::absolute(&ChunkStorage::get_chunks_path(&path, *chunk_id));
-        std::fs::OpenOptions::new()
-            .create(true)
-            .open(&chunk_path)
+    if input.chunks.len() == 0 {
+        return Ok(PlacementDB::get_instance().get(&path));
+    }
+    if StorageContext::get_instance().output() {
+        println!("handling place of {} chunks of '{}'....", input.chunks.len(), path);
+    }
+    let local = NetworkContext::get_instance().get_local_host_id();
+    let distributor = NetworkContext::get_instance().get_distributor();
+    let mut avoid = SpaceTable::get_instance().nearly_full_hosts();
+    avoid.extend(input.full.iter());
+    let placement = {
+        let _guard = PlacementDB::get_instance().lock();
+        let mut placement = PlacementDB::get_instance().get(&path);
+        let mut changed = false;
+        for chunk_id in input.chunks.iter() {
+            if placement.replicas(*chunk_id).is_some() {
+                continue;
+            }
+            let hosts = distributor.locate_data_replicas_avoiding(&path, *chunk_id, &avoid);
+            if hosts != distributor.locate_data_replicas(&path, *chunk_id) {
+                changed |= placement.record(*chunk_id, hosts);
+            }
+        }
+        if !changed {
+            return Ok(placement);
+        }
+        let err = PlacementDB::get_instance().put(&path, &placement);
+        if err != 0 {
+            return Err(err);
+        }
+        placement
+    };
+    let replicas = distributor.locate_file_metadata_replicas(&path);
+    let quorum = match StorageContext::get_instance().get_write_quorum() as usize {
+        0 => replicas.len(),
+        quorum => usize::min(quorum, replicas.len()),
+    };
+    let mut acks = 0;
+    for host in replicas {
+        if host == local {
+            acks += 1;
+            continue;
+        }
+        let client = NetworkContext::get_instance()
+            .get_clients()
+            .get(host as usize)
             .unwrap();
+        match NetworkService::post_stuff::<&str>(
+            client,
+            path.as_str(),
+            serialize(&placement),
+            PostOption::PutPlacement,
+        ) {
+            Ok(res) if res.err == 0 => acks += 1,
+            Ok(res) => error_msg(
+                "server::handle::handle_place".to_string(),
+                format!("error {} occurs while replicating placement of '{}' to host {}", res.err, path, host),
+            ),
+            Err(e) => error_msg(
+                "server::handle::handle_place".to_string(),
+                format!("error {} occurs while replicating placement of '{}' to host {}", e, path, host),
+            ),
+        }
     }
+    // a replica missing the placement could not find the moved chunks after a failover
+    if acks < quorum {
+        return Err(EIO);
+    }
+    Ok(placement)
+}
+pub fn handle_put_placement(path: &String, data: &Vec<u8>) -> i32 {
+    let placement = match try_deserialize::<Placement>(data) {
+        Ok(placement) => placement,
+        Err(e) => return e,
+    };
+    let _guard = PlacementDB::get_instance().lock();
+    PlacementDB::get_instance().put(path, &placement)
 }
@@ -8,22 +8,29 @@ use crate::rdma::write_server::recver_server;
 use crate::server::{config::IGNORE_IF_EXISTS, network::network_context::NetworkContext};
 use crate::server::{
     filesystem::storage_context::StorageContext, storage::data::chunk_storage::*,
-    storage::metadata::db::MetadataDB,
+    storage::metadata::db::MetadataDB, storage::metadata::dirent_db::DirentDB,
+    storage::metadata::lease::LeaseTable, storage::metadata::placement_db::PlacementDB,
 };
 use config::ENABLE_PRECREATE;
+use error_msg::error_msg;
 use futures::channel::oneshot;
 use futures::executor::block_on;
 use futures::{FutureExt, SinkExt, TryFutureExt, TryStreamExt};
 use grpcio::{Environment, ServerBuilder, WriteFlags};
 use handle::handle_precreate;
-use libc::{getgid, getuid, EINVAL, ENOENT, S_IFDIR, S_IRWXG, S_IRWXO, S_IRWXU};
+use libc::{getgid, getuid, EINVAL, ENOENT, EPERM, S_IFDIR, S_IRWXG, S_IRWXO, S_IRWXU};
 use server::network::network_service::NetworkService;
+use server::repair::{backfill_dirents, encode_stripe, repair_host, replicate_chunk};
+use server::config::{BLOCK_REPORT_INTERVAL_S, HEARTBEAT_INTERVAL_S};
+use server::scrub::scrubber;
+use server::space::{heartbeat, SpaceTable};
+use server::stage::{stage, stage_chunks, stage_progress, start_stage};
+use server::storage::data::migrate::migrate_chunks;
 use server::storage::data::stuff_db::StuffDB;
-use sfs_global::global::distributor::Distributor;
-use sfs_global::global::fsconfig::ENABLE_STUFFING;
 use sfs_global::global::network::forward_data::PreCreateData;
+use sfs_global::global::network::handshake::HandshakeInfo;
 use sfs_global::global::network::post::{i2option, PostOption};
-use sfs_global::global::util::serde_util::{deserialize, serialize};
+use sfs_global::global::util::serde_util::{serialize, try_deserialize};
 use sfs_global::{
     global::network::post::PostOption::*,
     global::{
@@ -32,15 +39,24 @@ use sfs_global::{
         network::{
             config::CHUNK_SIZE,
             forward_data::{
-                CreateData, DecrData, DirentData, TruncData, UpdateMetadentryData,
+                BlocksData, CreateData, DecrData, DirentData, DirentRecordData, EncodeStripeData,
+                HeartbeatData, LinkChunksData, PathData,
+                PlaceData, ReadChunkData, RemoveData, RepairChunkData,
+                RepairData, ReplicateChunkData, SizeBatchData, StageChunksData, StageData, StatBatchData, TruncData,
+                UpdateMetadentryData,
             },
         },
         util::net_util::get_my_hostname,
     },
 };
 use sfs_rdma::chunk_operation::ChunkOp;
-use sfs_rpc::post_result;
-use sfs_rpc::proto::server::{Post, PostResult};
+use sfs_rpc::proto::server::{
+    CreateRequest, CreateResponse, DecreaseSizeRequest, Dirent, DirentRequest, GetDirentsRequest,
+    HandshakeRequest, HandshakeResponse, PathRequest, Post, PostResult, SizeResponse, StatRequest,
+    StatResponse, StatusResponse, UpdateSizeRequest,
+};
+use sfs_rpc::security::{has_server_token, TokenChecker};
+use sfs_rpc::{post_result, status, status_response};
 use sfs_rpc::proto::server_grpc::{create_sfs_handle, SfsHandle};
 use std::collections::HashMap;
 use std::io::Read;
@@ -53,61 +69,69 @@ use std::{
     path::Path,
 };
 
-use crate::handle::{handle_trunc};
+use crate::handle::{
+    attr_to_proto, block_reporter, handle_add_dirent, handle_create, handle_decrease_size,
+    handle_get_dirents, handle_get_size, handle_place, handle_put_placement, handle_remove_dirent,
+    handle_remove_metadata, handle_stat, handle_stat_batch, handle_stuffed_data, handle_trunc,
+    handle_update_size, handle_update_size_batch, local_handshake,
+};
 
 #[allow(unused)]
 use std::time::Instant;
 
+// malformed payloads get EINVAL instead of bringing the server down
+macro_rules! decode {
+    ($t:ty, $data:expr) => {
+        match try_deserialize::<$t>(&$data) {
+            Ok(data) => data,
+            Err(e) => return post_result(e, vec![0; 0], vec![0; 0]),
+        }
+    };
+}
+// compatibility shim for peers still using 'PostOption', the metadata operations are served by
+// the typed methods of 'ServerHandler' through the same handlers
 fn handle_request(post: &Post) -> PostResult {
     let option = i2option(post.option);
     match option {
         Stat => {
-            let path = deserialize::<&str>(&post.data);
-            if StorageContext::get_instance().output() {
-                println!("handling metadata of '{}'....", path);
+            let path = decode!(&str, post.data);
+            match handle_stat(&path.to_string(), 0) {
+                Ok((md, _)) => return post_result(0, md, vec![0; 0]),
+                Err(e) => return post_result(e, vec![0; 0], vec![0; 0]),
             }
-            let md_res = MetadataDB::get_instance().get(&path.to_string());
-            if let Some(md) = md_res {
-                return post_result(0, md, vec![0; 0]);
-            } else {
-                return post_result(ENOENT, vec![0; 0], vec![0; 0]);
+        }
+        StatLease => {
+            let data: PathData = decode!(PathData, post.data);
+            match handle_stat(&data.path.to_string(), data.client) {
+                Ok((md, lease)) => return post_result(0, md, serialize(lease)),
+                Err(e) => return post_result(e, vec![0; 0], vec![0; 0]),
             }
         }
         Create => {
-            let create_data: CreateData = deserialize::<CreateData>(&post.data);
-            if StorageContext::get_instance().output() {
-                println!("handling create of '{}'....", create_data.path);
-            }
-            let mode = create_data.mode;
-            let mut md = Metadata::new();
-            md.set_mode(mode);
-            let create_res = MetadataDB::get_instance().put(
+            let create_data: CreateData = decode!(CreateData, post.data);
+            let create_res = handle_create(
                 &create_data.path.to_string(),
-                md.serialize(),
-                IGNORE_IF_EXISTS,
+                create_data.mode,
+                0,
+                create_data.client,
             );
-            return post_result(create_res, vec![0; 0], vec![0; 0]);
+            match create_res {
+                Ok(file_id) => return post_result(0, serialize(file_id), vec![0; 0]),
+                Err(e) => return post_result(e, vec![0; 0], vec![0; 0]),
+            }
         }
         Remove => {
-            let path = deserialize::<&str>(&post.data);
+            let data: RemoveData = decode!(RemoveData, post.data);
             if StorageContext::get_instance().output() {
-                println!("handling remove of '{}'....", path);
+                println!("handling remove of '{}'....", data.path);
             }
-            ChunkStorage::destroy_chunk_space(&path.to_string());
+            ChunkStorage::destroy_chunk_space(&data.path.to_string(), data.file_id);
             return post_result(0, vec![0; 0], vec![0; 0]);
         }
         RemoveMeta => {
-            let path = deserialize::<&str>(&post.data);
-            if StorageContext::get_instance().output() {
-                println!("handling remove metadata of '{}'....", path);
-            }
-            let md_res = MetadataDB::get_instance().get(&path.to_string());
-            if let None = md_res {
-                return post_result(ENOENT, vec![0; 0], vec![0; 0]);
-            } else {
-                MetadataDB::get_instance().remove(&path.to_string());
-                return post_result(0, vec![0; 0], vec![0; 0]);
-            }
+            let data: PathData = decode!(PathData, post.data);
+            let err = handle_remove_metadata(&data.path.to_string(), data.client);
+            return post_result(err, vec![0; 0], vec![0; 0]);
         }
         Lookup => {
             if StorageContext::get_instance().output() {
@@ -129,60 +153,44 @@ fn handle_request(post: &Post) -> PostResult {
             fs_config.blocks_state = StorageContext::get_instance().get_blocks_state();
             fs_config.uid = unsafe { getuid() };
             fs_config.gid = unsafe { getgid() };
+            fs_config.replicas = StorageContext::get_instance().get_replicas();
+            fs_config.write_quorum = StorageContext::get_instance().get_write_quorum();
+            fs_config.erasure_dirs = StorageContext::get_instance().get_erasure_dirs().clone();
+            fs_config.compress_dirs = StorageContext::get_instance().get_compress_dirs().clone();
+            fs_config.dedup = StorageContext::get_instance().get_dedup();
+            fs_config.consistency = StorageContext::get_instance().get_consistency();
+            fs_config.lease_ms = StorageContext::get_instance().get_lease_ms();
             return post_result(0, serialize(&fs_config), vec![0; 0]);
         }
         UpdateMetadentry => {
-            let update_data: UpdateMetadentryData = deserialize::<UpdateMetadentryData>(&post.data);
-            if StorageContext::get_instance().output() {
-                println!("handling update metadentry of '{}'....", update_data.path);
-            }
-            let path = update_data.path.to_string();
-
-            MetadataDB::get_instance().increase_size(
-                &path,
-                update_data.size as usize + update_data.offset as usize,
+            let update_data: UpdateMetadentryData = decode!(UpdateMetadentryData, post.data);
+            // a write kept inline is answered with its length in 'extra'
+            match handle_update_size(
+                &update_data.path.to_string(),
+                update_data.size,
+                update_data.offset,
                 update_data.append,
-            );
-            let extra = vec![0; 0];
-            // stuffing the first chunk
-            // stuffing is disabled due to that RDMA is added to this project
-            if ENABLE_STUFFING {
-                /*
-                let md = Metadata::deserialize(&MetadataDB::get_instance().get(&path).unwrap());
-                if md.is_stuffed() {
-                    let write_tot = ChunkStorage::write_chunk(
-                        &path,
-                        0,
-                        &post.extra,
-                        update_data.size,
-                        update_data.offset as u64,
-                    );
-                    if let Ok(len) = write_tot {
-                        extra = serialize(len);
-                    }
-                }
-                */
+                update_data.client,
+                &post.extra,
+            ) {
+                Ok((end, true)) => return post_result(0, serialize(end), serialize(update_data.size)),
+                Ok((end, false)) => return post_result(0, serialize(end), vec![0; 0]),
+                Err(e) => return post_result(e, vec![0; 0], vec![0; 0]),
             }
-            return post_result(
-                0,
-                serialize(update_data.size + update_data.offset as u64),
-                extra,
-            );
         }
-        GetMetadentry => {
+        UpdateSizeBatch => {
+            let data: SizeBatchData = decode!(SizeBatchData, post.data);
             if StorageContext::get_instance().output() {
-                println!("handling get metadentry....");
+                println!("handling size update of {} files....", data.entries.len());
             }
-            let path = deserialize::<&str>(&post.data);
-            let md_str = MetadataDB::get_instance().get(&path.to_string());
-            match md_str {
-                None => {
-                    return post_result(ENOENT, vec![0; 0], vec![0; 0]);
-                }
-                Some(str) => {
-                    let md = Metadata::deserialize(&str);
-                    return post_result(0, serialize(md.get_size()), vec![0; 0]);
-                }
+            let err = handle_update_size_batch(&data.entries, data.client);
+            return post_result(err, vec![0; 0], vec![0; 0]);
+        }
+        GetMetadentry => {
+            let path = decode!(&str, post.data);
+            match handle_get_size(&path.to_string()) {
+                Ok(size) => return post_result(0, serialize(size), vec![0; 0]),
+                Err(e) => return post_result(e, vec![0; 0], vec![0; 0]),
             }
         }
         ChunkStat => {
@@ -194,35 +202,257 @@ fn handle_request(post: &Post) -> PostResult {
             return post_result;
         }
         DecrSize => {
-            let decr_data: DecrData = deserialize::<DecrData>(&post.data);
-            if StorageContext::get_instance().output() {
-                println!("handling decrease size of '{}'....", decr_data.path);
-            }
-            MetadataDB::get_instance()
-                .decrease_size(&decr_data.path.to_string(), decr_data.new_size as usize);
-            return post_result(0, vec![0; 0], vec![0; 0]);
+            let decr_data: DecrData = decode!(DecrData, post.data);
+            let err = handle_decrease_size(
+                &decr_data.path.to_string(),
+                decr_data.new_size,
+                decr_data.client,
+            );
+            return post_result(err, vec![0; 0], vec![0; 0]);
         }
         Trunc => {
-            let trunc_data: TruncData = deserialize::<TruncData>(&post.data);
+            let trunc_data: TruncData = decode!(TruncData, post.data);
             if StorageContext::get_instance().output() {
                 println!("handling truncate of '{}'....", trunc_data.path);
             }
             return handle_trunc(trunc_data);
         }
         PreCreate => {
-            let data: PreCreateData = deserialize::<PreCreateData>(&post.data);
+            let data: PreCreateData = decode!(PreCreateData, post.data);
             if StorageContext::get_instance().output() {
                 println!("handling precreate of '{}'....", data.path);
             }
-            handle_precreate(&data);
+            return post_result(handle_precreate(&data), vec![0; 0], vec![0; 0]);
+        }
+        Repair => {
+            let data: RepairData = decode!(RepairData, post.data);
+            if StorageContext::get_instance().output() {
+                println!("handling repair of host {}....", data.host_id);
+            }
+            let target = data.host_id;
+            thread::spawn(move || repair_host(target));
+            return post_result(0, vec![0; 0], vec![0; 0]);
+        }
+        RepairChunk => {
+            let data: RepairChunkData = decode!(RepairChunkData, post.data);
+            if StorageContext::get_instance().output() {
+                println!("handling repair chunk {} of '{}'....", data.chunk_id, data.path);
+            }
+            let err =
+                replicate_chunk(&data.path.to_string(), data.file_id, data.chunk_id, data.target);
+            return post_result(err, vec![0; 0], vec![0; 0]);
+        }
+        ReplicateChunk => {
+            let data: ReplicateChunkData = decode!(ReplicateChunkData, post.data);
+            if StorageContext::get_instance().output() {
+                println!("handling replicate chunk {} of '{}'....", data.chunk_id, data.path);
+            }
+            let write_res = ChunkStorage::write_chunk(
+                &data.path.to_string(),
+                data.file_id,
+                data.chunk_id,
+                post.extra.as_ptr() as *mut u8,
+                post.extra.len() as u64,
+                0,
+            );
+            if let Err(e) = write_res {
+                return post_result(e, vec![0; 0], vec![0; 0]);
+            }
+            return post_result(0, vec![0; 0], vec![0; 0]);
+        }
+        ReplicateMetadata => {
+            let path = decode!(&str, post.data);
+            if StorageContext::get_instance().output() {
+                println!("handling replicate metadata of '{}'....", path);
+            }
+            let put_res = MetadataDB::get_instance().put(&path.to_string(), post.extra.clone(), false);
+            LeaseTable::get_instance().revoke(&path.to_string());
+            return post_result(put_res, vec![0; 0], vec![0; 0]);
+        }
+        ReplicateStuff => {
+            let path = decode!(&str, post.data);
+            if StorageContext::get_instance().output() {
+                println!("handling replicate stuffed data of '{}'....", path);
+            }
+            let put_res = StuffDB::get_instance().put(&path.to_string(), post.extra.clone());
+            return post_result(put_res, vec![0; 0], vec![0; 0]);
+        }
+        ReadChunk => {
+            let data: ReadChunkData = decode!(ReadChunkData, post.data);
+            if StorageContext::get_instance().output() {
+                println!("handling read chunk {} of '{}'....", data.chunk_id, data.path);
+            }
+            let mut buf = vec![0u8; CHUNK_SIZE as usize];
+            let read_res = ChunkStorage::read_chunk(
+                &data.path.to_string(),
+                data.file_id,
+                data.chunk_id,
+                buf.as_mut_ptr(),
+                CHUNK_SIZE,
+                0,
+            );
+            match read_res {
+                Ok(len) => buf.truncate(len as usize),
+                // chunk never written, it is a hole
+                Err(-2) => buf.clear(),
+                Err(e) => return post_result(e, vec![0; 0], vec![0; 0]),
+            }
+            return post_result(0, vec![0; 0], buf);
+        }
+        EncodeStripe => {
+            let data: EncodeStripeData = decode!(EncodeStripeData, post.data);
+            if StorageContext::get_instance().output() {
+                println!("handling encode stripe {} of '{}'....", data.stripe_id, data.path);
+            }
+            let err = encode_stripe(&data.path.to_string(), data.file_id, data.stripe_id);
+            return post_result(err, vec![0; 0], vec![0; 0]);
+        }
+        AddBlocks => {
+            let data: BlocksData = decode!(BlocksData, post.data);
+            if StorageContext::get_instance().output() {
+                println!("handling add blocks of '{}'....", data.path);
+            }
+            let path = data.path.to_string();
+            // the file may be gone while its chunks were still rewritten
+            if MetadataDB::get_instance().get(&path).is_some() {
+                MetadataDB::get_instance().add_blocks(&path, data.blocks);
+            }
             return post_result(0, vec![0; 0], vec![0; 0]);
         }
+        LinkChunks => {
+            let data: LinkChunksData = decode!(LinkChunksData, post.data);
+            if StorageContext::get_instance().output() {
+                println!("handling link chunks of '{}'....", data.path);
+            }
+            let linked = if StorageContext::get_instance().get_dedup() {
+                ChunkStorage::link_chunks(&data.path.to_string(), data.file_id, &data.chunks)
+            } else {
+                Vec::new()
+            };
+            return post_result(0, serialize(&linked), vec![0; 0]);
+        }
+        Stage => {
+            let data: StageData = decode!(StageData, post.data);
+            if StorageContext::get_instance().output() {
+                println!("handling stage of '{}'....", data.sfs_dir);
+            }
+            let err = start_stage(&data);
+            return post_result(err, vec![0; 0], vec![0; 0]);
+        }
+        StageChunks => {
+            let data: StageChunksData = decode!(StageChunksData, post.data);
+            if StorageContext::get_instance().output() {
+                println!("handling stage of {} chunks of '{}'....", data.chunks.len(), data.path);
+            }
+            let err = stage_chunks(&data);
+            return post_result(err, vec![0; 0], vec![0; 0]);
+        }
+        StageStatus => {
+            let data: StageData = decode!(StageData, post.data);
+            match stage_progress(&data) {
+                Some(progress) => return post_result(0, serialize(&progress), vec![0; 0]),
+                None => return post_result(ENOENT, vec![0; 0], vec![0; 0]),
+            }
+        }
+        Place => {
+            let data: PlaceData = decode!(PlaceData, post.data);
+            match handle_place(&data) {
+                Ok(placement) => return post_result(0, serialize(&placement), vec![0; 0]),
+                Err(e) => return post_result(e, vec![0; 0], vec![0; 0]),
+            }
+        }
+        PutPlacement => {
+            let path = decode!(&str, post.data);
+            if StorageContext::get_instance().output() {
+                println!("handling put placement of '{}'....", path);
+            }
+            let put_res = handle_put_placement(&path.to_string(), &post.extra);
+            return post_result(put_res, vec![0; 0], vec![0; 0]);
+        }
+        Heartbeat => {
+            let data: HeartbeatData = decode!(HeartbeatData, post.data);
+            SpaceTable::get_instance().update(data.host, &data.stat);
+            return post_result(0, vec![0; 0], vec![0; 0]);
+        }
+        AddDirent => {
+            let data: DirentRecordData = decode!(DirentRecordData, post.data);
+            let put_res =
+                handle_add_dirent(&data.dir.to_string(), &data.name.to_string(), data.is_dir);
+            return post_result(put_res, vec![0; 0], vec![0; 0]);
+        }
+        RemoveDirent => {
+            let data: DirentRecordData = decode!(DirentRecordData, post.data);
+            let remove_res = handle_remove_dirent(&data.dir.to_string(), &data.name.to_string());
+            return post_result(remove_res, vec![0; 0], vec![0; 0]);
+        }
+        StatBatch => {
+            let data: StatBatchData = decode!(StatBatchData, post.data);
+            if StorageContext::get_instance().output() {
+                println!("handling stat of {} entries....", data.paths.len());
+            }
+            return post_result(
+                0,
+                serialize(handle_stat_batch(&data.paths, data.client)),
+                vec![0; 0],
+            );
+        }
         _ => {
             println!("invalid option on 'handle': {:?}", option);
             return post_result(EINVAL, vec![0; 0], vec![0; 0]);
         }
     }
 }
+// create the chunk files a write up to 'end' is going to fill, ahead of the data
+fn spawn_precreate(ctx: &grpcio::RpcContext, path: String, end: u64) {
+    let (chunk_start, file_id) = if let Some(md) = MetadataDB::get_instance().get(&path) {
+        let md = Metadata::deserialize(&md);
+        (md.get_size() as u64 / CHUNK_SIZE + 1, md.get_file_id())
+    } else {
+        (0, 0)
+    };
+    let chunk_end = end / CHUNK_SIZE;
+    let placement = PlacementDB::get_instance().get(&path);
+    let f = async move {
+        let mut hosts = HashMap::new();
+        let distributor = NetworkContext::get_instance().get_distributor();
+        for chunk_id in chunk_start..(chunk_end + 1) {
+            let host = placement.locate(distributor.as_ref(), &path, chunk_id)[0];
+            hosts.entry(host).or_insert(Vec::new()).push(chunk_id);
+        }
+        for (host, chunks) in hosts {
+            let client = NetworkContext::get_instance()
+                .get_clients()
+                .get(host as usize)
+                .unwrap();
+            let pre_create = PreCreateData {
+                path: path.as_str(),
+                file_id,
+                chunks,
+            };
+            // chunk files not made ahead are created by the write itself
+            let post_res =
+                NetworkService::post_async::<PreCreateData>(client, pre_create, PreCreate).await;
+            if let Err(e) = post_res {
+                error_msg(
+                    "server::spawn_precreate".to_string(),
+                    format!("error {} occurs while pre-creating chunks on host {}", e, host),
+                );
+            }
+        }
+    };
+    ctx.spawn(f);
+}
+fn size_response(res: Result<i64, i32>) -> SizeResponse {
+    let mut response = SizeResponse::default();
+    match res {
+        Ok(size) => {
+            response.set_status(status(0, String::new()));
+            response.set_size(size);
+        }
+        Err(e) => response.set_status(status(e, String::new())),
+    }
+    response
+}
 #[derive(Clone, Default)]
 struct ServerHandler {}
 impl SfsHandle for ServerHandler {
@@ -234,46 +464,28 @@ impl SfsHandle for ServerHandler {
     ) {
         if ENABLE_PRECREATE {
             if let PostOption::UpdateMetadentry = i2option(req.option) {
-                let update_data: UpdateMetadentryData =
-                    deserialize::<UpdateMetadentryData>(&req.data);
-                let chunk_start = if let Some(md) =
-                    MetadataDB::get_instance().get(&update_data.path.to_string())
-                {
-                    Metadata::deserialize(&md).get_size() as u64 / CHUNK_SIZE + 1
-                } else {
-                    0
-                };
-                let chunk_end = (update_data.size + update_data.offset as u64) / CHUNK_SIZE;
-                let path = update_data.path.clone().to_string();
-                let f = async move {
-                    let mut hosts = HashMap::new();
-                    let distributor = NetworkContext::get_instance().get_distributor();
-                    for chunk_id in chunk_start..(chunk_end + 1) {
-                        let host = distributor.locate_data(&path, chunk_id);
-                        if !hosts.contains_key(&chunk_id) {
-                            hosts.insert(host, Vec::new());
-                        } else {
-                            hosts.get_mut(&host).unwrap().push(chunk_id);
-                        }
-                    }
-                    for (host, chunks) in hosts {
-                        let client = NetworkContext::get_instance()
-                            .get_clients()
-                            .get(host as usize)
-                            .unwrap();
-                        let pre_create = PreCreateData {
-                            path: path.as_str(),
-                            chunks,
-                        };
-                        NetworkService::post::<PreCreateData>(client, pre_create, PreCreate)
-                            .unwrap();
-                    }
-                };
-                ctx.spawn(f);
+                if let Ok(update_data) = try_deserialize::<UpdateMetadentryData>(&req.data) {
+                    spawn_precreate(
+                        &ctx,
+                        update_data.path.to_string(),
+                        update_data.size + update_data.offset as u64,
+                    );
+                }
             }
         }
+        // staging reads and writes host files with the rights of the server, only servers may ask.
+        // they come from an address in the host file and carry the secret of the servers
+        let allowed = !matches!(
+            i2option(req.option),
+            PostOption::Stage | PostOption::StageChunks
+        ) || (NetworkContext::get_instance().is_server_peer(&ctx.peer())
+            && has_server_token(&ctx, StorageContext::get_instance().get_server_token()));
         let f = async move {
-            let handle_result = handle_request(&req);
+            let handle_result = if allowed {
+                handle_request(&req)
+            } else {
+                post_result(EPERM, vec![0; 0], vec![0; 0])
+            };
             sink.success(handle_result).await.unwrap();
         };
         ctx.spawn(f);
@@ -318,17 +530,30 @@ impl SfsHandle for ServerHandler {
     ) {
         let f = async move {
             let option = i2option(req.option);
-            match option {
-                GetDirents => {
-                    let data: DirentData = deserialize::<DirentData>(&req.data);
-                    let path = data.path;
-                    if StorageContext::get_instance().output() {
-                        println!("handling get dirents of '{}'....", path);
+            match (&option, try_deserialize::<DirentData>(&req.data)) {
+                (GetDirents, Ok(data)) => {
+                    let dirents = handle_get_dirents(
+                        &data.path.to_string(),
+                        &data.start_after.to_string(),
+                        data.limit,
+                        data.plus,
+                        data.client,
+                    )
+                    .await;
+                    for (entry, attr) in dirents {
+                        let extra = match attr {
+                            Some(attr) => serialize(attr),
+                            None => vec![0; 0],
+                        };
+                        sink.send((
+                            post_result(0, serialize(entry), extra),
+                            WriteFlags::default(),
+                        ))
+                        .await?;
                     }
-                    MetadataDB::get_instance().get_dirents(&path.to_string(), &mut sink).await;
                 }
                 _ => {
-                    println!("invalid option on 'handle_dirents': {:?}", option);
+                    println!("invalid request on 'handle_dirents': {:?}", option);
                     sink.send((
                         post_result(EINVAL, vec![0; 0], vec![0; 0]),
                         WriteFlags::default(),
@@ -345,6 +570,212 @@ impl SfsHandle for ServerHandler {
         .map(|_| ());
         ctx.spawn(f);
     }
+
+    fn handshake(
+        &mut self,
+        ctx: grpcio::RpcContext,
+        req: HandshakeRequest,
+        sink: grpcio::UnarySink<HandshakeResponse>,
+    ) {
+        let f = async move {
+            let local = local_handshake();
+            let client = HandshakeInfo {
+                protocol_version: req.protocol_version,
+                features: req.features,
+                chunk_size: req.chunk_size,
+                cluster_id: req.cluster_id,
+            };
+            let mut response = HandshakeResponse::default();
+            match client.check(&local) {
+                Ok(_) => response.set_status(status(0, String::new())),
+                Err(reason) => {
+                    println!("refusing incompatible client: {}", reason);
+                    response.set_status(status(EINVAL, reason));
+                }
+            }
+            response.set_protocol_version(local.protocol_version);
+            response.set_features(local.features);
+            response.set_chunk_size(local.chunk_size);
+            response.set_cluster_id(local.cluster_id);
+            sink.success(response).await.unwrap();
+        };
+        ctx.spawn(f);
+    }
+
+    fn stat(
+        &mut self,
+        ctx: grpcio::RpcContext,
+        req: StatRequest,
+        sink: grpcio::UnarySink<StatResponse>,
+    ) {
+        let f = async move {
+            let mut response = StatResponse::default();
+            match handle_stat(&req.path, req.client) {
+                Ok((md, lease)) => {
+                    response.set_status(status(0, String::new()));
+                    response.set_attr(attr_to_proto(&Metadata::deserialize(&md).to_attr()));
+                    response.set_lease_ms(lease);
+                    if let Some(data) = handle_stuffed_data(&req.path) {
+                        response.set_stuffed(true);
+                        response.set_data(data);
+                    }
+                    let placement = PlacementDB::get_instance().get(&req.path);
+                    if !placement.is_empty() {
+                        response.set_placement(serialize(&placement));
+                    }
+                }
+                Err(e) => response.set_status(status(e, format!("no metadata of '{}'", req.path))),
+            }
+            sink.success(response).await.unwrap();
+        };
+        ctx.spawn(f);
+    }
+
+    fn create(
+        &mut self,
+        ctx: grpcio::RpcContext,
+        req: CreateRequest,
+        sink: grpcio::UnarySink<CreateResponse>,
+    ) {
+        let f = async move {
+            let mut response = CreateResponse::default();
+            match handle_create(&req.path, req.mode, req.file_id, req.client) {
+                Ok(file_id) => {
+                    response.set_status(status(0, String::new()));
+                    response.set_file_id(file_id);
+                }
+                Err(e) => response.set_status(status(e, String::new())),
+            }
+            sink.success(response).await.unwrap();
+        };
+        ctx.spawn(f);
+    }
+
+    fn remove_metadata(
+        &mut self,
+        ctx: grpcio::RpcContext,
+        req: PathRequest,
+        sink: grpcio::UnarySink<StatusResponse>,
+    ) {
+        let f = async move {
+            let err = handle_remove_metadata(&req.path, req.client);
+            sink.success(status_response(err)).await.unwrap();
+        };
+        ctx.spawn(f);
+    }
+
+    fn update_size(
+        &mut self,
+        ctx: grpcio::RpcContext,
+        req: UpdateSizeRequest,
+        sink: grpcio::UnarySink<SizeResponse>,
+    ) {
+        if ENABLE_PRECREATE {
+            spawn_precreate(&ctx, req.path.clone(), req.size + req.offset as u64);
+        }
+        let f = async move {
+            let update_res = handle_update_size(
+                &req.path,
+                req.size,
+                req.offset,
+                req.append,
+                req.client,
+                req.get_data(),
+            );
+            let mut response = size_response(update_res.map(|(end, _)| end as i64));
+            response.set_stuffed(update_res.map(|(_, stuffed)| stuffed).unwrap_or(false));
+            // writers keep the placement current without a stat
+            if update_res.is_ok() {
+                let placement = PlacementDB::get_instance().get(&req.path);
+                if !placement.is_empty() {
+                    response.set_placement(serialize(&placement));
+                }
+            }
+            sink.success(response).await.unwrap();
+        };
+        ctx.spawn(f);
+    }
+
+    fn decrease_size(
+        &mut self,
+        ctx: grpcio::RpcContext,
+        req: DecreaseSizeRequest,
+        sink: grpcio::UnarySink<StatusResponse>,
+    ) {
+        let f = async move {
+            let err = handle_decrease_size(&req.path, req.new_size, req.client);
+            sink.success(status_response(err)).await.unwrap();
+        };
+        ctx.spawn(f);
+    }
+
+    fn get_size(
+        &mut self,
+        ctx: grpcio::RpcContext,
+        req: PathRequest,
+        sink: grpcio::UnarySink<SizeResponse>,
+    ) {
+        let f = async move {
+            let size_res = handle_get_size(&req.path);
+            sink.success(size_response(size_res)).await.unwrap();
+        };
+        ctx.spawn(f);
+    }
+
+    fn add_dirent(
+        &mut self,
+        ctx: grpcio::RpcContext,
+        req: DirentRequest,
+        sink: grpcio::UnarySink<StatusResponse>,
+    ) {
+        let f = async move {
+            let err = handle_add_dirent(&req.dir, &req.name, req.is_dir);
+            sink.success(status_response(err)).await.unwrap();
+        };
+        ctx.spawn(f);
+    }
+
+    fn remove_dirent(
+        &mut self,
+        ctx: grpcio::RpcContext,
+        req: DirentRequest,
+        sink: grpcio::UnarySink<StatusResponse>,
+    ) {
+        let f = async move {
+            let err = handle_remove_dirent(&req.dir, &req.name);
+            sink.success(status_response(err)).await.unwrap();
+        };
+        ctx.spawn(f);
+    }
+
+    fn get_dirents(
+        &mut self,
+        ctx: grpcio::RpcContext,
+        req: GetDirentsRequest,
+        mut sink: grpcio::ServerStreamingSink<Dirent>,
+    ) {
+        let f = async move {
+            let dirents =
+                handle_get_dirents(&req.path, &req.start_after, req.limit, req.plus, req.client).await;
+            for ((name, is_dir), attr) in dirents {
+                let mut dirent = Dirent::default();
+                dirent.set_status(status(0, String::new()));
+                dirent.set_name(name);
+                dirent.set_is_dir(is_dir);
+                if let Some(attr) = attr {
+                    dirent.set_attr(attr_to_proto(&attr));
+                }
+                sink.send((dirent, WriteFlags::default())).await?;
+            }
+            sink.close().await?;
+            Ok(())
+        }
+        .map_err(|e: grpcio::Error| {
+            println!("server::get_dirents failed to handle stream: {:?}", e);
+        })
+        .map(|_| ());
+        ctx.spawn(f);
+    }
 }
 async fn init_server(addr: &String) -> Result<(), Error> {
     let server_addr: (Ipv4Addr, u16) = (addr.parse().unwrap(), 8082);
@@ -352,17 +783,52 @@ async fn init_server(addr: &String) -> Result<(), Error> {
     let env = Arc::new(Environment::new(16));
     let instance = ServerHandler {};
     let service = create_sfs_handle(instance);
-    let mut server = ServerBuilder::new(env)
-        .register_service(service)
-        .bind(addr, 8082)
-        .build()
-        .unwrap();
+    let mut builder = ServerBuilder::new(env).register_service(service);
+    builder = match StorageContext::get_instance().get_tls() {
+        Some(tls) => builder.bind_with_cred(addr, 8082, tls.server_credentials()),
+        None => builder.bind(addr, 8082),
+    };
+    if StorageContext::get_instance().get_token().len() != 0 {
+        builder = builder.add_checker(TokenChecker::new(
+            StorageContext::get_instance().get_token().clone(),
+        ));
+    }
+    let mut server = builder.build().unwrap();
     server.start();
     NetworkContext::get_instance();
+    // files created before directory entries were kept show up in listings again
+    if DirentDB::get_instance().needs_backfill() {
+        thread::spawn(backfill_dirents);
+    }
+    if StorageContext::get_instance().get_repair() {
+        let local = NetworkContext::get_instance().get_local_host_id();
+        thread::spawn(move || {
+            for (host_id, client) in NetworkContext::get_instance().get_clients().iter().enumerate() {
+                if host_id as u64 == local {
+                    continue;
+                }
+                // peers that are not up yet simply miss this round
+                if let Err(e) = NetworkService::post::<RepairData>(
+                    client,
+                    RepairData { host_id: local },
+                    Repair,
+                ) {
+                    println!("fail to request repair from host {}: {:?}", host_id, e);
+                }
+            }
+        });
+    }
+
+    let scrub_interval_s = StorageContext::get_instance().get_scrub_interval_s();
+    if scrub_interval_s != 0 {
+        thread::spawn(move || scrubber(scrub_interval_s));
+    }
+    thread::spawn(move || heartbeat(HEARTBEAT_INTERVAL_S));
+    thread::spawn(move || block_reporter(BLOCK_REPORT_INTERVAL_S));
 
     let (tx, rx) = oneshot::channel();
     let addr_clone = addr.clone();
-    thread::spawn(move || recver_server(&addr_clone, ChunkOp{ op: ChunkStorage::write_chunk}, 10));
+    thread::spawn(move || recver_server(&addr_clone, ChunkOp{ op: ChunkStorage::write_client_chunk}, 10));
     let addr_clone = addr.clone();
     thread::spawn(move || sender_server(&addr_clone, ChunkOp{ op: ChunkStorage::read_chunk}, 10));
     thread::spawn(move || {
@@ -399,7 +865,9 @@ fn populates_host_file() -> Option<Error> {
 async fn init_environment() -> Result<(), Error> {
     ChunkStorage::get_instance();
     MetadataDB::get_instance();
+    DirentDB::get_instance();
     StuffDB::get_instance();
+    PlacementDB::get_instance();
 
     let mut root_md = Metadata::new();
     root_md.set_mode(S_IFDIR | S_IRWXU | S_IRWXG | S_IRWXO);
@@ -416,8 +884,392 @@ async fn init_environment() -> Result<(), Error> {
 //fn destroy_environment() {}
 #[tokio::main]
 pub async fn main() -> Result<(), Error> {
+    // sfs_server migrate-chunks <chunk directory> <metadata database>...
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() > 2 && args[1] == "migrate-chunks" {
+        let left = migrate_chunks(&args[2], &args[3..].to_vec());
+        std::process::exit(if left == 0 { 0 } else { 1 });
+    }
+    // sfs_server stage-in <host directory> <SimpleFS directory>
+    // sfs_server stage-out <SimpleFS directory> <host directory>
+    if args.len() > 3 && (args[1] == "stage-in" || args[1] == "stage-out") {
+        let err = if args[1] == "stage-in" {
+            stage(true, &args[2], &args[3])
+        } else {
+            stage(false, &args[3], &args[2])
+        };
+        std::process::exit(if err == 0 { 0 } else { 1 });
+    }
     StorageContext::get_instance();
 
     init_environment().await?;
     Ok(())
 }
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use libc::{EIO, ENOSPC};
+    use sfs_global::global::network::forward_data::ChunkStat;
+
+    use crate::server::stage::resolve_host_path;
+    use crate::server::storage::data::backend::ChunkBackend;
+    use crate::server::storage::data::chunk_storage::ChunkStorage;
+    use crate::server::storage::data::cipher::{Cipher, CIPHER_BLOCK_SIZE, NONCE_SIZE};
+    use crate::server::storage::data::dedup_backend::DedupBackend;
+    use crate::server::storage::data::memory_backend::MemoryBackend;
+    use crate::server::storage::data::nonce_db::NonceDB;
+    use crate::server::storage::data::stuff_db::StuffDB;
+    use crate::server::storage::data::tiered_backend::TieredBackend;
+
+    // four cipher blocks
+    const CHUNK: u64 = 16384;
+    const FILE_ID: u64 = 1 << 40;
+
+    fn no_report(_path: &String, _chunk_id: u64, _blocks: i64) {}
+    // memory chunks the test keeps a hold of, to look at what the layers above stored
+    struct Shared(Arc<MemoryBackend>);
+    impl ChunkBackend for Shared {
+        fn write(
+            &self,
+            path: &String,
+            file_id: u64,
+            chunk_id: u64,
+            buf: &[u8],
+            offset: u64,
+        ) -> Result<i64, i32> {
+            self.0.write(path, file_id, chunk_id, buf, offset)
+        }
+        fn read(
+            &self,
+            path: &String,
+            file_id: u64,
+            chunk_id: u64,
+            buf: &mut [u8],
+            offset: u64,
+        ) -> Result<i64, i32> {
+            self.0.read(path, file_id, chunk_id, buf, offset)
+        }
+        fn exists(&self, path: &String, file_id: u64, chunk_id: u64) -> bool {
+            self.0.exists(path, file_id, chunk_id)
+        }
+        fn truncate(&self, path: &String, file_id: u64, chunk_id: u64, length: u64) {
+            self.0.truncate(path, file_id, chunk_id, length)
+        }
+        fn trim(&self, path: &String, file_id: u64, chunk_start: u64) {
+            self.0.trim(path, file_id, chunk_start)
+        }
+        fn remove(&self, path: &String, file_id: u64, chunk_id: u64) {
+            self.0.remove(path, file_id, chunk_id)
+        }
+        fn destroy(&self, path: &String, file_id: u64) {
+            self.0.destroy(path, file_id)
+        }
+        fn stat(&self) -> ChunkStat {
+            self.0.stat()
+        }
+        fn sync(&self, path: &String, file_id: u64) -> i32 {
+            self.0.sync(path, file_id)
+        }
+    }
+    // an empty directory of the test for a database
+    fn db_path(name: &str) -> String {
+        let path = std::env::temp_dir()
+            .join(format!("sfs-{}-{}", name, std::process::id()))
+            .to_string_lossy()
+            .into_owned();
+        let _ = std::fs::remove_dir_all(&path);
+        path
+    }
+    // chunks in memory below the given layers, the databases in a directory of the test. with
+    // the memory the chunks land in, the fast tier if tiered, and the slow tier
+    fn storage(
+        name: &str,
+        compress: bool,
+        encrypt: bool,
+        dedup: bool,
+        tiered: bool,
+    ) -> (ChunkStorage, Arc<MemoryBackend>, Arc<MemoryBackend>) {
+        let root = std::env::temp_dir()
+            .join(format!("sfs-chunks-{}-{}", name, std::process::id()))
+            .to_string_lossy()
+            .into_owned();
+        for dir in [
+            ChunkStorage::get_checksum_path(&root),
+            ChunkStorage::get_size_path(&root),
+            ChunkStorage::get_nonce_path(&root),
+            ChunkStorage::get_dedup_path(&root),
+            ChunkStorage::get_tier_path(&root),
+        ] {
+            let _ = std::fs::remove_dir_all(dir);
+        }
+        let _ = std::fs::remove_file(ChunkStorage::get_compressed_dirs_path(&root));
+        let slow = Arc::new(MemoryBackend::new(64 * CHUNK, CHUNK));
+        let (memory, backend): (_, Box<dyn ChunkBackend>) = if tiered {
+            let fast = Arc::new(MemoryBackend::new(2 * CHUNK, CHUNK));
+            let tiers = TieredBackend::new(
+                &ChunkStorage::get_tier_path(&root),
+                Box::new(Shared(fast.clone())),
+                Box::new(Shared(slow.clone())),
+                CHUNK,
+            );
+            (fast, Box::new(tiers.unwrap()))
+        } else {
+            let memory = Arc::new(MemoryBackend::new(64 * CHUNK, CHUNK));
+            (memory.clone(), Box::new(Shared(memory)))
+        };
+        let backend: Box<dyn ChunkBackend> = if dedup {
+            let dedup_path = ChunkStorage::get_dedup_path(&root);
+            Box::new(DedupBackend::new(&dedup_path, backend, CHUNK).unwrap())
+        } else {
+            backend
+        };
+        let compress_dirs = if compress { vec!["/data".to_string()] } else { Vec::new() };
+        let cipher = if encrypt { Some(Cipher::new([7; 32])) } else { None };
+        let storage =
+            ChunkStorage::with_backend(&root, CHUNK, backend, true, compress_dirs, cipher, no_report)
+                .unwrap();
+        (storage, memory, slow)
+    }
+    fn read_back(storage: &ChunkStorage, path: &String, file_id: u64, chunk_id: u64) -> Vec<u8> {
+        let mut buf = vec![0u8; CHUNK as usize];
+        let len = storage.read(path, file_id, chunk_id, &mut buf, 0).unwrap();
+        buf.truncate(len as usize);
+        buf
+    }
+    // a chunk as the memory below the layers holds it
+    fn raw_chunk(memory: &MemoryBackend, path: &String, chunk_id: u64) -> Vec<u8> {
+        let mut buf = vec![0u8; 2 * CHUNK as usize];
+        let len = memory.read(path, FILE_ID, chunk_id, &mut buf, 0).unwrap();
+        buf.truncate(len as usize);
+        buf
+    }
+    fn exercise(storage: &ChunkStorage) {
+        let path = "/data/file".to_string();
+        let data: Vec<u8> = (0..CHUNK).map(|i| (i % 251) as u8).collect();
+        assert_eq!(storage.write(&path, FILE_ID, 0, &data, 0), Ok(CHUNK as i64));
+        // in the middle of the second cipher block
+        assert_eq!(storage.write(&path, FILE_ID, 0, b"hello", 5000), Ok(5));
+        let mut expected = data.clone();
+        expected[5000..5005].copy_from_slice(b"hello");
+        assert_eq!(read_back(storage, &path, FILE_ID, 0), expected);
+        let mut buf = vec![0u8; 100];
+        assert_eq!(storage.read(&path, FILE_ID, 0, &mut buf, 4990), Ok(100));
+        assert_eq!(buf, expected[4990..5090]);
+
+        assert_eq!(storage.write(&path, FILE_ID, 1, &data[..9000], 0), Ok(9000));
+        storage.truncate(&path, FILE_ID, 1, 6000);
+        assert_eq!(read_back(storage, &path, FILE_ID, 1), data[..6000]);
+        assert_eq!(storage.write(&path, FILE_ID, 2, &data[..100], 0), Ok(100));
+        // past the end of the chunk, the gap reads as zeros
+        assert_eq!(storage.write(&path, FILE_ID, 2, b"tail", 9000), Ok(4));
+        let mut grown = data[..100].to_vec();
+        grown.resize(9000, 0);
+        grown.extend_from_slice(b"tail");
+        assert_eq!(read_back(storage, &path, FILE_ID, 2), grown);
+        assert_eq!(storage.read(&path, FILE_ID, 3, &mut buf, 0), Err(-2));
+
+        storage.trim(&path, FILE_ID, 1);
+        assert_eq!(storage.read(&path, FILE_ID, 1, &mut buf, 0), Err(-2));
+        assert_eq!(storage.read(&path, FILE_ID, 2, &mut buf, 0), Err(-2));
+        assert_eq!(read_back(storage, &path, FILE_ID, 0), expected);
+
+        storage.destroy(&path, FILE_ID);
+        assert_eq!(storage.read(&path, FILE_ID, 0, &mut buf, 0), Err(-2));
+    }
+    #[test]
+    fn plain_chunks() {
+        exercise(&storage("plain", false, false, false, false).0);
+    }
+    #[test]
+    fn compressed_chunks() {
+        let (storage, memory, _) = storage("compressed", true, false, false, false);
+        exercise(&storage);
+        let path = "/data/file".to_string();
+        let data: Vec<u8> = (0..CHUNK).map(|i| (i % 251) as u8).collect();
+        assert_eq!(storage.write(&path, FILE_ID, 0, &data, 0), Ok(CHUNK as i64));
+        let raw = raw_chunk(&memory, &path, 0);
+        assert!(raw.len() < data.len() / 2);
+        assert_ne!(raw[..], data[..raw.len()]);
+        assert_eq!(read_back(&storage, &path, FILE_ID, 0), data);
+        // outside "compress_dirs" chunks are kept as they are written
+        let other = "/other/file".to_string();
+        assert_eq!(storage.write(&other, FILE_ID, 0, &data, 0), Ok(CHUNK as i64));
+        assert_eq!(raw_chunk(&memory, &other, 0), data);
+    }
+    #[test]
+    fn encrypted_chunks() {
+        let (storage, memory, _) = storage("encrypted", false, true, false, false);
+        exercise(&storage);
+        let path = "/data/file".to_string();
+        let data = vec![b'a'; CHUNK as usize];
+        assert_eq!(storage.write(&path, FILE_ID, 0, &data, 0), Ok(CHUNK as i64));
+        // not a single cipher block of the data is stored in the clear
+        let raw = raw_chunk(&memory, &path, 0);
+        assert!(!raw.is_empty());
+        assert!(!raw.windows(16).any(|window| window == &data[..16]));
+        assert_eq!(read_back(&storage, &path, FILE_ID, 0), data);
+    }
+    #[test]
+    fn deduplicated_chunks() {
+        let (storage, memory, _) = storage("dedup", false, false, true, false);
+        exercise(&storage);
+        let data: Vec<u8> = (0..CHUNK).map(|i| (i % 251) as u8).collect();
+        let (a, b) = ("/data/a".to_string(), "/data/b".to_string());
+        let free = memory.stat().chunk_free;
+        assert_eq!(storage.write(&a, FILE_ID, 0, &data, 0), Ok(CHUNK as i64));
+        assert_eq!(storage.write(&b, FILE_ID + 1, 3, &data, 0), Ok(CHUNK as i64));
+        // both chunks point at one content kept under its digest
+        assert_eq!(memory.stat().chunk_free, free - 1);
+        assert!(!memory.exists(&a, FILE_ID, 0));
+        storage.destroy(&a, FILE_ID);
+        assert_eq!(read_back(&storage, &b, FILE_ID + 1, 3), data);
+        storage.destroy(&b, FILE_ID + 1);
+        assert_eq!(memory.stat().chunk_free, free);
+    }
+    #[test]
+    fn dedup_keeps_chunks_from_before() {
+        let inner = MemoryBackend::new(64 * CHUNK, CHUNK);
+        let path = "/data/file".to_string();
+        assert_eq!(inner.write(&path, FILE_ID, 0, b"before", 0), Ok(6));
+        let dedup = DedupBackend::new(&db_path("dedup-before"), Box::new(inner), CHUNK).unwrap();
+        let mut buf = vec![0u8; 16];
+        assert_eq!(dedup.read(&path, FILE_ID, 0, &mut buf, 0), Ok(6));
+        assert_eq!(buf[..6], b"before"[..]);
+        // moved under its digest by the next write
+        assert_eq!(dedup.write(&path, FILE_ID, 0, b"after", 6), Ok(5));
+        assert_eq!(dedup.read(&path, FILE_ID, 0, &mut buf, 0), Ok(11));
+        assert_eq!(buf[..11], b"beforeafter"[..]);
+        dedup.destroy(&path, FILE_ID);
+        assert_eq!(dedup.read(&path, FILE_ID, 0, &mut buf, 0), Err(-2));
+    }
+    #[test]
+    fn tiered_chunks() {
+        let (storage, fast, slow) = storage("tiered", false, false, false, true);
+        exercise(&storage);
+        let path = "/data/tiered".to_string();
+        let data: Vec<u8> = (0..CHUNK).map(|i| (i % 251) as u8).collect();
+        for chunk_id in 0..3 {
+            assert_eq!(storage.write(&path, FILE_ID, chunk_id, &data, 0), Ok(CHUNK as i64));
+        }
+        // the fast tier holds two chunks, the rest spilled to the slow one
+        assert!((0..3).any(|chunk_id| slow.exists(&path, FILE_ID, chunk_id)));
+        assert!((0..3).any(|chunk_id| !fast.exists(&path, FILE_ID, chunk_id)));
+        for chunk_id in 0..3 {
+            assert_eq!(read_back(&storage, &path, FILE_ID, chunk_id), data);
+        }
+    }
+    #[test]
+    fn every_layer_stacked() {
+        exercise(&storage("stacked", true, true, true, true).0);
+    }
+    #[test]
+    fn encrypted_write_within_a_block() {
+        let storage = storage("within", false, true, false, false);
+        let path = "/data/file".to_string();
+        let data: Vec<u8> = (0..100).collect();
+        assert_eq!(storage.write(&path, FILE_ID, 0, &data, 0), Ok(100));
+        assert_eq!(storage.write(&path, FILE_ID, 0, b"hello", 50), Ok(5));
+        storage.truncate(&path, FILE_ID, 0, 70);
+        let mut expected = data[..70].to_vec();
+        expected[50..55].copy_from_slice(b"hello");
+        assert_eq!(read_back(&storage, &path, FILE_ID, 0), expected);
+    }
+    #[test]
+    fn cipher_blocks_round_trip() {
+        let cipher = Cipher::new([7; 32]);
+        let plain: Vec<u8> = (0..3 * CIPHER_BLOCK_SIZE + 100).map(|i| (i % 251) as u8).collect();
+        let mut data = plain.clone();
+        let mut nonces = Vec::new();
+        cipher.encrypt_blocks(&mut data, 0, &mut nonces);
+        assert_eq!(nonces.len(), 4 * NONCE_SIZE);
+        assert_ne!(data, plain);
+        // the middle of the second block, re-encrypted under a fresh nonce
+        let mut expected = plain.clone();
+        expected[CIPHER_BLOCK_SIZE + 10..CIPHER_BLOCK_SIZE + 15].copy_from_slice(b"hello");
+        let mut block = expected[CIPHER_BLOCK_SIZE..2 * CIPHER_BLOCK_SIZE].to_vec();
+        cipher.encrypt_blocks(&mut block, 1, &mut nonces);
+        data[CIPHER_BLOCK_SIZE..2 * CIPHER_BLOCK_SIZE].copy_from_slice(&block);
+        // cut within the last block
+        data.truncate(3 * CIPHER_BLOCK_SIZE + 50);
+        expected.truncate(3 * CIPHER_BLOCK_SIZE + 50);
+        assert_eq!(cipher.decrypt_blocks(&mut data, &nonces), Ok(()));
+        assert_eq!(data, expected);
+
+        let mut sealed = cipher.seal(b"stuffed");
+        assert_eq!(cipher.open(&sealed), Some(b"stuffed".to_vec()));
+        *sealed.last_mut().unwrap() ^= 1;
+        assert_eq!(cipher.open(&sealed), None);
+    }
+    #[test]
+    fn nonces_are_kept_per_chunk() {
+        let nonces = NonceDB::new(&db_path("nonces"), "nonces").unwrap();
+        let path = "/data/file".to_string();
+        assert_eq!(nonces.put(&path, 0, &vec![1; 2 * NONCE_SIZE]), Ok(()));
+        assert_eq!(nonces.put(&path, 2, &vec![2; NONCE_SIZE]), Ok(()));
+        assert_eq!(nonces.get(&path, 0), Some(vec![1; 2 * NONCE_SIZE]));
+        assert_eq!(nonces.chunk_ids(&path, 1), vec![2]);
+        nonces.remove(&path, 0);
+        assert_eq!(nonces.get(&path, 0), None);
+    }
+    #[test]
+    fn stuffed_values_plain_and_sealed() {
+        let path = db_path("stuff");
+        let (a, b) = ("/a".to_string(), "/b".to_string());
+        {
+            let plain = StuffDB::new(&path, None).unwrap();
+            assert_eq!(plain.write(&a, 0, 5, b"plain"), 0);
+        }
+        {
+            // values written without a key stay readable, rewritten ones are sealed
+            let sealed = StuffDB::new(&path, Some(Cipher::new([7; 32]))).unwrap();
+            assert_eq!(sealed.get(&a), Some(b"plain".to_vec()));
+            assert_eq!(sealed.write(&b, 0, 6, b"sealed"), 0);
+            assert_eq!(sealed.get(&b), Some(b"sealed".to_vec()));
+            assert_ne!(sealed.db.get(&b).unwrap().unwrap().to_vec(), b"sealed".to_vec());
+            assert_eq!(sealed.write(&a, 5, 4, b"text"), 0);
+            assert_eq!(sealed.get(&a), Some(b"plaintext".to_vec()));
+            assert_eq!(sealed.truncate(&a, 5), 0);
+            assert_eq!(sealed.get(&a), Some(b"plain".to_vec()));
+        }
+        {
+            // under another key sealed values are neither read nor replaced
+            let other = StuffDB::new(&path, Some(Cipher::new([8; 32]))).unwrap();
+            assert_eq!(other.get(&b), None);
+            assert_eq!(other.write(&b, 0, 5, b"other"), EIO);
+        }
+        let sealed = StuffDB::new(&path, Some(Cipher::new([7; 32]))).unwrap();
+        assert_eq!(sealed.get(&b), Some(b"sealed".to_vec()));
+    }
+    #[test]
+    fn memory_backend_keeps_to_its_capacity() {
+        let backend = MemoryBackend::new(CHUNK + CHUNK / 2, CHUNK);
+        let path = "/data/file".to_string();
+        let mut buf = vec![0u8; CHUNK as usize];
+        assert_eq!(backend.write(&path, FILE_ID, 0, &[1u8; CHUNK as usize], 0), Ok(CHUNK as i64));
+        assert_eq!(backend.write(&path, FILE_ID, 1, &[1u8; 100], 0), Ok(100));
+        // no room to grow, the chunk stays as it is
+        backend.truncate(&path, FILE_ID, 1, CHUNK);
+        assert_eq!(backend.read(&path, FILE_ID, 1, &mut buf, 0), Ok(100));
+        backend.truncate(&path, FILE_ID, 1, CHUNK / 2);
+        assert_eq!(backend.read(&path, FILE_ID, 1, &mut buf, 0), Ok((CHUNK / 2) as i64));
+        assert_eq!(backend.write(&path, FILE_ID, 2, &[1u8; 10], 0), Err(ENOSPC));
+        backend.truncate(&path, FILE_ID, 0, 0);
+        assert_eq!(backend.write(&path, FILE_ID, 2, &[1u8; 10], 0), Ok(10));
+        assert_eq!(backend.stat().chunk_free, 0);
+    }
+    #[test]
+    fn host_paths_resolve_past_what_exists() {
+        let dir = db_path("stage");
+        std::fs::create_dir_all(format!("{}/real", dir)).unwrap();
+        std::os::unix::fs::symlink(format!("{}/real", dir), format!("{}/link", dir)).unwrap();
+        let real = std::fs::canonicalize(format!("{}/real", dir)).unwrap();
+        assert_eq!(
+            resolve_host_path(&format!("{}/link/new/file", dir)),
+            Some(real.join("new/file"))
+        );
+        assert_eq!(resolve_host_path(&format!("{}/link/../real", dir)), None);
+        assert_eq!(resolve_host_path(&format!("{}/new/../real", dir)), None);
+        assert_eq!(resolve_host_path("relative/dir"), None);
+    }
+}
@@ -1,2 +1,34 @@
 pub mod read_server;
 pub mod write_server;
+
+use rdma_sys::{rdma_cm_event, rdma_cm_id, rdma_destroy_id, rdma_reject};
+use sfs_global::global::util::checksum_util::{token_digest, TOKEN_DIGEST_SIZE};
+use sfs_rpc::security::token_eq;
+use std::ptr::null;
+
+use crate::error_msg::error_msg;
+use crate::server::filesystem::storage_context::StorageContext;
+
+// whether a connection request carries the digest of the token of this server, any request
+// does without a token. the private data is gone once the event is acknowledged
+pub unsafe fn authenticated(cm_event: *mut rdma_cm_event) -> bool {
+    let token = StorageContext::get_instance().get_token();
+    if token.len() == 0 {
+        return true;
+    }
+    let conn = (*cm_event).param.conn;
+    if conn.private_data.is_null() || (conn.private_data_len as usize) < TOKEN_DIGEST_SIZE {
+        return false;
+    }
+    let digest = std::slice::from_raw_parts(conn.private_data as *const u8, TOKEN_DIGEST_SIZE);
+    token_eq(digest, &token_digest(token))
+}
+// turns a connection request away before anything is set up for it
+pub unsafe fn reject(cm_id: *mut rdma_cm_id) {
+    error_msg(
+        "server::rdma::reject".to_string(),
+        "connection request without the token of the job".to_string(),
+    );
+    rdma_reject(cm_id, null(), 0);
+    rdma_destroy_id(cm_id);
+}
@@ -8,7 +8,7 @@ use rdma_sys::{
     ibv_qp_type::IBV_QPT_RC,
     ibv_recv_wr, ibv_reg_mr, ibv_req_notify_cq, ibv_send_flags, ibv_send_wr, ibv_sge, ibv_wc,
     ibv_wc_opcode::{IBV_WC_RECV, IBV_WC_RECV_RDMA_WITH_IMM},
-    ibv_wr_opcode::{IBV_WR_RDMA_WRITE_WITH_IMM, IBV_WR_SEND},
+    ibv_wr_opcode::{IBV_WR_RDMA_WRITE, IBV_WR_RDMA_WRITE_WITH_IMM, IBV_WR_SEND},
     imm_data_invalidated_rkey_union_t, rdma_accept, rdma_ack_cm_event, rdma_bind_addr,
     rdma_cm_event,
     rdma_cm_event_type::{
@@ -20,10 +20,11 @@ use rdma_sys::{
     rdma_port_space::RDMA_PS_TCP,
 };
 use sfs_global::global::network::config::CHUNK_SIZE;
+use sfs_global::global::util::checksum_util::{chunk_checksum, CHECKSUM_SIZE};
 use sfs_rdma::{
     chunk_operation::ChunkInfo,
     rdma::RDMAContext,
-    transfer::{ChunkMetadata, MessageType, TransferMetadata},
+    transfer::{ChunkMetadata, MessageType, TransferMetadata, READ_FAILED, READ_MISSING},
     RDMA_READ_PORT,
 };
 
@@ -32,6 +33,7 @@ use sfs_rdma::{
     CQ_CAPACITY, MAX_SGE, MAX_WR,
 };
 
+use super::{authenticated, reject};
 use crate::server::filesystem::storage_context::StorageContext;
 
 struct SenderServerContext {
@@ -43,6 +45,9 @@ struct SenderServerContext {
 
     pub metadata: ChunkMetadata,
     pub s_ctx: *mut RDMAContext,
+
+    // the client of the current transfer wants the checksum of every piece
+    pub checksum: bool,
 }
 pub(crate) fn sender_server(addr: &String, op: ChunkOp, _nthreads: u32) {
     unsafe {
@@ -94,7 +99,12 @@ pub(crate) fn sender_server(addr: &String, op: ChunkOp, _nthreads: u32) {
                 RDMA_CM_EVENT_CONNECT_REQUEST => {
                     let cm_id = (*cm_event).id;
                     //println!("connecting: {}", cm_id as u64);
+                    let accepted = authenticated(cm_event);
                     rdma_ack_cm_event(cm_event);
+                    if !accepted {
+                        reject(cm_id);
+                        continue;
+                    }
 
                     let mut s_ctx: *mut RDMAContext = RDMAContext::new_ptr();
                     // prepare RDMA resource
@@ -139,8 +149,11 @@ pub(crate) fn sender_server(addr: &String, op: ChunkOp, _nthreads: u32) {
                     (*cm_id).context = ctx.cast();
                     (*ctx).s_ctx = s_ctx;
 
-                    let rdma_buffer_size =
-                        usize::max(CHUNK_SIZE as usize, std::mem::size_of::<TransferMetadata>());
+                    // the checksum of a piece is put behind the largest piece
+                    let rdma_buffer_size = usize::max(
+                        CHUNK_SIZE as usize + CHECKSUM_SIZE,
+                        std::mem::size_of::<TransferMetadata>(),
+                    );
                     (*ctx).buffer = calloc(1, rdma_buffer_size).cast();
                     (*ctx).buffer_mr = ibv_reg_mr(
                         pd,
@@ -245,6 +258,7 @@ fn on_completion(wc: *mut ibv_wc, _pd: *mut ibv_pd, op: &ChunkOp) -> Result<i64,
             (*ctx).metadata.chunk_start = transfer_md.chunk_start;
             (*ctx).metadata.offset = transfer_md.offset;
             (*ctx).metadata.size = transfer_md.size;
+            (*ctx).checksum = transfer_md.checksum;
 
             (*(*ctx).msg).mtype = MessageType::MSG_READY;
             send_message(id);
@@ -259,36 +273,64 @@ fn on_completion(wc: *mut ibv_wc, _pd: *mut ibv_pd, op: &ChunkOp) -> Result<i64,
                 chunk_id: (*(*ctx).msg).data,
                 metadata: (*ctx).metadata.clone(),
                 data: (*ctx).buffer,
+                checksum: None,
             };
             // read chunk
             let res = op.submit(info);
 
-            let len = if let Err(_e) = res {0} else {res.unwrap()};
+            let len = match res {
+                Ok(len) => len as u32,
+                // never written here, a hole or a chunk placed elsewhere
+                Err(-2) => READ_MISSING,
+                Err(_e) => READ_FAILED,
+            };
             // send to receiver client
             let mut wr: ibv_send_wr = std::mem::zeroed();
             wr.wr_id = id as u64;
             wr.opcode = IBV_WR_RDMA_WRITE_WITH_IMM;
             wr.send_flags = ibv_send_flags::IBV_SEND_SIGNALED.0;
             wr.imm_data_invalidated_rkey_union = imm_data_invalidated_rkey_union_t {
-                imm_data: (len as u32).to_be(),
+                imm_data: len.to_be(),
             };
             wr.wr.rdma.remote_addr = (*(*ctx).msg).addr;
             wr.wr.rdma.rkey = (*(*ctx).msg).rkey;
 
             let mut sge = ibv_sge {
                 addr: (*ctx).buffer as u64,
-                length: len as u32,
+                length: len,
                 lkey: (*(*ctx).buffer_mr).lkey,
             };
-            if len > 0 {
+            if len > 0 && len < READ_MISSING {
                 wr.sg_list = (&mut sge) as *mut ibv_sge;
                 wr.num_sge = 1;
             }
+            // the checksum goes ahead of the piece, it has landed once the client sees the piece
+            let mut checksum_wr: ibv_send_wr = std::mem::zeroed();
+            let checksum_buf = (*ctx).buffer.add(CHUNK_SIZE as usize);
+            let mut checksum_sge = ibv_sge {
+                addr: checksum_buf as u64,
+                length: CHECKSUM_SIZE as u32,
+                lkey: (*(*ctx).buffer_mr).lkey,
+            };
+            let first_wr = if (*ctx).checksum && len < READ_MISSING {
+                let piece = std::slice::from_raw_parts((*ctx).buffer, len as usize);
+                (checksum_buf as *mut u64).write_unaligned(chunk_checksum(piece));
+                checksum_wr.wr_id = id as u64;
+                checksum_wr.opcode = IBV_WR_RDMA_WRITE;
+                checksum_wr.sg_list = (&mut checksum_sge) as *mut ibv_sge;
+                checksum_wr.num_sge = 1;
+                checksum_wr.wr.rdma.remote_addr = (*(*ctx).msg).checksum_addr;
+                checksum_wr.wr.rdma.rkey = (*(*ctx).msg).checksum_rkey;
+                checksum_wr.next = &mut wr;
+                &mut checksum_wr
+            } else {
+                &mut wr
+            };
             let mut bad_wr: *mut ibv_send_wr = null_mut();
             post_receive_msg(id);
             ibv_post_send(
                 (*id).qp,
-                (&mut wr) as *mut ibv_send_wr,
+                first_wr as *mut ibv_send_wr,
                 (&mut bad_wr) as *mut *mut ibv_send_wr,
             );
         }
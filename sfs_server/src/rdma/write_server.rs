@@ -19,8 +19,9 @@ use rdma_sys::{
     rdma_port_space::RDMA_PS_TCP,
 };
 use sfs_global::global::network::config::CHUNK_SIZE;
+use sfs_global::global::util::checksum_util::CHECKSUM_SIZE;
 use sfs_rdma::{
-    chunk_operation::ChunkInfo,
+    chunk_operation::{chunk_piece, ChunkInfo},
     rdma::RDMAContext,
     transfer::{ChunkMetadata, TransferMetadata},
     RDMA_WRITE_PORT,
@@ -35,6 +36,7 @@ use sfs_rdma::{
     CQ_CAPACITY, MAX_SGE, MAX_WR,
 };
 
+use super::{authenticated, reject};
 use crate::server::filesystem::storage_context::StorageContext;
 
 struct ReceiverServerContext {
@@ -48,6 +50,10 @@ struct ReceiverServerContext {
     pub s_ctx: *mut RDMAContext,
 
     pub data_receive: u64,
+    // pieces of the current transfer are followed by their checksum
+    pub checksum: bool,
+    // first error of the current transfer, reported instead of the bytes received
+    pub error: i32,
 }
 pub(crate) fn recver_server(addr: &String, op: ChunkOp, _nthreads: usize) {
     unsafe {
@@ -98,7 +104,12 @@ pub(crate) fn recver_server(addr: &String, op: ChunkOp, _nthreads: usize) {
                 RDMA_CM_EVENT_CONNECT_REQUEST => {
                     let cm_id = (*cm_event).id;
                     //println!("connecting: {}", cm_id as u64);
+                    let accepted = authenticated(cm_event);
                     rdma_ack_cm_event(cm_event);
+                    if !accepted {
+                        reject(cm_id);
+                        continue;
+                    }
 
                     let mut s_ctx: *mut RDMAContext = RDMAContext::new_ptr();
                     // prepare RDMA resource
@@ -144,8 +155,10 @@ pub(crate) fn recver_server(addr: &String, op: ChunkOp, _nthreads: usize) {
                     (*ctx).s_ctx = s_ctx;
                     (*ctx).data_receive = 0;
 
-                    let rdma_buffer_size =
-                        usize::max(CHUNK_SIZE as usize, std::mem::size_of::<TransferMetadata>());
+                    let rdma_buffer_size = usize::max(
+                        CHUNK_SIZE as usize + CHECKSUM_SIZE,
+                        std::mem::size_of::<TransferMetadata>(),
+                    );
                     (*ctx).buffer = calloc(1, rdma_buffer_size).cast();
                     (*ctx).buffer_mr = ibv_reg_mr(
                         pd,
@@ -242,21 +255,45 @@ fn on_completion(wc: *mut ibv_wc, _pd: *mut ibv_pd, op: &ChunkOp) -> Result<i64,
                 (*ctx).metadata = ChunkMetadata::default();
                 post_receive(id);
                 (*(*ctx).msg).mtype = MessageType::MSG_DONE;
-                (*(*ctx).msg).data = (*ctx).data_receive;
+                (*(*ctx).msg).data = if (*ctx).error != 0 {
+                    -(*ctx).error as i64 as u64
+                } else {
+                    (*ctx).data_receive
+                };
                 (*ctx).data_receive = 0;
+                (*ctx).error = 0;
                 send_message(id);
                 return Ok(0);
             } else if (*ctx).metadata.size != 0 {
                 post_receive(id);
                 (*(*ctx).msg).mtype = MessageType::MSG_READY;
+                let checksum = if (*ctx).checksum {
+                    let (_buffer_offset, size, _offset) =
+                        chunk_piece(&(*ctx).metadata, chunk_id as u64);
+                    Some(((*ctx).buffer.add(size as usize) as *const u64).read_unaligned())
+                } else {
+                    None
+                };
                 let ret = op.submit(ChunkInfo {
                     chunk_id: chunk_id as u64,
                     metadata: (*ctx).metadata.clone(),
                     data: (*ctx).buffer,
+                    checksum,
                 });
-                (*ctx).data_receive += ret.unwrap() as u64;
                 send_message(id);
-                return ret;
+                // the transfer goes on, the client learns about the error once it is done
+                match ret {
+                    Ok(len) => {
+                        (*ctx).data_receive += len as u64;
+                        return Ok(len);
+                    }
+                    Err(e) => {
+                        if (*ctx).error == 0 {
+                            (*ctx).error = e.abs();
+                        }
+                        return Ok(0);
+                    }
+                }
             } else {
                 post_receive(id);
                 let len = chunk_id;
@@ -272,6 +309,7 @@ fn on_completion(wc: *mut ibv_wc, _pd: *mut ibv_pd, op: &ChunkOp) -> Result<i64,
                 (*ctx).metadata.chunk_start = transfer_md.chunk_start;
                 (*ctx).metadata.offset = transfer_md.offset;
                 (*ctx).metadata.size = transfer_md.size;
+                (*ctx).checksum = transfer_md.checksum;
 
                 (*(*ctx).msg).mtype = MessageType::MSG_READY;
                 send_message(id);
@@ -1,4 +1,7 @@
+use std::collections::HashMap;
+
 use serde::Deserialize;
+use sfs_global::global::fsconfig::Consistency;
 #[derive(Debug, Deserialize)]
 pub struct ServerConfig {
     pub mountdir: String,
@@ -7,7 +10,110 @@ pub struct ServerConfig {
     pub hosts_file: String,
     pub listen: String,
     pub output: bool,
+    #[serde(default = "default_replicas")]
+    pub replicas: u64,
+    // 0 means every replica has to acknowledge a write
+    #[serde(default)]
+    pub write_quorum: u64,
+    // re-populate this host from its peers after a restart
+    #[serde(default)]
+    pub repair: bool,
+    // directory -> [data pieces, parity pieces], files below it are erasure-coded
+    #[serde(default)]
+    pub erasure_dirs: HashMap<String, (u64, u64)>,
+    // files below these directories keep their chunks lz4-compressed on the chunk hosts
+    #[serde(default)]
+    pub compress_dirs: Vec<String>,
+    // host directories the data mover may read and write, it moves nothing elsewhere
+    #[serde(default)]
+    pub stage_dirs: Vec<String>,
+    // keep identical chunks once, clients skip sending chunks the server holds already
+    #[serde(default)]
+    pub dedup: bool,
+    // "strict", "lease" or "relaxed"
+    #[serde(default = "default_consistency")]
+    pub consistency: Consistency,
+    // lease time in "lease" mode, cache time in "relaxed" mode
+    #[serde(default = "default_lease_ms")]
+    pub lease_ms: u64,
+    // clients may pin the cluster they expect, servers of one cluster share the id
+    #[serde(default)]
+    pub cluster_id: String,
+    // PEM files, TLS is used once a certificate is given. with a CA clients and peers have to
+    // present a certificate signed by it
+    #[serde(default)]
+    pub tls_cert: String,
+    #[serde(default)]
+    pub tls_key: String,
+    #[serde(default)]
+    pub tls_ca: String,
+    // file holding the shared secret of the job, calls without it are rejected
+    #[serde(default)]
+    pub auth_token_file: String,
+    // file holding a secret only the servers share, stage requests without it are rejected.
+    // nothing can be staged without one
+    #[serde(default)]
+    pub server_token_file: String,
+    // file holding the key of the job, 64 hex digits. chunks and stuffed data are encrypted
+    // once a key is given
+    #[serde(default)]
+    pub encryption_key_file: String,
+    // directory on a larger, slower device taking the cold chunks once the chunks under
+    // "rootdir" fill up their device, none if empty
+    #[serde(default)]
+    pub capacity_tier: String,
+    // where chunks are kept: "file", "rocksdb" or "memory"
+    #[serde(default = "default_chunk_backend")]
+    pub chunk_backend: String,
+    // chunk pieces travel with their checksum and the server keeps one per block of each chunk,
+    // clients follow the servers. all servers of a cluster have to agree on it
+    #[serde(default)]
+    pub checksum: bool,
+    // seconds between two passes of the scrubber over the local chunks, 0 turns it off
+    #[serde(default = "default_scrub_interval_s")]
+    pub scrub_interval_s: u64,
 }
-pub const STUFF_WITH_ROCKSDB: bool = true;
+fn default_replicas() -> u64 {
+    1
+}
+fn default_consistency() -> Consistency {
+    Consistency::Strict
+}
+fn default_lease_ms() -> u64 {
+    200
+}
+fn default_chunk_backend() -> String {
+    "file".to_string()
+}
+fn default_scrub_interval_s() -> u64 {
+    24 * 3600
+}
+// bytes the "memory" chunk backend may hold
+pub const MEMORY_BACKEND_CAPACITY: u64 = 4 * 1024 * 1024 * 1024;
 pub const IGNORE_IF_EXISTS: bool = true;
 pub const TRUNCATE_DIRECTORY: bool = true;
+// leases of expired holders are swept once this many paths are leased
+pub const LEASE_TABLE_CAPACITY: usize = 1 << 20;
+// share of the chunks of the node-local tier in use above which cold chunks move to the
+// capacity tier, and down to which they are moved. chunks are promoted below the low mark
+pub const TIER_HIGH_WATERMARK: f64 = 0.9;
+pub const TIER_LOW_WATERMARK: f64 = 0.8;
+// workers of a data mover job on each server, chunks moved per request and seconds between
+// progress reports
+pub const STAGE_THREADS: usize = 8;
+pub const STAGE_BATCH: usize = 64;
+pub const STAGE_REPORT_INTERVAL_S: u64 = 5;
+// progress reports a server may miss in a row before the job is given up
+pub const STAGE_STATUS_RETRIES: u32 = 12;
+// seconds between the capacity reports the chunk servers send each other, a report older than
+// three intervals is not trusted any longer
+pub const HEARTBEAT_INTERVAL_S: u64 = 10;
+// seconds the changes of the blocks of compressed chunks are gathered before they are sent to
+// the metadata hosts
+pub const BLOCK_REPORT_INTERVAL_S: u64 = 2;
+// share of free chunks below which a server takes no new chunks, they are placed elsewhere
+pub const SPACE_RESERVE: f64 = 0.05;
+// file ids of a metadata host carry the host id from this bit on, below it they count up.
+// ids are reserved this many at a time
+pub const FILE_ID_HOST_SHIFT: u32 = 40;
+pub const FILE_ID_BATCH: u64 = 1024;
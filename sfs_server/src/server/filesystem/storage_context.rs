@@ -11,12 +11,18 @@ use std::{
     sync::{Arc, Mutex},
     task::Poll,
 };
+use sfs_global::global::{erasure::check_erasure_dirs, fsconfig::Consistency};
+use sfs_rpc::security::{load_token, TlsConfig};
 use tokio::runtime::{Builder, Runtime};
 
 #[allow(unused)]
 use crate::{
     error_msg::error_msg,
-    server::{config::ServerConfig, storage::metadata::db::MetadataDB},
+    server::{
+        config::ServerConfig,
+        stage::resolve_host_path,
+        storage::{data::cipher::load_key, metadata::db::MetadataDB},
+    },
 };
 
 pub fn init_context() -> StorageContext {
@@ -69,6 +75,57 @@ pub fn init_context() -> StorageContext {
     context.set_blocks_state(true);
 
     context.set_output(config.output);
+    context.set_replicas(config.replicas);
+    context.set_write_quorum(config.write_quorum);
+    context.set_repair(config.repair);
+    context.set_erasure_dirs(
+        config
+            .erasure_dirs
+            .into_iter()
+            .map(|(dir, (data, parity))| (dir, data, parity))
+            .collect(),
+    );
+    if let Err(e) = check_erasure_dirs(context.get_erasure_dirs()) {
+        panic!("invalid layout in 'erasure_dirs', error {}", e);
+    }
+    context.set_compress_dirs(config.compress_dirs);
+    // links of the directories are followed once, host paths are checked where they lead. a
+    // directory created later is resolved as far as it exists
+    context.set_stage_dirs(
+        config
+            .stage_dirs
+            .into_iter()
+            .map(|dir| match resolve_host_path(&dir) {
+                Some(real) => real.to_string_lossy().into_owned(),
+                None => panic!("invalid directory '{}' in 'stage_dirs'", dir),
+            })
+            .collect(),
+    );
+    context.set_dedup(config.dedup);
+    context.set_consistency(config.consistency);
+    context.set_lease_ms(config.lease_ms);
+    context.set_cluster_id(config.cluster_id);
+    context.set_capacity_tier(config.capacity_tier);
+    context.set_chunk_backend(config.chunk_backend);
+    context.set_checksum(config.checksum);
+    context.set_scrub_interval_s(config.scrub_interval_s);
+    let tls = TlsConfig::load(&config.tls_ca, &config.tls_cert, &config.tls_key)
+        .expect("fail to read TLS certificates");
+    if let Some(tls) = &tls {
+        if tls.cert.len() == 0 || tls.key.len() == 0 {
+            panic!("'tls_cert' and 'tls_key' are both required for TLS");
+        }
+    }
+    context.set_tls(tls);
+    let token = load_token(std::env::var("SFS_TOKEN").ok(), Some(config.auth_token_file))
+        .expect("fail to read auth token file");
+    context.set_token(token);
+    let server_token = load_token(None, Some(config.server_token_file))
+        .expect("fail to read server token file");
+    context.set_server_token(server_token);
+    let key = load_key(std::env::var("SFS_ENCRYPTION_KEY").ok(), &config.encryption_key_file)
+        .expect("fail to read encryption key");
+    context.set_encryption_key(key);
 
     context
 }
@@ -92,6 +149,25 @@ pub struct StorageContext {
     rdma_runtime: Runtime,
 
     output: bool,
+
+    replicas_: u64,
+    write_quorum_: u64,
+    repair_: bool,
+    erasure_dirs_: Vec<(String, u64, u64)>,
+    compress_dirs_: Vec<String>,
+    stage_dirs_: Vec<String>,
+    dedup_: bool,
+    consistency_: Consistency,
+    lease_ms_: u64,
+    cluster_id_: String,
+    capacity_tier_: String,
+    chunk_backend_: String,
+    checksum_: bool,
+    scrub_interval_s_: u64,
+    tls_: Option<TlsConfig>,
+    token_: String,
+    server_token_: String,
+    encryption_key_: Option<[u8; 32]>,
 }
 lazy_static! {
     static ref CTX: StorageContext = init_context();
@@ -125,6 +201,25 @@ impl StorageContext {
                 .build()
                 .unwrap(),
             output: false,
+
+            replicas_: 1,
+            write_quorum_: 0,
+            repair_: false,
+            erasure_dirs_: Vec::new(),
+            compress_dirs_: Vec::new(),
+            stage_dirs_: Vec::new(),
+            dedup_: false,
+            consistency_: Consistency::Lease,
+            lease_ms_: 0,
+            cluster_id_: "".to_string(),
+            capacity_tier_: "".to_string(),
+            chunk_backend_: "file".to_string(),
+            checksum_: false,
+            scrub_interval_s_: 0,
+            tls_: None,
+            token_: "".to_string(),
+            server_token_: "".to_string(),
+            encryption_key_: None,
         }
     }
     pub fn get_rootdir(&self) -> &String {
@@ -221,6 +316,114 @@ impl StorageContext {
     pub fn get_runtime(&self) -> &Runtime {
         &self.rdma_runtime
     }
+    pub fn get_replicas(&self) -> u64 {
+        self.replicas_
+    }
+    pub fn set_replicas(&mut self, replicas_: u64) {
+        self.replicas_ = replicas_;
+    }
+    pub fn get_write_quorum(&self) -> u64 {
+        self.write_quorum_
+    }
+    pub fn set_write_quorum(&mut self, write_quorum_: u64) {
+        self.write_quorum_ = write_quorum_;
+    }
+    pub fn get_repair(&self) -> bool {
+        self.repair_
+    }
+    pub fn set_repair(&mut self, repair_: bool) {
+        self.repair_ = repair_;
+    }
+    pub fn get_erasure_dirs(&self) -> &Vec<(String, u64, u64)> {
+        &self.erasure_dirs_
+    }
+    pub fn set_erasure_dirs(&mut self, erasure_dirs_: Vec<(String, u64, u64)>) {
+        self.erasure_dirs_ = erasure_dirs_;
+    }
+    pub fn get_compress_dirs(&self) -> &Vec<String> {
+        &self.compress_dirs_
+    }
+    pub fn set_compress_dirs(&mut self, compress_dirs_: Vec<String>) {
+        self.compress_dirs_ = compress_dirs_;
+    }
+    pub fn get_stage_dirs(&self) -> &Vec<String> {
+        &self.stage_dirs_
+    }
+    pub fn set_stage_dirs(&mut self, stage_dirs_: Vec<String>) {
+        self.stage_dirs_ = stage_dirs_;
+    }
+    pub fn get_dedup(&self) -> bool {
+        self.dedup_
+    }
+    pub fn set_dedup(&mut self, dedup_: bool) {
+        self.dedup_ = dedup_;
+    }
+    pub fn get_consistency(&self) -> Consistency {
+        self.consistency_
+    }
+    pub fn set_consistency(&mut self, consistency_: Consistency) {
+        self.consistency_ = consistency_;
+    }
+    pub fn get_lease_ms(&self) -> u64 {
+        self.lease_ms_
+    }
+    pub fn set_lease_ms(&mut self, lease_ms_: u64) {
+        self.lease_ms_ = lease_ms_;
+    }
+    pub fn get_cluster_id(&self) -> &String {
+        &self.cluster_id_
+    }
+    pub fn set_cluster_id(&mut self, cluster_id_: String) {
+        self.cluster_id_ = cluster_id_;
+    }
+    pub fn get_capacity_tier(&self) -> &String {
+        &self.capacity_tier_
+    }
+    pub fn set_capacity_tier(&mut self, capacity_tier_: String) {
+        self.capacity_tier_ = capacity_tier_;
+    }
+    pub fn get_chunk_backend(&self) -> &String {
+        &self.chunk_backend_
+    }
+    pub fn set_chunk_backend(&mut self, chunk_backend_: String) {
+        self.chunk_backend_ = chunk_backend_;
+    }
+    pub fn get_checksum(&self) -> bool {
+        self.checksum_
+    }
+    pub fn set_checksum(&mut self, checksum_: bool) {
+        self.checksum_ = checksum_;
+    }
+    pub fn get_scrub_interval_s(&self) -> u64 {
+        self.scrub_interval_s_
+    }
+    pub fn set_scrub_interval_s(&mut self, scrub_interval_s_: u64) {
+        self.scrub_interval_s_ = scrub_interval_s_;
+    }
+    pub fn get_tls(&self) -> &Option<TlsConfig> {
+        &self.tls_
+    }
+    pub fn set_tls(&mut self, tls_: Option<TlsConfig>) {
+        self.tls_ = tls_;
+    }
+    pub fn get_token(&self) -> &String {
+        &self.token_
+    }
+    pub fn set_token(&mut self, token_: String) {
+        self.token_ = token_;
+    }
+    pub fn get_server_token(&self) -> &String {
+        &self.server_token_
+    }
+    pub fn set_server_token(&mut self, server_token_: String) {
+        self.server_token_ = server_token_;
+    }
+    pub fn get_encryption_key(&self) -> Option<[u8; 32]> {
+        self.encryption_key_
+    }
+    pub fn set_encryption_key(&mut self, encryption_key_: Option<[u8; 32]>) {
+        self.encryption_key_ = encryption_key_;
+    }
 }
 /*
 enum RwStat {
@@ -1,4 +1,8 @@
 pub mod config;
 pub mod filesystem;
 pub mod network;
+pub mod repair;
+pub mod scrub;
+pub mod space;
+pub mod stage;
 pub mod storage;
@@ -1,6 +1,8 @@
 use std::{
+    collections::HashSet,
     fs::OpenOptions,
     io::{BufRead, BufReader, Error},
+    net::{IpAddr, SocketAddr, ToSocketAddrs},
     path::Path,
     sync::Arc,
 };
@@ -15,6 +17,7 @@ use sfs_global::global::{
     util::env_util::{get_hostname, get_var},
 };
 use sfs_rpc::proto::server_grpc::SfsHandleClient;
+use sfs_rpc::security::connect;
 use tokio::runtime::{Builder, Runtime};
 
 use crate::{error_msg::error_msg, server::filesystem::storage_context::StorageContext};
@@ -64,8 +67,15 @@ fn connect_hosts(hosts: &mut Vec<(String, String)>, context: &mut NetworkContext
 
         let endp = SFSEndpoint { addr: uri.clone() };
         let env = Arc::new(Environment::new(12));
-        let channel = ChannelBuilder::new(env).connect(&format!("{}:{}", endp.addr, 8082));
+        let channel = connect(
+            ChannelBuilder::new(env),
+            &format!("{}:{}", endp.addr, 8082),
+            StorageContext::get_instance().get_tls(),
+        );
         let client = SfsHandleClient::new(channel);
+        if let Ok(resolved) = (uri.as_str(), 0).to_socket_addrs() {
+            context.peer_ips_.extend(resolved.map(|addr| addr.ip()));
+        }
         addrs[id as usize] = endp;
         clients.push(client);
         if !local_host_found && hostname.eq(&local_hostname) {
@@ -102,6 +112,8 @@ pub struct NetworkContext {
     distributor_: Arc<SimpleHashDistributor>,
     local_host_id: u64,
     runtime_: Runtime,
+    // addresses of the servers in the host file, calls only they may make are told apart by them
+    peer_ips_: HashSet<IpAddr>,
 }
 lazy_static! {
     static ref NTC: NetworkContext = init_network();
@@ -119,7 +131,11 @@ fn init_network() -> NetworkContext {
 
     let host_id = context.get_local_host_id();
     let host_len = context.get_hosts().len() as u64;
-    let distributor = SimpleHashDistributor::new(host_id, host_len);
+    let distributor = SimpleHashDistributor::with_replicas(
+        host_id,
+        host_len,
+        StorageContext::get_instance().get_replicas(),
+    );
     context.set_distributor(distributor);
     context.set_self_addr(StorageContext::get_instance().get_bind_addr().clone());
 
@@ -141,6 +157,7 @@ impl NetworkContext {
                 .thread_stack_size(24 * 1024 * 1024)
                 .build()
                 .unwrap(),
+            peer_ips_: HashSet::new(),
         }
     }
     pub fn get_self_addr(&self) -> &String {
@@ -179,4 +196,12 @@ impl NetworkContext {
     pub fn get_runtime(&self) -> &Runtime {
         &self.runtime_
     }
+    // whether 'peer', as gRPC reports it ("ipv4:<ip>:<port>" or "ipv6:[<ip>]:<port>"), is one
+    // of the servers in the host file
+    pub fn is_server_peer(&self, peer: &str) -> bool {
+        let addr = peer
+            .split_once(':')
+            .and_then(|(_, addr)| addr.parse::<SocketAddr>().ok());
+        addr.map_or(false, |addr| self.peer_ips_.contains(&addr.ip()))
+    }
 }
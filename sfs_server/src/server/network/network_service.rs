@@ -1,16 +1,25 @@
-use grpcio::Error;
+use grpcio::{CallOption, Error};
 use serde::Serialize;
 use sfs_global::global::util::serde_util::serialize;
 use sfs_rpc::{
     post,
     proto::{server::PostResult, server_grpc::SfsHandleClient},
+    security,
 };
 
+use crate::server::filesystem::storage_context::StorageContext;
+
 use sfs_global::global::network::post::{option2i, PostOption};
 
-// This module is for pre-create, but it is currently disabled
+// This module is used by pre-create and replica repair
 pub struct NetworkService {}
 impl NetworkService {
+    // peers check the token like they do for clients, and the secret of the servers where only
+    // servers may ask
+    pub fn call_option() -> CallOption {
+        let context = StorageContext::get_instance();
+        security::server_call_option(context.get_token(), context.get_server_token())
+    }
     pub fn post<T: Serialize>(
         client: &SfsHandleClient,
         data: T,
@@ -18,7 +27,28 @@ impl NetworkService {
     ) -> Result<PostResult, Error> {
         let serialized_data = serialize(&data);
         let post = post(option2i(&opt), serialized_data, vec![0; 0]);
-        let post_result = client.handle(&post)?;
+        let post_result = client.handle_opt(&post, Self::call_option())?;
+        return Ok(post_result);
+    }
+    // same as 'post' without holding the thread, for handlers running on the gRPC threads
+    pub async fn post_async<T: Serialize>(
+        client: &SfsHandleClient,
+        data: T,
+        opt: PostOption,
+    ) -> Result<PostResult, Error> {
+        let serialized_data = serialize(&data);
+        let post = post(option2i(&opt), serialized_data, vec![0; 0]);
+        return client.handle_async_opt(&post, Self::call_option())?.await;
+    }
+    pub fn post_stuff<T: Serialize>(
+        client: &SfsHandleClient,
+        data: T,
+        stuff: Vec<u8>,
+        opt: PostOption,
+    ) -> Result<PostResult, Error> {
+        let serialized_data = serialize(&data);
+        let post = post(option2i(&opt), serialized_data, stuff);
+        let post_result = client.handle_opt(&post, Self::call_option())?;
         return Ok(post_result);
     }
 }
@@ -0,0 +1,489 @@
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use lazy_static::*;
+use libc::{EINVAL, EIO, ENOENT};
+use sfs_global::global::{
+    distributor::Distributor,
+    erasure::{erasure_layout, ErasureLayout, ReedSolomon},
+    metadata::{Metadata, S_ISDIR, S_ISREG},
+    network::{
+        config::CHUNK_SIZE,
+        forward_data::{DirentRecordData, ReadChunkData, RepairChunkData, ReplicateChunkData},
+        post::PostOption,
+    },
+    util::{path_util::dirname, serde_util::serialize},
+};
+
+use crate::error_msg::error_msg;
+
+use super::{
+    config::HEARTBEAT_INTERVAL_S,
+    filesystem::storage_context::StorageContext,
+    network::{network_context::NetworkContext, network_service::NetworkService},
+    storage::{
+        data::{
+            backend::StripedLocks,
+            chunk_storage::{ChunkStorage, CHUNK_LOCKS},
+            stuff_db::StuffDB,
+        },
+        metadata::{db::MetadataDB, dirent_db::DirentDB, placement_db::PlacementDB},
+    },
+};
+
+lazy_static! {
+    // parity updates of the stripes this host is the first parity host of, one at a time
+    static ref STRIPE_LOCKS: StripedLocks = StripedLocks::new(CHUNK_LOCKS);
+    // (path, file id, stripe id) of stripes whose parity is encoded again until it is stored
+    static ref STALE_STRIPES: Mutex<HashSet<(String, u64, u64)>> = Mutex::new(HashSet::new());
+}
+
+// push every metadata entry, dirent record and chunk that 'target' should hold but lost, e.g. after a restart.
+// for each entry only the first surviving metadata replica does the work, so nothing is sent twice
+pub fn repair_host(target: u64) {
+    let local = NetworkContext::get_instance().get_local_host_id();
+    let distributor = NetworkContext::get_instance().get_distributor();
+    for (path, md_str) in MetadataDB::get_instance().get_all() {
+        let md_replicas = distributor.locate_file_metadata_replicas(&path);
+        let source = md_replicas.iter().find(|host| **host != target);
+        if source != Some(&local) {
+            continue;
+        }
+        if StorageContext::get_instance().output() {
+            println!("repairing '{}' on host {}....", path, target);
+        }
+        if md_replicas.contains(&target) {
+            let client = NetworkContext::get_instance()
+                .get_clients()
+                .get(target as usize)
+                .unwrap();
+            if let Err(e) = NetworkService::post_stuff::<&str>(
+                client,
+                path.as_str(),
+                md_str.clone(),
+                PostOption::ReplicateMetadata,
+            ) {
+                error_msg(
+                    "server::repair::repair_host".to_string(),
+                    format!("error {} occurs while replicating metadata of '{}'", e, path),
+                );
+            }
+            // the content of a stuffed file lives with its metadata
+            if let Some(data) = StuffDB::get_instance().get(&path) {
+                if let Err(e) = NetworkService::post_stuff::<&str>(
+                    client,
+                    path.as_str(),
+                    data,
+                    PostOption::ReplicateStuff,
+                ) {
+                    error_msg(
+                        "server::repair::repair_host".to_string(),
+                        format!("error {} occurs while replicating stuffed data of '{}'", e, path),
+                    );
+                }
+            }
+            let placement = PlacementDB::get_instance().get(&path);
+            if !placement.is_empty() {
+                if let Err(e) = NetworkService::post_stuff::<&str>(
+                    client,
+                    path.as_str(),
+                    serialize(&placement),
+                    PostOption::PutPlacement,
+                ) {
+                    error_msg(
+                        "server::repair::repair_host".to_string(),
+                        format!("error {} occurs while replicating placement of '{}'", e, path),
+                    );
+                }
+            }
+        }
+        let md = Metadata::deserialize(&md_str);
+        if !S_ISREG(md.get_mode()) || md.get_size() <= 0 || md.is_stuffed() {
+            continue;
+        }
+        // erasure-coded pieces are rebuilt from the rest of their stripe
+        let layout = erasure_layout(StorageContext::get_instance().get_erasure_dirs(), &path);
+        if let Some(layout) = layout {
+            repair_stripes(&path, md.get_file_id(), md.get_size() as u64, &layout, target);
+            continue;
+        }
+        let chunk_end = (md.get_size() as u64 - 1) / CHUNK_SIZE;
+        let placement = PlacementDB::get_instance().get(&path);
+        for chunk_id in 0..(chunk_end + 1) {
+            let replicas = placement.locate(distributor.as_ref(), &path, chunk_id);
+            if !replicas.contains(&target) {
+                continue;
+            }
+            let chunk_source = replicas.iter().find(|host| **host != target);
+            match chunk_source {
+                None => continue,
+                Some(host) if *host == local => {
+                    replicate_chunk(&path, md.get_file_id(), chunk_id, target);
+                }
+                Some(host) => {
+                    let client = NetworkContext::get_instance()
+                        .get_clients()
+                        .get(*host as usize)
+                        .unwrap();
+                    let repair_chunk = RepairChunkData {
+                        path: path.as_str(),
+                        file_id: md.get_file_id(),
+                        chunk_id,
+                        target,
+                    };
+                    if let Err(e) = NetworkService::post::<RepairChunkData>(
+                        client,
+                        repair_chunk,
+                        PostOption::RepairChunk,
+                    ) {
+                        error_msg(
+                            "server::repair::repair_host".to_string(),
+                            format!("error {} occurs while requesting chunk repair from host {}", e, host),
+                        );
+                    }
+                }
+            }
+        }
+    }
+    for (dir, name, is_dir) in DirentDB::get_instance().get_all() {
+        let dir_replicas = distributor.locate_dir_metadata(&dir);
+        if !dir_replicas.contains(&target)
+            || dir_replicas.iter().find(|host| **host != target) != Some(&local)
+        {
+            continue;
+        }
+        let client = NetworkContext::get_instance()
+            .get_clients()
+            .get(target as usize)
+            .unwrap();
+        let record = DirentRecordData {
+            dir: dir.as_str(),
+            name: name.as_str(),
+            is_dir,
+        };
+        if let Err(e) = NetworkService::post::<DirentRecordData>(client, record, PostOption::AddDirent) {
+            error_msg(
+                "server::repair::repair_host".to_string(),
+                format!("error {} occurs while replicating dirent '{}' of '{}'", e, name, dir),
+            );
+        }
+    }
+}
+// rebuild the pieces of an erasure-coded file of 'size' bytes that 'target' should hold
+fn repair_stripes(path: &String, file_id: u64, size: u64, layout: &ErasureLayout, target: u64) {
+    let distributor = NetworkContext::get_instance().get_distributor();
+    let chunk_end = (size - 1) / CHUNK_SIZE;
+    for stripe_id in 0..(layout.stripe_of(chunk_end) + 1) {
+        let hosts = distributor.locate_stripe(path, stripe_id, layout.width());
+        for piece in 0..layout.width() {
+            let chunk_id = layout.piece_chunk_id(stripe_id, piece);
+            // data chunks past the end are zeros to the parity, they are not kept
+            if hosts[piece as usize] != target || (piece < layout.data && chunk_id > chunk_end) {
+                continue;
+            }
+            let err = match rebuild_piece(path, file_id, layout, stripe_id, piece, Some(target)) {
+                Ok(mut data) => {
+                    if piece < layout.data {
+                        data.truncate(data_len(chunk_id, size));
+                    }
+                    send_chunk(path, file_id, chunk_id, data, target)
+                }
+                Err(e) => e,
+            };
+            if err != 0 {
+                error_msg(
+                    "server::repair::repair_stripes".to_string(),
+                    format!("fail to rebuild chunk {} of '{}' on host {}", chunk_id, path, target),
+                );
+            }
+        }
+    }
+}
+// length of data chunk 'chunk_id' in a file of 'size' bytes
+pub fn data_len(chunk_id: u64, size: u64) -> usize {
+    CHUNK_SIZE.min(size.saturating_sub(chunk_id * CHUNK_SIZE)) as usize
+}
+// content of a chunk as 'host' holds it, empty for a hole. read locally on this host
+pub fn fetch_chunk(host: u64, path: &String, file_id: u64, chunk_id: u64) -> Result<Vec<u8>, i32> {
+    if host == NetworkContext::get_instance().get_local_host_id() {
+        let mut buf = vec![0u8; CHUNK_SIZE as usize];
+        let read_res =
+            ChunkStorage::read_chunk(path, file_id, chunk_id, buf.as_mut_ptr(), CHUNK_SIZE, 0);
+        return match read_res {
+            Ok(len) => {
+                buf.truncate(len as usize);
+                Ok(buf)
+            }
+            Err(-2) => Ok(Vec::new()),
+            Err(e) => Err(e),
+        };
+    }
+    let client = NetworkContext::get_instance()
+        .get_clients()
+        .get(host as usize)
+        .unwrap();
+    let read_chunk = ReadChunkData {
+        path: path.as_str(),
+        file_id,
+        chunk_id,
+    };
+    match NetworkService::post::<ReadChunkData>(client, read_chunk, PostOption::ReadChunk) {
+        Ok(res) if res.err == 0 => Ok(res.extra),
+        Ok(res) => Err(res.err),
+        Err(e) => {
+            error_msg(
+                "server::repair::fetch_chunk".to_string(),
+                format!("error {} occurs while reading chunk from host {}", e, host),
+            );
+            Err(libc::EBUSY)
+        }
+    }
+}
+// piece 'piece' of a stripe rebuilt from k other pieces, padded to a whole chunk like every piece
+// the parity is computed from. the pieces on 'lost' are not asked for, that host lost them
+pub fn rebuild_piece(
+    path: &String,
+    file_id: u64,
+    layout: &ErasureLayout,
+    stripe_id: u64,
+    piece: u64,
+    lost: Option<u64>,
+) -> Result<Vec<u8>, i32> {
+    let hosts = NetworkContext::get_instance()
+        .get_distributor()
+        .locate_stripe(path, stripe_id, layout.width());
+    let mut pieces: Vec<Option<Vec<u8>>> = vec![None; layout.width() as usize];
+    let mut found = 0;
+    for other in 0..layout.width() {
+        let host = hosts[other as usize];
+        if found == layout.data {
+            break;
+        }
+        if other == piece || Some(host) == lost {
+            continue;
+        }
+        let chunk_id = layout.piece_chunk_id(stripe_id, other);
+        if let Ok(mut buf) = fetch_chunk(host, path, file_id, chunk_id) {
+            buf.resize(CHUNK_SIZE as usize, 0);
+            pieces[other as usize] = Some(buf);
+            found += 1;
+        }
+    }
+    ReedSolomon::from_layout(layout).reconstruct(&mut pieces)?;
+    Ok(pieces[piece as usize].take().unwrap())
+}
+// metadata of 'path' as its metadata replicas know it
+pub fn file_metadata(path: &String) -> Result<Metadata, i32> {
+    let local = NetworkContext::get_instance().get_local_host_id();
+    let replicas = NetworkContext::get_instance()
+        .get_distributor()
+        .locate_file_metadata_replicas(path);
+    let mut err = EIO;
+    for host in replicas {
+        if host == local {
+            match MetadataDB::get_instance().get(path) {
+                Some(md) => return Ok(Metadata::deserialize(&md)),
+                None => err = ENOENT,
+            }
+            continue;
+        }
+        let client = NetworkContext::get_instance()
+            .get_clients()
+            .get(host as usize)
+            .unwrap();
+        match NetworkService::post::<&str>(client, path.as_str(), PostOption::Stat) {
+            Ok(res) if res.err == 0 => return Ok(Metadata::deserialize(&res.data)),
+            Ok(res) => err = res.err,
+            Err(_e) => {}
+        }
+    }
+    Err(err)
+}
+// recompute the parity of a stripe from its data pieces and store it on its parity hosts, on
+// the first parity host of the stripe. updates of a stripe are serialised here, so the
+// read-modify-write of writes to different chunks of a stripe can not interleave. a stripe
+// whose parity is not stored everywhere is encoded again later
+pub fn encode_stripe(path: &String, file_id: u64, stripe_id: u64) -> i32 {
+    let layout = erasure_layout(StorageContext::get_instance().get_erasure_dirs(), path);
+    if layout.is_none() {
+        return EINVAL;
+    }
+    let layout = layout.unwrap();
+    if layout.parity == 0 {
+        return 0;
+    }
+    let err = store_parity(path, file_id, &layout, stripe_id);
+    if err != 0 {
+        encode_later(path, file_id, layout, stripe_id);
+    }
+    err
+}
+fn store_parity(path: &String, file_id: u64, layout: &ErasureLayout, stripe_id: u64) -> i32 {
+    let _guard = STRIPE_LOCKS.lock(&(path, stripe_id));
+    let hosts = NetworkContext::get_instance()
+        .get_distributor()
+        .locate_stripe(path, stripe_id, layout.width());
+    let mut data = Vec::new();
+    for piece in 0..layout.data {
+        let chunk_id = layout.piece_chunk_id(stripe_id, piece);
+        match fetch_chunk(hosts[piece as usize], path, file_id, chunk_id) {
+            Ok(mut buf) => {
+                buf.resize(CHUNK_SIZE as usize, 0);
+                data.push(buf);
+            }
+            Err(e) => {
+                error_msg(
+                    "server::repair::store_parity".to_string(),
+                    format!("fail to fetch chunk {} of '{}'", chunk_id, path),
+                );
+                return e;
+            }
+        }
+    }
+    let parity = ReedSolomon::from_layout(layout).encode(&data);
+    if let Err(e) = parity {
+        return e;
+    }
+    let mut err = 0;
+    for (p, piece) in parity.unwrap().into_iter().enumerate() {
+        let host = hosts[(layout.data + p as u64) as usize];
+        let parity_id = layout.parity_chunk_id(stripe_id, p as u64);
+        let store_err = send_chunk(path, file_id, parity_id, piece, host);
+        if store_err != 0 {
+            error_msg(
+                "server::repair::store_parity".to_string(),
+                format!("fail to store parity {} of stripe {} on host {}", p, stripe_id, host),
+            );
+            err = store_err;
+        }
+    }
+    err
+}
+// encode a stripe again every heartbeat interval until its parity is stored on every parity
+// host, or the file is gone or cut before the stripe
+fn encode_later(path: &String, file_id: u64, layout: ErasureLayout, stripe_id: u64) {
+    let stripe = (path.clone(), file_id, stripe_id);
+    if !STALE_STRIPES.lock().unwrap().insert(stripe.clone()) {
+        return;
+    }
+    thread::spawn(move || {
+        let (path, file_id, stripe_id) = &stripe;
+        loop {
+            thread::sleep(Duration::from_secs(HEARTBEAT_INTERVAL_S));
+            let stripe_begin = layout.piece_chunk_id(*stripe_id, 0) * CHUNK_SIZE;
+            match file_metadata(path) {
+                Ok(md) if md.get_file_id() == *file_id && md.get_size() as u64 > stripe_begin => {}
+                Ok(_) | Err(ENOENT) => break,
+                Err(_e) => continue,
+            }
+            if store_parity(path, *file_id, &layout, *stripe_id) == 0 {
+                break;
+            }
+        }
+        STALE_STRIPES.lock().unwrap().remove(&stripe);
+    });
+}
+// rebuild the dirent records of metadata kept before directory entries were, once per store.
+// every entry is sent by its first metadata replica, again each round until its directory
+// owners all took it
+pub fn backfill_dirents() {
+    let local = NetworkContext::get_instance().get_local_host_id();
+    let distributor = NetworkContext::get_instance().get_distributor();
+    let mut pending: Vec<(String, String, bool)> = Vec::new();
+    for (path, md_str) in MetadataDB::get_instance().get_all() {
+        if path == "/" || distributor.locate_file_metadata_replicas(&path)[0] != local {
+            continue;
+        }
+        let name = path[path.rfind('/').unwrap() + 1..].to_string();
+        let is_dir = S_ISDIR(Metadata::deserialize(&md_str).get_mode());
+        pending.push((dirname(&path), name, is_dir));
+    }
+    loop {
+        pending.retain(|(dir, name, is_dir)| {
+            let mut sent = true;
+            for host in distributor.locate_dir_metadata(dir).iter() {
+                if *host == local {
+                    DirentDB::get_instance().put(dir, name, *is_dir);
+                    continue;
+                }
+                let client = NetworkContext::get_instance()
+                    .get_clients()
+                    .get(*host as usize)
+                    .unwrap();
+                let record = DirentRecordData {
+                    dir: dir.as_str(),
+                    name: name.as_str(),
+                    is_dir: *is_dir,
+                };
+                match NetworkService::post::<DirentRecordData>(client, record, PostOption::AddDirent) {
+                    Ok(res) if res.err == 0 => {}
+                    _ => sent = false,
+                }
+            }
+            !sent
+        });
+        if pending.is_empty() {
+            break;
+        }
+        // peers that are not up yet are tried again
+        thread::sleep(Duration::from_secs(HEARTBEAT_INTERVAL_S));
+    }
+    DirentDB::get_instance().mark_backfilled();
+}
+// copy a local chunk to the 'target' host
+pub fn replicate_chunk(path: &String, file_id: u64, chunk_id: u64, target: u64) -> i32 {
+    let mut buf = vec![0u8; CHUNK_SIZE as usize];
+    let read_res =
+        ChunkStorage::read_chunk(path, file_id, chunk_id, buf.as_mut_ptr(), CHUNK_SIZE, 0);
+    if let Err(e) = read_res {
+        error_msg(
+            "server::repair::replicate_chunk".to_string(),
+            format!("fail to read chunk {} of '{}'", chunk_id, path),
+        );
+        return e;
+    }
+    buf.truncate(read_res.unwrap() as usize);
+    send_chunk(path, file_id, chunk_id, buf, target)
+}
+// store 'data' as chunk 'chunk_id' on the 'target' host, this host writes it itself
+pub fn send_chunk(
+    path: &String,
+    file_id: u64,
+    chunk_id: u64,
+    mut data: Vec<u8>,
+    target: u64,
+) -> i32 {
+    if target == NetworkContext::get_instance().get_local_host_id() {
+        let len = data.len() as u64;
+        return match ChunkStorage::write_chunk(path, file_id, chunk_id, data.as_mut_ptr(), len, 0) {
+            Ok(_) => 0,
+            Err(e) => e,
+        };
+    }
+    let client = NetworkContext::get_instance()
+        .get_clients()
+        .get(target as usize)
+        .unwrap();
+    let replicate = ReplicateChunkData {
+        path: path.as_str(),
+        file_id,
+        chunk_id,
+    };
+    match NetworkService::post_stuff::<ReplicateChunkData>(
+        client,
+        replicate,
+        data,
+        PostOption::ReplicateChunk,
+    ) {
+        Ok(res) => res.err,
+        Err(e) => {
+            error_msg(
+                "server::repair::send_chunk".to_string(),
+                format!("error {} occurs while sending chunk to host {}", e, target),
+            );
+            libc::EBUSY
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+use std::thread;
+use std::time::Duration;
+
+use sfs_global::global::{
+    distributor::Distributor,
+    erasure::{erasure_layout, ErasureLayout},
+    placement::Placement,
+};
+
+use crate::error_msg::error_msg;
+use crate::handle::file_placement;
+
+use super::{
+    filesystem::storage_context::StorageContext,
+    network::network_context::NetworkContext,
+    repair::{data_len, fetch_chunk, file_metadata, rebuild_piece},
+    storage::data::chunk_storage::ChunkStorage,
+};
+
+// a local piece of an erasure-coded file rebuilt from the rest of its stripe
+fn rebuild_local(
+    path: &String,
+    file_id: u64,
+    layout: &ErasureLayout,
+    chunk_id: u64,
+) -> Result<Vec<u8>, i32> {
+    let (stripe_id, piece) = layout.piece_of(chunk_id);
+    let mut data = rebuild_piece(path, file_id, layout, stripe_id, piece, None)?;
+    if piece < layout.data {
+        data.truncate(data_len(chunk_id, file_metadata(path)?.get_size() as u64));
+    }
+    Ok(data)
+}
+// re-read every local chunk having a checksum. a chunk not matching it any longer or gone is
+// copied back from another replica, or rebuilt from the rest of its stripe if it is a piece of
+// an erasure-coded file. returns (damaged chunks, repaired chunks)
+pub fn scrub_chunks() -> (usize, usize) {
+    let local = NetworkContext::get_instance().get_local_host_id();
+    let distributor = NetworkContext::get_instance().get_distributor();
+    let mut damaged = 0;
+    let mut repaired = 0;
+    let mut placements: HashMap<String, Placement> = HashMap::new();
+    for (path, file_id, chunk_id) in ChunkStorage::checksummed_chunks() {
+        let err = match ChunkStorage::verify_chunk(&path, file_id, chunk_id) {
+            Ok(()) => continue,
+            Err(e) => e,
+        };
+        damaged += 1;
+        error_msg(
+            "server::scrub::scrub_chunks".to_string(),
+            format!(
+                "chunk {} of '{}' is {}",
+                chunk_id,
+                path,
+                if err == -2 { "gone" } else { "damaged" }
+            ),
+        );
+        let layout = erasure_layout(StorageContext::get_instance().get_erasure_dirs(), &path);
+        let restored = if let Some(layout) = layout {
+            match rebuild_local(&path, file_id, &layout, chunk_id) {
+                Ok(data) => ChunkStorage::restore_chunk(&path, file_id, chunk_id, &data).is_ok(),
+                Err(_e) => false,
+            }
+        } else {
+            let placement = placements
+                .entry(path.clone())
+                .or_insert_with(|| file_placement(&path));
+            let replicas = placement.locate(distributor.as_ref(), &path, chunk_id);
+            replicas
+                .iter()
+                .filter(|host| **host != local)
+                .any(|host| match fetch_chunk(*host, &path, file_id, chunk_id) {
+                    Ok(data) => {
+                        ChunkStorage::restore_chunk(&path, file_id, chunk_id, &data).is_ok()
+                    }
+                    Err(_e) => false,
+                })
+        };
+        if restored {
+            if StorageContext::get_instance().output() {
+                println!("scrub: chunk {} of '{}' repaired", chunk_id, path);
+            }
+            repaired += 1;
+        } else {
+            error_msg(
+                "server::scrub::scrub_chunks".to_string(),
+                format!("no intact replica or stripe of chunk {} of '{}'", chunk_id, path),
+            );
+        }
+    }
+    (damaged, repaired)
+}
+// scrub the local chunks every 'interval_s' seconds
+pub fn scrubber(interval_s: u64) {
+    loop {
+        thread::sleep(Duration::from_secs(interval_s));
+        let (damaged, repaired) = scrub_chunks();
+        if StorageContext::get_instance().output() || damaged != 0 {
+            println!("scrub: {} damaged chunks, {} repaired", damaged, repaired);
+        }
+    }
+}
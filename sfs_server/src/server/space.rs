@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use lazy_static::*;
+use sfs_global::global::network::{
+    forward_data::{ChunkStat, HeartbeatData},
+    post::PostOption,
+};
+
+use super::{
+    config::{HEARTBEAT_INTERVAL_S, SPACE_RESERVE},
+    network::{network_context::NetworkContext, network_service::NetworkService},
+    storage::data::chunk_storage::ChunkStorage,
+};
+
+// free share of the chunks of every server as their last heartbeats reported it
+pub struct SpaceTable {
+    shares_: Mutex<HashMap<u64, (f64, Instant)>>,
+}
+lazy_static! {
+    static ref SPACE: SpaceTable = SpaceTable {
+        shares_: Mutex::new(HashMap::new()),
+    };
+}
+impl SpaceTable {
+    pub fn get_instance() -> &'static SpaceTable {
+        &SPACE
+    }
+    pub fn update(&self, host: u64, stat: &ChunkStat) {
+        // a server not knowing its capacity is taken as having room
+        let share = if stat.chunk_total == 0 {
+            1.0
+        } else {
+            stat.chunk_free as f64 / stat.chunk_total as f64
+        };
+        self.shares_
+            .lock()
+            .unwrap()
+            .insert(host, (share, Instant::now()));
+    }
+    // whether 'host' is below the reserve, hosts not heard of lately are not
+    pub fn nearly_full(&self, host: u64) -> bool {
+        match self.shares_.lock().unwrap().get(&host) {
+            Some((share, at)) => {
+                *share < SPACE_RESERVE && at.elapsed() < Duration::from_secs(3 * HEARTBEAT_INTERVAL_S)
+            }
+            None => false,
+        }
+    }
+    pub fn nearly_full_hosts(&self) -> Vec<u64> {
+        let hosts: Vec<u64> = self.shares_.lock().unwrap().keys().cloned().collect();
+        hosts
+            .into_iter()
+            .filter(|host| self.nearly_full(*host))
+            .collect()
+    }
+}
+// report the local capacity to every other server each 'interval_s' seconds
+pub fn heartbeat(interval_s: u64) {
+    let local = NetworkContext::get_instance().get_local_host_id();
+    loop {
+        let data = HeartbeatData {
+            host: local,
+            stat: ChunkStorage::chunk_stat(),
+        };
+        SpaceTable::get_instance().update(local, &data.stat);
+        for (host, client) in NetworkContext::get_instance().get_clients().iter().enumerate() {
+            if host as u64 == local {
+                continue;
+            }
+            // a server that is down misses this one, its peers stop trusting the last report
+            let _ = NetworkService::post::<&HeartbeatData>(client, &data, PostOption::Heartbeat);
+        }
+        thread::sleep(Duration::from_secs(interval_s));
+    }
+}
@@ -0,0 +1,777 @@
+use std::collections::HashMap;
+use std::fs::{self, File, FileTimes, OpenOptions, Permissions};
+use std::os::unix::fs::{FileExt, MetadataExt, PermissionsExt};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, UNIX_EPOCH};
+
+use lazy_static::*;
+use libc::{EACCES, EBUSY, EINVAL, EIO, ENOENT, S_IFDIR, S_IFREG};
+use serde::Serialize;
+use sfs_global::global::{
+    distributor::Distributor,
+    erasure::{erasure_layout, ErasureLayout, ReedSolomon},
+    fsconfig::{ENABLE_STUFFING, STUFF_SIZE},
+    metadata::{Metadata, S_ISDIR, S_ISREG},
+    network::{
+        config::CHUNK_SIZE,
+        forward_data::{
+            DirentRecordData, PathData, RemoveData, ReplicateChunkData, StageChunksData,
+            StageData, StageProgress,
+        },
+        post::PostOption,
+    },
+    placement::Placement,
+    util::{path_util::dirname, serde_util::try_deserialize},
+};
+use sfs_rpc::proto::server::PostResult;
+
+use crate::{
+    error_msg::error_msg,
+    handle::{file_placement, handle_stuffed_data, is_primary},
+};
+
+use super::{
+    config::{STAGE_BATCH, STAGE_REPORT_INTERVAL_S, STAGE_STATUS_RETRIES, STAGE_THREADS},
+    filesystem::storage_context::StorageContext,
+    network::{network_context::NetworkContext, network_service::NetworkService},
+    repair::{file_metadata, rebuild_piece},
+    storage::{
+        data::chunk_storage::ChunkStorage,
+        metadata::{db::MetadataDB, file_ids::FileIds},
+    },
+};
+
+// modification time of a file whose stage-in has not completed yet
+const STAGING_MTIME: i64 = -1;
+
+// (stage-in, host directory, SimpleFS directory)
+type Job = (bool, String, String);
+lazy_static! {
+    // progress of the data mover jobs this server took part in
+    static ref JOBS: Mutex<HashMap<Job, StageProgress>> = Mutex::new(HashMap::new());
+}
+fn update_progress(job: &Job, update: impl FnOnce(&mut StageProgress)) {
+    if let Some(progress) = JOBS.lock().unwrap().get_mut(job) {
+        update(progress);
+    }
+}
+fn post_to<T: Serialize>(
+    host: u64,
+    data: T,
+    stuff: Vec<u8>,
+    opt: PostOption,
+) -> Result<PostResult, i32> {
+    let client = NetworkContext::get_instance()
+        .get_clients()
+        .get(host as usize)
+        .unwrap();
+    match NetworkService::post_stuff::<T>(client, data, stuff, opt) {
+        Ok(res) if res.err == 0 => Ok(res),
+        Ok(res) => Err(res.err),
+        Err(e) => {
+            error_msg(
+                "server::stage::post_to".to_string(),
+                format!("error {} occurs while posting to host {}", e, host),
+            );
+            Err(EBUSY)
+        }
+    }
+}
+fn child_path(dir: &String, name: &str) -> String {
+    if dir == "/" {
+        format!("/{}", name)
+    } else {
+        format!("{}/{}", dir, name)
+    }
+}
+// 'host_path' with its links resolved as far as it exists, the rest appended as it is. none if
+// it is relative or goes up with "..", which a link in the missing part could turn around
+pub fn resolve_host_path(host_path: &str) -> Option<PathBuf> {
+    let path = Path::new(host_path);
+    if !path.is_absolute()
+        || path
+            .components()
+            .any(|component| component == Component::ParentDir)
+    {
+        return None;
+    }
+    let mut existing = path;
+    let mut rest = Vec::new();
+    let mut real = loop {
+        if let Ok(real) = fs::canonicalize(existing) {
+            break real;
+        }
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                rest.push(name);
+                existing = parent;
+            }
+            _ => return None,
+        }
+    };
+    for name in rest.into_iter().rev() {
+        real.push(name);
+    }
+    Some(real)
+}
+// whether 'host_path' lies below one of the configured stage directories, so no link leads out
+// of them
+fn host_path_allowed(host_path: &str) -> bool {
+    match resolve_host_path(host_path) {
+        Some(real) => StorageContext::get_instance()
+            .get_stage_dirs()
+            .iter()
+            .any(|dir| real.starts_with(PathBuf::from(dir))),
+        None => false,
+    }
+}
+// offset and length of chunk 'chunk_id' in a file of 'size' bytes
+fn chunk_range(chunk_id: u64, size: u64) -> (u64, usize) {
+    let offset = chunk_id * CHUNK_SIZE;
+    (offset, CHUNK_SIZE.min(size.saturating_sub(offset)) as usize)
+}
+
+// run a data mover job on every server and report its progress until it is done.
+// 0 if no entry failed, a job run again skips the entries it moved already
+pub fn stage(stage_in: bool, host_dir: &String, sfs_dir: &String) -> i32 {
+    if !host_dir.starts_with('/') || !sfs_dir.starts_with('/') {
+        println!("both directories have to be absolute paths");
+        return EINVAL;
+    }
+    let trim = |dir: &String| {
+        let trimmed = dir.trim_end_matches('/');
+        if trimmed.is_empty() {
+            "/".to_string()
+        } else {
+            trimmed.to_string()
+        }
+    };
+    let (host_dir, sfs_dir) = (trim(host_dir), trim(sfs_dir));
+    let job = || StageData {
+        stage_in,
+        host_dir: host_dir.as_str(),
+        sfs_dir: sfs_dir.as_str(),
+    };
+    let hosts = NetworkContext::get_instance().get_clients().len() as u64;
+    // progress reports missed in a row by each host
+    let mut missed = vec![0u32; hosts as usize];
+    for host in 0..hosts {
+        if let Err(e) = post_to(host, job(), Vec::new(), PostOption::Stage) {
+            println!("fail to start the job on host {}: {}", host, e);
+            return e;
+        }
+    }
+    loop {
+        thread::sleep(Duration::from_secs(STAGE_REPORT_INTERVAL_S));
+        let mut total = StageProgress {
+            done: true,
+            ..Default::default()
+        };
+        for host in 0..hosts {
+            let status_res = post_to(host, job(), Vec::new(), PostOption::StageStatus);
+            if status_res.is_err() {
+                missed[host as usize] += 1;
+                if missed[host as usize] > STAGE_STATUS_RETRIES {
+                    println!("host {} stopped reporting progress, giving up", host);
+                    return EIO;
+                }
+            }
+            match status_res {
+                Ok(res) => {
+                    missed[host as usize] = 0;
+                    let progress = try_deserialize::<StageProgress>(&res.data).unwrap_or_default();
+                    total.files += progress.files;
+                    total.skipped += progress.skipped;
+                    total.bytes += progress.bytes;
+                    total.errors += progress.errors;
+                    total.done &= progress.done;
+                }
+                // the host restarted and lost the job, it starts over skipping what is moved
+                Err(ENOENT) => {
+                    let _ = post_to(host, job(), Vec::new(), PostOption::Stage);
+                    total.done = false;
+                }
+                Err(e) => {
+                    println!("fail to get the progress of host {}: {}", host, e);
+                    total.done = false;
+                }
+            }
+        }
+        println!(
+            "{} files moved, {} skipped, {} bytes, {} errors",
+            total.files, total.skipped, total.bytes, total.errors
+        );
+        if total.done {
+            return if total.errors == 0 { 0 } else { EIO };
+        }
+    }
+}
+// start the local part of a job unless it is running already
+pub fn start_stage(data: &StageData) -> i32 {
+    if !host_path_allowed(data.host_dir) {
+        error_msg(
+            "server::stage::start_stage".to_string(),
+            format!("'{}' is not below a stage directory", data.host_dir),
+        );
+        return EACCES;
+    }
+    let job = (
+        data.stage_in,
+        data.host_dir.to_string(),
+        data.sfs_dir.to_string(),
+    );
+    let mut jobs = JOBS.lock().unwrap();
+    if jobs.get(&job).map_or(false, |progress| !progress.done) {
+        return 0;
+    }
+    jobs.insert(job.clone(), StageProgress::default());
+    thread::spawn(move || run_stage(job));
+    0
+}
+pub fn stage_progress(data: &StageData) -> Option<StageProgress> {
+    let job = (
+        data.stage_in,
+        data.host_dir.to_string(),
+        data.sfs_dir.to_string(),
+    );
+    JOBS.lock().unwrap().get(&job).cloned()
+}
+fn run_stage(job: Job) {
+    let (stage_in, host_dir, sfs_dir) = job.clone();
+    if StorageContext::get_instance().output() {
+        println!(
+            "staging {} '{}' and '{}'....",
+            if stage_in { "in" } else { "out" },
+            host_dir,
+            sfs_dir
+        );
+    }
+    let files = if stage_in {
+        stage_in_entries(&job, &host_dir, &sfs_dir)
+    } else {
+        stage_out_entries(&job, &host_dir, &sfs_dir)
+    };
+    let queue = Mutex::new(files);
+    thread::scope(|scope| {
+        for _ in 0..STAGE_THREADS {
+            scope.spawn(|| loop {
+                let next = queue.lock().unwrap().pop();
+                let (host_path, path) = match next {
+                    Some(file) => file,
+                    None => return,
+                };
+                let res = if stage_in {
+                    stage_in_file(&host_path, &path)
+                } else {
+                    stage_out_file(&host_path, &path)
+                };
+                if let Err(e) = res {
+                    error_msg(
+                        "server::stage::run_stage".to_string(),
+                        format!("error {} occurs while staging '{}'", e, path),
+                    );
+                }
+                update_progress(&job, |progress| match res {
+                    Ok(Some(bytes)) => {
+                        progress.files += 1;
+                        progress.bytes += bytes;
+                    }
+                    Ok(None) => progress.skipped += 1,
+                    Err(_e) => progress.errors += 1,
+                });
+            });
+        }
+    });
+    update_progress(&job, |progress| progress.done = true);
+}
+fn put_metadata(path: &String, md: &Metadata) -> Result<(), i32> {
+    let replicas = NetworkContext::get_instance()
+        .get_distributor()
+        .locate_file_metadata_replicas(path);
+    for host in replicas {
+        post_to(
+            host,
+            path.as_str(),
+            md.serialize(),
+            PostOption::ReplicateMetadata,
+        )?;
+    }
+    Ok(())
+}
+fn add_dirent(path: &String, is_dir: bool) -> Result<(), i32> {
+    if path == "/" {
+        return Ok(());
+    }
+    let dir = dirname(path);
+    let replicas = NetworkContext::get_instance()
+        .get_distributor()
+        .locate_file_metadata_replicas(&dir);
+    for host in replicas {
+        let record = DirentRecordData {
+            dir: dir.as_str(),
+            name: &path[path.rfind('/').unwrap() + 1..],
+            is_dir,
+        };
+        post_to(host, record, Vec::new(), PostOption::AddDirent)?;
+    }
+    Ok(())
+}
+// hosts holding data chunk 'chunk_id', in the order they are read from. a data chunk of an
+// erasure-coded file has one host, it is rebuilt from the rest of its stripe if that host fails
+fn chunk_hosts(
+    path: &String,
+    chunk_id: u64,
+    layout: &Option<ErasureLayout>,
+    placement: &Placement,
+) -> Vec<u64> {
+    let distributor = NetworkContext::get_instance().get_distributor();
+    match layout {
+        Some(layout) => vec![
+            distributor.locate_stripe(path, layout.stripe_of(chunk_id), layout.width())
+                [(chunk_id % layout.data) as usize],
+        ],
+        None => placement.locate(distributor.as_ref(), path, chunk_id),
+    }
+}
+// let the chunk hosts move the data chunks between their storage and the host file. on
+// stage-in every replica is written, on stage-out a chunk comes from its first replica able
+// to send it, or is rebuilt here once no host of an erasure-coded chunk could
+fn move_chunks(
+    stage_in: bool,
+    host_path: &String,
+    path: &String,
+    file_id: u64,
+    size: u64,
+    layout: &Option<ErasureLayout>,
+) -> Result<(), i32> {
+    let mut pending: Vec<u64> = (0..(size + CHUNK_SIZE - 1) / CHUNK_SIZE).collect();
+    let placement = file_placement(path);
+    let mut lost = Vec::new();
+    let mut rank = 0;
+    while !pending.is_empty() {
+        let mut groups: HashMap<u64, Vec<u64>> = HashMap::new();
+        for chunk_id in pending.iter() {
+            let hosts = chunk_hosts(path, *chunk_id, layout, &placement);
+            if stage_in {
+                for host in hosts {
+                    groups.entry(host).or_default().push(*chunk_id);
+                }
+            } else if let Some(host) = hosts.get(rank) {
+                groups.entry(*host).or_default().push(*chunk_id);
+            } else if layout.is_some() {
+                lost.push((*chunk_id, hosts[0]));
+            } else {
+                return Err(EIO);
+            }
+        }
+        // hosts work in parallel, each on batches of its chunks
+        let failed: Vec<u64> = thread::scope(|scope| {
+            let workers: Vec<_> = groups
+                .into_iter()
+                .map(|(host, chunks)| {
+                    scope.spawn(move || {
+                        let mut failed = Vec::new();
+                        for batch in chunks.chunks(STAGE_BATCH) {
+                            let stage_chunks = StageChunksData {
+                                stage_in,
+                                path: path.as_str(),
+                                file_id,
+                                host_path: host_path.as_str(),
+                                size,
+                                chunks: batch.to_vec(),
+                            };
+                            if let Err(e) =
+                                post_to(host, stage_chunks, Vec::new(), PostOption::StageChunks)
+                            {
+                                error_msg(
+                                    "server::stage::move_chunks".to_string(),
+                                    format!(
+                                        "error {} occurs while moving chunks of '{}' on host {}",
+                                        e, path, host
+                                    ),
+                                );
+                                failed.extend_from_slice(batch);
+                            }
+                        }
+                        failed
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().unwrap())
+                .collect()
+        });
+        if stage_in && !failed.is_empty() {
+            return Err(EIO);
+        }
+        pending = failed;
+        rank += 1;
+    }
+    match layout {
+        Some(layout) if !lost.is_empty() => {
+            rebuild_chunks(host_path, path, file_id, size, layout, &lost)
+        }
+        _ => Ok(()),
+    }
+}
+// write the data chunks of an erasure-coded file their host failed to send to the host file,
+// each rebuilt from the other pieces of its stripe. 'lost' holds (chunk id, its host)
+fn rebuild_chunks(
+    host_path: &String,
+    path: &String,
+    file_id: u64,
+    size: u64,
+    layout: &ErasureLayout,
+    lost: &[(u64, u64)],
+) -> Result<(), i32> {
+    let file = OpenOptions::new()
+        .write(true)
+        .open(host_path)
+        .map_err(|_e| EIO)?;
+    for (chunk_id, host) in lost {
+        let (stripe_id, piece) = layout.piece_of(*chunk_id);
+        let data = rebuild_piece(path, file_id, layout, stripe_id, piece, Some(*host))?;
+        let (offset, len) = chunk_range(*chunk_id, size);
+        file.write_all_at(&data[..len], offset)
+            .map_err(|_e| EIO)?;
+    }
+    Ok(())
+}
+// move chunks of a file between the local chunk storage and the host file, on the chunk host
+pub fn stage_chunks(data: &StageChunksData) -> i32 {
+    if !host_path_allowed(data.host_path) {
+        error_msg(
+            "server::stage::stage_chunks".to_string(),
+            format!("'{}' is not below a stage directory", data.host_path),
+        );
+        return EACCES;
+    }
+    let path = data.path.to_string();
+    let open_res = if data.stage_in {
+        File::open(data.host_path)
+    } else {
+        OpenOptions::new().write(true).open(data.host_path)
+    };
+    if let Err(e) = open_res {
+        error_msg(
+            "server::stage::stage_chunks".to_string(),
+            format!("fail to open '{}': {}", data.host_path, e),
+        );
+        return EIO;
+    }
+    let file = open_res.unwrap();
+    let mut buf = vec![0u8; CHUNK_SIZE as usize];
+    for chunk_id in data.chunks.iter() {
+        let (offset, len) = chunk_range(*chunk_id, data.size);
+        if data.stage_in {
+            if let Err(_e) = file.read_exact_at(&mut buf[..len], offset) {
+                return EIO;
+            }
+            let write_res = ChunkStorage::write_chunk(
+                &path,
+                data.file_id,
+                *chunk_id,
+                buf.as_mut_ptr(),
+                len as u64,
+                0,
+            );
+            if let Err(e) = write_res {
+                return e;
+            }
+        } else {
+            let read_res = ChunkStorage::read_chunk(
+                &path,
+                data.file_id,
+                *chunk_id,
+                buf.as_mut_ptr(),
+                CHUNK_SIZE,
+                0,
+            );
+            match read_res {
+                Ok(read) => {
+                    if let Err(_e) = file.write_all_at(&buf[..len.min(read as usize)], offset) {
+                        return EIO;
+                    }
+                }
+                // a hole, the host file is sized by the metadata host
+                Err(-2) => {}
+                Err(e) => return e,
+            }
+        }
+    }
+    0
+}
+
+// (host path, path in SimpleFS, is a directory) of the files and directories in 'host_path'.
+// links and special files have no place in SimpleFS
+fn list_dir(job: &Job, host_path: &String, path: &String) -> Vec<(String, String, bool)> {
+    let read_res = fs::read_dir(host_path);
+    if let Err(e) = read_res {
+        error_msg(
+            "server::stage::list_dir".to_string(),
+            format!("fail to list '{}': {}", host_path, e),
+        );
+        update_progress(job, |progress| progress.errors += 1);
+        return Vec::new();
+    }
+    let mut entries = Vec::new();
+    for entry in read_res.unwrap().flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let host_child = Path::new(host_path)
+            .join(&name)
+            .to_string_lossy()
+            .to_string();
+        match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() || file_type.is_file() => {
+                entries.push((host_child, child_path(path, &name), file_type.is_dir()))
+            }
+            _ => {}
+        }
+    }
+    entries
+}
+// files below 'host_dir' this host moves, with their paths in SimpleFS. the entries of
+// 'host_dir' are split by the primary metadata replica of their path, which walks the subtree
+// below its entries alone and moves everything in it, so every directory is listed by one
+// server. directories are created on the way
+fn stage_in_entries(job: &Job, host_dir: &String, sfs_dir: &String) -> Vec<(String, String)> {
+    let mut files = Vec::new();
+    let mut dirs = Vec::new();
+    if is_primary(sfs_dir) {
+        if let Err(e) = stage_in_dir(host_dir, sfs_dir) {
+            error_msg(
+                "server::stage::stage_in_entries".to_string(),
+                format!("error {} occurs while creating directory '{}'", e, sfs_dir),
+            );
+            update_progress(job, |progress| progress.errors += 1);
+        }
+    }
+    for (host_child, child, is_dir) in list_dir(job, host_dir, sfs_dir) {
+        if !is_primary(&child) {
+            continue;
+        }
+        if is_dir {
+            dirs.push((host_child, child));
+        } else {
+            files.push((host_child, child));
+        }
+    }
+    while let Some((host_path, path)) = dirs.pop() {
+        if let Err(e) = stage_in_dir(&host_path, &path) {
+            error_msg(
+                "server::stage::stage_in_entries".to_string(),
+                format!("error {} occurs while creating directory '{}'", e, path),
+            );
+            update_progress(job, |progress| progress.errors += 1);
+        }
+        for (host_child, child, is_dir) in list_dir(job, &host_path, &path) {
+            if is_dir {
+                dirs.push((host_child, child));
+            } else {
+                files.push((host_child, child));
+            }
+        }
+    }
+    files
+}
+fn stage_in_dir(host_path: &String, path: &String) -> Result<(), i32> {
+    match file_metadata(path) {
+        Ok(_) => return Ok(()),
+        Err(ENOENT) => {}
+        Err(e) => return Err(e),
+    }
+    let meta = fs::metadata(host_path).map_err(|_e| EIO)?;
+    let mut md = Metadata::new();
+    md.set_mode(S_IFDIR | (meta.mode() & 0o7777));
+    put_metadata(path, &md)?;
+    add_dirent(path, true)
+}
+// the size of the file, none if it is staged already
+fn stage_in_file(host_path: &String, path: &String) -> Result<Option<u64>, i32> {
+    let meta = fs::metadata(host_path).map_err(|_e| EIO)?;
+    let size = meta.len();
+    match file_metadata(path) {
+        Ok(md) => {
+            if md.get_size() as u64 == size && md.get_modify_time() == meta.mtime() {
+                return Ok(None);
+            }
+            // changed since or left half-way, it is staged from scratch
+            remove_file(path, md.get_file_id())?;
+        }
+        Err(ENOENT) => {}
+        Err(e) => return Err(e),
+    }
+    let layout = erasure_layout(StorageContext::get_instance().get_erasure_dirs(), path);
+    let mut md = Metadata::new();
+    md.set_mode(S_IFREG | (meta.mode() & 0o7777));
+    md.set_size(size as i64);
+    md.set_modify_time(STAGING_MTIME);
+    // ids carry the host handing them out, so they are unique whichever server stages the file
+    md.set_file_id(FileIds::get_instance().next()?);
+    let stuffed = ENABLE_STUFFING && size <= STUFF_SIZE && layout.is_none();
+    if !stuffed {
+        md.unstuff();
+    }
+    put_metadata(path, &md)?;
+    add_dirent(path, false)?;
+    if stuffed {
+        let data = fs::read(host_path).map_err(|_e| EIO)?;
+        let replicas = NetworkContext::get_instance()
+            .get_distributor()
+            .locate_file_metadata_replicas(path);
+        for host in replicas {
+            post_to(
+                host,
+                path.as_str(),
+                data.clone(),
+                PostOption::ReplicateStuff,
+            )?;
+        }
+    } else if size > 0 {
+        move_chunks(true, host_path, path, md.get_file_id(), size, &layout)?;
+        if let Some(layout) = layout {
+            encode_parity(host_path, path, md.get_file_id(), size, &layout)?;
+        }
+    }
+    // chunk hosts may have added the blocks of compressed chunks meanwhile
+    let mut md = file_metadata(path)?;
+    md.set_access_time(meta.atime());
+    md.set_modify_time(meta.mtime());
+    put_metadata(path, &md)?;
+    Ok(Some(size))
+}
+// chunks and metadata an earlier stage-in left of 'path'
+fn remove_file(path: &String, file_id: u64) -> Result<(), i32> {
+    for host in 0..NetworkContext::get_instance().get_clients().len() as u64 {
+        let remove = RemoveData {
+            path: path.as_str(),
+            file_id,
+        };
+        post_to(host, remove, Vec::new(), PostOption::Remove)?;
+    }
+    let replicas = NetworkContext::get_instance()
+        .get_distributor()
+        .locate_file_metadata_replicas(path);
+    for host in replicas {
+        let remove = PathData {
+            path: path.as_str(),
+            client: 0,
+        };
+        match post_to(host, remove, Vec::new(), PostOption::RemoveMeta) {
+            Ok(_) | Err(ENOENT) => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+// parity pieces of every stripe, computed from the host file
+fn encode_parity(
+    host_path: &String,
+    path: &String,
+    file_id: u64,
+    size: u64,
+    layout: &ErasureLayout,
+) -> Result<(), i32> {
+    let file = File::open(host_path).map_err(|_e| EIO)?;
+    let stripe_size = CHUNK_SIZE * layout.data;
+    for stripe_id in 0..(size + stripe_size - 1) / stripe_size {
+        let mut data = Vec::new();
+        for piece in 0..layout.data {
+            // pieces past the end of the file are zeros
+            let mut buf = vec![0u8; CHUNK_SIZE as usize];
+            let (offset, len) = chunk_range(layout.piece_chunk_id(stripe_id, piece), size);
+            file.read_exact_at(&mut buf[..len], offset)
+                .map_err(|_e| EIO)?;
+            data.push(buf);
+        }
+        let hosts = NetworkContext::get_instance()
+            .get_distributor()
+            .locate_stripe(path, stripe_id, layout.width());
+        let parity = ReedSolomon::from_layout(layout).encode(&data)?;
+        for (p, piece) in parity.into_iter().enumerate() {
+            let replicate = ReplicateChunkData {
+                path: path.as_str(),
+                file_id,
+                chunk_id: layout.parity_chunk_id(stripe_id, p as u64),
+            };
+            let host = hosts[(layout.data + p as u64) as usize];
+            post_to(host, replicate, piece, PostOption::ReplicateChunk)?;
+        }
+    }
+    Ok(())
+}
+
+// files of 'sfs_dir' this host moves, the ones it is the primary metadata replica of, with
+// their paths on the host. directories are created on the way
+fn stage_out_entries(job: &Job, host_dir: &String, sfs_dir: &String) -> Vec<(String, String)> {
+    let prefix = child_path(sfs_dir, "");
+    let mut files = Vec::new();
+    for (path, md_str) in MetadataDB::get_instance().get_all() {
+        if (path != *sfs_dir && !path.starts_with(&prefix)) || !is_primary(&path) {
+            continue;
+        }
+        let host_path = if path == *sfs_dir {
+            host_dir.clone()
+        } else {
+            Path::new(host_dir)
+                .join(&path[prefix.len()..])
+                .to_string_lossy()
+                .to_string()
+        };
+        let md = Metadata::deserialize(&md_str);
+        if S_ISDIR(md.get_mode()) {
+            if let Err(e) = fs::create_dir_all(&host_path) {
+                error_msg(
+                    "server::stage::stage_out_entries".to_string(),
+                    format!("fail to create '{}': {}", host_path, e),
+                );
+                update_progress(job, |progress| progress.errors += 1);
+            }
+        } else if S_ISREG(md.get_mode()) {
+            files.push((host_path, path));
+        }
+    }
+    files
+}
+// the size of the file, none if the host holds it already
+fn stage_out_file(host_path: &String, path: &String) -> Result<Option<u64>, i32> {
+    // links already below the host directory are not followed out of the stage directories
+    if !host_path_allowed(host_path) {
+        return Err(EACCES);
+    }
+    let md = Metadata::deserialize(&MetadataDB::get_instance().get(path).ok_or(ENOENT)?);
+    let size = md.get_size() as u64;
+    if let Ok(meta) = fs::metadata(host_path) {
+        if meta.len() == size && meta.mtime() == md.get_modify_time() {
+            return Ok(None);
+        }
+    }
+    if let Some(parent) = Path::new(host_path).parent() {
+        fs::create_dir_all(parent).map_err(|_e| EIO)?;
+    }
+    // holes have to read back as zeros, a file left half-way is written from scratch
+    let file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(host_path)
+        .map_err(|_e| EIO)?;
+    let layout = erasure_layout(StorageContext::get_instance().get_erasure_dirs(), path);
+    match handle_stuffed_data(path) {
+        Some(data) if layout.is_none() => file.write_all_at(&data, 0).map_err(|_e| EIO)?,
+        _ if size > 0 => move_chunks(false, host_path, path, md.get_file_id(), size, &layout)?,
+        _ => {}
+    }
+    file.set_len(size).map_err(|_e| EIO)?;
+    file.set_permissions(Permissions::from_mode(md.get_mode() & 0o7777))
+        .map_err(|_e| EIO)?;
+    let time = |secs: i64| UNIX_EPOCH + Duration::from_secs(secs.max(0) as u64);
+    let times = FileTimes::new()
+        .set_accessed(time(md.get_access_time()))
+        .set_modified(time(md.get_modify_time()));
+    file.set_times(times).map_err(|_e| EIO)?;
+    Ok(Some(size))
+}
@@ -0,0 +1,106 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+use nix::sys::statfs::statfs;
+use sfs_global::global::network::forward_data::ChunkStat;
+
+use crate::error_msg::error_msg;
+
+// where the chunks of files live. 'path' is the absolute path of the file inside the file system
+// and 'file_id' the id from its metadata, 0 for files created before file ids. a chunk holds at
+// most the chunk size of bytes and reads past its end are short
+pub trait ChunkBackend: Send + Sync {
+    // bytes written, the chunk is created or extended as needed
+    fn write(
+        &self,
+        path: &String,
+        file_id: u64,
+        chunk_id: u64,
+        buf: &[u8],
+        offset: u64,
+    ) -> Result<i64, i32>;
+    // bytes read, Err(-2) if the chunk was never written
+    fn read(
+        &self,
+        path: &String,
+        file_id: u64,
+        chunk_id: u64,
+        buf: &mut [u8],
+        offset: u64,
+    ) -> Result<i64, i32>;
+    // whether the chunk was written, or created ahead by 'precreate'
+    fn exists(&self, path: &String, file_id: u64, chunk_id: u64) -> bool;
+    // cut or extend a single chunk to 'length' bytes
+    fn truncate(&self, path: &String, file_id: u64, chunk_id: u64, length: u64);
+    // drop every chunk from 'chunk_start' on the way 'trims' tells
+    fn trim(&self, path: &String, file_id: u64, chunk_start: u64);
+    // drop a single chunk
+    fn remove(&self, path: &String, file_id: u64, chunk_id: u64);
+    // drop every chunk of the file
+    fn destroy(&self, path: &String, file_id: u64);
+    fn stat(&self) -> ChunkStat;
+    // make the chunks of the file durable
+    fn sync(&self, path: &String, file_id: u64) -> i32;
+    // create empty chunks ahead of the writes, only worth it where creating is expensive
+    fn precreate(&self, _path: &String, _file_id: u64, _chunks: &Vec<u64>) -> i32 {
+        0
+    }
+    // point the chunk at a content of 'len' bytes with 'digest' the backend holds already,
+    // false if it holds none or the chunk is longer. only backends storing by content can
+    fn link(
+        &self,
+        _path: &String,
+        _file_id: u64,
+        _chunk_id: u64,
+        _digest: &[u8],
+        _len: u64,
+    ) -> bool {
+        false
+    }
+}
+// locks striped over their keys, a chunk as (path, chunk id) or a content by its digest. two
+// keys seldom share a lock
+pub struct StripedLocks {
+    locks_: Vec<Mutex<()>>,
+}
+impl StripedLocks {
+    pub fn new(stripes: usize) -> StripedLocks {
+        StripedLocks {
+            locks_: (0..stripes).map(|_| Mutex::new(())).collect(),
+        }
+    }
+    pub fn lock<K: Hash + ?Sized>(&self, key: &K) -> MutexGuard<()> {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        self.locks_[hasher.finish() as usize % self.locks_.len()]
+            .lock()
+            .unwrap()
+    }
+}
+// capacity of the local file system holding 'dir'
+pub fn fs_chunk_stat(dir: &String, chunk_size: u64) -> ChunkStat {
+    let statfs = statfs(Path::new(dir));
+    if let Err(_e) = statfs {
+        error_msg(
+            "server::storage::backend::fs_chunk_stat".to_string(),
+            "error occurs while get fs stat".to_string(),
+        );
+        return ChunkStat {
+            chunk_size: 0,
+            chunk_total: 0,
+            chunk_free: 0,
+            tiers: Vec::new(),
+        };
+    }
+    let statfs = statfs.unwrap();
+    let bytes_tot = statfs.block_size() as u64 * statfs.blocks();
+    let bytes_free = statfs.block_size() as u64 * statfs.blocks_available();
+    ChunkStat {
+        chunk_size,
+        chunk_total: bytes_tot / chunk_size,
+        chunk_free: bytes_free / chunk_size,
+        tiers: Vec::new(),
+    }
+}
@@ -0,0 +1,41 @@
+use sfs_global::global::util::checksum_util::chunk_checksum;
+
+use super::chunk_record_db::{ChunkRecord, ChunkRecordDB};
+
+// bytes covered by one checksum, a multiple of the cipher block so a read of whole checksum
+// blocks decrypts
+pub const CHECKSUM_BLOCK_SIZE: usize = 4096;
+
+// checksum of every block of 'data', the last one may be short
+pub fn block_checksums(data: &[u8]) -> Vec<u64> {
+    data.chunks(CHECKSUM_BLOCK_SIZE).map(chunk_checksum).collect()
+}
+
+fn decode_u64(val: &[u8]) -> u64 {
+    let mut num = [0u8; 8];
+    num.copy_from_slice(&val[..8]);
+    u64::from_be_bytes(num)
+}
+// checksums of the blocks of a chunk. the file id comes first, the scrubber reads the chunk by it
+pub struct ChunkChecksums {
+    pub file_id: u64,
+    pub sums: Vec<u64>,
+}
+impl ChunkRecord for ChunkChecksums {
+    fn encode(&self) -> Vec<u8> {
+        let mut val = self.file_id.to_be_bytes().to_vec();
+        for checksum in &self.sums {
+            val.extend_from_slice(&checksum.to_be_bytes());
+        }
+        val
+    }
+    fn decode(val: &[u8]) -> ChunkChecksums {
+        ChunkChecksums {
+            file_id: if val.len() >= 8 { decode_u64(val) } else { 0 },
+            sums: val.get(8..).unwrap_or(&[]).chunks(8).map(decode_u64).collect(),
+        }
+    }
+}
+// checksums of every chunk on this host. a chunk without checksums was written before checksums
+// and is trusted. a lost update would flag a good chunk
+pub type ChecksumDB = ChunkRecordDB<ChunkChecksums>;
@@ -0,0 +1,87 @@
+use std::marker::PhantomData;
+use std::path::Path;
+
+use libc::EIO;
+use rocksdb::{IteratorMode, Options, WriteOptions, DB};
+
+use crate::error_msg::error_msg;
+
+use super::rocksdb_backend::{chunk_ids, chunk_key};
+
+// what a database of chunk records keeps for a chunk
+pub trait ChunkRecord: Sized {
+    fn encode(&self) -> Vec<u8>;
+    fn decode(val: &[u8]) -> Self;
+}
+// a record for some chunks of this host, keyed like the chunks of the "rocksdb" backend.
+// writes go through the write-ahead log. 'name_' tells the records apart in the log
+pub struct ChunkRecordDB<R: ChunkRecord> {
+    db: DB,
+    write_opts: WriteOptions,
+    name_: &'static str,
+    record_: PhantomData<R>,
+}
+impl<R: ChunkRecord> ChunkRecordDB<R> {
+    pub fn new(path: &String, name: &'static str) -> Option<ChunkRecordDB<R>> {
+        let mut options = Options::default();
+        options.create_if_missing(true);
+        if let Ok(rdb) = DB::open(&options, Path::new(path)) {
+            Some(ChunkRecordDB {
+                db: rdb,
+                write_opts: WriteOptions::default(),
+                name_: name,
+                record_: PhantomData,
+            })
+        } else {
+            error_msg(
+                "server::storage::chunk_record_db::new".to_string(),
+                format!("fail to open database of {}", name),
+            );
+            None
+        }
+    }
+    pub fn get(&self, file_path: &String, chunk_id: u64) -> Option<R> {
+        match self.db.get(chunk_key(file_path, chunk_id)) {
+            Ok(Some(val)) => Some(R::decode(&val)),
+            _ => None,
+        }
+    }
+    // Err(EIO) if the record is not kept
+    pub fn put(&self, file_path: &String, chunk_id: u64, record: &R) -> Result<(), i32> {
+        let key = chunk_key(file_path, chunk_id);
+        if let Err(_e) = self.db.put_opt(key, record.encode(), &self.write_opts) {
+            error_msg(
+                "server::storage::chunk_record_db::put".to_string(),
+                format!("fail to record {} of chunk {} of '{}'", self.name_, chunk_id, file_path),
+            );
+            return Err(EIO);
+        }
+        Ok(())
+    }
+    pub fn remove(&self, file_path: &String, chunk_id: u64) {
+        if let Err(_e) = self.db.delete_opt(chunk_key(file_path, chunk_id), &self.write_opts) {
+            error_msg(
+                "server::storage::chunk_record_db::remove".to_string(),
+                format!("fail to remove {} of chunk {} of '{}'", self.name_, chunk_id, file_path),
+            );
+        }
+    }
+    // chunk ids of the file from 'chunk_start' on
+    pub fn chunk_ids(&self, file_path: &String, chunk_start: u64) -> Vec<u64> {
+        chunk_ids(&self.db, file_path, chunk_start)
+    }
+    // (path, chunk id, record) of every chunk having a record
+    pub fn get_all(&self) -> Vec<(String, u64, R)> {
+        let mut records = Vec::new();
+        for (k, v) in self.db.iterator(IteratorMode::Start) {
+            if k.len() < 9 || k[k.len() - 9] != 0 {
+                continue;
+            }
+            let mut id = [0u8; 8];
+            id.copy_from_slice(&k[k.len() - 8..]);
+            let path = String::from_utf8_lossy(&k[..k.len() - 9]).to_string();
+            records.push((path, u64::from_be_bytes(id), R::decode(&v)));
+        }
+        records
+    }
+}
@@ -0,0 +1,21 @@
+use super::chunk_record_db::{ChunkRecord, ChunkRecordDB};
+
+// (logical, physical)
+impl ChunkRecord for (u64, u64) {
+    fn encode(&self) -> Vec<u8> {
+        let mut val = self.0.to_be_bytes().to_vec();
+        val.extend_from_slice(&self.1.to_be_bytes());
+        val
+    }
+    fn decode(val: &[u8]) -> (u64, u64) {
+        let mut logical = [0u8; 8];
+        let mut physical = [0u8; 8];
+        logical.copy_from_slice(&val[..8]);
+        physical.copy_from_slice(&val[8..16]);
+        (u64::from_be_bytes(logical), u64::from_be_bytes(physical))
+    }
+}
+// logical and physical size of every chunk of the compressed files on this host. a chunk is kept
+// compressed when its physical size is below its logical one, compressing does not pay off for
+// every chunk
+pub type ChunkSizeDB = ChunkRecordDB<(u64, u64)>;
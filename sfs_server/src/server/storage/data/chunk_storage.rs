@@ -1,41 +1,105 @@
 use std::fs;
 use std::os::unix::fs::PermissionsExt;
-#[allow(unused)]
-use std::os::unix::prelude::FileExt;
 use std::path::Path;
+use std::sync::MutexGuard;
 
-use libc::{S_IRUSR, S_IWUSR};
-use nix::sys::statfs::statfs;
+use libc::{EDQUOT, EIO, S_IRUSR, S_IWUSR};
 
-use sfs_global::global::fsconfig::ENABLE_STUFFING;
+use lz4_flex::block::{compress_prepend_size, decompress_size_prepended};
+use sfs_global::global::compression::is_compressed;
+use sfs_global::global::erasure::{erasure_layout, is_parity_chunk, trims};
 use sfs_global::global::network::config::CHUNK_SIZE;
 use sfs_global::global::network::forward_data::ChunkStat;
+use sfs_global::global::util::net_util::get_my_hostname;
 use sfs_global::global::util::path_util::is_absolute;
 
 use lazy_static::*;
 
 use crate::error_msg::error_msg;
-use crate::server::config::{STUFF_WITH_ROCKSDB, TRUNCATE_DIRECTORY};
+use crate::handle::report_blocks;
+use crate::server::config::{MEMORY_BACKEND_CAPACITY, TRUNCATE_DIRECTORY};
 use crate::server::filesystem::storage_context::StorageContext;
+use crate::server::network::network_context::NetworkContext;
+use crate::server::space::SpaceTable;
 
-use super::stuff_db::StuffDB;
+use super::backend::{ChunkBackend, StripedLocks};
+use super::checksum_db::{block_checksums, ChecksumDB, ChunkChecksums, CHECKSUM_BLOCK_SIZE};
+use super::chunk_size_db::ChunkSizeDB;
+use super::cipher::{Cipher, CIPHER_BLOCK_SIZE, NONCE_SIZE};
+use super::dedup_backend::DedupBackend;
+use super::file_backend::FileBackend;
+use super::memory_backend::MemoryBackend;
+use super::nonce_db::NonceDB;
+use super::rocksdb_backend::RocksDBBackend;
+use super::tiered_backend::TieredBackend;
 
 #[allow(unused_must_use)]
 pub fn init_chunk() -> ChunkStorage {
-    let chunk_storage_path =
-        StorageContext::get_instance().get_rootdir().clone() + &"/data/chunks".to_string();
+    let backend = StorageContext::get_instance().get_chunk_backend().clone();
+    let chunk_storage_path = StorageContext::get_instance().get_rootdir().clone()
+        + if backend == "rocksdb" {
+            "/data/chunk_db"
+        } else {
+            "/data/chunks"
+        };
     if TRUNCATE_DIRECTORY {
         std::fs::remove_dir_all(Path::new(&chunk_storage_path));
+        std::fs::remove_dir_all(Path::new(&ChunkStorage::get_checksum_path(&chunk_storage_path)));
+    }
+    // chunks of the "memory" backend vanish with the server, so do their sizes, nonces, maps
+    // and the chunks moved down from them
+    if TRUNCATE_DIRECTORY || backend == "memory" {
+        std::fs::remove_dir_all(Path::new(&ChunkStorage::get_size_path(&chunk_storage_path)));
+        std::fs::remove_file(Path::new(&ChunkStorage::get_compressed_dirs_path(
+            &chunk_storage_path,
+        )));
+        std::fs::remove_dir_all(Path::new(&ChunkStorage::get_nonce_path(&chunk_storage_path)));
+        std::fs::remove_dir_all(Path::new(&ChunkStorage::get_dedup_path(&chunk_storage_path)));
+        std::fs::remove_dir_all(Path::new(&ChunkStorage::get_tier_path(&chunk_storage_path)));
+        if let Some(capacity_path) = ChunkStorage::get_capacity_path() {
+            std::fs::remove_dir_all(Path::new(&capacity_path));
+        }
     }
     std::fs::create_dir_all(Path::new(&chunk_storage_path))
         .expect("fail to create chunk storage directory");
+    if let Some(capacity_path) = ChunkStorage::get_capacity_path() {
+        std::fs::create_dir_all(Path::new(&capacity_path))
+            .expect("fail to create capacity tier directory");
+    }
 
-    return ChunkStorage::new(&chunk_storage_path, CHUNK_SIZE)
+    return ChunkStorage::new(&chunk_storage_path, CHUNK_SIZE, &backend)
         .expect("fail to create chunk storage");
 }
+// 512-byte blocks taken by 'size' bytes on disk, what st_blocks counts
+fn blocks_of(size: u64) -> i64 {
+    ((size + 511) / 512) as i64
+}
+// locks of the chunks are striped, a chunk is not checked while it changes
+pub const CHUNK_LOCKS: usize = 64;
+// chunks of the files on this host, kept by the backend chosen with "chunk_backend", which
+// spills cold chunks to "capacity_tier" and stores identical chunks once with "dedup".
+// stuffed files have no chunks, their content is kept by the metadata hosts.
+// with checksums on, the blocks of a chunk a read or a partial write touches are checked
+// against their checksums.
+// chunks of the files below "compress_dirs" are compressed as a whole, a partial write
+// rewrites the whole chunk. with a key chunks are encrypted, a write re-encrypts the cipher
+// blocks it touches
 pub struct ChunkStorage {
     pub root_path_: String,
     pub chunk_size_: u64,
+    backend_: Box<dyn ChunkBackend>,
+    // none for the "memory" backend, its chunks vanish with the server
+    checksums_: Option<ChecksumDB>,
+    sizes_: ChunkSizeDB,
+    nonces_: NonceDB,
+    locks_: StripedLocks,
+    // none without a key, chunks are kept in plain then
+    cipher_: Option<Cipher>,
+    compress_dirs_: Vec<String>,
+    // "compress_dirs" of now and of before, only their files may have compressed chunks
+    compressed_dirs_: Vec<String>,
+    // where the change of the 512-byte blocks a chunk takes goes
+    report_: fn(&String, u64, i64),
 }
 lazy_static! {
     static ref CNK: ChunkStorage = init_chunk();
@@ -44,51 +108,7 @@ impl ChunkStorage {
     pub fn get_instance() -> &'static ChunkStorage {
         &CNK
     }
-    pub fn default() -> ChunkStorage {
-        ChunkStorage {
-            root_path_: "".to_string(),
-            chunk_size_: CHUNK_SIZE,
-        }
-    }
-    pub fn absolute(internel_path: &String) -> String {
-        if is_absolute(&internel_path) {
-            error_msg(
-                "server::storage::chunk_storage::absolute".to_string(),
-                "path should be relative".to_string(),
-            );
-            return internel_path.clone();
-        }
-        format!("{}/{}", CNK.get_root_path(), internel_path)
-    }
-    pub fn get_chunks_dir(file_path: &String) -> String {
-        if !is_absolute(file_path) {
-            error_msg(
-                "server::storage::chunk_storage::get_chunks_dir".to_string(),
-                "path should be absolute".to_string(),
-            );
-            return file_path.replace("/", ":");
-        }
-        let chunk_dir = file_path[1..].to_string();
-        chunk_dir.replace("/", ":")
-    }
-    pub fn get_chunks_path(file_path: &String, chunk_id: u64) -> String {
-        format!("{}/{}", ChunkStorage::get_chunks_dir(file_path), chunk_id)
-    }
-    pub fn init_chunk_space(file_path: &String) {
-        let chunk_dir = ChunkStorage::absolute(&ChunkStorage::get_chunks_dir(file_path));
-        let path = Path::new(&chunk_dir);
-        if path.exists() {
-            return;
-        }
-        if let Err(_e) = fs::create_dir_all(path) {
-            error_msg(
-                "server::storage::chunk_storage::init_chunk_space".to_string(),
-                "fail to create chunk directory".to_string(),
-            );
-        }
-    }
-
-    pub fn new(path: &String, chunk_size: u64) -> Option<ChunkStorage> {
+    pub fn new(path: &String, chunk_size: u64, backend_name: &String) -> Option<ChunkStorage> {
         if !is_absolute(&path) {
             error_msg(
                 "server::storage::chunk_storage::new".to_string(),
@@ -96,7 +116,7 @@ impl ChunkStorage {
             );
             return None;
         }
-        let perm = std::fs::metadata(path).unwrap().permissions();
+        let perm = fs::metadata(path).unwrap().permissions();
         let mode: u32 = perm.mode();
         if mode & S_IRUSR == 0 || mode & S_IWUSR == 0 {
             error_msg(
@@ -104,225 +124,680 @@ impl ChunkStorage {
                 "can't create chunk storage with enough permissions".to_string(),
             );
         }
+        let backend: Box<dyn ChunkBackend> = match backend_name.as_str() {
+            "file" => Box::new(FileBackend::new(path, chunk_size)),
+            "rocksdb" => Box::new(RocksDBBackend::new(path, chunk_size)?),
+            "memory" => Box::new(MemoryBackend::new(MEMORY_BACKEND_CAPACITY, chunk_size)),
+            _ => {
+                error_msg(
+                    "server::storage::chunk_storage::new".to_string(),
+                    format!("unknown chunk backend '{}'", backend_name),
+                );
+                return None;
+            }
+        };
+        let backend: Box<dyn ChunkBackend> = match ChunkStorage::get_capacity_path() {
+            Some(capacity_path) => Box::new(TieredBackend::new(
+                &ChunkStorage::get_tier_path(path),
+                backend,
+                Box::new(FileBackend::new(&capacity_path, chunk_size)),
+                chunk_size,
+            )?),
+            None => backend,
+        };
+        let backend: Box<dyn ChunkBackend> = if StorageContext::get_instance().get_dedup() {
+            Box::new(DedupBackend::new(
+                &ChunkStorage::get_dedup_path(path),
+                backend,
+                chunk_size,
+            )?)
+        } else {
+            backend
+        };
+        ChunkStorage::with_backend(
+            path,
+            chunk_size,
+            backend,
+            StorageContext::get_instance().get_checksum() && backend_name != "memory",
+            StorageContext::get_instance().get_compress_dirs().clone(),
+            StorageContext::get_instance().get_encryption_key().map(Cipher::new),
+            report_blocks,
+        )
+    }
+    // chunks kept by 'backend', their checksums, sizes and nonces in databases next to 'path'
+    pub fn with_backend(
+        path: &String,
+        chunk_size: u64,
+        backend: Box<dyn ChunkBackend>,
+        checksum: bool,
+        compress_dirs: Vec<String>,
+        cipher: Option<Cipher>,
+        report: fn(&String, u64, i64),
+    ) -> Option<ChunkStorage> {
+        let checksums = if checksum {
+            Some(ChecksumDB::new(&ChunkStorage::get_checksum_path(path), "checksums")?)
+        } else {
+            None
+        };
+        let compressed_dirs = ChunkStorage::remember_compress_dirs(path, &compress_dirs);
         Some(ChunkStorage {
             root_path_: path.clone(),
             chunk_size_: chunk_size,
+            backend_: backend,
+            checksums_: checksums,
+            sizes_: ChunkSizeDB::new(&ChunkStorage::get_size_path(path), "sizes")?,
+            nonces_: NonceDB::new(&ChunkStorage::get_nonce_path(path), "nonces")?,
+            locks_: StripedLocks::new(CHUNK_LOCKS),
+            cipher_: cipher,
+            compress_dirs_: compress_dirs,
+            compressed_dirs_: compressed_dirs,
+            report_: report,
         })
     }
-    pub fn destroy_chunk_space(file_path: &String) {
-        if ENABLE_STUFFING && STUFF_WITH_ROCKSDB {
-            StuffDB::get_instance().remove(file_path);
+    // next to the chunks, a directory inside could be taken for the chunks of a file
+    pub fn get_checksum_path(root_path: &String) -> String {
+        format!("{}_checksum", root_path.trim_end_matches('/'))
+    }
+    pub fn get_size_path(root_path: &String) -> String {
+        format!("{}_sizes", root_path.trim_end_matches('/'))
+    }
+    pub fn get_nonce_path(root_path: &String) -> String {
+        format!("{}_nonces", root_path.trim_end_matches('/'))
+    }
+    pub fn get_compressed_dirs_path(root_path: &String) -> String {
+        format!("{}_compress_dirs", root_path.trim_end_matches('/'))
+    }
+    pub fn get_dedup_path(root_path: &String) -> String {
+        format!("{}_dedup", root_path.trim_end_matches('/'))
+    }
+    pub fn get_tier_path(root_path: &String) -> String {
+        format!("{}_tiers", root_path.trim_end_matches('/'))
+    }
+    // chunks on the capacity tier are kept by the "file" backend, in a directory of each host
+    // so the tier may be a shared file system
+    pub fn get_capacity_path() -> Option<String> {
+        let capacity_tier = StorageContext::get_instance().get_capacity_tier();
+        if capacity_tier.len() == 0 {
+            return None;
+        }
+        Some(format!(
+            "{}/{}/data/chunks",
+            capacity_tier.trim_end_matches('/'),
+            get_my_hostname(true)
+        ))
+    }
+    // "compress_dirs" along with the ones recorded next to the chunks at 'root_path' before,
+    // which are recorded as well
+    fn remember_compress_dirs(root_path: &String, compress_dirs: &[String]) -> Vec<String> {
+        let path = ChunkStorage::get_compressed_dirs_path(root_path);
+        let mut dirs: Vec<String> = fs::read_to_string(&path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or(Vec::new());
+        let known = dirs.len();
+        for dir in compress_dirs {
+            if !dirs.contains(dir) {
+                dirs.push(dir.clone());
+            }
+        }
+        if dirs.len() > known {
+            if let Err(_e) = fs::write(&path, serde_json::to_string(&dirs).unwrap()) {
+                error_msg(
+                    "server::storage::chunk_storage::remember_compress_dirs".to_string(),
+                    format!("fail to record compressed directories in {}", path),
+                );
+            }
+        }
+        dirs
+    }
+    // a chunk compressed before its directory left "compress_dirs" stays compressed. files
+    // outside the directories ever compressed have none
+    fn compressed(&self, file_path: &String, chunk_id: u64) -> bool {
+        if !is_compressed(&self.compressed_dirs_, file_path) {
+            return false;
         }
-        let chunk_dir = ChunkStorage::absolute(&ChunkStorage::get_chunks_dir(file_path));
-        if let Err(_e) = fs::remove_dir_all(Path::new(&chunk_dir)) {
+        is_compressed(&self.compress_dirs_, file_path)
+            || self.sizes_.get(file_path, chunk_id).is_some()
+    }
+    fn lock_chunk(&self, file_path: &String, chunk_id: u64) -> MutexGuard<()> {
+        self.locks_.lock(&(file_path, chunk_id))
+    }
+    // checksums of the blocks of the chunk, none with checksums off or before its first write
+    fn get_checksums(&self, file_path: &String, chunk_id: u64) -> Option<Vec<u64>> {
+        let checksums = self.checksums_.as_ref()?;
+        checksums.get(file_path, chunk_id).map(|record| record.sums)
+    }
+    // a lost update is logged, the scrubber flags the chunk then
+    fn put_checksums(&self, file_path: &String, file_id: u64, chunk_id: u64, sums: Vec<u64>) {
+        if let Some(checksums) = &self.checksums_ {
+            let _ = checksums.put(file_path, chunk_id, &ChunkChecksums { file_id, sums });
+        }
+    }
+    // end of the checksum block holding byte 'end' - 1, or of the chunk
+    fn block_end(&self, end: usize) -> usize {
+        let end = (end + CHECKSUM_BLOCK_SIZE - 1) / CHECKSUM_BLOCK_SIZE * CHECKSUM_BLOCK_SIZE;
+        usize::min(end, self.chunk_size_ as usize)
+    }
+    // stored bytes of the chunk from 'start' up to 'end' or its end, decrypted. 'start' starts
+    // a cipher block
+    fn load(
+        &self,
+        file_path: &String,
+        file_id: u64,
+        chunk_id: u64,
+        start: usize,
+        end: usize,
+    ) -> Result<Vec<u8>, i32> {
+        let mut data = vec![0u8; end - start];
+        let len = self.backend_.read(file_path, file_id, chunk_id, &mut data, start as u64)?;
+        data.truncate(len as usize);
+        if let Some(nonces) = self.nonces_.get(file_path, chunk_id) {
+            let nonces = nonces.get(start / CIPHER_BLOCK_SIZE * NONCE_SIZE..).unwrap_or(&[]);
+            let decrypt_res = match &self.cipher_ {
+                Some(cipher) => cipher.decrypt_blocks(&mut data, nonces),
+                None => Err(EIO),
+            };
+            if let Err(e) = decrypt_res {
+                error_msg(
+                    "server::storage::chunk_storage::load".to_string(),
+                    format!("fail to decrypt chunk {} of '{}'", chunk_id, file_path),
+                );
+                return Err(e);
+            }
+        }
+        Ok(data)
+    }
+    // Err(EIO) if the checksum blocks 'data' holds from 'start' on do not match their checksums.
+    // 'last' tells 'data' runs to the end of the chunk
+    fn verify_blocks(
+        &self,
+        file_path: &String,
+        chunk_id: u64,
+        start: usize,
+        data: &[u8],
+        last: bool,
+    ) -> Result<(), i32> {
+        let checksums = match self.get_checksums(file_path, chunk_id) {
+            Some(checksums) => checksums,
+            None => return Ok(()),
+        };
+        let blocks = block_checksums(data);
+        let stored = &checksums[usize::min(start / CHECKSUM_BLOCK_SIZE, checksums.len())..];
+        let stored = if last {
+            stored
+        } else {
+            &stored[..usize::min(blocks.len(), stored.len())]
+        };
+        if stored != blocks.as_slice() {
             error_msg(
-                "server::storage::chunk_storage::destroy_chunk_space".to_string(),
-                "fail to remove chunk directory".to_string(),
+                "server::storage::chunk_storage::verify_blocks".to_string(),
+                format!("checksum mismatch on chunk {} of '{}'", chunk_id, file_path),
             );
+            return Err(EIO);
+        }
+        Ok(())
+    }
+    // whole content of the chunk, Err(EIO) if it does not match its checksums any longer
+    fn read_whole(&self, file_path: &String, file_id: u64, chunk_id: u64) -> Result<Vec<u8>, i32> {
+        let mut chunk = self.load(file_path, file_id, chunk_id, 0, self.chunk_size_ as usize)?;
+        if let Some((logical, physical)) = self.sizes_.get(file_path, chunk_id) {
+            if physical < logical {
+                chunk.truncate(physical as usize);
+                chunk = match decompress_size_prepended(&chunk) {
+                    Ok(chunk) => chunk,
+                    Err(_e) => {
+                        error_msg(
+                            "server::storage::chunk_storage::read_whole".to_string(),
+                            format!("fail to decompress chunk {} of '{}'", chunk_id, file_path),
+                        );
+                        return Err(EIO);
+                    }
+                };
+            }
+        }
+        self.verify_blocks(file_path, chunk_id, 0, &chunk, true)?;
+        Ok(chunk)
+    }
+    // checksum blocks a change of the bytes from 'offset' to 'end' has to rewrite. they reach
+    // back to the old end of the chunk so the gap up to 'offset' is covered. a chunk without
+    // checksums or nonces gets them for its whole content
+    fn block_range(
+        &self,
+        file_path: &String,
+        chunk_id: u64,
+        offset: usize,
+        end: usize,
+    ) -> (usize, usize) {
+        let whole = (0, self.chunk_size_ as usize);
+        let mut start = offset;
+        if self.checksums_.is_some() {
+            match self.get_checksums(file_path, chunk_id) {
+                Some(checksums) => {
+                    let last = checksums.len().saturating_sub(1) * CHECKSUM_BLOCK_SIZE;
+                    start = usize::min(start, last);
+                }
+                None => return whole,
+            }
+        }
+        if self.cipher_.is_some() {
+            match self.nonces_.get(file_path, chunk_id) {
+                Some(nonces) => {
+                    let last = (nonces.len() / NONCE_SIZE).saturating_sub(1) * CIPHER_BLOCK_SIZE;
+                    start = usize::min(start, last);
+                }
+                None => return whole,
+            }
+        }
+        (start / CHECKSUM_BLOCK_SIZE * CHECKSUM_BLOCK_SIZE, self.block_end(end))
+    }
+    // write 'data' at 'offset' of the stored chunk, with a key under fresh nonces. 'offset'
+    // starts a cipher block then. the nonces are recorded first, the write fails without them
+    fn store(
+        &self,
+        file_path: &String,
+        file_id: u64,
+        chunk_id: u64,
+        data: &[u8],
+        offset: u64,
+    ) -> Result<i64, i32> {
+        let cipher = match &self.cipher_ {
+            Some(cipher) => cipher,
+            None => return self.backend_.write(file_path, file_id, chunk_id, data, offset),
+        };
+        let mut nonces = self.nonces_.get(file_path, chunk_id).unwrap_or(Vec::new());
+        let mut data = data.to_vec();
+        cipher.encrypt_blocks(&mut data, offset as usize / CIPHER_BLOCK_SIZE, &mut nonces);
+        self.nonces_.put(file_path, chunk_id, &nonces)?;
+        self.backend_.write(file_path, file_id, chunk_id, &data, offset)
+    }
+    // cut the stored chunk, a cut cipher block still decrypts
+    fn shorten(&self, file_path: &String, file_id: u64, chunk_id: u64, length: u64) {
+        self.backend_.truncate(file_path, file_id, chunk_id, length);
+        if let Some(mut nonces) = self.nonces_.get(file_path, chunk_id) {
+            let blocks = (length as usize + CIPHER_BLOCK_SIZE - 1) / CIPHER_BLOCK_SIZE;
+            nonces.truncate(blocks * NONCE_SIZE);
+            // nonces of blocks cut off are not used any longer, 'put' tells of a failure
+            let _ = self.nonces_.put(file_path, chunk_id, &nonces);
+        }
+    }
+    // whether the chunk was written before, a hole was not
+    fn holds(&self, file_path: &String, file_id: u64, chunk_id: u64) -> bool {
+        self.backend_.exists(file_path, file_id, chunk_id)
+    }
+    // replace the content of a chunk, compressed if 'compress' and that saves space
+    fn write_whole(
+        &self,
+        file_path: &String,
+        file_id: u64,
+        chunk_id: u64,
+        chunk: &[u8],
+        compress: bool,
+    ) -> Result<(), i32> {
+        if !compress {
+            self.store(file_path, file_id, chunk_id, chunk, 0)?;
+            self.shorten(file_path, file_id, chunk_id, chunk.len() as u64);
+            return Ok(());
+        }
+        let old_physical = match self.sizes_.get(file_path, chunk_id) {
+            Some((_logical, physical)) => physical,
+            None => 0,
+        };
+        let compressed = compress_prepend_size(chunk);
+        let data = if compressed.len() < chunk.len() {
+            &compressed[..]
+        } else {
+            chunk
+        };
+        self.store(file_path, file_id, chunk_id, data, 0)?;
+        self.shorten(file_path, file_id, chunk_id, data.len() as u64);
+        self.sizes_
+            .put(file_path, chunk_id, &(chunk.len() as u64, data.len() as u64))?;
+        (self.report_)(
+            file_path,
+            chunk_id,
+            blocks_of(data.len() as u64) - blocks_of(old_physical),
+        );
+        Ok(())
+    }
+    pub fn precreate_chunks(file_path: &String, file_id: u64, chunks: &Vec<u64>) -> i32 {
+        CNK.backend_.precreate(file_path, file_id, chunks)
+    }
+    pub fn destroy_chunk_space(file_path: &String, file_id: u64) {
+        CNK.destroy(file_path, file_id);
+    }
+    pub fn destroy(&self, file_path: &String, file_id: u64) {
+        self.backend_.destroy(file_path, file_id);
+        for chunk_id in self.sizes_.chunk_ids(file_path, 0) {
+            self.sizes_.remove(file_path, chunk_id);
         }
+        for chunk_id in self.nonces_.chunk_ids(file_path, 0) {
+            self.nonces_.remove(file_path, chunk_id);
+        }
+        if let Some(checksums) = &self.checksums_ {
+            for chunk_id in checksums.chunk_ids(file_path, 0) {
+                checksums.remove(file_path, chunk_id);
+            }
+        }
+    }
+    // chunk operation of the RDMA write server. a nearly full server takes no new chunks from
+    // clients, they have them placed on other hosts. EDQUOT tells the refusal apart from a write
+    // the disk had no room for
+    pub fn write_client_chunk(
+        file_path: &String,
+        file_id: u64,
+        chunk_id: u64,
+        buf: *mut u8,
+        size: u64,
+        offset: u64,
+    ) -> Result<i64, i32> {
+        let local = NetworkContext::get_instance().get_local_host_id();
+        if SpaceTable::get_instance().nearly_full(local)
+            && !CNK.holds(file_path, file_id, chunk_id)
+        {
+            return Err(EDQUOT);
+        }
+        ChunkStorage::write_chunk(file_path, file_id, chunk_id, buf, size, offset)
     }
+    // same shape as 'read_chunk'
     pub fn write_chunk(
         file_path: &String,
+        file_id: u64,
         chunk_id: u64,
         buf: *mut u8,
         size: u64,
         offset: u64,
     ) -> Result<i64, i32> {
         let buf = unsafe { std::slice::from_raw_parts(buf.cast(), size as usize) };
-        if size + offset > CNK.get_chunk_size() {
+        CNK.write(file_path, file_id, chunk_id, buf, offset)
+    }
+    pub fn write(
+        &self,
+        file_path: &String,
+        file_id: u64,
+        chunk_id: u64,
+        buf: &[u8],
+        offset: u64,
+    ) -> Result<i64, i32> {
+        let size = buf.len() as u64;
+        if size + offset > self.get_chunk_size() {
             error_msg(
                 "server::storage::chunk_storage::write_chunk".to_string(),
                 "beyond chunk storage range".to_string(),
             );
         }
-        if ENABLE_STUFFING && STUFF_WITH_ROCKSDB && chunk_id == 0 {
-            StuffDB::get_instance().write(file_path, offset, size, buf);
-            return Ok(size as i64);
+        let compressed = self.compressed(file_path, chunk_id);
+        let encrypted = self.cipher_.is_some();
+        if self.checksums_.is_none() && !compressed && !encrypted {
+            return self.backend_.write(file_path, file_id, chunk_id, buf, offset);
         }
-        ChunkStorage::init_chunk_space(file_path);
-        let chunk_path =
-            ChunkStorage::absolute(&ChunkStorage::get_chunks_path(file_path, chunk_id));
-        let f = std::fs::OpenOptions::new()
-            .create(true)
-            .write(true)
-            .open(chunk_path)
-            .unwrap();
-        let mut wrote_tot: u64 = 0;
-
-        while wrote_tot != size {
-            if let Ok(bytes) =
-                f.write_at(&buf[wrote_tot as usize..size as usize], offset + wrote_tot)
-            {
-                wrote_tot += bytes as u64;
+        let _guard = self.lock_chunk(file_path, chunk_id);
+        let end = (offset + size) as usize;
+        if compressed {
+            // a whole chunk needs none of the old content
+            let mut chunk = if offset == 0 && size == self.get_chunk_size() {
+                Vec::new()
             } else {
-                error_msg(
-                    "server::storage::chunk_storage::write_chunk".to_string(),
-                    "error occured while writing to chunk".to_string(),
-                );
-                return Err(-1);
+                match self.read_whole(file_path, file_id, chunk_id) {
+                    Ok(chunk) => chunk,
+                    Err(-2) => Vec::new(),
+                    Err(e) => return Err(e),
+                }
+            };
+            if chunk.len() < end {
+                chunk.resize(end, 0);
             }
+            chunk[offset as usize..end].copy_from_slice(buf);
+            self.write_whole(file_path, file_id, chunk_id, &chunk, true)?;
+            self.put_checksums(file_path, file_id, chunk_id, block_checksums(&chunk));
+            return Ok(size as i64);
+        }
+        // the checksum blocks the write touches, with the old content around it
+        let (start, stop) = self.block_range(file_path, chunk_id, offset as usize, end);
+        let mut piece = if offset == 0 && size == self.get_chunk_size() {
+            Vec::new()
+        } else {
+            match self.load(file_path, file_id, chunk_id, start, stop) {
+                Ok(piece) => {
+                    let last = piece.len() < stop - start;
+                    self.verify_blocks(file_path, chunk_id, start, &piece, last)?;
+                    piece
+                }
+                Err(-2) => Vec::new(),
+                Err(e) => return Err(e),
+            }
+        };
+        if piece.len() < end - start {
+            piece.resize(end - start, 0);
+        }
+        piece[offset as usize - start..end - start].copy_from_slice(buf);
+        let write_res = if encrypted {
+            self.store(file_path, file_id, chunk_id, &piece, start as u64)?;
+            size as i64
+        } else {
+            self.backend_.write(file_path, file_id, chunk_id, buf, offset)?
+        };
+        if self.checksums_.is_some() {
+            let mut sums = self.get_checksums(file_path, chunk_id).unwrap_or(Vec::new());
+            let blocks = block_checksums(&piece);
+            let first = start / CHECKSUM_BLOCK_SIZE;
+            if sums.len() < first + blocks.len() {
+                sums.resize(first + blocks.len(), 0);
+            }
+            sums[first..first + blocks.len()].copy_from_slice(&blocks);
+            self.put_checksums(file_path, file_id, chunk_id, sums);
         }
-        Ok(wrote_tot as i64)
+        Ok(write_res)
     }
+    // Err(-2) if the chunk was never written, it is a hole then
     pub fn read_chunk(
         file_path: &String,
+        file_id: u64,
         chunk_id: u64,
         buf: *mut u8,
         size: u64,
-        mut offset: u64,
+        offset: u64,
     ) -> Result<i64, i32> {
-        if size + offset > CNK.get_chunk_size() {
+        let buf = unsafe { std::slice::from_raw_parts_mut(buf, size as usize) };
+        CNK.read(file_path, file_id, chunk_id, buf, offset)
+    }
+    pub fn read(
+        &self,
+        file_path: &String,
+        file_id: u64,
+        chunk_id: u64,
+        buf: &mut [u8],
+        offset: u64,
+    ) -> Result<i64, i32> {
+        if buf.len() as u64 + offset > self.get_chunk_size() {
             error_msg(
                 "server::storage::chunk_storage::read_chunk".to_string(),
                 "beyond chunk storage range".to_string(),
             );
         }
-        /*
-        if ENABLE_STUFFING && STUFF_WITH_ROCKSDB && chunk_id == 0 {
-            if let Some(data) = StuffDB::get_instance().get(file_path) {
-                if offset as usize > data.len() {
-                    return Err(-1);
-                }
-                let start = offset as usize;
-                let end = std::cmp::min((offset + size) as usize, data.len());
-                let mut read = data[start..end].to_vec();
-                let len = read.len() as u64;
-                buf.clear();
-                buf.append(&mut read);
-                return Ok(len);
-            } else {
-                return Err(-1);
-            }
-        }
-        */
-        ChunkStorage::init_chunk_space(file_path);
-        let chunk_path =
-            ChunkStorage::absolute(&ChunkStorage::get_chunks_path(file_path, chunk_id));
-        let open_res = std::fs::OpenOptions::new().read(true).open(chunk_path.as_str());
-        if let Err(_e) = open_res {
-            error_msg(
-                "server::storage::chunk_storage::read_chunk".to_string(),
-                format!("fail to open chunk file {}", chunk_path),
-            );
-            return Err(-2);
-        }
-        let f = open_res.unwrap();
-        let mut read_tot: u64 = 0;
-        let mut buf = unsafe { std::slice::from_raw_parts_mut(buf, size as usize) };
-        while !buf.is_empty() {
-            match f.read_at(buf, offset) {
-                Ok(0) => break,
-                Ok(n) => {
-                    let tmp = buf;
-                    buf = &mut tmp[n..];
-                    offset += n as u64;
-                    read_tot += n as u64;
-                }
-                Err(_e) => {
-                    error_msg(
-                        "server::storage::chunk_storage::read_chunk".to_string(),
-                        "error occurs while reading from chunks".to_string(),
-                    );
-                    return Err(-1);
-                }
-            }
+        if self.checksums_.is_none()
+            && !self.compressed(file_path, chunk_id)
+            && self.cipher_.is_none()
+        {
+            return self.backend_.read(file_path, file_id, chunk_id, buf, offset);
         }
-        if !buf.is_empty() {
-            error_msg(
-                "server::storage::chunk_storage::read_chunk".to_string(),
-                "unable to fill the buf because of reaching EOF".to_string(),
-            );
-            Ok(read_tot as i64)
+        let _guard = self.lock_chunk(file_path, chunk_id);
+        // a compressed chunk is read whole, others from the checksum blocks the read touches
+        let (chunk, start) = if self.compressed(file_path, chunk_id) {
+            (self.read_whole(file_path, file_id, chunk_id)?, 0)
         } else {
-            Ok(read_tot as i64)
+            let start = offset as usize / CHECKSUM_BLOCK_SIZE * CHECKSUM_BLOCK_SIZE;
+            let stop = self.block_end(offset as usize + buf.len());
+            let piece = self.load(file_path, file_id, chunk_id, start, stop)?;
+            self.verify_blocks(file_path, chunk_id, start, &piece, piece.len() < stop - start)?;
+            (piece, start)
+        };
+        let offset = offset as usize - start;
+        if offset >= chunk.len() {
+            return Ok(0);
         }
+        let len = usize::min(buf.len(), chunk.len() - offset);
+        buf[..len].copy_from_slice(&chunk[offset..offset + len]);
+        Ok(len as i64)
     }
-    pub fn trim_chunk_space(file_path: &String, chunk_start: u64) {
-        if ENABLE_STUFFING && STUFF_WITH_ROCKSDB && chunk_start == 0 {
-            StuffDB::get_instance().remove(file_path);
+    pub fn trim_chunk_space(file_path: &String, file_id: u64, chunk_start: u64) {
+        CNK.trim(file_path, file_id, chunk_start);
+        // parity of the stripes wholly past the new end has nothing left to protect
+        if let Some(layout) = erasure_layout(StorageContext::get_instance().get_erasure_dirs(), file_path) {
+            CNK.trim(file_path, file_id, layout.parity_trim_start(chunk_start));
         }
-        let chunk_dir = ChunkStorage::absolute(&ChunkStorage::get_chunks_dir(file_path));
-        let dir_res = std::fs::read_dir(Path::new(&chunk_dir));
-        if let Err(_e) = dir_res {
-            return;
-        }
-        let dir_iter = dir_res.unwrap();
-        let err = false;
-        for entry in dir_iter {
-            let entry = entry.unwrap();
-            let chunk_path = entry.path();
-            let chunk_id = chunk_path
-                .file_name()
-                .unwrap()
-                .to_str()
-                .unwrap()
-                .parse::<u64>()
-                .unwrap();
-            if chunk_id >= chunk_start {
-                if let Err(_e) = fs::remove_file(chunk_path.as_path()) {
-                    continue;
-                    //error_msg("server::storage::chunk_storage::trim_chunk_space".to_string(), "fail to remove file".to_string());
-                    //err = true;
+    }
+    pub fn trim(&self, file_path: &String, file_id: u64, chunk_start: u64) {
+        self.backend_.trim(file_path, file_id, chunk_start);
+        for chunk_id in self.sizes_.chunk_ids(file_path, chunk_start) {
+            if !trims(chunk_start, chunk_id) {
+                continue;
+            }
+            if let Some((_logical, physical)) = self.sizes_.get(file_path, chunk_id) {
+                if !is_parity_chunk(chunk_id) {
+                    (self.report_)(file_path, chunk_id, -blocks_of(physical));
                 }
             }
+            self.sizes_.remove(file_path, chunk_id);
         }
-        if err {
-            error_msg(
-                "server::storage::chunk_storage::trim_chunk_space".to_string(),
-                "error occurs while truncating".to_string(),
-            );
+        for chunk_id in self.nonces_.chunk_ids(file_path, chunk_start) {
+            if trims(chunk_start, chunk_id) {
+                self.nonces_.remove(file_path, chunk_id);
+            }
+        }
+        if let Some(checksums) = &self.checksums_ {
+            for chunk_id in checksums.chunk_ids(file_path, chunk_start) {
+                if trims(chunk_start, chunk_id) {
+                    checksums.remove(file_path, chunk_id);
+                }
+            }
         }
     }
-    pub fn truncate_chunk_file(file_path: &String, chunk_id: u64, length: u64) {
-        if length > CNK.get_chunk_size() {
+    pub fn truncate_chunk_file(file_path: &String, file_id: u64, chunk_id: u64, length: u64) {
+        CNK.truncate(file_path, file_id, chunk_id, length);
+    }
+    pub fn truncate(&self, file_path: &String, file_id: u64, chunk_id: u64, length: u64) {
+        if length > self.get_chunk_size() {
             error_msg(
                 "server::storage::chunk_storage::truncate_chunk_file".to_string(),
                 "invalid length".to_string(),
             );
             return;
         }
-        if ENABLE_STUFFING && STUFF_WITH_ROCKSDB && chunk_id == 0 {
-            StuffDB::get_instance().truncate(file_path, length);
-            return;
+        let compressed = self.compressed(file_path, chunk_id);
+        if self.checksums_.is_none() && !compressed && self.cipher_.is_none() {
+            return self.backend_.truncate(file_path, file_id, chunk_id, length);
         }
-        let chunk_path =
-            ChunkStorage::absolute(&ChunkStorage::get_chunks_path(file_path, chunk_id));
-        let f_res = fs::OpenOptions::new()
-            .write(true)
-            .read(true)
-            .open(Path::new(&chunk_path));
-        if let Err(_e) = f_res {
+        let _guard = self.lock_chunk(file_path, chunk_id);
+        // a damaged chunk keeps its old checksums, the scrubber finds it
+        if compressed {
+            if let Ok(mut chunk) = self.read_whole(file_path, file_id, chunk_id) {
+                chunk.resize(length as usize, 0);
+                if let Err(e) = self.write_whole(file_path, file_id, chunk_id, &chunk, true) {
+                    error_msg(
+                        "server::storage::chunk_storage::truncate_chunk_file".to_string(),
+                        format!(
+                            "error {} occurs while rewriting chunk {} of '{}'",
+                            e, chunk_id, file_path
+                        ),
+                    );
+                    return;
+                }
+                self.put_checksums(file_path, file_id, chunk_id, block_checksums(&chunk));
+            }
             return;
         }
-        let f = f_res.unwrap();
-        if let Err(_e) = f.set_len(length) {
-            error_msg(
-                "server::storage::chunk_storage::truncate_chunk_file".to_string(),
-                "error occurs while truncating chunk file".to_string(),
-            );
+        if self.checksums_.is_none() {
+            return self.shorten(file_path, file_id, chunk_id, length);
+        }
+        // the checksum block the new end falls in, from the old end on if the chunk grows
+        let length = length as usize;
+        let (start, stop) = self.block_range(file_path, chunk_id, length, length);
+        let piece = self
+            .load(file_path, file_id, chunk_id, start, stop)
+            .and_then(|piece| {
+                let last = piece.len() < stop - start;
+                self.verify_blocks(file_path, chunk_id, start, &piece, last)?;
+                Ok(piece)
+            });
+        self.shorten(file_path, file_id, chunk_id, length as u64);
+        if let Ok(mut piece) = piece {
+            piece.resize(length - start, 0);
+            let mut sums = self.get_checksums(file_path, chunk_id).unwrap_or(Vec::new());
+            sums.truncate(start / CHECKSUM_BLOCK_SIZE);
+            sums.extend(block_checksums(&piece));
+            self.put_checksums(file_path, file_id, chunk_id, sums);
         }
     }
-    pub fn chunk_stat() -> ChunkStat {
-        let statfs = statfs(Path::new(CNK.get_root_path()));
-        if let Err(_e) = statfs {
-            error_msg(
-                "server::storage::chunk_storage::chunk_stat".to_string(),
-                "error occurs while get fs stat".to_string(),
-            );
-            return ChunkStat {
-                chunk_size: 0,
-                chunk_total: 0,
-                chunk_free: 0,
-            };
+    // (path, file id, chunk id) of the chunks having checksums, the ones the scrubber checks
+    pub fn checksummed_chunks() -> Vec<(String, u64, u64)> {
+        match &CNK.checksums_ {
+            Some(checksums) => checksums
+                .get_all()
+                .into_iter()
+                .map(|(path, chunk_id, record)| (path, record.file_id, chunk_id))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+    // Err(EIO) if the chunk does not match its checksums, Err(-2) if its content is gone
+    pub fn verify_chunk(file_path: &String, file_id: u64, chunk_id: u64) -> Result<(), i32> {
+        let _guard = CNK.lock_chunk(file_path, chunk_id);
+        CNK.read_whole(file_path, file_id, chunk_id).map(|_chunk| ())
+    }
+    // replace a damaged chunk by 'data', unless the chunk was dropped or rewritten meanwhile
+    pub fn restore_chunk(
+        file_path: &String,
+        file_id: u64,
+        chunk_id: u64,
+        data: &[u8],
+    ) -> Result<(), i32> {
+        if CNK.checksums_.is_none() {
+            return Ok(());
         }
-        let statfs = statfs.unwrap();
-        let bytes_tot = statfs.block_size() as u64 * statfs.blocks();
-        let bytes_free = statfs.block_size() as u64 * statfs.blocks_available();
-        ChunkStat {
-            chunk_size: CNK.get_chunk_size(),
-            chunk_total: bytes_tot / CNK.get_chunk_size(),
-            chunk_free: bytes_free / CNK.get_chunk_size(),
+        let _guard = CNK.lock_chunk(file_path, chunk_id);
+        if CNK.get_checksums(file_path, chunk_id).is_none() {
+            return Ok(());
         }
+        // a write since the scrubber checked it may have replaced the damage
+        if CNK.read_whole(file_path, file_id, chunk_id).is_ok() {
+            return Ok(());
+        }
+        CNK.write_whole(file_path, file_id, chunk_id, data, CNK.compressed(file_path, chunk_id))?;
+        CNK.put_checksums(file_path, file_id, chunk_id, block_checksums(data));
+        Ok(())
+    }
+    // point chunks a client is about to write from their start at contents held already, in
+    // dedup mode. (chunk id, digest, length) each, the ids of the linked chunks are returned
+    pub fn link_chunks(
+        file_path: &String,
+        file_id: u64,
+        chunks: &Vec<(u64, Vec<u8>, u64)>,
+    ) -> Vec<u64> {
+        // compressed and encrypted chunks are not stored as they are sent
+        if CNK.cipher_.is_some() {
+            return Vec::new();
+        }
+        let mut linked = Vec::new();
+        for (chunk_id, digest, len) in chunks {
+            let _guard = CNK.lock_chunk(file_path, *chunk_id);
+            if CNK.compressed(file_path, *chunk_id)
+                || !CNK.backend_.link(file_path, file_id, *chunk_id, digest, *len)
+            {
+                continue;
+            }
+            if CNK.checksums_.is_some() {
+                let mut chunk = vec![0u8; *len as usize];
+                if let Ok(read) = CNK.backend_.read(file_path, file_id, *chunk_id, &mut chunk, 0) {
+                    chunk.truncate(read as usize);
+                    CNK.put_checksums(file_path, file_id, *chunk_id, block_checksums(&chunk));
+                }
+            }
+            linked.push(*chunk_id);
+        }
+        linked
+    }
+    pub fn chunk_stat() -> ChunkStat {
+        CNK.backend_.stat()
     }
     pub fn get_chunk_size(&self) -> u64 {
         self.chunk_size_
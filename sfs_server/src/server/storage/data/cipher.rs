@@ -0,0 +1,95 @@
+use std::fs;
+use std::io::{Error, ErrorKind};
+
+use chacha20::cipher::{KeyIvInit, StreamCipher};
+use chacha20::{ChaCha20, Key, Nonce};
+use chacha20poly1305::aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::ChaCha20Poly1305;
+use libc::EIO;
+
+// chunks are encrypted in blocks of this size, each under a nonce of its own
+pub const CIPHER_BLOCK_SIZE: usize = 4096;
+pub const NONCE_SIZE: usize = 12;
+
+// key of the job, 64 hex digits taken from 'key' or else from the file 'key_file'.
+// none if neither is given
+pub fn load_key(key: Option<String>, key_file: &String) -> Result<Option<[u8; 32]>, Error> {
+    let hex = match key {
+        Some(key) => key.trim().to_string(),
+        None if key_file.len() != 0 => fs::read_to_string(key_file)?.trim().to_string(),
+        None => return Ok(None),
+    };
+    let invalid = || {
+        Error::new(
+            ErrorKind::InvalidData,
+            "encryption key must be 64 hex digits",
+        )
+    };
+    if hex.len() != 64 {
+        return Err(invalid());
+    }
+    let mut key = [0u8; 32];
+    for (i, byte) in key.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(hex.get(2 * i..2 * i + 2).ok_or_else(invalid)?, 16)
+            .map_err(|_e| invalid())?;
+    }
+    Ok(Some(key))
+}
+// encryption at rest with the key of the job. chunk blocks are encrypted with ChaCha20, which
+// keeps their length, so chunk offsets and truncation work on the encrypted chunk as they are.
+// values of the stuffing database are sealed with ChaCha20-Poly1305, the nonce goes in front
+pub struct Cipher {
+    key_: [u8; 32],
+}
+impl Cipher {
+    pub fn new(key: [u8; 32]) -> Cipher {
+        Cipher { key_: key }
+    }
+    // encrypt 'data' in place under fresh nonces, it starts at block 'first_block' of its chunk.
+    // 'nonces' holds the nonces of the blocks of the chunk
+    pub fn encrypt_blocks(&self, data: &mut [u8], first_block: usize, nonces: &mut Vec<u8>) {
+        let end =
+            (first_block + (data.len() + CIPHER_BLOCK_SIZE - 1) / CIPHER_BLOCK_SIZE) * NONCE_SIZE;
+        if nonces.len() < end {
+            nonces.resize(end, 0);
+        }
+        for (i, block) in data.chunks_mut(CIPHER_BLOCK_SIZE).enumerate() {
+            let nonce =
+                &mut nonces[(first_block + i) * NONCE_SIZE..(first_block + i + 1) * NONCE_SIZE];
+            OsRng.fill_bytes(nonce);
+            ChaCha20::new(Key::from_slice(&self.key_), Nonce::from_slice(nonce))
+                .apply_keystream(block);
+        }
+    }
+    // decrypt the leading blocks of a chunk in place
+    pub fn decrypt_blocks(&self, data: &mut [u8], nonces: &[u8]) -> Result<(), i32> {
+        for (i, block) in data.chunks_mut(CIPHER_BLOCK_SIZE).enumerate() {
+            let nonce = nonces
+                .get(i * NONCE_SIZE..(i + 1) * NONCE_SIZE)
+                .ok_or(EIO)?;
+            ChaCha20::new(Key::from_slice(&self.key_), Nonce::from_slice(nonce))
+                .apply_keystream(block);
+        }
+        Ok(())
+    }
+    pub fn seal(&self, data: &[u8]) -> Vec<u8> {
+        let aead = ChaCha20Poly1305::new(Key::from_slice(&self.key_));
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let mut sealed = nonce.to_vec();
+        // fails for data beyond 256 GiB only
+        sealed.extend(aead.encrypt(&nonce, data).unwrap());
+        sealed
+    }
+    // none if 'sealed' was not sealed with this key or was altered
+    pub fn open(&self, sealed: &[u8]) -> Option<Vec<u8>> {
+        if sealed.len() < NONCE_SIZE {
+            return None;
+        }
+        let aead = ChaCha20Poly1305::new(Key::from_slice(&self.key_));
+        aead.decrypt(
+            Nonce::from_slice(&sealed[..NONCE_SIZE]),
+            &sealed[NONCE_SIZE..],
+        )
+        .ok()
+    }
+}
@@ -0,0 +1,318 @@
+use std::path::Path;
+use std::sync::Mutex;
+
+use libc::EIO;
+use rocksdb::{Options, WriteBatch, WriteOptions, DB};
+use sfs_global::global::erasure::trims;
+use sfs_global::global::network::forward_data::ChunkStat;
+use sfs_global::global::util::checksum_util::chunk_digest;
+
+use crate::error_msg::error_msg;
+
+use super::backend::{ChunkBackend, StripedLocks};
+use super::chunk_storage::CHUNK_LOCKS;
+use super::rocksdb_backend::{chunk_ids, chunk_key};
+
+// reference count of a content, paths of the chunk maps start with '/'
+fn ref_key(digest: &[u8]) -> Vec<u8> {
+    let mut key = b"#".to_vec();
+    key.extend_from_slice(digest);
+    key
+}
+// the inner backend keeps a content as the only chunk of a file named by its digest, the file
+// has no id
+fn content_path(digest: &[u8]) -> String {
+    let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("/.dedup/{}", hex)
+}
+// chunks kept by the digest of their content on top of another backend, identical chunks are
+// stored once. the chunk map of a file points at (digest, length), a content is dropped with
+// its last reference. a write rewrites the whole chunk under the digest of its new content.
+// chunks written before dedup was turned on have no map, they are read from the inner backend
+// as they are and move under their digest when they change
+#[allow(dead_code)]
+pub struct DedupBackend {
+    db: DB,
+    options: Options,
+    write_opts: WriteOptions,
+    path: String,
+    inner_: Box<dyn ChunkBackend>,
+    chunk_size_: u64,
+    // held while a chunk changes
+    chunk_locks_: StripedLocks,
+    // held while a content is stored or dropped, by its digest
+    content_locks_: StripedLocks,
+    // held while reference counts change, together with the chunk map
+    refs_lock_: Mutex<()>,
+}
+impl DedupBackend {
+    pub fn new(
+        path: &String,
+        inner: Box<dyn ChunkBackend>,
+        chunk_size: u64,
+    ) -> Option<DedupBackend> {
+        let mut options = Options::default();
+        options.create_if_missing(true);
+        if let Ok(rdb) = DB::open(&options, Path::new(path)) {
+            Some(DedupBackend {
+                db: rdb,
+                options: options,
+                write_opts: WriteOptions::default(),
+                path: path.clone(),
+                inner_: inner,
+                chunk_size_: chunk_size,
+                chunk_locks_: StripedLocks::new(CHUNK_LOCKS),
+                content_locks_: StripedLocks::new(CHUNK_LOCKS),
+                refs_lock_: Mutex::new(()),
+            })
+        } else {
+            error_msg(
+                "server::storage::dedup_backend::new".to_string(),
+                "fail to open database".to_string(),
+            );
+            None
+        }
+    }
+    // (digest, length) of the content of the chunk
+    fn get_map(&self, path: &String, chunk_id: u64) -> Option<(Vec<u8>, u64)> {
+        if let Ok(Some(val)) = self.db.get(chunk_key(path, chunk_id)) {
+            let mut len = [0u8; 8];
+            len.copy_from_slice(&val[val.len() - 8..]);
+            Some((val[..val.len() - 8].to_vec(), u64::from_be_bytes(len)))
+        } else {
+            None
+        }
+    }
+    fn get_refs(&self, digest: &[u8]) -> u64 {
+        if let Ok(Some(val)) = self.db.get(ref_key(digest)) {
+            let mut refs = [0u8; 8];
+            refs.copy_from_slice(&val[..8]);
+            u64::from_be_bytes(refs)
+        } else {
+            0
+        }
+    }
+    // whole content of the chunk and whether it is kept from before dedup, Err(-2) if it has none
+    fn content(
+        &self,
+        path: &String,
+        file_id: u64,
+        chunk_id: u64,
+    ) -> Result<(Vec<u8>, bool), i32> {
+        let (digest, len) = match self.get_map(path, chunk_id) {
+            Some(map) => map,
+            None => {
+                let mut content = vec![0u8; self.chunk_size_ as usize];
+                let read = self.inner_.read(path, file_id, chunk_id, &mut content, 0)?;
+                content.truncate(read as usize);
+                return Ok((content, true));
+            }
+        };
+        let mut content = vec![0u8; len as usize];
+        let read = self.inner_.read(&content_path(&digest), 0, 0, &mut content, 0)?;
+        if read as u64 != len {
+            error_msg(
+                "server::storage::dedup_backend::content".to_string(),
+                format!("content of chunk {} of '{}' is short", chunk_id, path),
+            );
+            return Err(EIO);
+        }
+        Ok((content, false))
+    }
+    // point the chunk at a stored content or at none, the old content loses a reference. its
+    // digest is returned if that was the last one
+    fn repoint(&self, path: &String, chunk_id: u64, new: Option<(&[u8], u64)>) -> Option<Vec<u8>> {
+        let _guard = self.refs_lock_.lock().unwrap();
+        let old = self.get_map(path, chunk_id);
+        let mut batch = WriteBatch::default();
+        match new {
+            Some((digest, len)) => {
+                if old.as_ref().map(|(old_digest, _len)| old_digest.as_slice()) != Some(digest) {
+                    batch.put(ref_key(digest), (self.get_refs(digest) + 1).to_be_bytes());
+                }
+                let mut val = digest.to_vec();
+                val.extend_from_slice(&len.to_be_bytes());
+                batch.put(chunk_key(path, chunk_id), val);
+            }
+            None => batch.delete(chunk_key(path, chunk_id)),
+        }
+        let mut dropped = None;
+        if let Some((old_digest, _len)) = old {
+            if new.map(|(digest, _len)| digest) != Some(old_digest.as_slice()) {
+                let refs = self.get_refs(&old_digest).saturating_sub(1);
+                if refs == 0 {
+                    batch.delete(ref_key(&old_digest));
+                    dropped = Some(old_digest);
+                } else {
+                    batch.put(ref_key(&old_digest), refs.to_be_bytes());
+                }
+            }
+        }
+        if let Err(_e) = self.db.write_opt(batch, &self.write_opts) {
+            error_msg(
+                "server::storage::dedup_backend::repoint".to_string(),
+                format!("fail to repoint chunk {} of '{}'", chunk_id, path),
+            );
+            return None;
+        }
+        dropped
+    }
+    // remove a content left without references, unless it got one back meanwhile
+    fn drop_content(&self, digest: Option<Vec<u8>>) {
+        if let Some(digest) = digest {
+            let _guard = self.content_locks_.lock(&digest);
+            if self.get_refs(&digest) == 0 {
+                self.inner_.destroy(&content_path(&digest), 0);
+            }
+        }
+    }
+    fn unmap(&self, path: &String, chunk_id: u64) {
+        let _guard = self.chunk_locks_.lock(&(path, chunk_id));
+        let dropped = self.repoint(path, chunk_id, None);
+        self.drop_content(dropped);
+    }
+    // store 'content' unless it is stored already, and point the chunk at it. the copy of the
+    // chunk from before dedup is not read any longer, it goes if there is one
+    fn store(
+        &self,
+        path: &String,
+        file_id: u64,
+        chunk_id: u64,
+        content: &[u8],
+        legacy: bool,
+    ) -> Result<(), i32> {
+        let digest = chunk_digest(content);
+        let dropped = {
+            let _guard = self.content_locks_.lock(&digest);
+            if self.get_refs(&digest) == 0 {
+                let content_path = content_path(&digest);
+                self.inner_.write(&content_path, 0, 0, content, 0)?;
+                self.inner_.truncate(&content_path, 0, 0, content.len() as u64);
+            }
+            self.repoint(path, chunk_id, Some((&digest, content.len() as u64)))
+        };
+        self.drop_content(dropped);
+        if legacy {
+            self.inner_.remove(path, file_id, chunk_id);
+        }
+        Ok(())
+    }
+}
+impl ChunkBackend for DedupBackend {
+    fn write(
+        &self,
+        path: &String,
+        file_id: u64,
+        chunk_id: u64,
+        buf: &[u8],
+        offset: u64,
+    ) -> Result<i64, i32> {
+        let _guard = self.chunk_locks_.lock(&(path, chunk_id));
+        let (mut content, legacy) = match self.content(path, file_id, chunk_id) {
+            Ok(content) => content,
+            Err(-2) => (Vec::new(), false),
+            Err(e) => return Err(e),
+        };
+        let end = offset as usize + buf.len();
+        if content.len() < end {
+            content.resize(end, 0);
+        }
+        content[offset as usize..end].copy_from_slice(buf);
+        self.store(path, file_id, chunk_id, &content, legacy)?;
+        Ok(buf.len() as i64)
+    }
+    fn read(
+        &self,
+        path: &String,
+        file_id: u64,
+        chunk_id: u64,
+        buf: &mut [u8],
+        offset: u64,
+    ) -> Result<i64, i32> {
+        let _guard = self.chunk_locks_.lock(&(path, chunk_id));
+        match self.get_map(path, chunk_id) {
+            Some((digest, _len)) => self.inner_.read(&content_path(&digest), 0, 0, buf, offset),
+            None => self.inner_.read(path, file_id, chunk_id, buf, offset),
+        }
+    }
+    fn exists(&self, path: &String, file_id: u64, chunk_id: u64) -> bool {
+        self.get_map(path, chunk_id).is_some() || self.inner_.exists(path, file_id, chunk_id)
+    }
+    fn truncate(&self, path: &String, file_id: u64, chunk_id: u64, length: u64) {
+        let _guard = self.chunk_locks_.lock(&(path, chunk_id));
+        let (mut content, legacy) = match self.content(path, file_id, chunk_id) {
+            Ok(content) => content,
+            Err(_e) => return,
+        };
+        content.resize(length as usize, 0);
+        if let Err(e) = self.store(path, file_id, chunk_id, &content, legacy) {
+            error_msg(
+                "server::storage::dedup_backend::truncate".to_string(),
+                format!("error {} occurs while truncating chunk {} of '{}'", e, chunk_id, path),
+            );
+        }
+    }
+    fn trim(&self, path: &String, file_id: u64, chunk_start: u64) {
+        for chunk_id in chunk_ids(&self.db, path, chunk_start) {
+            if trims(chunk_start, chunk_id) {
+                self.unmap(path, chunk_id);
+            }
+        }
+        // chunks from before dedup
+        self.inner_.trim(path, file_id, chunk_start);
+    }
+    fn remove(&self, path: &String, file_id: u64, chunk_id: u64) {
+        self.unmap(path, chunk_id);
+        self.inner_.remove(path, file_id, chunk_id);
+    }
+    fn destroy(&self, path: &String, file_id: u64) {
+        for chunk_id in chunk_ids(&self.db, path, 0) {
+            self.unmap(path, chunk_id);
+        }
+        self.inner_.destroy(path, file_id);
+    }
+    fn stat(&self) -> ChunkStat {
+        self.inner_.stat()
+    }
+    fn sync(&self, path: &String, file_id: u64) -> i32 {
+        for chunk_id in chunk_ids(&self.db, path, 0) {
+            let _guard = self.chunk_locks_.lock(&(path, chunk_id));
+            if let Some((digest, _len)) = self.get_map(path, chunk_id) {
+                let err = self.inner_.sync(&content_path(&digest), 0);
+                if err != 0 {
+                    return err;
+                }
+            }
+        }
+        let err = self.inner_.sync(path, file_id);
+        if err != 0 {
+            return err;
+        }
+        if let Err(_e) = self.db.flush() {
+            return EIO;
+        }
+        return 0;
+    }
+    fn link(&self, path: &String, file_id: u64, chunk_id: u64, digest: &[u8], len: u64) -> bool {
+        let _guard = self.chunk_locks_.lock(&(path, chunk_id));
+        // a longer chunk would keep its tail, one from before dedup may be longer
+        match self.get_map(path, chunk_id) {
+            Some((_digest, old_len)) if old_len > len => return false,
+            Some(_map) => {}
+            None => {
+                if self.inner_.exists(path, file_id, chunk_id) {
+                    return false;
+                }
+            }
+        }
+        let dropped = {
+            let _guard = self.content_locks_.lock(digest);
+            if self.get_refs(digest) == 0 {
+                return false;
+            }
+            self.repoint(path, chunk_id, Some((digest, len)))
+        };
+        self.drop_content(dropped);
+        true
+    }
+}
@@ -0,0 +1,264 @@
+use std::fs;
+#[allow(unused)]
+use std::os::unix::prelude::FileExt;
+use std::path::Path;
+
+use libc::{EIO, ENOENT};
+use sfs_global::global::erasure::trims;
+use sfs_global::global::network::forward_data::ChunkStat;
+use sfs_global::global::util::path_util::is_absolute;
+
+use crate::error_msg::error_msg;
+
+use super::backend::{fs_chunk_stat, ChunkBackend};
+
+// one file per chunk, 'root/<xx>/<yy>/<file id>/<chunk id>'. 'xx' and 'yy' are taken from a
+// hash of the id so no directory grows too large. files without an id keep the directory named
+// by their path, 'root/a:b:c/<chunk id>' for '/a/b/c'
+pub struct FileBackend {
+    root_path_: String,
+    chunk_size_: u64,
+}
+impl FileBackend {
+    pub fn new(root_path: &String, chunk_size: u64) -> FileBackend {
+        FileBackend {
+            root_path_: root_path.clone(),
+            chunk_size_: chunk_size,
+        }
+    }
+    pub fn absolute(&self, internel_path: &String) -> String {
+        if is_absolute(&internel_path) {
+            error_msg(
+                "server::storage::file_backend::absolute".to_string(),
+                "path should be relative".to_string(),
+            );
+            return internel_path.clone();
+        }
+        format!("{}/{}", self.root_path_, internel_path)
+    }
+    pub fn get_chunks_dir(file_path: &String, file_id: u64) -> String {
+        if file_id == 0 {
+            return FileBackend::get_legacy_chunks_dir(file_path);
+        }
+        let hash = file_id.wrapping_mul(0x9e3779b97f4a7c15);
+        format!("{:02x}/{:02x}/{:016x}", hash >> 56, (hash >> 48) & 0xff, file_id)
+    }
+    pub fn get_chunks_path(file_path: &String, file_id: u64, chunk_id: u64) -> String {
+        format!("{}/{}", FileBackend::get_chunks_dir(file_path, file_id), chunk_id)
+    }
+    // directory name of the chunks of files without an id, '/a/b:c' and '/a:b/c' both get
+    // 'a:b:c'. only the contents kept by "dedup" are still named this way by new servers
+    pub fn get_legacy_chunks_dir(file_path: &String) -> String {
+        if !is_absolute(file_path) {
+            error_msg(
+                "server::storage::file_backend::get_legacy_chunks_dir".to_string(),
+                "path should be absolute".to_string(),
+            );
+            return file_path.replace("/", ":");
+        }
+        let chunk_dir = file_path[1..].to_string();
+        chunk_dir.replace("/", ":")
+    }
+    pub fn init_chunk_space(&self, file_path: &String, file_id: u64) {
+        let chunk_dir = self.absolute(&FileBackend::get_chunks_dir(file_path, file_id));
+        let path = Path::new(&chunk_dir);
+        if path.exists() {
+            return;
+        }
+        if let Err(_e) = fs::create_dir_all(path) {
+            error_msg(
+                "server::storage::file_backend::init_chunk_space".to_string(),
+                "fail to create chunk directory".to_string(),
+            );
+        }
+    }
+}
+impl ChunkBackend for FileBackend {
+    fn write(
+        &self,
+        path: &String,
+        file_id: u64,
+        chunk_id: u64,
+        buf: &[u8],
+        offset: u64,
+    ) -> Result<i64, i32> {
+        self.init_chunk_space(path, file_id);
+        let chunk_path = self.absolute(&FileBackend::get_chunks_path(path, file_id, chunk_id));
+        let open_res = std::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .open(chunk_path.as_str());
+        if let Err(e) = open_res {
+            error_msg(
+                "server::storage::file_backend::write".to_string(),
+                format!("fail to create chunk file {}", chunk_path),
+            );
+            return Err(e.raw_os_error().unwrap_or(EIO));
+        }
+        let f = open_res.unwrap();
+        let size = buf.len() as u64;
+        let mut wrote_tot: u64 = 0;
+
+        while wrote_tot != size {
+            match f.write_at(&buf[wrote_tot as usize..size as usize], offset + wrote_tot) {
+                Ok(bytes) => wrote_tot += bytes as u64,
+                // ENOSPC among them, it reaches the client as is
+                Err(e) => {
+                    error_msg(
+                        "server::storage::file_backend::write".to_string(),
+                        "error occured while writing to chunk".to_string(),
+                    );
+                    return Err(e.raw_os_error().unwrap_or(EIO));
+                }
+            }
+        }
+        Ok(wrote_tot as i64)
+    }
+    fn read(
+        &self,
+        path: &String,
+        file_id: u64,
+        chunk_id: u64,
+        mut buf: &mut [u8],
+        mut offset: u64,
+    ) -> Result<i64, i32> {
+        let chunk_path = self.absolute(&FileBackend::get_chunks_path(path, file_id, chunk_id));
+        let open_res = std::fs::OpenOptions::new().read(true).open(chunk_path.as_str());
+        if let Err(e) = open_res {
+            // a chunk never written has no file
+            if e.raw_os_error() == Some(ENOENT) {
+                return Err(-2);
+            }
+            error_msg(
+                "server::storage::file_backend::read".to_string(),
+                format!("fail to open chunk file {}", chunk_path),
+            );
+            return Err(-1);
+        }
+        let f = open_res.unwrap();
+        let mut read_tot: u64 = 0;
+        while !buf.is_empty() {
+            match f.read_at(buf, offset) {
+                Ok(0) => break,
+                Ok(n) => {
+                    let tmp = buf;
+                    buf = &mut tmp[n..];
+                    offset += n as u64;
+                    read_tot += n as u64;
+                }
+                Err(_e) => {
+                    error_msg(
+                        "server::storage::file_backend::read".to_string(),
+                        "error occurs while reading from chunks".to_string(),
+                    );
+                    return Err(-1);
+                }
+            }
+        }
+        if !buf.is_empty() {
+            error_msg(
+                "server::storage::file_backend::read".to_string(),
+                "unable to fill the buf because of reaching EOF".to_string(),
+            );
+        }
+        Ok(read_tot as i64)
+    }
+    fn exists(&self, path: &String, file_id: u64, chunk_id: u64) -> bool {
+        let chunk_path = self.absolute(&FileBackend::get_chunks_path(path, file_id, chunk_id));
+        Path::new(&chunk_path).exists()
+    }
+    fn truncate(&self, path: &String, file_id: u64, chunk_id: u64, length: u64) {
+        let chunk_path = self.absolute(&FileBackend::get_chunks_path(path, file_id, chunk_id));
+        let f_res = fs::OpenOptions::new()
+            .write(true)
+            .read(true)
+            .open(Path::new(&chunk_path));
+        if let Err(_e) = f_res {
+            return;
+        }
+        let f = f_res.unwrap();
+        if let Err(_e) = f.set_len(length) {
+            error_msg(
+                "server::storage::file_backend::truncate".to_string(),
+                "error occurs while truncating chunk file".to_string(),
+            );
+        }
+    }
+    fn trim(&self, path: &String, file_id: u64, chunk_start: u64) {
+        let chunk_dir = self.absolute(&FileBackend::get_chunks_dir(path, file_id));
+        let dir_res = std::fs::read_dir(Path::new(&chunk_dir));
+        if let Err(_e) = dir_res {
+            return;
+        }
+        for entry in dir_res.unwrap() {
+            let entry = entry.unwrap();
+            let chunk_path = entry.path();
+            let chunk_id = chunk_path
+                .file_name()
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .parse::<u64>()
+                .unwrap();
+            if trims(chunk_start, chunk_id) {
+                if let Err(_e) = fs::remove_file(chunk_path.as_path()) {
+                    continue;
+                }
+            }
+        }
+    }
+    fn remove(&self, path: &String, file_id: u64, chunk_id: u64) {
+        let chunk_path = self.absolute(&FileBackend::get_chunks_path(path, file_id, chunk_id));
+        // a chunk never written has no file
+        let _ = fs::remove_file(Path::new(&chunk_path));
+    }
+    fn destroy(&self, path: &String, file_id: u64) {
+        let chunk_dir = self.absolute(&FileBackend::get_chunks_dir(path, file_id));
+        if let Err(_e) = fs::remove_dir_all(Path::new(&chunk_dir)) {
+            error_msg(
+                "server::storage::file_backend::destroy".to_string(),
+                "fail to remove chunk directory".to_string(),
+            );
+        }
+    }
+    fn stat(&self) -> ChunkStat {
+        fs_chunk_stat(&self.root_path_, self.chunk_size_)
+    }
+    fn sync(&self, path: &String, file_id: u64) -> i32 {
+        let chunk_dir = self.absolute(&FileBackend::get_chunks_dir(path, file_id));
+        let dir_res = std::fs::read_dir(Path::new(&chunk_dir));
+        if let Err(_e) = dir_res {
+            return 0;
+        }
+        for entry in dir_res.unwrap() {
+            let chunk_path = entry.unwrap().path();
+            let synced = fs::File::open(&chunk_path).and_then(|f| f.sync_all());
+            if let Err(_e) = synced {
+                error_msg(
+                    "server::storage::file_backend::sync".to_string(),
+                    format!("fail to sync chunk file {:?}", chunk_path),
+                );
+                return EIO;
+            }
+        }
+        return 0;
+    }
+    fn precreate(&self, path: &String, file_id: u64, chunks: &Vec<u64>) -> i32 {
+        self.init_chunk_space(path, file_id);
+        for chunk_id in chunks.iter() {
+            let chunk_path = self.absolute(&FileBackend::get_chunks_path(path, file_id, *chunk_id));
+            let created = std::fs::OpenOptions::new()
+                .create(true)
+                .write(true)
+                .open(&chunk_path);
+            if let Err(e) = created {
+                error_msg(
+                    "server::storage::file_backend::precreate".to_string(),
+                    format!("fail to create chunk file {}", chunk_path),
+                );
+                return e.raw_os_error().unwrap_or(EIO);
+            }
+        }
+        0
+    }
+}
@@ -0,0 +1,146 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+use libc::ENOSPC;
+use sfs_global::global::erasure::trims;
+use sfs_global::global::network::forward_data::ChunkStat;
+
+use super::backend::ChunkBackend;
+
+struct MemoryChunks {
+    files: HashMap<String, BTreeMap<u64, Vec<u8>>>,
+    // bytes held by all chunks
+    used: u64,
+}
+// chunks in memory, for tests and scratch data that may vanish with the server.
+// 'capacity_' bytes at most, reported as the size of the storage
+pub struct MemoryBackend {
+    chunks_: Mutex<MemoryChunks>,
+    capacity_: u64,
+    chunk_size_: u64,
+}
+impl MemoryBackend {
+    pub fn new(capacity: u64, chunk_size: u64) -> MemoryBackend {
+        MemoryBackend {
+            chunks_: Mutex::new(MemoryChunks {
+                files: HashMap::new(),
+                used: 0,
+            }),
+            capacity_: capacity,
+            chunk_size_: chunk_size,
+        }
+    }
+}
+impl ChunkBackend for MemoryBackend {
+    fn write(
+        &self,
+        path: &String,
+        _file_id: u64,
+        chunk_id: u64,
+        buf: &[u8],
+        offset: u64,
+    ) -> Result<i64, i32> {
+        let mut chunks = self.chunks_.lock().unwrap();
+        let end = (offset as usize) + buf.len();
+        let old_len = chunks
+            .files
+            .get(path)
+            .and_then(|file| file.get(&chunk_id))
+            .map(|chunk| chunk.len())
+            .unwrap_or(0);
+        if end > old_len {
+            if chunks.used + (end - old_len) as u64 > self.capacity_ {
+                return Err(ENOSPC);
+            }
+            chunks.used += (end - old_len) as u64;
+        }
+        let chunk = chunks
+            .files
+            .entry(path.clone())
+            .or_insert(BTreeMap::new())
+            .entry(chunk_id)
+            .or_insert(Vec::new());
+        if chunk.len() < end {
+            chunk.resize(end, 0);
+        }
+        chunk[offset as usize..end].copy_from_slice(buf);
+        Ok(buf.len() as i64)
+    }
+    fn read(
+        &self,
+        path: &String,
+        _file_id: u64,
+        chunk_id: u64,
+        buf: &mut [u8],
+        offset: u64,
+    ) -> Result<i64, i32> {
+        let chunks = self.chunks_.lock().unwrap();
+        let chunk = match chunks.files.get(path).and_then(|file| file.get(&chunk_id)) {
+            Some(chunk) => chunk,
+            None => return Err(-2),
+        };
+        if offset as usize >= chunk.len() {
+            return Ok(0);
+        }
+        let len = usize::min(buf.len(), chunk.len() - offset as usize);
+        buf[..len].copy_from_slice(&chunk[offset as usize..offset as usize + len]);
+        Ok(len as i64)
+    }
+    fn exists(&self, path: &String, _file_id: u64, chunk_id: u64) -> bool {
+        let chunks = self.chunks_.lock().unwrap();
+        chunks.files.get(path).map_or(false, |file| file.contains_key(&chunk_id))
+    }
+    fn truncate(&self, path: &String, _file_id: u64, chunk_id: u64, length: u64) {
+        let mut chunks = self.chunks_.lock().unwrap();
+        let chunks = &mut *chunks;
+        if let Some(chunk) = chunks.files.get_mut(path).and_then(|file| file.get_mut(&chunk_id)) {
+            let old_len = chunk.len() as u64;
+            // bytes past the end read as zeros anyway, a chunk only grows while there is room
+            if length > old_len && chunks.used + length - old_len > self.capacity_ {
+                return;
+            }
+            chunks.used = chunks.used + length - old_len;
+            chunk.resize(length as usize, 0);
+        }
+    }
+    fn trim(&self, path: &String, _file_id: u64, chunk_start: u64) {
+        let mut chunks = self.chunks_.lock().unwrap();
+        let chunks = &mut *chunks;
+        if let Some(file) = chunks.files.get_mut(path) {
+            let mut freed = 0;
+            file.retain(|chunk_id, chunk| {
+                let keep = !trims(chunk_start, *chunk_id);
+                if !keep {
+                    freed += chunk.len() as u64;
+                }
+                keep
+            });
+            chunks.used -= freed;
+        }
+    }
+    fn remove(&self, path: &String, _file_id: u64, chunk_id: u64) {
+        let mut chunks = self.chunks_.lock().unwrap();
+        let chunks = &mut *chunks;
+        if let Some(chunk) = chunks.files.get_mut(path).and_then(|file| file.remove(&chunk_id)) {
+            chunks.used -= chunk.len() as u64;
+        }
+    }
+    fn destroy(&self, path: &String, _file_id: u64) {
+        let mut chunks = self.chunks_.lock().unwrap();
+        if let Some(file) = chunks.files.remove(path) {
+            chunks.used -= file.values().map(|chunk| chunk.len() as u64).sum::<u64>();
+        }
+    }
+    fn stat(&self) -> ChunkStat {
+        let used = self.chunks_.lock().unwrap().used;
+        ChunkStat {
+            chunk_size: self.chunk_size_,
+            chunk_total: self.capacity_ / self.chunk_size_,
+            chunk_free: (self.capacity_ - u64::min(used, self.capacity_)) / self.chunk_size_,
+            tiers: Vec::new(),
+        }
+    }
+    fn sync(&self, _path: &String, _file_id: u64) -> i32 {
+        0
+    }
+}
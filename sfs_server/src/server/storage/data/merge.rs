@@ -41,8 +41,9 @@ pub fn full_merge(
                     data[0..size as usize].iter().cloned(),
                 );
             }
+            // like 'set_len', growing fills with zeros
             Operand::Truncate { offset } => {
-                result = result[0..offset as usize].to_vec();
+                result.resize(offset as usize, 0);
             }
         }
     }
@@ -0,0 +1,189 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+use sfs_global::global::metadata::{Metadata, S_ISREG};
+use sfs_global::global::network::config::CHUNK_SIZE;
+
+use crate::error_msg::error_msg;
+use crate::server::config::FILE_ID_HOST_SHIFT;
+use crate::server::storage::metadata::db::MetadataDB;
+
+use super::file_backend::FileBackend;
+
+// an old chunk directory holds chunk files, the fan-out directories of file ids only hold
+// directories
+fn is_legacy_dir(dir: &Path) -> bool {
+    if !dir.is_dir() {
+        return false;
+    }
+    match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .any(|entry| entry.path().is_file()),
+        Err(_e) => false,
+    }
+}
+// move the chunks of 'old_dir' under 'file_id'. chunks the file already has under its id were
+// written later and win
+fn adopt(backend: &FileBackend, old_dir: &Path, path: &String, file_id: u64) -> bool {
+    let new_dir = backend.absolute(&FileBackend::get_chunks_dir(path, file_id));
+    let new_dir = Path::new(&new_dir);
+    if !new_dir.exists() {
+        if let Err(_e) = fs::create_dir_all(new_dir.parent().unwrap()) {
+            return false;
+        }
+        return fs::rename(old_dir, new_dir).is_ok();
+    }
+    for entry in fs::read_dir(old_dir).unwrap().filter_map(|entry| entry.ok()) {
+        let target = new_dir.join(entry.file_name());
+        if !target.exists() {
+            if let Err(_e) = fs::rename(entry.path(), target) {
+                return false;
+            }
+        }
+    }
+    fs::remove_dir_all(old_dir).is_ok()
+}
+// hand out file ids to the regular files of 'mdbs' created before file ids and write them into
+// every database holding the file. ids a file already has are kept, new ones are taken below
+// 'FILE_ID_HOST_SHIFT' where no server hands out ids, so running again on the other data hosts
+// with the same databases gives the same ids. returns the id of every regular file
+fn assign_file_ids(mdbs: &Vec<MetadataDB>) -> Option<BTreeMap<String, u64>> {
+    let mut ids: BTreeMap<String, u64> = BTreeMap::new();
+    let mut next = 1;
+    for mdb in mdbs.iter() {
+        for (path, md) in mdb.get_all() {
+            let md = Metadata::deserialize(&md);
+            if !S_ISREG(md.get_mode()) {
+                continue;
+            }
+            let file_id = ids.entry(path).or_insert(0);
+            if *file_id == 0 {
+                *file_id = md.get_file_id();
+            }
+            if md.get_file_id() >> FILE_ID_HOST_SHIFT == 0 && md.get_file_id() >= next {
+                next = md.get_file_id() + 1;
+            }
+        }
+    }
+    for (_path, file_id) in ids.iter_mut() {
+        if *file_id != 0 {
+            continue;
+        }
+        if next >> FILE_ID_HOST_SHIFT != 0 {
+            error_msg(
+                "server::storage::migrate::assign_file_ids".to_string(),
+                "out of file ids".to_string(),
+            );
+            return None;
+        }
+        *file_id = next;
+        next += 1;
+    }
+    for mdb in mdbs.iter() {
+        for (path, md) in mdb.get_all() {
+            let mut md = Metadata::deserialize(&md);
+            if !S_ISREG(md.get_mode()) || md.get_file_id() != 0 {
+                continue;
+            }
+            md.set_file_id(*ids.get(&path).unwrap());
+            // 'put' leaves an existing value alone
+            mdb.remove(&path);
+            if mdb.put(&path, md.serialize(), false) != 0 {
+                return None;
+            }
+        }
+    }
+    Some(ids)
+}
+// rename the chunk directories of 'chunk_dir' written before file ids ('a:b:c' for '/a/b/c')
+// to the fan-out directories of the ids the files get in 'metadata_dirs'. those must be the
+// metadata databases of the whole cluster, the ids are written into them. the directories that
+// can't be placed stay in '<chunk_dir>_legacy' and are reported, a later run picks them up
+// again. returns the number of directories left behind
+pub fn migrate_chunks(chunk_dir: &String, metadata_dirs: &Vec<String>) -> usize {
+    if metadata_dirs.len() == 0 {
+        error_msg(
+            "server::storage::migrate::migrate_chunks".to_string(),
+            "the metadata databases are needed to hand out file ids".to_string(),
+        );
+        return 1;
+    }
+    let backend = FileBackend::new(chunk_dir, CHUNK_SIZE);
+    let mut mdbs = Vec::new();
+    for metadata_dir in metadata_dirs.iter() {
+        if !Path::new(metadata_dir).exists() {
+            error_msg(
+                "server::storage::migrate::migrate_chunks".to_string(),
+                format!("no metadata database at '{}'", metadata_dir),
+            );
+            return 1;
+        }
+        match MetadataDB::new(metadata_dir) {
+            Some(mdb) => mdbs.push(mdb),
+            None => return 1,
+        }
+    }
+    let ids = match assign_file_ids(&mdbs) {
+        Some(ids) => ids,
+        None => return 1,
+    };
+    let mut owners: HashMap<String, Vec<String>> = HashMap::new();
+    for path in ids.keys() {
+        owners
+            .entry(FileBackend::get_legacy_chunks_dir(path))
+            .or_insert(Vec::new())
+            .push(path.clone());
+    }
+    // old directories are set aside first, one of them may carry the name of a fan-out directory
+    let legacy_dir = format!("{}_legacy", chunk_dir.trim_end_matches('/'));
+    if let Err(_e) = fs::create_dir_all(Path::new(&legacy_dir)) {
+        error_msg(
+            "server::storage::migrate::migrate_chunks".to_string(),
+            format!("fail to create '{}'", legacy_dir),
+        );
+        return 1;
+    }
+    for entry in fs::read_dir(Path::new(chunk_dir)).unwrap().filter_map(|entry| entry.ok()) {
+        // the contents kept by "dedup" have no file id and keep their names
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with(".dedup:") || !is_legacy_dir(&entry.path()) {
+            continue;
+        }
+        if let Err(_e) = fs::rename(entry.path(), Path::new(&legacy_dir).join(entry.file_name())) {
+            error_msg(
+                "server::storage::migrate::migrate_chunks".to_string(),
+                format!("fail to set aside {:?}", entry.path()),
+            );
+        }
+    }
+    let mut left = 0;
+    for entry in fs::read_dir(Path::new(&legacy_dir)).unwrap().filter_map(|entry| entry.ok()) {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let path = match owners.get(&name) {
+            Some(paths) if paths.len() == 1 => paths[0].clone(),
+            Some(paths) => {
+                println!("'{}' is shared by {}, left behind", name, paths.join(", "));
+                left += 1;
+                continue;
+            }
+            None => {
+                println!("'{}' belongs to no file, left behind", name);
+                left += 1;
+                continue;
+            }
+        };
+        let file_id = *ids.get(&path).unwrap();
+        if adopt(&backend, &entry.path(), &path, file_id) {
+            println!("'{}' -> '{}' ({:016x})", name, path, file_id);
+        } else {
+            error_msg(
+                "server::storage::migrate::migrate_chunks".to_string(),
+                format!("fail to move chunks of '{}'", path),
+            );
+            left += 1;
+        }
+    }
+    left
+}
@@ -1,3 +1,15 @@
+pub mod backend;
+pub mod checksum_db;
+pub mod chunk_record_db;
+pub mod chunk_size_db;
 pub mod chunk_storage;
+pub mod cipher;
+pub mod dedup_backend;
+pub mod file_backend;
+pub mod memory_backend;
 pub mod merge;
+pub mod migrate;
+pub mod nonce_db;
+pub mod rocksdb_backend;
 pub mod stuff_db;
+pub mod tiered_backend;
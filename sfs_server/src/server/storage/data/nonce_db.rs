@@ -0,0 +1,14 @@
+use super::chunk_record_db::{ChunkRecord, ChunkRecordDB};
+
+// nonces of the cipher blocks of a chunk, one after another
+impl ChunkRecord for Vec<u8> {
+    fn encode(&self) -> Vec<u8> {
+        self.clone()
+    }
+    fn decode(val: &[u8]) -> Vec<u8> {
+        val.to_vec()
+    }
+}
+// nonces of every encrypted chunk on this host. a chunk without nonces was written without a key
+// and is kept in plain. a lost update would garble a chunk, blocks are not written without theirs
+pub type NonceDB = ChunkRecordDB<Vec<u8>>;
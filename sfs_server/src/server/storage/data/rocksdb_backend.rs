@@ -0,0 +1,194 @@
+use std::path::Path;
+
+use libc::{EINVAL, EIO, ENOSPC};
+use rocksdb::{Direction, IteratorMode, Options, WriteOptions, DB};
+use sfs_global::global::erasure::trims;
+use sfs_global::global::network::forward_data::ChunkStat;
+use sfs_global::global::util::serde_util::serialize;
+
+use crate::config::USE_WRITE_AHEAD_LOG;
+use crate::error_msg::error_msg;
+
+use super::backend::{fs_chunk_stat, ChunkBackend};
+use super::merge::{self, Operand};
+
+// chunks are kept as '<path>\0<chunk id, big endian>' records, so the chunks of a file are
+// adjacent and in order. partial writes are merge operands, writers of one chunk don't race
+static CHUNK_SEPERATOR: char = '\0';
+
+pub fn chunk_prefix(path: &String) -> Vec<u8> {
+    format!("{}{}", path, CHUNK_SEPERATOR).into_bytes()
+}
+pub fn chunk_key(path: &String, chunk_id: u64) -> Vec<u8> {
+    let mut key = chunk_prefix(path);
+    key.extend_from_slice(&chunk_id.to_be_bytes());
+    key
+}
+// ids of the chunks of the file from 'chunk_start' on, in a database keyed by 'chunk_key'
+pub fn chunk_ids(db: &DB, path: &String, chunk_start: u64) -> Vec<u64> {
+    let prefix = chunk_prefix(path);
+    let start = chunk_key(path, chunk_start);
+    let iter = db.iterator(IteratorMode::From(&start, Direction::Forward));
+    let mut ids = Vec::new();
+    for (k, _v) in iter {
+        if !k.starts_with(&prefix) || k.len() != prefix.len() + 8 {
+            break;
+        }
+        let mut id = [0u8; 8];
+        id.copy_from_slice(&k[prefix.len()..]);
+        ids.push(u64::from_be_bytes(id));
+    }
+    ids
+}
+#[allow(dead_code)]
+pub struct RocksDBBackend {
+    db: DB,
+    options: Options,
+    write_opts: WriteOptions,
+    path: String,
+    chunk_size_: u64,
+}
+impl RocksDBBackend {
+    pub fn new(path: &String, chunk_size: u64) -> Option<RocksDBBackend> {
+        let mut options = Options::default();
+        options.increase_parallelism(10);
+        options.optimize_level_style_compaction(512 * 1024 * 1024);
+        options.create_if_missing(true);
+        options.set_merge_operator(
+            "simplefs chunk merge operator",
+            merge::full_merge,
+            merge::partial_merge,
+        );
+        options.set_max_successive_merges(125);
+        let mut write_options = WriteOptions::default();
+        write_options.disable_wal(!USE_WRITE_AHEAD_LOG);
+        if let Ok(rdb) = DB::open(&options, Path::new(path)) {
+            Some(RocksDBBackend {
+                db: rdb,
+                options: options,
+                write_opts: write_options,
+                path: path.clone(),
+                chunk_size_: chunk_size,
+            })
+        } else {
+            error_msg(
+                "server::storage::rocksdb_backend::new".to_string(),
+                "fail to open database".to_string(),
+            );
+            None
+        }
+    }
+    fn merge(&self, key: Vec<u8>, op: Operand) -> i32 {
+        if let Err(e) = self.db.merge_opt(key, serialize(op), &self.write_opts) {
+            error_msg(
+                "server::storage::rocksdb_backend::merge".to_string(),
+                "fail to merge value".to_string(),
+            );
+            // rocksdb only tells a full disk by its message
+            if e.to_string().contains("No space left") {
+                return ENOSPC;
+            }
+            return EINVAL;
+        }
+        return 0;
+    }
+    // chunk ids of the file from 'chunk_start' on
+    fn chunk_ids(&self, path: &String, chunk_start: u64) -> Vec<u64> {
+        chunk_ids(&self.db, path, chunk_start)
+    }
+}
+impl ChunkBackend for RocksDBBackend {
+    fn write(
+        &self,
+        path: &String,
+        _file_id: u64,
+        chunk_id: u64,
+        buf: &[u8],
+        offset: u64,
+    ) -> Result<i64, i32> {
+        let op = Operand::Write {
+            offset,
+            size: buf.len() as u64,
+            data: buf.to_vec(),
+        };
+        let err = self.merge(chunk_key(path, chunk_id), op);
+        if err != 0 {
+            return Err(err);
+        }
+        Ok(buf.len() as i64)
+    }
+    fn read(
+        &self,
+        path: &String,
+        _file_id: u64,
+        chunk_id: u64,
+        buf: &mut [u8],
+        offset: u64,
+    ) -> Result<i64, i32> {
+        let chunk = match self.db.get(chunk_key(path, chunk_id)) {
+            Ok(Some(chunk)) => chunk,
+            Ok(None) => return Err(-2),
+            Err(_e) => {
+                error_msg(
+                    "server::storage::rocksdb_backend::read".to_string(),
+                    "fail to read chunk".to_string(),
+                );
+                return Err(-1);
+            }
+        };
+        if offset as usize >= chunk.len() {
+            return Ok(0);
+        }
+        let len = usize::min(buf.len(), chunk.len() - offset as usize);
+        buf[..len].copy_from_slice(&chunk[offset as usize..offset as usize + len]);
+        Ok(len as i64)
+    }
+    fn exists(&self, path: &String, _file_id: u64, chunk_id: u64) -> bool {
+        matches!(self.db.get_pinned(chunk_key(path, chunk_id)), Ok(Some(_)))
+    }
+    fn truncate(&self, path: &String, _file_id: u64, chunk_id: u64, length: u64) {
+        let key = chunk_key(path, chunk_id);
+        // like a chunk file, a missing chunk is not created
+        if let Ok(None) = self.db.get(&key) {
+            return;
+        }
+        self.merge(key, Operand::Truncate { offset: length });
+    }
+    fn trim(&self, path: &String, _file_id: u64, chunk_start: u64) {
+        for chunk_id in self.chunk_ids(path, chunk_start) {
+            if trims(chunk_start, chunk_id) {
+                if let Err(_e) = self.db.delete_opt(chunk_key(path, chunk_id), &self.write_opts) {
+                    continue;
+                }
+            }
+        }
+    }
+    fn remove(&self, path: &String, _file_id: u64, chunk_id: u64) {
+        if let Err(_e) = self.db.delete_opt(chunk_key(path, chunk_id), &self.write_opts) {
+            error_msg(
+                "server::storage::rocksdb_backend::remove".to_string(),
+                "fail to delete chunk".to_string(),
+            );
+        }
+    }
+    fn destroy(&self, path: &String, _file_id: u64) {
+        for chunk_id in self.chunk_ids(path, 0) {
+            if let Err(_e) = self.db.delete_opt(chunk_key(path, chunk_id), &self.write_opts) {
+                error_msg(
+                    "server::storage::rocksdb_backend::destroy".to_string(),
+                    "fail to delete chunk".to_string(),
+                );
+            }
+        }
+    }
+    fn stat(&self) -> ChunkStat {
+        fs_chunk_stat(&self.path, self.chunk_size_)
+    }
+    // without the write-ahead log only a flush makes writes durable
+    fn sync(&self, _path: &String, _file_id: u64) -> i32 {
+        if let Err(_e) = self.db.flush() {
+            return EIO;
+        }
+        return 0;
+    }
+}
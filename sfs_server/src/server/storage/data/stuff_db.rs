@@ -1,7 +1,8 @@
 use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
 
 use lazy_static::*;
-use libc::EINVAL;
+use libc::{EINVAL, EIO};
 use rocksdb::{Options, WriteOptions, DB};
 use sfs_global::global::{error_msg::error_msg, util::serde_util::serialize};
 
@@ -10,6 +11,7 @@ use crate::{
     server::{config::TRUNCATE_DIRECTORY, filesystem::storage_context::StorageContext},
 };
 
+use super::cipher::Cipher;
 use super::merge::{self, Operand};
 
 #[allow(unused_must_use)]
@@ -19,14 +21,23 @@ pub fn init_sdb() -> StuffDB {
     if TRUNCATE_DIRECTORY {
         std::fs::remove_dir_all(Path::new(&stuff_path));
     }
-    return StuffDB::new(&stuff_path).unwrap();
+    let cipher = StorageContext::get_instance().get_encryption_key().map(Cipher::new);
+    return StuffDB::new(&stuff_path, cipher).unwrap();
 }
+// sealed values start with it, values without it were written in plain
+const SEALED_TAG: &[u8] = b"\xffsfs-sealed\x01";
+// content of stuffed files, kept on the metadata hosts of the file next to its metadata.
+// with a key the content is sealed, it is then rewritten whole instead of merged
 #[allow(dead_code)]
 pub struct StuffDB {
     pub db: DB,
     options: Options,
     write_opts: WriteOptions,
     path: String,
+    // held while a file is checked for being stuffed and its content changes with the outcome
+    lock_: Mutex<()>,
+    // none without a key, content is kept in plain then
+    cipher_: Option<Cipher>,
 }
 lazy_static! {
     static ref SDB: StuffDB = init_sdb();
@@ -38,7 +49,7 @@ impl StuffDB {
     pub fn optimize_rocksdb_options(options: &mut Options) {
         options.set_max_successive_merges(125);
     }
-    pub fn new(path: &String) -> Option<StuffDB> {
+    pub fn new(path: &String, cipher: Option<Cipher>) -> Option<StuffDB> {
         let mut options = Options::default();
         options.increase_parallelism(10);
         options.optimize_level_style_compaction(512 * 1024 * 1024);
@@ -58,6 +69,8 @@ impl StuffDB {
                 options: options,
                 write_opts: write_options,
                 path: path.clone(),
+                lock_: Mutex::new(()),
+                cipher_: cipher,
             })
         } else {
             error_msg(
@@ -67,16 +80,50 @@ impl StuffDB {
             None
         }
     }
+    pub fn lock(&self) -> MutexGuard<()> {
+        self.lock_.lock().unwrap()
+    }
     pub fn get(&self, key: &String) -> Option<Vec<u8>> {
         //println!("getting key: {}", key);
         if let Ok(Some(val)) = self.db.get(key) {
-            Some(val)
+            let sealed = match val.strip_prefix(SEALED_TAG) {
+                Some(sealed) => sealed,
+                None => return Some(val.to_vec()),
+            };
+            let data = self.cipher_.as_ref().and_then(|cipher| cipher.open(sealed));
+            if data.is_none() {
+                error_msg(
+                    "server::storage::stuff_db::get".to_string(),
+                    format!("fail to open stuffed data of '{}'", key),
+                );
+            }
+            data
         } else {
             None
         }
     }
+    // content of 'key' to be rewritten, empty if there is none. Err(EIO) if it can't be
+    // opened, it is not replaced then
+    fn content(&self, key: &String) -> Result<Vec<u8>, i32> {
+        if !self.exists(key) {
+            return Ok(Vec::new());
+        }
+        self.get(key).ok_or(EIO)
+    }
     pub fn write(&self, key: &String, offset: u64, size: u64, data: &[u8]) -> i32 {
         //println!("putting key: {}", key);
+        if self.cipher_.is_some() {
+            let mut content = match self.content(key) {
+                Ok(content) => content,
+                Err(e) => return e,
+            };
+            let end = (offset + size) as usize;
+            if content.len() < end {
+                content.resize(end, 0);
+            }
+            content[offset as usize..end].copy_from_slice(&data[..size as usize]);
+            return self.put(key, content);
+        }
         let op = Operand::Write {
             offset,
             size,
@@ -93,6 +140,14 @@ impl StuffDB {
         return 0;
     }
     pub fn truncate(&self, key: &String, offset: u64) -> i32 {
+        if self.cipher_.is_some() {
+            let mut content = match self.content(key) {
+                Ok(content) => content,
+                Err(e) => return e,
+            };
+            content.resize(offset as usize, 0);
+            return self.put(key, content);
+        }
         let op = Operand::Truncate { offset };
         let v = serialize(op);
         if let Err(_e) = self.db.merge_opt(key, v, &self.write_opts) {
@@ -104,6 +159,20 @@ impl StuffDB {
         }
         return 0;
     }
+    pub fn put(&self, key: &String, data: Vec<u8>) -> i32 {
+        let data = match &self.cipher_ {
+            Some(cipher) => [SEALED_TAG, &cipher.seal(&data)].concat(),
+            None => data,
+        };
+        if let Err(_e) = self.db.put_opt(key, data, &self.write_opts) {
+            error_msg(
+                "server::storage::stuff_db::put".to_string(),
+                "fail to put value".to_string(),
+            );
+            return EINVAL;
+        }
+        return 0;
+    }
     pub fn remove(&self, key: &String) {
         if let Err(_e) = self.db.delete(key) {
             error_msg(
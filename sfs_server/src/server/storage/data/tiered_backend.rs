@@ -0,0 +1,385 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::sync::mpsc::{channel, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+
+use libc::{EDQUOT, ENOSPC};
+use rocksdb::{Options, WriteOptions, DB};
+use sfs_global::global::erasure::trims;
+use sfs_global::global::network::forward_data::ChunkStat;
+
+use crate::error_msg::error_msg;
+use crate::server::config::{TIER_HIGH_WATERMARK, TIER_LOW_WATERMARK};
+
+use super::backend::ChunkBackend;
+use super::rocksdb_backend::{chunk_ids, chunk_key};
+
+const TIER_LOCKS: usize = 64;
+
+// last accesses of the chunks on the fast tier
+struct Recency {
+    tick: u64,
+    chunks: BTreeMap<(String, u64), u64>,
+    // the least recently used chunk first, with the id of its file
+    order: BTreeMap<u64, (String, u64, u64)>,
+}
+impl Recency {
+    fn touch(&mut self, path: &String, file_id: u64, chunk_id: u64) {
+        self.tick += 1;
+        if let Some(old) = self.chunks.insert((path.clone(), chunk_id), self.tick) {
+            self.order.remove(&old);
+        }
+        self.order.insert(self.tick, (path.clone(), file_id, chunk_id));
+    }
+    fn forget(&mut self, path: &String, chunk_id: u64) {
+        if let Some(tick) = self.chunks.remove(&(path.clone(), chunk_id)) {
+            self.order.remove(&tick);
+        }
+    }
+    // chunks of the file from 'chunk_start' on, only those a trim drops with 'trim'
+    fn forget_from(&mut self, path: &String, chunk_start: u64, trim: bool) {
+        let chunk_ids: Vec<u64> = self
+            .chunks
+            .range((path.clone(), chunk_start)..=(path.clone(), u64::MAX))
+            .map(|((_path, chunk_id), _tick)| *chunk_id)
+            .filter(|chunk_id| !trim || trims(chunk_start, *chunk_id))
+            .collect();
+        for chunk_id in chunk_ids {
+            self.forget(path, chunk_id);
+        }
+    }
+    fn pop_oldest(&mut self) -> Option<(String, u64, u64)> {
+        let (_tick, (path, file_id, chunk_id)) = self.order.pop_first()?;
+        self.chunks.remove(&(path.clone(), chunk_id));
+        Some((path, file_id, chunk_id))
+    }
+}
+// chunks on two tiers, the node-local 'fast_' one and the larger 'slow_' capacity tier. a chunk
+// lives on one tier, the ones on the capacity tier are recorded in 'db'. once a write finds the
+// fast tier used above the high watermark, a background thread moves its least recently used
+// chunks down until it is used below the low one, and a write it has no room for goes down at
+// once. a chunk on the capacity tier comes back up when it is read or written while the fast
+// tier has room.
+// recency is kept in memory, chunks not accessed since the server started are not moved down
+pub struct TieredBackend {
+    tiers_: Arc<Tiers>,
+    // wakes the thread moving chunks down, it ends with the backend
+    evict_: Mutex<Sender<()>>,
+}
+#[allow(dead_code)]
+struct Tiers {
+    db: DB,
+    options: Options,
+    write_opts: WriteOptions,
+    path: String,
+    fast_: Box<dyn ChunkBackend>,
+    slow_: Box<dyn ChunkBackend>,
+    chunk_size_: u64,
+    recency_: Mutex<Recency>,
+    // a chunk does not change while it moves between tiers
+    locks_: Vec<Mutex<()>>,
+}
+impl TieredBackend {
+    pub fn new(
+        path: &String,
+        fast: Box<dyn ChunkBackend>,
+        slow: Box<dyn ChunkBackend>,
+        chunk_size: u64,
+    ) -> Option<TieredBackend> {
+        let mut options = Options::default();
+        options.create_if_missing(true);
+        if let Ok(rdb) = DB::open(&options, Path::new(path)) {
+            let tiers = Arc::new(Tiers {
+                db: rdb,
+                options: options,
+                write_opts: WriteOptions::default(),
+                path: path.clone(),
+                fast_: fast,
+                slow_: slow,
+                chunk_size_: chunk_size,
+                recency_: Mutex::new(Recency {
+                    tick: 0,
+                    chunks: BTreeMap::new(),
+                    order: BTreeMap::new(),
+                }),
+                locks_: (0..TIER_LOCKS).map(|_| Mutex::new(())).collect(),
+            });
+            let (evict, wakeups) = channel();
+            let evictor = tiers.clone();
+            thread::spawn(move || {
+                while wakeups.recv().is_ok() {
+                    // wakeups sent while chunks were moving are served by this round
+                    while wakeups.try_recv().is_ok() {}
+                    evictor.make_room();
+                }
+            });
+            Some(TieredBackend {
+                tiers_: tiers,
+                evict_: Mutex::new(evict),
+            })
+        } else {
+            error_msg(
+                "server::storage::tiered_backend::new".to_string(),
+                "fail to open database".to_string(),
+            );
+            None
+        }
+    }
+    fn wake_evictor(&self) {
+        let _ = self.evict_.lock().unwrap().send(());
+    }
+}
+impl Tiers {
+    fn lock_chunk(&self, path: &String, chunk_id: u64) -> MutexGuard<()> {
+        let mut hasher = DefaultHasher::new();
+        path.hash(&mut hasher);
+        chunk_id.hash(&mut hasher);
+        self.locks_[hasher.finish() as usize % TIER_LOCKS].lock().unwrap()
+    }
+    fn on_slow(&self, path: &String, chunk_id: u64) -> bool {
+        matches!(self.db.get(chunk_key(path, chunk_id)), Ok(Some(_)))
+    }
+    fn set_slow(&self, path: &String, chunk_id: u64, slow: bool) {
+        let key = chunk_key(path, chunk_id);
+        let res = if slow {
+            self.db.put_opt(key, b"", &self.write_opts)
+        } else {
+            self.db.delete_opt(key, &self.write_opts)
+        };
+        if let Err(_e) = res {
+            error_msg(
+                "server::storage::tiered_backend::set_slow".to_string(),
+                format!("fail to record the tier of chunk {} of '{}'", chunk_id, path),
+            );
+        }
+    }
+    // share of the fast tier in use
+    fn fast_used(&self) -> f64 {
+        let stat = self.fast_.stat();
+        if stat.chunk_total == 0 {
+            return 1.0;
+        }
+        1.0 - stat.chunk_free as f64 / stat.chunk_total as f64
+    }
+    // bytes to move down for the fast tier to be used below the low watermark
+    fn over_low(&self) -> u64 {
+        let stat = self.fast_.stat();
+        let used = stat.chunk_total - stat.chunk_free.min(stat.chunk_total);
+        used.saturating_sub((stat.chunk_total as f64 * TIER_LOW_WATERMARK) as u64)
+    }
+    // move a chunk down to the capacity tier or up from it, the caller holds its lock. the bytes
+    // moved, Err(-2) if it has no content on the tier it leaves
+    fn relocate(
+        &self,
+        path: &String,
+        file_id: u64,
+        chunk_id: u64,
+        down: bool,
+    ) -> Result<u64, i32> {
+        let (from, to) = if down {
+            (&self.fast_, &self.slow_)
+        } else {
+            (&self.slow_, &self.fast_)
+        };
+        let mut chunk = vec![0u8; self.chunk_size_ as usize];
+        let len = from.read(path, file_id, chunk_id, &mut chunk, 0)?;
+        chunk.truncate(len as usize);
+        if let Err(e) = to.write(path, file_id, chunk_id, &chunk, 0) {
+            // a partial copy is dropped, the chunk stays where it was
+            to.remove(path, file_id, chunk_id);
+            return Err(e);
+        }
+        to.truncate(path, file_id, chunk_id, len as u64);
+        self.set_slow(path, chunk_id, down);
+        from.remove(path, file_id, chunk_id);
+        Ok(len as u64)
+    }
+    // move the least recently used chunks down once the fast tier is used above the high
+    // watermark, until it is used below the low one. runs on the evicting thread, the fast tier
+    // is looked at once per round rather than per chunk
+    fn make_room(&self) {
+        if self.fast_used() < TIER_HIGH_WATERMARK {
+            return;
+        }
+        let mut to_move = self.over_low();
+        while to_move > 0 {
+            let oldest = self.recency_.lock().unwrap().pop_oldest();
+            let (path, file_id, chunk_id) = match oldest {
+                Some(oldest) => oldest,
+                None => return,
+            };
+            let _guard = self.lock_chunk(&path, chunk_id);
+            if self.on_slow(&path, chunk_id) {
+                continue;
+            }
+            match self.relocate(&path, file_id, chunk_id, true) {
+                Ok(moved) => to_move = to_move.saturating_sub(moved.max(1)),
+                // dropped meanwhile
+                Err(-2) => {}
+                Err(e) => {
+                    error_msg(
+                        "server::storage::tiered_backend::make_room".to_string(),
+                        format!("error {} occurs while moving chunk {} of '{}' down", e, chunk_id, path),
+                    );
+                    return;
+                }
+            }
+        }
+    }
+}
+impl ChunkBackend for TieredBackend {
+    fn write(
+        &self,
+        path: &String,
+        file_id: u64,
+        chunk_id: u64,
+        buf: &[u8],
+        offset: u64,
+    ) -> Result<i64, i32> {
+        let tiers = &self.tiers_;
+        let fast_used = tiers.fast_used();
+        if fast_used >= TIER_HIGH_WATERMARK {
+            self.wake_evictor();
+        }
+        let _guard = tiers.lock_chunk(path, chunk_id);
+        if tiers.on_slow(path, chunk_id) {
+            if fast_used >= TIER_HIGH_WATERMARK
+                || tiers.relocate(path, file_id, chunk_id, false).is_err()
+            {
+                return tiers.slow_.write(path, file_id, chunk_id, buf, offset);
+            }
+        }
+        match tiers.fast_.write(path, file_id, chunk_id, buf, offset) {
+            Ok(written) => {
+                tiers.recency_.lock().unwrap().touch(path, file_id, chunk_id);
+                Ok(written)
+            }
+            // the fast tier filled up before chunks were moved down, only this one goes down now
+            Err(e) if e == ENOSPC || e == EDQUOT => {
+                error_msg(
+                    "server::storage::tiered_backend::write".to_string(),
+                    format!(
+                        "fast tier is full, chunk {} of '{}' moves to the capacity tier",
+                        chunk_id, path
+                    ),
+                );
+                self.wake_evictor();
+                tiers.recency_.lock().unwrap().forget(path, chunk_id);
+                match tiers.relocate(path, file_id, chunk_id, true) {
+                    Ok(_moved) => {}
+                    Err(-2) => {
+                        tiers.fast_.remove(path, file_id, chunk_id);
+                        tiers.set_slow(path, chunk_id, true);
+                    }
+                    Err(e) => return Err(e),
+                }
+                tiers.slow_.write(path, file_id, chunk_id, buf, offset)
+            }
+            Err(e) => Err(e),
+        }
+    }
+    fn read(
+        &self,
+        path: &String,
+        file_id: u64,
+        chunk_id: u64,
+        buf: &mut [u8],
+        offset: u64,
+    ) -> Result<i64, i32> {
+        let tiers = &self.tiers_;
+        let _guard = tiers.lock_chunk(path, chunk_id);
+        if tiers.on_slow(path, chunk_id) {
+            // promoted without moving others down
+            if tiers.fast_used() >= TIER_LOW_WATERMARK
+                || tiers.relocate(path, file_id, chunk_id, false).is_err()
+            {
+                return tiers.slow_.read(path, file_id, chunk_id, buf, offset);
+            }
+        }
+        let read = tiers.fast_.read(path, file_id, chunk_id, buf, offset)?;
+        tiers.recency_.lock().unwrap().touch(path, file_id, chunk_id);
+        Ok(read)
+    }
+    fn exists(&self, path: &String, file_id: u64, chunk_id: u64) -> bool {
+        let tiers = &self.tiers_;
+        let _guard = tiers.lock_chunk(path, chunk_id);
+        if tiers.on_slow(path, chunk_id) {
+            tiers.slow_.exists(path, file_id, chunk_id)
+        } else {
+            tiers.fast_.exists(path, file_id, chunk_id)
+        }
+    }
+    fn truncate(&self, path: &String, file_id: u64, chunk_id: u64, length: u64) {
+        let tiers = &self.tiers_;
+        let _guard = tiers.lock_chunk(path, chunk_id);
+        if tiers.on_slow(path, chunk_id) {
+            tiers.slow_.truncate(path, file_id, chunk_id, length);
+        } else {
+            tiers.fast_.truncate(path, file_id, chunk_id, length);
+        }
+    }
+    fn trim(&self, path: &String, file_id: u64, chunk_start: u64) {
+        let tiers = &self.tiers_;
+        tiers.fast_.trim(path, file_id, chunk_start);
+        tiers.slow_.trim(path, file_id, chunk_start);
+        for chunk_id in chunk_ids(&tiers.db, path, chunk_start) {
+            if trims(chunk_start, chunk_id) {
+                tiers.set_slow(path, chunk_id, false);
+            }
+        }
+        tiers
+            .recency_
+            .lock()
+            .unwrap()
+            .forget_from(path, chunk_start, true);
+    }
+    fn remove(&self, path: &String, file_id: u64, chunk_id: u64) {
+        let tiers = &self.tiers_;
+        let _guard = tiers.lock_chunk(path, chunk_id);
+        if tiers.on_slow(path, chunk_id) {
+            tiers.slow_.remove(path, file_id, chunk_id);
+            tiers.set_slow(path, chunk_id, false);
+        } else {
+            tiers.fast_.remove(path, file_id, chunk_id);
+        }
+        tiers.recency_.lock().unwrap().forget(path, chunk_id);
+    }
+    fn destroy(&self, path: &String, file_id: u64) {
+        let tiers = &self.tiers_;
+        tiers.fast_.destroy(path, file_id);
+        tiers.slow_.destroy(path, file_id);
+        for chunk_id in chunk_ids(&tiers.db, path, 0) {
+            tiers.set_slow(path, chunk_id, false);
+        }
+        tiers.recency_.lock().unwrap().forget_from(path, 0, false);
+    }
+    fn stat(&self) -> ChunkStat {
+        let tiers = &self.tiers_;
+        let fast = tiers.fast_.stat();
+        let slow = tiers.slow_.stat();
+        ChunkStat {
+            chunk_size: fast.chunk_size,
+            chunk_total: fast.chunk_total + slow.chunk_total,
+            chunk_free: fast.chunk_free + slow.chunk_free,
+            tiers: vec![
+                (fast.chunk_total, fast.chunk_free),
+                (slow.chunk_total, slow.chunk_free),
+            ],
+        }
+    }
+    fn sync(&self, path: &String, file_id: u64) -> i32 {
+        let tiers = &self.tiers_;
+        let err = tiers.fast_.sync(path, file_id);
+        if err != 0 {
+            return err;
+        }
+        tiers.slow_.sync(path, file_id)
+    }
+    fn precreate(&self, path: &String, file_id: u64, chunks: &Vec<u64>) -> i32 {
+        let tiers = &self.tiers_;
+        tiers.fast_.precreate(path, file_id, chunks)
+    }
+}
@@ -3,11 +3,8 @@ use std::{
     time::{self, UNIX_EPOCH},
 };
 
-use futures::SinkExt;
-use grpcio::WriteFlags;
-use libc::{EEXIST, EINVAL};
+use libc::{EEXIST, EINVAL, EIO};
 use rocksdb::{Options, WriteOptions, DB};
-use sfs_rpc::post_result;
 
 use crate::{
     config::USE_WRITE_AHEAD_LOG,
@@ -18,7 +15,6 @@ use crate::{
     },
 };
 use sfs_global::global::{
-    metadata::{Metadata, S_ISDIR},
     util::{
         path_util::{has_trailing_slash, is_absolute},
         serde_util::serialize,
@@ -29,6 +25,9 @@ use lazy_static::*;
 
 use super::merge::Operand;
 
+// the bound of the file ids reserved by this host is kept under the empty key, paths are absolute
+static FILE_ID_BOUND_KEY: &str = "";
+
 #[allow(unused_must_use)]
 pub fn init_mdb() -> MetadataDB {
     let metadata_path =
@@ -190,44 +189,57 @@ impl MetadataDB {
             );
         }
     }
-    pub async fn get_dirents(
-        &self,
-        dir: &String,
-        sink: &mut grpcio::ServerStreamingSink<sfs_rpc::proto::server::PostResult>,
-    ) {
-        let mut root_path = dir.clone();
-        if !is_absolute(&root_path) {
+    pub fn unstuff(&self, key: &String) {
+        let v = serialize(&Operand::Unstuff);
+        if let Err(_e) = self.db.merge_opt(key, v, &self.write_opts) {
             error_msg(
-                "server::storage::metadata::db::get_dirents".to_string(),
-                "dir is not absolute".to_string(),
+                "server::storage::metadata::db::unstuff".to_string(),
+                "fail to merge operands".to_string(),
             );
-            return;
         }
-        if !has_trailing_slash(&root_path) && root_path.len() != 1 {
-            root_path.push('/');
+    }
+    pub fn add_blocks(&self, key: &String, blocks: i64) {
+        let v = serialize(&Operand::AddBlocks { blocks });
+        if let Err(_e) = self.db.merge_opt(key, v, &self.write_opts) {
+            error_msg(
+                "server::storage::metadata::db::add_blocks".to_string(),
+                "fail to merge operands".to_string(),
+            );
+        }
+    }
+    pub fn get_file_id_bound(&self) -> u64 {
+        if let Ok(Some(val)) = self.db.get(FILE_ID_BOUND_KEY) {
+            let mut bound = [0u8; 8];
+            bound.copy_from_slice(&val[..8]);
+            u64::from_be_bytes(bound)
+        } else {
+            0
+        }
+    }
+    // synced whether or not the metadata goes through the write-ahead log, an id handed out
+    // twice would mix the chunks of two files
+    pub fn set_file_id_bound(&self, bound: u64) -> i32 {
+        let mut write_opts = WriteOptions::default();
+        write_opts.set_sync(true);
+        if let Err(_e) = self.db.put_opt(FILE_ID_BOUND_KEY, bound.to_be_bytes(), &write_opts) {
+            error_msg(
+                "server::storage::metadata::db::set_file_id_bound".to_string(),
+                "fail to reserve file ids".to_string(),
+            );
+            return EIO;
         }
-        let iter = self.db.prefix_iterator(root_path.as_bytes());
+        return 0;
+    }
+    pub fn get_all(&self) -> Vec<(String, Vec<u8>)> {
+        let mut entries = Vec::new();
+        let iter = self.db.iterator(rocksdb::IteratorMode::Start);
         for (k, v) in iter {
-            let s = String::from_utf8(k.to_vec()).unwrap();
-            if !s.starts_with(&root_path) || s.len() == root_path.len() {
-                continue;
-            }
-            if let Some(_idx) = s[root_path.len()..].to_string().find('/') {
-                continue;
-            }
-            let name = s[root_path.len()..].to_string();
-            if name.len() == 0 {
+            if k.len() == 0 {
                 continue;
             }
-            let md = Metadata::deserialize(&v.to_vec());
-            let entry = (name, S_ISDIR(md.get_mode()));
-            sink.send((
-                post_result(0, serialize(entry), vec![0; 0]),
-                WriteFlags::default(),
-            ))
-            .await
-            .unwrap();
+            entries.push((String::from_utf8(k.to_vec()).unwrap(), v.to_vec()));
         }
+        entries
     }
     #[allow(unused_variables)]
     #[allow(unused_assignments)]
@@ -0,0 +1,146 @@
+use std::path::Path;
+
+use lazy_static::*;
+use libc::EINVAL;
+use rocksdb::{Direction, IteratorMode, Options, WriteOptions, DB};
+use sfs_global::global::util::serde_util::{deserialize, serialize};
+
+use crate::{
+    config::USE_WRITE_AHEAD_LOG,
+    error_msg::error_msg,
+    server::{config::TRUNCATE_DIRECTORY, filesystem::storage_context::StorageContext},
+};
+
+// directory entries are kept as '<dir>\0<name>' records on the host owning the directory's metadata,
+// so listing a directory only contacts that host and only scans its direct children
+static DIRENT_SEPERATOR: char = '\0';
+// set once the records of the metadata kept before directory entries were are rebuilt
+static BACKFILLED_KEY: &str = "\0backfilled";
+
+#[allow(unused_must_use)]
+pub fn init_ddb() -> DirentDB {
+    let dirent_path =
+        StorageContext::get_instance().get_metadir().clone() + &"/dirents".to_string();
+
+    if TRUNCATE_DIRECTORY {
+        std::fs::remove_dir_all(Path::new(&dirent_path));
+    }
+    return DirentDB::new(&dirent_path).unwrap();
+}
+fn dirent_prefix(dir: &String) -> String {
+    format!("{}{}", dir, DIRENT_SEPERATOR)
+}
+#[allow(dead_code)]
+pub struct DirentDB {
+    pub db: DB,
+    options: Options,
+    write_opts: WriteOptions,
+    path: String,
+}
+lazy_static! {
+    static ref DDB: DirentDB = init_ddb();
+}
+impl DirentDB {
+    pub fn get_instance() -> &'static DirentDB {
+        &DDB
+    }
+    pub fn new(path: &String) -> Option<DirentDB> {
+        let mut options = Options::default();
+        options.increase_parallelism(10);
+        options.optimize_level_style_compaction(512 * 1024 * 1024);
+        options.create_if_missing(true);
+        let mut write_options = WriteOptions::default();
+        write_options.disable_wal(!USE_WRITE_AHEAD_LOG);
+        if let Ok(rdb) = DB::open(&options, Path::new(path)) {
+            Some(DirentDB {
+                db: rdb,
+                options: options,
+                write_opts: write_options,
+                path: path.clone(),
+            })
+        } else {
+            error_msg(
+                "server::storage::dirent_db::new".to_string(),
+                "fail to open database".to_string(),
+            );
+            None
+        }
+    }
+    pub fn put(&self, dir: &String, name: &String, is_dir: bool) -> i32 {
+        let key = dirent_prefix(dir) + name;
+        if let Err(_e) = self.db.put_opt(key, serialize(is_dir), &self.write_opts) {
+            error_msg(
+                "server::storage::dirent_db::put".to_string(),
+                "fail to put dirent".to_string(),
+            );
+            return EINVAL;
+        }
+        return 0;
+    }
+    pub fn remove(&self, dir: &String, name: &String) -> i32 {
+        let key = dirent_prefix(dir) + name;
+        if let Err(_e) = self.db.delete_opt(key, &self.write_opts) {
+            error_msg(
+                "server::storage::dirent_db::remove".to_string(),
+                "fail to delete dirent".to_string(),
+            );
+            return EINVAL;
+        }
+        return 0;
+    }
+    // names come out in key order, so the last name listed is a stable cursor for the next page
+    pub fn list_dirents(&self, dir: &String, start_after: &String, limit: u64) -> Vec<(String, bool)> {
+        let prefix = dirent_prefix(dir);
+        let start = prefix.clone() + start_after;
+        let iter = self
+            .db
+            .iterator(IteratorMode::From(start.as_bytes(), Direction::Forward));
+        let mut entries = Vec::new();
+        for (k, v) in iter {
+            if !k.starts_with(prefix.as_bytes()) {
+                break;
+            }
+            let name = String::from_utf8(k[prefix.len()..].to_vec()).unwrap();
+            if start_after.len() != 0 && name.eq(start_after) {
+                continue;
+            }
+            if limit != 0 && entries.len() as u64 == limit {
+                break;
+            }
+            entries.push((name, deserialize::<bool>(&v.to_vec())));
+        }
+        entries
+    }
+    // whether the records of older metadata still have to be rebuilt, see 'backfill_dirents'
+    pub fn needs_backfill(&self) -> bool {
+        match self.db.get(BACKFILLED_KEY) {
+            Ok(res) => res.is_none(),
+            Err(_e) => true,
+        }
+    }
+    pub fn mark_backfilled(&self) {
+        if let Err(_e) = self.db.put_opt(BACKFILLED_KEY, serialize(true), &self.write_opts) {
+            error_msg(
+                "server::storage::dirent_db::mark_backfilled".to_string(),
+                "fail to put marker".to_string(),
+            );
+        }
+    }
+    // every record as (directory, name, is directory)
+    pub fn get_all(&self) -> Vec<(String, String, bool)> {
+        let mut entries = Vec::new();
+        let iter = self.db.iterator(IteratorMode::Start);
+        for (k, v) in iter {
+            let key = String::from_utf8(k.to_vec()).unwrap();
+            // the marker has no directory
+            if let Some(idx) = key.find(DIRENT_SEPERATOR).filter(|idx| *idx != 0) {
+                entries.push((
+                    key[..idx].to_string(),
+                    key[idx + 1..].to_string(),
+                    deserialize::<bool>(&v.to_vec()),
+                ));
+            }
+        }
+        entries
+    }
+}
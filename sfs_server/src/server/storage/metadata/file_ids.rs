@@ -0,0 +1,51 @@
+use lazy_static::*;
+use libc::ENOSPC;
+
+use std::sync::Mutex;
+
+use crate::server::{
+    config::{FILE_ID_BATCH, FILE_ID_HOST_SHIFT},
+    network::network_context::NetworkContext,
+};
+
+use super::db::MetadataDB;
+
+// file ids handed out by this metadata host. the host id sits above 'FILE_ID_HOST_SHIFT' so the
+// ids of two hosts never meet, ids below it are left to the migration of old data directories.
+// ids are reserved in batches, the bound is kept with the metadata and the ids reserved before
+// a restart are skipped
+pub struct FileIds {
+    // (next id, bound of the reserved ones)
+    ids_: Mutex<(u64, u64)>,
+}
+lazy_static! {
+    static ref FILE_IDS: FileIds = {
+        let bound = MetadataDB::get_instance().get_file_id_bound();
+        FileIds {
+            ids_: Mutex::new((bound, bound)),
+        }
+    };
+}
+impl FileIds {
+    pub fn get_instance() -> &'static FileIds {
+        &FILE_IDS
+    }
+    pub fn next(&self) -> Result<u64, i32> {
+        let mut ids = self.ids_.lock().unwrap();
+        if ids.0 == ids.1 {
+            let bound = ids.1 + FILE_ID_BATCH;
+            if bound >> FILE_ID_HOST_SHIFT != 0 {
+                return Err(ENOSPC);
+            }
+            let err = MetadataDB::get_instance().set_file_id_bound(bound);
+            if err != 0 {
+                return Err(err);
+            }
+            ids.1 = bound;
+        }
+        let id = ids.0;
+        ids.0 += 1;
+        let host = NetworkContext::get_instance().get_local_host_id();
+        Ok(((host + 1) << FILE_ID_HOST_SHIFT) | id)
+    }
+}
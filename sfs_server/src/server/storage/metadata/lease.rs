@@ -0,0 +1,119 @@
+use lazy_static::*;
+use libc::EAGAIN;
+use sfs_global::global::fsconfig::Consistency;
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::server::{config::LEASE_TABLE_CAPACITY, filesystem::storage_context::StorageContext};
+
+struct Leases {
+    // holders of each path and when their leases run out
+    holders: HashMap<String, HashMap<u64, Instant>>,
+    // changes of each path in flight and the time until which it takes no new leases, so a
+    // change turned away waits for the current holders only
+    recalls: HashMap<String, (usize, Instant)>,
+}
+// clients holding a metadata lease on each path of this host. a lease is never broken: a change
+// to a leased path is turned away with EAGAIN until the other holders' leases expired, the
+// client tries it again then. no thread waits on the server
+pub struct LeaseTable {
+    leases_: Mutex<Leases>,
+}
+lazy_static! {
+    static ref LEASES: LeaseTable = LeaseTable {
+        leases_: Mutex::new(Leases {
+            holders: HashMap::new(),
+            recalls: HashMap::new(),
+        }),
+    };
+}
+// a change of a path may go ahead while this is held, no lease is granted on it meanwhile
+pub struct Recall<'a> {
+    table: &'a LeaseTable,
+    path: String,
+}
+impl<'a> Drop for Recall<'a> {
+    fn drop(&mut self) {
+        let mut leases = self.table.leases_.lock().unwrap();
+        let done = match leases.recalls.get_mut(&self.path) {
+            Some((changes, until)) => {
+                *changes -= 1;
+                *changes == 0 && *until <= Instant::now()
+            }
+            None => false,
+        };
+        if done {
+            leases.recalls.remove(&self.path);
+        }
+    }
+}
+impl LeaseTable {
+    pub fn get_instance() -> &'static LeaseTable {
+        &LEASES
+    }
+    // returns the lease time in milliseconds, 0 if nothing is granted.
+    // the lease has to be recorded before the metadata is read, otherwise a change in between would not see it
+    pub fn grant(&self, path: &String, client: u64) -> u64 {
+        if client == 0 || StorageContext::get_instance().get_consistency() != Consistency::Lease {
+            return 0;
+        }
+        let lease_ms = StorageContext::get_instance().get_lease_ms();
+        let now = Instant::now();
+        let mut leases = self.leases_.lock().unwrap();
+        if let Some((changes, until)) = leases.recalls.get(path) {
+            if *changes != 0 || *until > now {
+                return 0;
+            }
+        }
+        if leases.holders.len() >= LEASE_TABLE_CAPACITY {
+            leases.holders.retain(|_, holders| {
+                holders.retain(|_, expire| *expire > now);
+                holders.len() != 0
+            });
+            leases.recalls.retain(|_, (changes, until)| *changes != 0 || *until > now);
+        }
+        leases
+            .holders
+            .entry(path.clone())
+            .or_insert(HashMap::new())
+            .insert(client, now + Duration::from_millis(lease_ms));
+        return lease_ms;
+    }
+    // called before 'path' changes. EAGAIN while a client but 'client' may still trust the old
+    // metadata, the change has to be sent again later. new leases wait for the retry meanwhile
+    pub fn recall(&self, path: &String, client: u64) -> Result<Recall<'_>, i32> {
+        let now = Instant::now();
+        let mut guard = self.leases_.lock().unwrap();
+        let leases = &mut *guard;
+        let expire = match leases.holders.get(path) {
+            Some(holders) => holders
+                .iter()
+                .filter(|(holder, _)| **holder != client)
+                .map(|(_, expire)| *expire)
+                .max(),
+            None => None,
+        };
+        let recall = leases.recalls.entry(path.clone()).or_insert((0, now));
+        if let Some(expire) = expire {
+            if expire > now {
+                // one lease time for the client to come back once the others expired
+                let lease_ms = StorageContext::get_instance().get_lease_ms();
+                recall.1 = expire + Duration::from_millis(lease_ms);
+                return Err(EAGAIN);
+            }
+        }
+        recall.0 += 1;
+        leases.holders.remove(path);
+        Ok(Recall {
+            table: self,
+            path: path.clone(),
+        })
+    }
+    // drop the leases on 'path' without waiting for them, only for metadata copied between
+    // servers, where no client waits for the change
+    pub fn revoke(&self, path: &String) {
+        self.leases_.lock().unwrap().holders.remove(path);
+    }
+}
@@ -1,8 +1,6 @@
 use rocksdb::MergeOperands;
 use serde::{Deserialize, Serialize};
-use sfs_global::global::{
-    metadata::Metadata, network::config::CHUNK_SIZE, util::serde_util::deserialize,
-};
+use sfs_global::global::{metadata::Metadata, util::serde_util::deserialize};
 
 use crate::error_msg::error_msg;
 #[derive(Debug, Deserialize, Serialize)]
@@ -19,6 +17,12 @@ pub enum Operand {
         size: usize,
         time: i64,
     },
+    // the content moved from the stuffing database to the chunks
+    Unstuff,
+    // physical blocks of compressed chunks changed on a chunk host
+    AddBlocks {
+        blocks: i64,
+    },
 }
 #[allow(unused_variables)]
 pub fn full_merge(
@@ -57,6 +61,8 @@ pub fn full_merge(
     let mut fsize = md.get_size();
     let mut mtime: i64 = md.get_modify_time();
     let mut ctime: i64 = md.get_change_time();
+    let mut stuffed = md.is_stuffed();
+    let mut blocks = md.get_blocks();
     let mut op;
     while {
         op = iter.next();
@@ -87,12 +93,19 @@ pub fn full_merge(
                 mtime = std::cmp::max(mtime, time);
                 ctime = std::cmp::max(ctime, time);
             }
+            Operand::Unstuff => {
+                stuffed = false;
+            }
+            Operand::AddBlocks { blocks: delta } => {
+                blocks = std::cmp::max(0, blocks + delta);
+            }
         }
     }
-    if fsize as u64 > CHUNK_SIZE {
+    if !stuffed {
         md.unstuff();
     }
     md.set_size(fsize);
+    md.set_blocks(blocks);
     md.set_modify_time(mtime);
     md.set_change_time(ctime);
     Some(md.serialize())
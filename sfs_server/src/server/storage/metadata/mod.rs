@@ -1,2 +1,6 @@
 pub mod db;
+pub mod dirent_db;
+pub mod file_ids;
 pub mod merge;
+pub mod lease;
+pub mod placement_db;
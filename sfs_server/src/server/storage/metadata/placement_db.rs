@@ -0,0 +1,97 @@
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+use lazy_static::*;
+use libc::EINVAL;
+use rocksdb::{Options, WriteOptions, DB};
+use sfs_global::global::{
+    error_msg::error_msg,
+    placement::Placement,
+    util::serde_util::{deserialize, serialize},
+};
+
+use crate::{
+    config::USE_WRITE_AHEAD_LOG,
+    server::{config::TRUNCATE_DIRECTORY, filesystem::storage_context::StorageContext},
+};
+
+#[allow(unused_must_use)]
+pub fn init_pdb() -> PlacementDB {
+    let placement_path = StorageContext::get_instance().get_rootdir().clone() + &"/placement".to_string();
+
+    if TRUNCATE_DIRECTORY {
+        std::fs::remove_dir_all(Path::new(&placement_path));
+    }
+    return PlacementDB::new(&placement_path).unwrap();
+}
+// chunks placed away from full hosts, kept on the metadata hosts of the file next to its metadata
+#[allow(dead_code)]
+pub struct PlacementDB {
+    pub db: DB,
+    options: Options,
+    write_opts: WriteOptions,
+    path: String,
+    // held while the placement of a file is read and extended
+    lock_: Mutex<()>,
+}
+lazy_static! {
+    static ref PDB: PlacementDB = init_pdb();
+}
+impl PlacementDB {
+    pub fn get_instance() -> &'static PlacementDB {
+        &PDB
+    }
+    pub fn new(path: &String) -> Option<PlacementDB> {
+        let mut options = Options::default();
+        options.create_if_missing(true);
+        let mut write_options = WriteOptions::default();
+        write_options.disable_wal(!USE_WRITE_AHEAD_LOG);
+        if let Ok(rdb) = DB::open(&options, Path::new(path)) {
+            Some(PlacementDB {
+                db: rdb,
+                options: options,
+                write_opts: write_options,
+                path: path.clone(),
+                lock_: Mutex::new(()),
+            })
+        } else {
+            error_msg(
+                "server::storage::placement_db::new".to_string(),
+                "fail to open database".to_string(),
+            );
+            None
+        }
+    }
+    pub fn lock(&self) -> MutexGuard<()> {
+        self.lock_.lock().unwrap()
+    }
+    // placement of 'key', empty if none of its chunks moved
+    pub fn get(&self, key: &String) -> Placement {
+        match self.db.get(key) {
+            Ok(Some(val)) => deserialize::<Placement>(&val),
+            _ => Placement::new(),
+        }
+    }
+    pub fn put(&self, key: &String, placement: &Placement) -> i32 {
+        if placement.is_empty() {
+            self.remove(key);
+            return 0;
+        }
+        if let Err(_e) = self.db.put_opt(key, serialize(placement), &self.write_opts) {
+            error_msg(
+                "server::storage::placement_db::put".to_string(),
+                "fail to put value".to_string(),
+            );
+            return EINVAL;
+        }
+        return 0;
+    }
+    pub fn remove(&self, key: &String) {
+        if let Err(_e) = self.db.delete(key) {
+            error_msg(
+                "server::storage::placement_db::remove".to_string(),
+                "fail to delete key".to_string(),
+            );
+        }
+    }
+}
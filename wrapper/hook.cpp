@@ -108,9 +108,8 @@ int
 hook_close(int fd) {
 
     if(fd_exist(fd)) {
-        // No call to the daemon is required
-        fd_remove(fd);
-        return 0;
+        // flushes the write-back buffer of the file before releasing fd
+        return with_errno(sfs_close(fd));
     }
 
     if(fd_is_internal(fd)) {
@@ -739,14 +738,13 @@ hook_fstatfs(unsigned int fd, struct statfs* buf) {
     return syscall_no_intercept_wrapper(SYS_fstatfs, fd, buf);
 }
 
-/* The function should broadcast a flush message (pmem_persist i.e.) if the
- * application needs the capabilities*/
+/* Writes buffered by the client are sent to the servers, servers do not
+ * persist them any further (pmem_persist i.e.)*/
 int
 hook_fsync(unsigned int fd) {
 
     if(fd_exist(fd)) {
-        errno = 0;
-        return 0;
+        return with_errno(sfs_fsync(fd));
     }
 
     return syscall_no_intercept_wrapper(SYS_fsync, fd);
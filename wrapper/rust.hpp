@@ -30,6 +30,8 @@ extern "C" int sfs_rmdir(const char*);
 extern "C" int sfs_opendir(const char*);
 extern "C" int sfs_getdents(int, struct dirent*, long);
 extern "C" int sfs_getdents64(int, struct dirent64*, long);
+extern "C" int sfs_close(int);
+extern "C" int sfs_fsync(int);
 
 extern "C" int relativize_fd_path(int dirfd, const char* cpath, char* resolved, bool follow_links);
 extern "C" bool relativize_path(const char* path, char* rel_path, bool follow_links);